@@ -4,8 +4,8 @@ use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
 fn main() {
   env_logger::init_from_env(Env::default().filter_or("RUST_LOG", "info"));
 
-  // If using an OpenAPI spec that contains references to other files, be sure to edit the
-  // `rerun_glob` (second argument) below so that updates trigger the codegen build script.
+  // Changes to the OpenAPI spec and to any files it references (directly or transitively) via
+  // `$ref` automatically trigger the codegen build script.
   CodeGenerator::new("openapi.yaml", ".openapi-lambda")
     // Divide the API into 3 Lambda functions based on the tag of each endpoint.
     .add_api_lambda(
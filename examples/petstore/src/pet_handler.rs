@@ -10,7 +10,7 @@ use openapi_lambda::__private::anyhow;
 use openapi_lambda::__private::aws_lambda_events::encodings::Body;
 use openapi_lambda::async_trait::async_trait;
 use openapi_lambda::{
-  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, StatusCode,
+  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, OperationInfo, StatusCode,
 };
 
 pub struct PetApiHandler {
@@ -45,6 +45,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(AddPetResponse, HeaderMap), Self::HandlerError> {
     Ok((
@@ -67,6 +68,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(DeletePetResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -78,6 +80,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(FindPetsByStatusResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -89,6 +92,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(FindPetsByTagsResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -100,6 +104,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(GetPetByIdResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -111,6 +116,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(UpdatePetResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -124,6 +130,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(UpdatePetWithFormResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -137,6 +144,7 @@ impl Api for PetApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(UploadFileResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -9,7 +9,7 @@ use openapi_lambda::__private::anyhow;
 use openapi_lambda::__private::aws_lambda_events::encodings::Body;
 use openapi_lambda::async_trait::async_trait;
 use openapi_lambda::{
-  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, StatusCode,
+  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, OperationInfo, StatusCode,
 };
 
 pub struct StoreApiHandler {
@@ -44,6 +44,7 @@ impl Api for StoreApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(DeleteOrderResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -54,6 +55,7 @@ impl Api for StoreApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(GetInventoryResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -65,6 +67,7 @@ impl Api for StoreApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(GetOrderByIdResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -76,6 +79,7 @@ impl Api for StoreApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(PlaceOrderResponse, HeaderMap), Self::HandlerError> {
     todo!()
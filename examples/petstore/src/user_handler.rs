@@ -10,7 +10,7 @@ use openapi_lambda::__private::anyhow;
 use openapi_lambda::__private::aws_lambda_events::encodings::Body;
 use openapi_lambda::async_trait::async_trait;
 use openapi_lambda::{
-  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, StatusCode,
+  ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, OperationInfo, StatusCode,
 };
 
 pub struct UserApiHandler {
@@ -45,6 +45,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(CreateUserResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -56,6 +57,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(CreateUsersWithListInputResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -67,6 +69,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(DeleteUserResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -78,6 +81,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(GetUserByNameResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -90,6 +94,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(LoginUserResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -100,6 +105,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(LogoutUserResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -112,6 +118,7 @@ impl Api for UserApiHandler {
     _headers: HeaderMap,
     _request_context: ApiGatewayProxyRequestContext,
     _lambda_context: LambdaContext,
+    _operation: OperationInfo,
     _auth_ok: Self::AuthOk,
   ) -> Result<(UpdateUserResponse, HeaderMap), Self::HandlerError> {
     todo!()
@@ -0,0 +1,90 @@
+use openapi_lambda::test_util::{Method, TestRequest};
+use openapi_lambda::Body;
+use petstore::middleware::ApiMiddleware;
+use petstore::pet::Api;
+use petstore::pet_handler::PetApiHandler;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+// Unlike `integration.rs`, these tests dispatch synthetic API Gateway events directly to the API
+// handler, so they don't require the API to be running separately.
+#[tokio::test]
+async fn test_add_pet() {
+  let api = PetApiHandler::new(());
+  let middleware = ApiMiddleware::new(());
+  let add_pet = |request: TestRequest| {
+    request
+      .with_operation_name("addPet")
+      .dispatch(|event| api.dispatch_request(event, &middleware))
+  };
+
+  // Success.
+  {
+    let response = add_pet(
+      TestRequest::new(Method::POST, "/pet")
+        .with_header("Authorization", "Bearer foobar")
+        .with_json_body(&json!({"name": "foo", "photoUrls": []})),
+    )
+    .await;
+
+    assert_eq!(response.status_code, 200);
+    let Some(Body::Text(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+      json!({
+        "name": "foo",
+        "photoUrls": []
+      })
+    );
+  }
+
+  // Missing `Authorization` header.
+  {
+    let response = add_pet(
+      TestRequest::new(Method::POST, "/pet")
+        .with_json_body(&json!({"name": "foo", "photoUrls": []})),
+    )
+    .await;
+
+    assert_eq!(response.status_code, 401);
+  }
+
+  // Invalid bearer token.
+  {
+    let response = add_pet(
+      TestRequest::new(Method::POST, "/pet")
+        .with_header("Authorization", "Bearer baz")
+        .with_json_body(&json!({"name": "foo", "photoUrls": []})),
+    )
+    .await;
+
+    assert_eq!(response.status_code, 401);
+  }
+
+  // Invalid request body.
+  {
+    let response = add_pet(
+      TestRequest::new(Method::POST, "/pet")
+        .with_header("Authorization", "Bearer foobar")
+        .with_json_body(&json!({})),
+    )
+    .await;
+
+    assert_eq!(response.status_code, 400);
+  }
+
+  // Wrong Content-Type.
+  {
+    let response = add_pet(
+      TestRequest::new(Method::POST, "/pet")
+        .with_header("Authorization", "Bearer foobar")
+        .with_header("Content-Type", "text/plain")
+        .with_body(r#"{"name": "foo", "photoUrls": []}"#),
+    )
+    .await;
+
+    assert_eq!(response.status_code, 400);
+  }
+}
@@ -1,10 +1,12 @@
 use crate::model::GeneratedModels;
 use crate::reference::resolve_local_reference;
-use crate::CodeGenerator;
+use crate::{CodeGenerator, CodegenError};
 
+use convert_case::{Case, Casing};
 use indexmap::IndexMap;
 use openapiv3::{
-  ReferenceOr, Schema, SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
+  MediaType, ReferenceOr, Schema, SchemaKind, StringFormat, StringType, Type,
+  VariantOrUnknownOrEmpty,
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
@@ -12,6 +14,48 @@ use quote::quote;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+/// Returns whether request and response bodies with the given MIME type are represented as raw
+/// byte strings (i.e., `Body::Binary`) in the generated code.
+///
+/// This must be kept consistent with [`CodeGenerator::gen_body_schema`].
+pub(crate) fn is_binary_mime_type(mime_type: &str) -> bool {
+  mime_type != "application/json" && !mime_type.starts_with("text/")
+}
+
+/// Returns the `Content-Type` of response bodies with the given media type or
+/// [media range](https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.2) (e.g., `image/*`).
+///
+/// Since a media range isn't a valid `Content-Type`, responses declared using a media range default
+/// to a generic content type, which the handler may replace by setting its own `Content-Type`
+/// response header.
+pub(crate) fn response_content_type(mime_type: &str) -> &str {
+  match mime_type.split_once('/') {
+    Some(("text", "*")) => "text/plain",
+    Some((_, "*")) => "application/octet-stream",
+    _ => mime_type,
+  }
+}
+
+/// Code used by generated clients to deserialize a JSON response body of the given type.
+fn client_deserialize_json(required_type: &TokenStream) -> TokenStream {
+  quote! {
+    serde_path_to_error::deserialize::<_, #required_type>(
+      &mut serde_json::Deserializer::from_slice(&body)
+    )
+    .map_err(|err| ClientError::InvalidResponseJson(Box::new(err)))
+  }
+}
+
+/// Code used by generated clients to decode a UTF-8 response body.
+fn client_deserialize_utf8() -> TokenStream {
+  quote! { String::from_utf8(body).map_err(ClientError::InvalidResponseUtf8) }
+}
+
+/// Code used by generated clients to pass through a raw response body.
+fn client_deserialize_binary() -> TokenStream {
+  quote! { Ok::<_, ClientError<_>>(body) }
+}
+
 /// Represents a request or response body type.
 pub struct BodySchema {
   /// Type of the request or response body as passed into the request handler (for request bodies)
@@ -25,9 +69,169 @@ pub struct BodySchema {
   /// Code that takes a `body` variable of `required_type` and converts it to a
   /// `aws_lambda_events::encodings::Body`.
   pub serialize: TokenStream,
+
+  /// Code that takes a `body` variable of type `Vec<u8>` received by a generated client and
+  /// converts it to a `Result<#required_type, ClientError<E>>` for any error response type `E`.
+  pub client_deserialize: TokenStream,
+
+  /// Code that takes a `body` variable of `required_type` and converts it to a value that can be
+  /// passed to `reqwest::RequestBuilder::body` by a generated client.
+  ///
+  /// This code may use the `?` operator to return a `ClientError`.
+  pub client_serialize: TokenStream,
+}
+
+/// Represents a `multipart/mixed` response body type.
+pub struct MultipartBodySchema {
+  /// Type of the response body stored in the response body enum variant.
+  pub required_type: TokenStream,
+
+  /// Code that takes a `body` variable of `required_type` and converts it to a
+  /// `(String, aws_lambda_events::encodings::Body)` tuple containing the `Content-Type` of the
+  /// response (including the multipart boundary) and the response body.
+  pub serialize: TokenStream,
+}
+
+/// How each part of a `multipart/mixed` body is serialized.
+enum PartEncoding {
+  Binary,
+  Json,
+  Text,
 }
 
 impl CodeGenerator {
+  /// Generates a `multipart/mixed` response body schema.
+  ///
+  /// The body schema must be an object, each property of which becomes one part of the body (in
+  /// the order the properties are defined). Optional properties that aren't set are omitted. The
+  /// `Content-Type` of each part is determined by the media type's `encoding` object or, if not
+  /// specified, by the default content types defined by the OpenAPI specification.
+  pub(crate) fn gen_multipart_response_body_schema(
+    &self,
+    media_type: &MediaType,
+    response_type: &str,
+    openapi_inline: &serde_yaml::Mapping,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    generated_models: &HashMap<Ident, TokenStream>,
+  ) -> Result<MultipartBodySchema, CodegenError> {
+    let Some(schema_or_ref) = &media_type.schema else {
+      unimplemented!("multipart/mixed response body without a schema in {response_type}");
+    };
+
+    let (required_type, _) = self.inline_ref_or_schema(
+      schema_or_ref,
+      components_schemas,
+      GeneratedModels::Done(generated_models),
+    );
+
+    let schema = match schema_or_ref {
+      ReferenceOr::Reference { reference } => {
+        Cow::Owned(resolve_local_reference::<Schema>(reference, openapi_inline)?.target)
+      }
+      ReferenceOr::Item(schema) => Cow::Borrowed(schema),
+    };
+    let SchemaKind::Type(Type::Object(object)) = &schema.schema_kind else {
+      unimplemented!(
+        "multipart/mixed response body with non-object schema in {response_type}: {schema:#?}"
+      );
+    };
+
+    let parts = object
+      .properties
+      .iter()
+      .map(|(property_name, property_ref_or_schema)| {
+        let property_schema = match property_ref_or_schema {
+          ReferenceOr::Reference { reference } => {
+            Cow::Owned(resolve_local_reference::<Schema>(reference, openapi_inline)?.target)
+          }
+          ReferenceOr::Item(schema) => Cow::Borrowed(schema.as_ref()),
+        };
+
+        let is_binary = matches!(
+          &property_schema.schema_kind,
+          SchemaKind::Type(Type::String(StringType {
+            format: VariantOrUnknownOrEmpty::Item(StringFormat::Binary),
+            ..
+          }))
+        );
+        let is_primitive = matches!(
+          &property_schema.schema_kind,
+          SchemaKind::Type(Type::String(_) | Type::Integer(_) | Type::Number(_) | Type::Boolean(_))
+        );
+
+        // See https://spec.openapis.org/oas/v3.0.3#encoding-object for the default content types.
+        let content_type = media_type
+          .encoding
+          .get(property_name)
+          .and_then(|encoding| encoding.content_type.clone())
+          .unwrap_or_else(|| {
+            if is_binary {
+              "application/octet-stream"
+            } else if is_primitive {
+              "text/plain"
+            } else {
+              "application/json"
+            }
+            .to_string()
+          });
+
+        let part_encoding = if is_binary {
+          PartEncoding::Binary
+        } else if !is_primitive
+          || content_type == "application/json"
+          || content_type.ends_with("+json")
+        {
+          PartEncoding::Json
+        } else {
+          PartEncoding::Text
+        };
+
+        let part_body = match part_encoding {
+          PartEncoding::Binary => quote! { part_value.clone() },
+          PartEncoding::Json => quote! {
+            to_json(part_value)
+              .map_err(|err| {
+                EventError::ToJsonResponse {
+                  type_name: std::borrow::Cow::Borrowed(#response_type),
+                  source: Box::new(err),
+                  backtrace: Backtrace::new()
+                }
+              })?
+          },
+          PartEncoding::Text => quote! { part_value.to_string() },
+        };
+
+        let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+        let push_part = quote! {
+          parts.push(multipart::Part::new(#content_type, #part_body));
+        };
+        Ok(if object.required.contains(property_name) {
+          quote! {
+            let part_value = &body.#property_ident;
+            #push_part
+          }
+        } else {
+          quote! {
+            if let Some(part_value) = &body.#property_ident {
+              #push_part
+            }
+          }
+        })
+      })
+      .collect::<Result<TokenStream, CodegenError>>()?;
+
+    Ok(MultipartBodySchema {
+      required_type,
+      serialize: quote! {
+        {
+          let mut parts = Vec::new();
+          #parts
+          multipart::to_multipart_mixed(parts)
+        }
+      },
+    })
+  }
+
   /// Generates both request and response body schemas to ensure symmetry.
   pub(crate) fn gen_body_schema(
     &self,
@@ -37,8 +241,8 @@ impl CodeGenerator {
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> BodySchema {
-    match (mime_type, schema_or_ref_opt) {
+  ) -> Result<BodySchema, CodegenError> {
+    Ok(match (mime_type, schema_or_ref_opt) {
       ("application/json", None) => BodySchema {
         required_type: quote! { serde_json::Value },
         deserialize: quote! {
@@ -51,11 +255,13 @@ impl CodeGenerator {
           .map_err(|err| EventError::InvalidBodyJson(Box::new(err), Backtrace::new()))
         },
         serialize: quote! { Body::Text(body.to_string()) },
+        client_deserialize: client_deserialize_json(&quote! { serde_json::Value }),
+        client_serialize: quote! { body.to_string() },
       },
       ("application/json", Some(schema_or_ref)) => {
         let schema = match schema_or_ref {
           ReferenceOr::Reference { reference } => {
-            Cow::Owned(resolve_local_reference::<Schema>(reference, openapi_inline).target)
+            Cow::Owned(resolve_local_reference::<Schema>(reference, openapi_inline)?.target)
           }
           ReferenceOr::Item(schema) => Cow::Borrowed(schema),
         };
@@ -63,16 +269,20 @@ impl CodeGenerator {
         // If the body schema is a string instead of an object or reference (e.g., for
         // webhook handlers that require the raw request body for HMAC verification), don't
         // deserialize it. We also don't use any newtypes here, even if the user defined a named
-        // schema for this type.
-        if let SchemaKind::Type(Type::String(StringType {
-          enumeration,
-          format,
-          ..
-        })) = &schema.as_ref().schema_kind
-        {
-          if !enumeration.is_empty() {
-            panic!("unexpected inline enum JSON request or response body: {schema:#?}");
+        // schema for this type. String enums are (de)serialized as JSON like any other model.
+        let raw_string_format = match &schema.as_ref().schema_kind {
+          SchemaKind::Type(Type::String(StringType {
+            enumeration,
+            format,
+            ..
+          }))
+            if enumeration.is_empty() =>
+          {
+            Some(format)
           }
+          _ => None,
+        };
+        if let Some(format) = raw_string_format {
           match format {
             // We assume that a binary type for a JSON request body wants the raw JSON as a byte
             // string, since there's no well-defined JSON representation for binary data (e.g.,
@@ -82,6 +292,8 @@ impl CodeGenerator {
               required_type: quote! { Vec<u8> },
               deserialize: quote! { .map(Ok).transpose() },
               serialize: quote! { Body::Binary(body) },
+              client_deserialize: client_deserialize_binary(),
+              client_serialize: quote! { body },
             },
             // We assume that a string type for a JSON request body wants the raw JSON as a
             // string, rather than expecting a JSON payload containing a quoted and escaped
@@ -100,6 +312,8 @@ impl CodeGenerator {
                 .map_err(|err| EventError::InvalidBodyUtf8(Box::new(err), Backtrace::new()))
               },
               serialize: quote! { Body::Text(body) },
+              client_deserialize: client_deserialize_utf8(),
+              client_serialize: quote! { body },
             },
           }
         } else {
@@ -131,6 +345,10 @@ impl CodeGenerator {
           };
 
           BodySchema {
+            client_deserialize: client_deserialize_json(&required_type),
+            client_serialize: quote! {
+              serde_json::to_vec(&body).map_err(ClientError::SerializeRequestBody)?
+            },
             required_type,
             deserialize,
             serialize,
@@ -144,6 +362,8 @@ impl CodeGenerator {
         required_type: quote! { Vec<u8> },
         deserialize: quote! { .map(Ok).transpose() },
         serialize: quote! { Body::Binary(body) },
+        client_deserialize: client_deserialize_binary(),
+        client_serialize: quote! { body },
       },
       // Treat all text types as UTF-8 strings.
       (mime, _) if mime.starts_with("text/") => BodySchema {
@@ -154,13 +374,18 @@ impl CodeGenerator {
           .map_err(|err| EventError::InvalidBodyUtf8(Box::new(err), Backtrace::new()))
         },
         serialize: quote! { Body::Text(body) },
+        client_deserialize: client_deserialize_utf8(),
+        client_serialize: quote! { body },
       },
-      // Any types we don't explicitly support we just leave as raw byte strings.
+      // Any types we don't explicitly support (including media ranges such as `image/*` or `*/*`) we
+      // just leave as raw byte strings.
       _ => BodySchema {
         required_type: quote! { Vec<u8> },
         deserialize: quote! { .map(Ok).transpose() },
         serialize: quote! { Body::Binary(body) },
+        client_deserialize: client_deserialize_binary(),
+        client_serialize: quote! { body },
       },
-    }
+    })
   }
 }
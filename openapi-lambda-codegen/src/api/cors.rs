@@ -0,0 +1,114 @@
+use crate::api::operation::PathOperation;
+use crate::CodeGenerator;
+
+use http::Method;
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+impl CodeGenerator {
+  /// Generates the dispatcher branch that responds to CORS preflight requests, if CORS is enabled
+  /// for the API Lambda.
+  pub(crate) fn gen_cors_preflight(
+    &self,
+    mod_name: &str,
+    operations: &[&PathOperation],
+    base_path: Option<&str>,
+    to_lambda_response: &TokenStream,
+  ) -> TokenStream {
+    let Some(cors) = self
+      .api_lambdas
+      .get(mod_name)
+      .and_then(|api_lambda| api_lambda.cors.as_ref())
+    else {
+      return quote! {};
+    };
+
+    let preflight_cases = operations
+      .iter()
+      .map(|op| (op.request_path.as_str(), &op.method))
+      .into_group_map()
+      .into_iter()
+      // Paths with an explicit `OPTIONS` operation are dispatched to the user's handler instead.
+      .filter(|(_, methods)| !methods.contains(&&Method::OPTIONS))
+      .sorted_by_key(|(request_path, _)| *request_path)
+      .map(|(request_path, methods)| {
+        let allowed_methods = methods
+          .into_iter()
+          .map(Method::as_str)
+          .chain(std::iter::once(Method::OPTIONS.as_str()))
+          .sorted()
+          .join(", ");
+        quote! {
+          Some(#request_path) => Some(#allowed_methods),
+        }
+      })
+      .collect::<TokenStream>();
+
+    let event_source = self.event_source(mod_name);
+    let http_method = event_source.http_method_expr();
+    let path_template = event_source.path_template_expr(operations, base_path);
+
+    let allow_origin = &cors.allow_origin;
+    let allow_headers = cors
+      .allow_headers_value()
+      .map(|allow_headers| quote! { Some(#allow_headers) })
+      .unwrap_or_else(|| quote! { None });
+    let allow_credentials = cors.allow_credentials;
+    let max_age_secs = cors
+      .max_age
+      .map(|max_age| {
+        let max_age_secs = max_age.as_secs();
+        quote! { Some(#max_age_secs) }
+      })
+      .unwrap_or_else(|| quote! { None });
+
+    quote! {
+      if #http_method == "OPTIONS" {
+        let path_template = #path_template;
+        let allowed_methods = match path_template {
+          #preflight_cases
+          _ => None,
+        };
+        if let Some(allowed_methods) = allowed_methods {
+          log::info!(
+            "Responding to CORS preflight request for {}",
+            path_template.unwrap_or_default(),
+          );
+          return #to_lambda_response(cors::preflight_response(
+            allowed_methods,
+            #allow_origin,
+            #allow_headers,
+            #allow_credentials,
+            #max_age_secs,
+          ));
+        }
+      }
+    }
+  }
+
+  /// Generates a function that converts an `HttpResponse` into the Lambda response type after
+  /// adding the CORS response headers required on every response, if CORS is enabled for the API
+  /// Lambda.
+  pub(crate) fn gen_cors_to_lambda_response_fn(
+    &self,
+    mod_name: &str,
+    response_type: &TokenStream,
+    response_conversion_fn: &TokenStream,
+  ) -> Option<TokenStream> {
+    let cors = self.api_lambdas.get(mod_name)?.cors.as_ref()?;
+    let allow_origin = &cors.allow_origin;
+    let allow_credentials = cors.allow_credentials;
+
+    Some(quote! {
+      /// Convert an `HttpResponse` into the Lambda response type, adding CORS response headers.
+      fn to_lambda_response(http_response: HttpResponse) -> #response_type {
+        #response_conversion_fn(cors::add_response_headers(
+          http_response,
+          #allow_origin,
+          #allow_credentials,
+        ))
+      }
+    })
+  }
+}
@@ -0,0 +1,49 @@
+use crate::CodeGenerator;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+impl CodeGenerator {
+  /// Generates the dispatcher branch that responds to the extra routes registered via
+  /// [`ApiLambda::with_extra_route`](crate::ApiLambda::with_extra_route), if any.
+  pub(crate) fn gen_extra_routes(
+    &self,
+    mod_name: &str,
+    base_path: Option<&str>,
+    to_lambda_response: &TokenStream,
+  ) -> TokenStream {
+    let Some(api_lambda) = self
+      .api_lambdas
+      .get(mod_name)
+      .filter(|api_lambda| !api_lambda.extra_routes.is_empty())
+    else {
+      return quote! {};
+    };
+
+    let event_source = self.event_source(mod_name);
+    let http_method = event_source.http_method_expr();
+    let request_path = event_source.routing_path_expr(base_path);
+
+    let extra_route_branches = api_lambda
+      .extra_routes
+      .iter()
+      .map(|route| {
+        let method = route.method.as_str();
+        let path = &route.path;
+        let handler = syn::parse_str::<syn::Expr>(&route.handler)
+          .expect("extra route handlers should have been validated");
+        quote! {
+          if http_method == #method && request_path == #path {
+            log::info!("Responding to extra route {} {}", #method, #path);
+            return #to_lambda_response(routing::respond_to_extra_route(#handler));
+          }
+        }
+      })
+      .collect::<TokenStream>();
+
+    quote! {
+      let (http_method, request_path) = (#http_method, #request_path);
+      #extra_route_branches
+    }
+  }
+}
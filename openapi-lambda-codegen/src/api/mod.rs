@@ -1,5 +1,6 @@
 use crate::api::operation::{ApiOperation, PathOperation};
-use crate::CodeGenerator;
+use crate::model::GeneratedModels;
+use crate::{CodeGenerator, CodegenError, EventSource, CALLBACKS_MOD_NAME};
 
 use convert_case::{Case, Casing};
 use indexmap::IndexMap;
@@ -15,9 +16,25 @@ use unzip_n::unzip_n;
 use std::collections::HashMap;
 
 pub mod body;
+mod client;
+mod cors;
+mod extra_route;
 pub mod operation;
 
-unzip_n!(6);
+unzip_n!(8);
+
+/// Security scheme extension that declares the schema of the claims (or context) produced by the
+/// API Gateway authorizer for the scheme.
+pub(crate) const AUTHORIZER_CLAIMS_EXTENSION: &str = "x-authorizer-claims";
+
+/// Generated code for a single API module.
+pub(crate) struct ApiModule {
+  /// Definition of the module (included in `out.rs`).
+  pub module: TokenStream,
+
+  /// Formatted source code of the `<MODULE_NAME>_handler.rs` placeholder handler.
+  pub handler: String,
+}
 
 /// Generated operations for a single API module.
 struct ApiModuleOperations {
@@ -36,6 +53,11 @@ struct ApiModuleOperations {
   /// These functions call the corresponding handler implemented by the user.
   handler_wrappers: TokenStream,
 
+  request_headers_idents: Vec<Option<Ident>>,
+
+  /// Definitions for operation typed request headers structs.
+  request_headers_structs: TokenStream,
+
   /// Definitions for operation response type enums.
   response_type_enums: TokenStream,
 
@@ -49,6 +71,8 @@ impl FromIterator<ApiOperation> for ApiModuleOperations {
       handler_impls,
       handler_prototypes,
       handler_wrappers,
+      request_headers_idents,
+      request_headers_structs,
       response_type_enums,
       response_type_idents,
     ) = iter
@@ -59,6 +83,8 @@ impl FromIterator<ApiOperation> for ApiModuleOperations {
            handler_impl,
            handler_prototype,
            handler_wrapper,
+           request_headers_ident,
+           request_headers_struct,
            response_type_enum,
            response_type_ident,
          }| {
@@ -67,6 +93,8 @@ impl FromIterator<ApiOperation> for ApiModuleOperations {
             handler_impl.to_string(),
             handler_prototype,
             handler_wrapper,
+            request_headers_ident,
+            request_headers_struct,
             response_type_enum,
             response_type_ident,
           )
@@ -79,15 +107,21 @@ impl FromIterator<ApiOperation> for ApiModuleOperations {
       handler_impls,
       handler_prototypes,
       handler_wrappers,
+      request_headers_idents,
+      request_headers_structs,
       response_type_enums,
       response_type_idents,
     }
   }
 }
 
-fn is_array_param(parameter_data: &ParameterData) -> bool {
+pub(crate) fn is_array_param(parameter_data: &ParameterData) -> bool {
+  is_array_format(&parameter_data.format)
+}
+
+pub(crate) fn is_array_format(format: &ParameterSchemaOrContent) -> bool {
   matches!(
-    parameter_data.format,
+    format,
     ParameterSchemaOrContent::Schema(ReferenceOr::Item(Schema {
       schema_kind: SchemaKind::Type(Type::Array(_)),
       ..
@@ -110,7 +144,114 @@ fn is_plain_string_schema(schema: &Schema) -> bool {
   )
 }
 
+/// Generates the `OPERATIONS` constant and `find_operation()` function, which expose the operations
+/// handled by an API module at runtime.
+fn gen_operation_registry(operations: &[&PathOperation]) -> TokenStream {
+  let operation_infos = operations
+    .iter()
+    .map(|op| {
+      (
+        op.op
+          .operation_id
+          .as_ref()
+          .expect("operation_id should have been checked")
+          .as_str(),
+        op.method.as_str(),
+        op.request_path.as_str(),
+      )
+    })
+    .sorted()
+    .map(|(operation_id, method, path)| {
+      quote! {
+        OperationInfo {
+          operation_id: #operation_id,
+          method: #method,
+          path: #path,
+        }
+      }
+    });
+
+  quote! {
+    /// Operations handled by this API module, sorted by operation ID.
+    pub const OPERATIONS: &[OperationInfo] = &[#(#operation_infos),*];
+
+    /// Look up an operation handled by this API module by its operation ID.
+    pub fn find_operation(operation_id: &str) -> Option<&'static OperationInfo> {
+      OPERATIONS
+        .iter()
+        .find(|operation| operation.operation_id == operation_id)
+    }
+  }
+}
+
 impl CodeGenerator {
+  /// Generates a `<SCHEME>_claims()` function for each security scheme that declares an
+  /// `x-authorizer-claims` extension, which extracts the typed authorizer claims from the request
+  /// context.
+  fn gen_authorizer_claims_fns(
+    &self,
+    event_source: EventSource,
+    openapi_inline: &serde_yaml::Mapping,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    generated_models: &HashMap<Ident, TokenStream>,
+  ) -> TokenStream {
+    // Load balancers don't support authorizers.
+    if event_source == EventSource::ApplicationLoadBalancer {
+      return TokenStream::new();
+    }
+
+    let Some(security_schemes) = openapi_inline
+      .get("components")
+      .and_then(|components| components.get("securitySchemes"))
+      .and_then(serde_yaml::Value::as_mapping)
+    else {
+      return TokenStream::new();
+    };
+
+    let request_context_type = event_source.request_context_type();
+    security_schemes
+      .iter()
+      .filter_map(|(scheme_name, security_scheme)| {
+        Some((
+          scheme_name.as_str()?,
+          security_scheme.get(AUTHORIZER_CLAIMS_EXTENSION)?,
+        ))
+      })
+      .sorted_by_key(|(scheme_name, _)| *scheme_name)
+      .map(|(scheme_name, claims_schema)| {
+        let claims_schema = serde_yaml::from_value::<ReferenceOr<Schema>>(claims_schema.clone())
+          .unwrap_or_else(|err| {
+            panic!(
+              "invalid `{AUTHORIZER_CLAIMS_EXTENSION}` schema for security scheme \
+                 `{scheme_name}`: {err}"
+            )
+          });
+        let (claims_type, _) = self.inline_ref_or_schema(
+          &claims_schema,
+          components_schemas,
+          GeneratedModels::Done(generated_models),
+        );
+
+        let fn_ident = self.identifier(&format!("{}_claims", scheme_name.to_case(Case::Snake)));
+        let doc = format!(
+          "Extract the authorizer claims for the `{scheme_name}` security scheme from the request \
+           context."
+        );
+        quote! {
+          #[doc = #doc]
+          ///
+          /// Returns [`EventError::InvalidAuthorizerContext`] if the claims don't match the
+          /// declared schema.
+          pub fn #fn_ident(
+            request_context: &#request_context_type,
+          ) -> Result<#claims_type, EventError> {
+            request_context.authorizer_claims()
+          }
+        }
+      })
+      .collect()
+  }
+
   pub(crate) fn gen_api_module(
     &self,
     mod_name: &str,
@@ -118,12 +259,22 @@ impl CodeGenerator {
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> TokenStream {
+  ) -> Result<ApiModule, CodegenError> {
+    // API Gateway doesn't invoke the Lambda function for operations with a preserved integration.
+    let operations = operations
+      .iter()
+      .copied()
+      .filter(|operation| !self.is_integration_preserved(&operation.op))
+      .collect::<Vec<_>>();
+    let operations = operations.as_slice();
+
     let ApiModuleOperations {
       api_dispatcher_cases,
       handler_impls,
       handler_prototypes,
       handler_wrappers,
+      request_headers_idents,
+      request_headers_structs,
       response_type_enums,
       response_type_idents,
     } = operations
@@ -139,41 +290,136 @@ impl CodeGenerator {
           generated_models,
         )
       })
-      .collect();
+      .collect::<Result<_, _>>()?;
 
-    self.gen_api_handler(mod_name, &handler_impls, &response_type_idents);
+    let api_mod_types = request_headers_idents
+      .into_iter()
+      .flatten()
+      .chain(response_type_idents)
+      .collect::<Vec<_>>();
+    let handler = self.gen_api_handler(mod_name, &handler_impls, &api_mod_types)?;
+
+    let event_source = self.event_source(mod_name);
+    let request_type = event_source.request_type();
+    let request_context_type = event_source.request_context_type();
+    let (response_type, response_conversion_fn) = if self.response_streaming(mod_name) {
+      (
+        quote! { StreamingResponse },
+        quote! { http_response_to_stream },
+      )
+    } else {
+      (
+        event_source.response_type(),
+        event_source.response_conversion_fn(),
+      )
+    };
+    let (to_lambda_response, to_lambda_response_fn) = match self.gen_cors_to_lambda_response_fn(
+      mod_name,
+      &response_type,
+      &response_conversion_fn,
+    ) {
+      Some(to_lambda_response_fn) => (quote! { to_lambda_response }, to_lambda_response_fn),
+      None => (response_conversion_fn, quote! {}),
+    };
+    let base_path = self.base_path(mod_name, openapi_inline);
+    let cors_preflight = self.gen_cors_preflight(
+      mod_name,
+      operations,
+      base_path.as_deref(),
+      &to_lambda_response,
+    );
+    let extra_routes = self.gen_extra_routes(mod_name, base_path.as_deref(), &to_lambda_response);
+    let operation_id_from_request = self.gen_operation_id_from_request(
+      mod_name,
+      event_source,
+      operations,
+      base_path.as_deref(),
+      &to_lambda_response,
+    );
+    let operation_registry = gen_operation_registry(operations);
+    let authorizer_claims_fns = self.gen_authorizer_claims_fns(
+      event_source,
+      openapi_inline,
+      components_schemas,
+      generated_models,
+    );
 
     let mod_name_ident = Ident::new(mod_name, Span::call_site());
 
     let crate_import = self.crate_use_name();
-    quote! {
+    let (negotiate_content_coding, compress_response) = if self.response_compression(mod_name) {
+      (
+        quote! {
+          // Negotiated up front since the request is moved into the handler wrapper.
+          let content_coding =
+            #crate_import::__private::compression::negotiate_content_coding(&request.headers);
+        },
+        quote! {
+          let http_response =
+            #crate_import::__private::compression::compress_response(http_response, content_coding);
+        },
+      )
+    } else {
+      (quote! {}, quote! {})
+    };
+    // Response types can be decoded from HTTP responses when the `client` feature is enabled.
+    let client_error_import = if self.client_mod_name.is_some() {
+      quote! { use #crate_import::ClientError; }
+    } else {
+      quote! {}
+    };
+    let datetime_crate = self.datetime_backend.crate_ident();
+    let to_json_fn = self.json_format.gen_to_json_fn(&crate_import);
+    let module = quote! {
       pub mod #mod_name_ident {
         #![allow(clippy::too_many_arguments)]
         #![allow(unused_imports)]
 
         use #crate_import::{
-          ApiGatewayProxyRequestContext, EventError, HeaderMap, HeaderName, http_response_to_apigw,
-          HttpResponse, LambdaContext, LambdaEvent, Middleware, Response, StatusCode,
+          AlbTargetGroupRequestContext, ApiGatewayProxyRequestContext,
+          ApiGatewayV2httpRequestContext, AuthorizerContext, ErrorContentType, EventError, HeaderMap, HeaderName,
+          http_response_to_alb, http_response_to_apigw, http_response_to_apigw_v2,
+          http_response_to_stream, HttpResponse, LambdaContext, LambdaEvent, Middleware,
+          OperationInfo, Response, ResponseStream, SecurityRequirement, SecuritySchemeRequirement,
+          StatusCode, StreamingResponse,
         };
+        #client_error_import
         use #crate_import::async_trait::async_trait;
+        use #crate_import::models::#datetime_crate;
         use #crate_import::__private::{
           log, panic_string, serde_json, serde_path_to_error, urlencoding,
         };
+        use #crate_import::__private::aws_lambda_events::alb::{
+          AlbTargetGroupRequest,
+          AlbTargetGroupResponse,
+        };
         use #crate_import::__private::aws_lambda_events::apigw::{
           ApiGatewayProxyRequest,
           ApiGatewayProxyResponse,
+          ApiGatewayV2httpRequest,
+          ApiGatewayV2httpResponse,
         };
         use #crate_import::__private::aws_lambda_events::encodings::Body;
+        use #crate_import::__private::{alb, apigw, routing};
         use #crate_import::__private::backtrace::Backtrace;
         use #crate_import::__private::base64::{self, Engine as _};
-        use #crate_import::__private::encoding::to_json;
+        use #crate_import::__private::cors;
+        #to_json_fn
         use #crate_import::__private::futures::FutureExt;
         use #crate_import::__private::headers::{ContentType, Header};
         use #crate_import::__private::mime::Mime;
+        use #crate_import::__private::multipart;
+        use #crate_import::__private::streaming::streaming_http_response;
         use #crate_import::error::format_error;
 
+        #request_headers_structs
+
         #response_type_enums
 
+        #operation_registry
+
+        #authorizer_claims_fns
+
         /// API Handler
         ///
         /// **This is an `#[async_trait]`.**
@@ -181,134 +427,337 @@ impl CodeGenerator {
         pub trait Api: Sized {
           /// User-defined authenticated identity type.
           ///
-          /// This type is returned when
-          /// [`Middleware::authenticate`](openapi_lambda::Middleware::authenticate) successfully
-          /// authenticates a request then passed as an argument to the request handler method of
-          /// this trait.
+          /// This type is returned when [`Middleware::authenticate`] successfully authenticates a
+          /// request then passed as an argument to the request handler method of this trait.
           ///
-          /// Note that [`Middleware::authenticate`](openapi_lambda::Middleware::authenticate) is
-          /// not invoked for unauthenticated endpoints (i.e., those with
+          /// Note that [`Middleware::authenticate`] is not invoked for unauthenticated endpoints
+          /// (i.e., those with
           /// [`security: [{}]`](https://swagger.io/specification/#operation-object)),
           /// and no `AuthOk` value is passed as an argument to the corresponding request
-          /// handler methods.
+          /// handler methods. Endpoints that also list other security requirements (e.g.,
+          /// `security: [{}, {bearerAuth: []}]`) accept optional authentication, and their request
+          /// handler methods receive an `Option<AuthOk>` that is `None` if authentication fails.
           type AuthOk: Send;
 
           /// User-defined error type (typically an `enum`).
           type HandlerError: Send;
 
-          async fn respond_to_event_error(&self, err: EventError) -> HttpResponse {
+          /// Respond to an error that occurred while processing a request (e.g., an invalid
+          /// request body).
+          ///
+          /// The `content_type` argument is negotiated based on the request's `Accept` header (see
+          /// [`ErrorContentType::negotiate`]). By default, logs the error and returns a response
+          /// of that content type (see [`EventError::to_response`]). Override this method to
+          /// customize error responses (e.g., to always return JSON error bodies via
+          /// [`EventError::to_json_response`]).
+          async fn respond_to_event_error(
+            &self,
+            err: EventError,
+            content_type: ErrorContentType,
+          ) -> HttpResponse {
             log::error!(
               "{}",
               format_error(&err, Some(&format!("EventError::{}", err.name())), err.backtrace()),
             );
 
-            err.into()
+            err.to_response(content_type)
           }
 
           async fn respond_to_handler_error(&self, err: Self::HandlerError) -> HttpResponse;
 
+          /// Respond to a request that doesn't match any operation handled by this API.
+          ///
+          /// Returns an empty `404 Not Found` response by default.
+          async fn respond_to_unknown_operation(&self) -> HttpResponse {
+            Response::builder()
+              .status(StatusCode::NOT_FOUND)
+              .body(Body::Empty)
+              .expect("failed to build response")
+          }
+
           #handler_prototypes
 
           async fn dispatch_request<M>(
             &self,
-            event: LambdaEvent<ApiGatewayProxyRequest>,
+            event: LambdaEvent<#request_type>,
             middleware: &M,
-          ) -> ApiGatewayProxyResponse
+          ) -> #response_type
           where
-            M: Middleware<AuthOk = <Self as Api>::AuthOk> + Sync
+            M: Middleware<#request_context_type, AuthOk = <Self as Api>::AuthOk> + Sync
           {
-            match std::panic::AssertUnwindSafe(
-              dispatch_request_impl(self, event.payload, event.context, middleware)
-            )
-            .catch_unwind()
-            .await {
-              Ok(response) => response,
-              Err(panic) => {
-                http_response_to_apigw(
-                  self.respond_to_event_error(
-                    EventError::Panic(
-                      // If the panic value isn't a String or &str, don't catch it since we can't
-                      // print it and it's unclear what we should do instead.
-                      panic_string(panic).unwrap_or_else(|err| std::panic::resume_unwind(err)),
-                      // Unfortunately, the panic doesn't give us a stack trace unless we set a
-                      // panic hook, which might interfere with the user's own error handling.
-                      // Instead, we just capture a backtrace indicating where we caught the
-                      // panic, for now.
-                      Backtrace::new(),
+            // Covers the whole request (including panics) when the `tracing` feature is enabled.
+            let span = log::RequestSpan::new(&event.context.request_id);
+            // Negotiated up front since the request is moved into `dispatch_request_impl`.
+            let error_content_type = ErrorContentType::negotiate(&event.payload.headers);
+            span.instrument(async move {
+              match std::panic::AssertUnwindSafe(
+                dispatch_request_impl(self, event.payload, event.context, middleware)
+              )
+              .catch_unwind()
+              .await {
+                Ok(response) => response,
+                Err(panic) => {
+                  #to_lambda_response(
+                    self.respond_to_event_error(
+                      EventError::Panic(
+                        // If the panic value isn't a String or &str, don't catch it since we can't
+                        // print it and it's unclear what we should do instead.
+                        panic_string(panic).unwrap_or_else(|err| std::panic::resume_unwind(err)),
+                        // Unfortunately, the panic doesn't give us a stack trace unless we set a
+                        // panic hook, which might interfere with the user's own error handling.
+                        // Instead, we just capture a backtrace indicating where we caught the
+                        // panic, for now.
+                        Backtrace::new(),
+                      ),
+                      error_content_type,
                     )
+                    .await
                   )
-                  .await
-                )
+                }
               }
-            }
+            })
+            .await
           }
         }
 
         #handler_wrappers
 
+        #to_lambda_response_fn
+
         async fn dispatch_request_impl<A, M>(
           api: &A,
-          request: ApiGatewayProxyRequest,
+          request: #request_type,
           lambda_context: LambdaContext,
           middleware: &M,
-        ) -> ApiGatewayProxyResponse
+        ) -> #response_type
         where
-          A: Api<AuthOk = <M as Middleware>::AuthOk> + Sync,
-          M: Middleware + Sync,
+          A: Api<AuthOk = <M as Middleware<#request_context_type>>::AuthOk> + Sync,
+          M: Middleware<#request_context_type> + Sync,
         {
           log::trace!("Request: {request:#?}");
           log::trace!("Lambda context: {lambda_context:#?}");
 
-          let operation_id = if let Some(ref operation_id) = request.request_context.operation_name {
-            operation_id
-          } else {
-            return http_response_to_apigw(
-              api
-                .respond_to_event_error(EventError::UnexpectedOperationId(
-                  "no operation_name provided in ApiGatewayProxyRequest".into(),
-                  Backtrace::new(),
-                ))
-                .await
-            );
-          };
+          #negotiate_content_coding
+
+          #cors_preflight
+
+          #extra_routes
 
-          let http_response = match operation_id.as_str() {
+          #operation_id_from_request
+          log::record_operation_id(operation_id);
+
+          let http_response = match operation_id {
             #api_dispatcher_cases
             _ => {
-              api
-                .respond_to_event_error(
-                  EventError::UnexpectedOperationId(operation_id.to_string(), Backtrace::new())
-                )
-                .await
+              log::warn!("unexpected operation ID: {operation_id}");
+              api.respond_to_unknown_operation().await
             }
           };
+          #compress_response
+
+          #to_lambda_response(http_response)
+        }
+      }
+    };
 
-          http_response_to_apigw(http_response)
+    Ok(ApiModule { module, handler })
+  }
+
+  /// Generates the statements that bind `operation_id` to the operation ID of the current request.
+  fn gen_operation_id_from_request(
+    &self,
+    mod_name: &str,
+    event_source: EventSource,
+    operations: &[&PathOperation],
+    base_path: Option<&str>,
+    to_lambda_response: &TokenStream,
+  ) -> TokenStream {
+    let routing_path = event_source.routing_path_expr(base_path);
+
+    let routes = operations
+      .iter()
+      .map(|op| {
+        (
+          op.method.as_str(),
+          op.request_path.as_str(),
+          op.op
+            .operation_id
+            .as_ref()
+            .expect("operation_id should have been checked")
+            .as_str(),
+        )
+      })
+      .sorted()
+      .map(|(method, request_path, operation_id)| {
+        quote! { (#method, #request_path, #operation_id) }
+      })
+      .collect::<Vec<_>>();
+
+    match event_source {
+      // Callback operations don't have request paths, so they can only be identified by their
+      // operation name.
+      EventSource::ApiGatewayProxy if mod_name == CALLBACKS_MOD_NAME => quote! {
+        let operation_id = if let Some(ref operation_id) = request.request_context.operation_name {
+          operation_id.as_str()
+        } else {
+          return #to_lambda_response(
+            api
+              .respond_to_event_error(
+                EventError::UnexpectedOperationId(
+                  "no operation_name provided in ApiGatewayProxyRequest".into(),
+                  Backtrace::new(),
+                ),
+                ErrorContentType::negotiate(&request.headers),
+              )
+              .await
+          );
+        };
+      },
+      // API Gateway only provides the operation name if the integration specifies one, so we fall
+      // back to matching the HTTP method and resource (or path) of the request against each
+      // operation.
+      EventSource::ApiGatewayProxy => {
+        quote! {
+          let mut request = request;
+          let operation_id = match request.request_context.operation_name.as_deref() {
+            Some(operation_id) => operation_id,
+            None => match routing::match_resource(
+              &[#(#routes),*],
+              request.http_method.as_str(),
+              request.resource.as_deref(),
+              #routing_path,
+            ) {
+              Some((operation_id, path_parameters)) => {
+                if let Some(path_parameters) = path_parameters {
+                  request.path_parameters = path_parameters;
+                }
+                operation_id
+              }
+              None => {
+                log::warn!(
+                  "no operation_name provided in ApiGatewayProxyRequest, and no operation \
+                   found for {} {}",
+                  request.http_method,
+                  request.path.as_deref().unwrap_or_default(),
+                );
+                return #to_lambda_response(api.respond_to_unknown_operation().await);
+              }
+            },
+          };
+        }
+      }
+      // HTTP API events don't include the operation ID, so we map each route key (e.g.,
+      // `GET /foo/{foo_id}`) to the corresponding operation.
+      EventSource::ApiGatewayV2Http => {
+        let route_key_cases = operations
+          .iter()
+          .map(|op| {
+            (
+              format!("{} {}", op.method, op.request_path),
+              op.op
+                .operation_id
+                .as_ref()
+                .expect("operation_id should have been checked"),
+            )
+          })
+          .sorted()
+          .map(|(route_key, operation_id)| quote! { Some(#route_key) => #operation_id, })
+          .collect::<TokenStream>();
+
+        quote! {
+          // Payload format version 2.0 moves the `Cookie` request header into a separate field.
+          let mut request = request;
+          apigw::restore_v2_cookie_header(&mut request);
+
+          let operation_id = match request.route_key.as_deref() {
+            #route_key_cases
+            // Lambda function URLs (and `$default` HTTP API routes) don't perform any routing, so
+            // we match the HTTP method and path of the request against each operation instead.
+            Some("$default") => match routing::match_route(
+              &[#(#routes),*],
+              request.request_context.http.method.as_str(),
+              #routing_path,
+            ) {
+              Some((operation_id, path_parameters)) => {
+                request.path_parameters = path_parameters;
+                operation_id
+              }
+              None => {
+                log::warn!(
+                  "no operation found for {} {}",
+                  request.request_context.http.method,
+                  request.raw_path.as_deref().unwrap_or_default(),
+                );
+                return #to_lambda_response(api.respond_to_unknown_operation().await);
+              }
+            },
+            route_key => {
+              log::warn!("no operation found for route key {route_key:?}");
+              return #to_lambda_response(api.respond_to_unknown_operation().await);
+            }
+          };
+        }
+      }
+      // Load balancer events don't identify the operation either, so we match the HTTP method
+      // and path of each request against the path templates of each operation.
+      EventSource::ApplicationLoadBalancer => {
+        quote! {
+          let mut request = request;
+          alb::normalize_request(&mut request);
+
+          let (operation_id, path_parameters) = match routing::match_route(
+            &[#(#routes),*],
+            request.http_method.as_str(),
+            #routing_path,
+          ) {
+            Some(route) => route,
+            None => {
+              log::warn!(
+                "no operation found for {} {}",
+                request.http_method,
+                request.path.as_deref().unwrap_or_default(),
+              );
+              return #to_lambda_response(api.respond_to_unknown_operation().await);
+            }
+          };
         }
       }
     }
   }
 
+  /// Generates the formatted source code of the `<MODULE_NAME>_handler.rs` placeholder handler.
   pub(crate) fn gen_api_handler(
     &self,
     mod_name: &str,
     handler_impls: &[String],
-    response_types: &[Ident],
-  ) {
-    let crate_import = self.crate_use_name();
+    api_mod_types: &[Ident],
+  ) -> Result<String, CodegenError> {
+    let crate_import = &self.crate_name;
     let mod_name_pascal = format!("{}ApiHandler", mod_name.to_case(Case::Pascal));
 
-    let api_mod_imports = response_types.iter().join(", ");
+    let api_mod_imports = api_mod_types.iter().join(", ");
 
     let handler_impls_str = handler_impls.join("\n\n");
 
+    if let Some(handler_template) = self.handler_template(mod_name) {
+      let handler = handler_template.render(&[
+        ("mod_name", mod_name),
+        ("mod_name_pascal", &mod_name_pascal),
+        ("api_mod_imports", &api_mod_imports),
+        ("handler_impls", &handler_impls_str),
+      ])?;
+      return self.format_rust_source(&format!("{mod_name}_handler.rs"), &handler);
+    }
+
+    let request_context_type = self.event_source(mod_name).request_context_type();
+
     let handler = format!(
       r#"#![allow(unused_imports)]
 
       use crate::{mod_name}::{{Api, {api_mod_imports}}};
 
       use {crate_import}::{{
-        ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, StatusCode,
+        {request_context_type}, HeaderMap, HttpResponse, LambdaContext, OperationInfo, StatusCode,
       }};
       use {crate_import}::async_trait::async_trait;
       use {crate_import}::__private::anyhow;
@@ -345,15 +794,6 @@ impl CodeGenerator {
       "#
     );
 
-    let handler_path = self.out_dir.join(format!("{mod_name}_handler.rs"));
-    log::info!("Writing `{mod_name}` handler to {}", handler_path.display());
-    std::fs::write(&handler_path, handler.as_bytes()).unwrap_or_else(|err| {
-      panic!(
-        "failed to write {mod_name} handler to `{}`: {err}",
-        handler_path.display()
-      )
-    });
-
-    self.rustfmt(&handler_path);
+    self.format_rust_source(&format!("{mod_name}_handler.rs"), &handler)
   }
 }
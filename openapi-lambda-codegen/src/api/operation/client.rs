@@ -0,0 +1,234 @@
+use crate::api::operation::parameter::RequestParameter;
+use crate::api::operation::response::{gen_from_http_fn, OperationResponses};
+use crate::api::operation::PathOperation;
+use crate::{description_to_doc_attr, CodeGenerator, CodegenError, EventSource};
+
+use convert_case::{Case, Casing};
+use indexmap::IndexMap;
+use itertools::Itertools;
+use openapiv3::{ReferenceOr, Schema};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+use std::collections::HashMap;
+
+/// A generated client method for a single API operation (e.g., `GET /foo`).
+pub struct ClientOperation {
+  /// Definition for the client method that sends a request to the operation.
+  pub method: TokenStream,
+
+  /// Definition for the operation response type enum with one variant for each HTTP status code,
+  /// along with the error response type enum (if the operation declares any error responses).
+  pub response_type_enum: TokenStream,
+}
+
+impl CodeGenerator {
+  pub(crate) fn gen_client_operation(
+    &self,
+    mod_name: &str,
+    operation: &PathOperation,
+    openapi_inline: &serde_yaml::Mapping,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    generated_models: &HashMap<Ident, TokenStream>,
+  ) -> Result<ClientOperation, CodegenError> {
+    let PathOperation {
+      method,
+      request_path,
+      op,
+    } = operation;
+
+    let operation_id = op
+      .operation_id
+      .as_ref()
+      .expect("operation_id should have been checked");
+
+    // The event source and maximum body size only affect how the handler wrapper parses
+    // parameters, which the client doesn't use.
+    let (client_path_values, client_serializations, param_doc_attrs, param_signatures) = self
+      .gen_request_parameters(
+        operation,
+        EventSource::default(),
+        None,
+        openapi_inline,
+        components_schemas,
+        generated_models,
+      )?
+      .into_iter()
+      .map(
+        |RequestParameter {
+           client_path_value,
+           client_serialization,
+           doc_attr,
+           signature,
+           ..
+         }| (client_path_value, client_serialization, doc_attr, signature),
+      )
+      .multiunzip::<(Vec<_>, TokenStream, TokenStream, TokenStream)>();
+    let client_path_values = client_path_values
+      .into_iter()
+      .flatten()
+      .collect::<HashMap<_, _>>();
+
+    let func_name_snake = operation_id.to_case(Case::Snake);
+    let func_name_ident = self.identifier(&func_name_snake);
+    let response_type_ident =
+      self.identifier(&format!("{}Response", operation_id.to_case(Case::Pascal)));
+    let error_type_ident = self.identifier(&format!(
+      "{}ErrorResponse",
+      operation_id.to_case(Case::Pascal)
+    ));
+
+    let OperationResponses {
+      client_cases,
+      client_error_variants,
+      header_structs,
+      response_variants,
+      ..
+    } = self.gen_responses(
+      operation,
+      &response_type_ident,
+      Some(&error_type_ident),
+      openapi_inline,
+      components_schemas,
+      generated_models,
+    )?;
+
+    // Operations without any declared error responses use the default `ClientError` type.
+    let (error_type_enum, client_error_type_ident) = if client_error_variants.is_empty() {
+      (quote! {}, None)
+    } else {
+      (
+        quote! {
+          #[allow(clippy::large_enum_variant)]
+          #[derive(Clone, Debug)]
+          #[doc = concat!(
+            "Error response to [`Client::", #func_name_snake, "`](crate::", #mod_name,
+            "::Client::", #func_name_snake, ").",
+          )]
+          ///
+          /// Returned as a [`ClientError::ErrorResponse`] error.
+          pub enum #error_type_ident {
+            #client_error_variants
+          }
+        },
+        Some(&error_type_ident),
+      )
+    };
+    let client_error_type = match client_error_type_ident {
+      Some(error_type_ident) => quote! { ClientError<#error_type_ident> },
+      None => quote! { ClientError },
+    };
+
+    let from_http_fn = gen_from_http_fn(operation, client_cases, client_error_type_ident);
+
+    let response_type_enum = quote! {
+      #header_structs
+      #error_type_enum
+
+      #[allow(clippy::large_enum_variant)]
+      #[derive(Clone, Debug)]
+      #[doc = concat!(
+        "Response to [`Client::", #func_name_snake, "`](crate::", #mod_name, "::Client::",
+        #func_name_snake, ").",
+      )]
+      pub enum #response_type_ident {
+        #response_variants
+      }
+      impl #response_type_ident {
+        #from_http_fn
+      }
+    };
+
+    let (url_format, url_args) = client_url_format(request_path, |param_name| {
+      client_path_values
+        .get(param_name)
+        .cloned()
+        .unwrap_or_else(|| panic!("undeclared path parameter `{param_name}` in `{request_path}`"))
+    });
+
+    let description_doc_attr = op
+      .description
+      .as_ref()
+      .map(|description| {
+        let doc_attr = description_to_doc_attr(description);
+        quote! {
+          #doc_attr
+          ///
+        }
+      })
+      .unwrap_or_default();
+
+    let method_upper = method.as_str();
+    let method_ident = Ident::new(method_upper, Span::call_site());
+    let method = quote! {
+      #description_doc_attr
+      #[doc = concat!("Endpoint: `", #method_upper, " ", #request_path, "`")]
+      ///
+      #[doc = concat!("Operation ID: `", #operation_id, "`")]
+      ///
+      /// # Arguments
+      ///
+      #param_doc_attrs
+      /// * `headers` - Additional HTTP request headers
+      pub async fn #func_name_ident(
+        &self,
+        #param_signatures
+        headers: HeaderMap,
+      ) -> Result<#response_type_ident, #client_error_type> {
+        let url = format!(
+          #url_format,
+          self.base_url.trim_end_matches('/'),
+          #(#url_args),*
+        );
+        let request = self.http_client.request(Method::#method_ident, url).headers(headers);
+        #client_serializations
+
+        let response = request.send().await.map_err(ClientError::Request)?;
+        let status_code = StatusCode::from_u16(response.status().as_u16())
+          .expect("reqwest should only return valid HTTP status codes");
+        let content_type = response
+          .headers()
+          .get(CONTENT_TYPE)
+          .and_then(|content_type| content_type.to_str().ok())
+          .map(String::from);
+        let response_headers = response.headers().clone();
+        let body = response.bytes().await.map_err(ClientError::Request)?.to_vec();
+
+        #response_type_ident::from_http(status_code, content_type, &response_headers, body)
+      }
+    };
+
+    Ok(ClientOperation {
+      method,
+      response_type_enum,
+    })
+  }
+}
+
+/// Returns a `format!` string for the URL of an operation (following a `{}` placeholder for the
+/// base URL) and the expressions for the path parameters to substitute into it, in order.
+///
+/// `param_value` returns the expression for the value of each path parameter (see
+/// [`RequestParameter::client_path_value`]).
+fn client_url_format<F>(request_path: &str, param_value: F) -> (String, Vec<TokenStream>)
+where
+  F: Fn(&str) -> TokenStream,
+{
+  let mut url_format = "{}".to_string();
+  let mut url_args = Vec::new();
+  let mut rest = request_path;
+  while let Some((prefix, param_and_rest)) = rest.split_once('{') {
+    let (param_name, suffix) = param_and_rest
+      .split_once('}')
+      .unwrap_or_else(|| panic!("unterminated path parameter in `{request_path}`"));
+    url_format.push_str(prefix);
+    url_format.push_str("{}");
+    // Greedy path parameters are named without the trailing `+`.
+    url_args.push(param_value(
+      param_name.strip_suffix('+').unwrap_or(param_name),
+    ));
+    rest = suffix;
+  }
+  url_format.push_str(rest);
+  (url_format, url_args)
+}
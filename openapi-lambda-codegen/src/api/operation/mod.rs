@@ -1,12 +1,15 @@
-use crate::api::operation::parameter::RequestParameter;
+use crate::api::operation::parameter::{RequestHeaderField, RequestParameter};
 use crate::inline::InlineApi;
 use crate::reference::resolve_local_reference;
-use crate::{description_to_doc_attr, CodeGenerator};
+use crate::{description_to_doc_attr, CodeGenerator, CodegenError, EventSource, ValidationIssue};
 
 use convert_case::{Case, Casing};
 use http::Method;
 use indexmap::IndexMap;
-use openapiv3::{Operation, PathItem, ReferenceOr, Schema};
+use itertools::Itertools;
+use openapiv3::{
+  Callback, Operation, Parameter, PathItem, ReferenceOr, Schema, SecurityRequirement,
+};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use unzip_n::unzip_n;
@@ -14,6 +17,7 @@ use unzip_n::unzip_n;
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+pub mod client;
 mod parameter;
 mod request_body;
 mod response;
@@ -32,57 +36,165 @@ pub(crate) struct PathOperation {
 pub(crate) fn collect_operations(
   openapi: &InlineApi,
   openapi_inline_mapping: &serde_yaml::Mapping,
-) -> Vec<PathOperation> {
+) -> Result<Vec<PathOperation>, CodegenError> {
   openapi
     .paths
     .iter()
-    .flat_map(|(request_path, path_item_or_ref)| {
+    .map(|(request_path, path_item_or_ref)| {
       let path_item = match path_item_or_ref {
         ReferenceOr::Item(path_item) => Cow::Borrowed(path_item),
         ReferenceOr::Reference { reference } => {
-          Cow::Owned(resolve_local_reference::<PathItem>(reference, openapi_inline_mapping).target)
+          Cow::Owned(resolve_local_reference::<PathItem>(reference, openapi_inline_mapping)?.target)
         }
       };
+      path_item_operations(request_path, path_item, openapi_inline_mapping)
+    })
+    .flatten_ok()
+    .collect()
+}
 
-      match path_item {
-        Cow::Borrowed(item) => vec![
-          item.get.as_ref().map(|op| (Method::GET, op.to_owned())),
-          item.put.as_ref().map(|op| (Method::PUT, op.to_owned())),
-          item.post.as_ref().map(|op| (Method::POST, op.to_owned())),
-          item
-            .delete
-            .as_ref()
-            .map(|op| (Method::DELETE, op.to_owned())),
-          item
-            .options
-            .as_ref()
-            .map(|op| (Method::OPTIONS, op.to_owned())),
-          item.head.as_ref().map(|op| (Method::HEAD, op.to_owned())),
-          item.patch.as_ref().map(|op| (Method::PATCH, op.to_owned())),
-          item.trace.as_ref().map(|op| (Method::TRACE, op.to_owned())),
-        ],
-        Cow::Owned(item) => vec![
-          item.get.map(|op| (Method::GET, op)),
-          item.put.map(|op| (Method::PUT, op)),
-          item.post.map(|op| (Method::POST, op)),
-          item.delete.map(|op| (Method::DELETE, op)),
-          item.options.map(|op| (Method::OPTIONS, op)),
-          item.head.map(|op| (Method::HEAD, op)),
-          item.patch.map(|op| (Method::PATCH, op)),
-          item.trace.map(|op| (Method::TRACE, op)),
-        ],
-      }
-      .into_iter()
-      .flatten()
-      .map(move |(method, op)| PathOperation {
-        method,
-        op,
-        request_path: request_path.to_owned(),
-      })
+/// Collect all callback and webhook operations into a flattened `Vec`.
+///
+/// The `request_path` of each callback operation is its runtime expression (e.g.,
+/// `{$request.body#/callbackUrl}`), while that of each webhook operation is the webhook name.
+pub(crate) fn collect_callback_operations(
+  openapi: &InlineApi,
+  openapi_inline_mapping: &serde_yaml::Mapping,
+) -> Result<Vec<PathOperation>, CodegenError> {
+  let operation_callbacks = openapi
+    .paths
+    .iter()
+    .map(|(_, path_item_or_ref)| match path_item_or_ref {
+      ReferenceOr::Item(path_item) => Ok(Cow::Borrowed(path_item)),
+      ReferenceOr::Reference { reference } => Ok(Cow::Owned(
+        resolve_local_reference::<PathItem>(reference, openapi_inline_mapping)?.target,
+      )),
     })
+    .collect::<Result<Vec<_>, CodegenError>>()?
+    .into_iter()
+    .flat_map(|path_item| {
+      path_item
+        .iter()
+        .flat_map(|(_, op)| op.callbacks.values().cloned())
+        .collect::<Vec<_>>()
+    })
+    .map(Cow::Owned);
+  let components_callbacks = openapi
+    .components
+    .iter()
+    .flat_map(|components| components.callbacks.values())
+    .map(|callback_or_ref| match callback_or_ref {
+      ReferenceOr::Item(callback) => Ok(Cow::Borrowed(callback)),
+      ReferenceOr::Reference { reference } => Ok(Cow::Owned(
+        resolve_local_reference::<Callback>(reference, openapi_inline_mapping)?.target,
+      )),
+    })
+    .collect::<Result<Vec<_>, CodegenError>>()?;
+
+  operation_callbacks
+    .chain(components_callbacks)
+    .chain(std::iter::once(Cow::Borrowed(&openapi.webhooks)))
+    .flat_map(|callback| callback.into_owned())
+    .map(|(expression, path_item)| {
+      path_item_operations(&expression, Cow::Owned(path_item), openapi_inline_mapping)
+    })
+    .flatten_ok()
     .collect()
 }
 
+/// Returns the operations defined by a single path item.
+fn path_item_operations(
+  request_path: &str,
+  path_item: Cow<PathItem>,
+  openapi_inline_mapping: &serde_yaml::Mapping,
+) -> Result<Vec<PathOperation>, CodegenError> {
+  let path_item_parameters = path_item.parameters.clone();
+
+  match path_item {
+    Cow::Borrowed(item) => vec![
+      item.get.as_ref().map(|op| (Method::GET, op.to_owned())),
+      item.put.as_ref().map(|op| (Method::PUT, op.to_owned())),
+      item.post.as_ref().map(|op| (Method::POST, op.to_owned())),
+      item
+        .delete
+        .as_ref()
+        .map(|op| (Method::DELETE, op.to_owned())),
+      item
+        .options
+        .as_ref()
+        .map(|op| (Method::OPTIONS, op.to_owned())),
+      item.head.as_ref().map(|op| (Method::HEAD, op.to_owned())),
+      item.patch.as_ref().map(|op| (Method::PATCH, op.to_owned())),
+      item.trace.as_ref().map(|op| (Method::TRACE, op.to_owned())),
+    ],
+    Cow::Owned(item) => vec![
+      item.get.map(|op| (Method::GET, op)),
+      item.put.map(|op| (Method::PUT, op)),
+      item.post.map(|op| (Method::POST, op)),
+      item.delete.map(|op| (Method::DELETE, op)),
+      item.options.map(|op| (Method::OPTIONS, op)),
+      item.head.map(|op| (Method::HEAD, op)),
+      item.patch.map(|op| (Method::PATCH, op)),
+      item.trace.map(|op| (Method::TRACE, op)),
+    ],
+  }
+  .into_iter()
+  .flatten()
+  .map(|(method, mut op)| {
+    op.parameters = merge_parameters(
+      &path_item_parameters,
+      std::mem::take(&mut op.parameters),
+      openapi_inline_mapping,
+    )?;
+    Ok(PathOperation {
+      method,
+      op,
+      request_path: request_path.to_owned(),
+    })
+  })
+  .collect()
+}
+
+/// Merge the parameters shared by all operations in a path item with an operation's parameters.
+///
+/// Per the OpenAPI spec, an operation-level parameter overrides a path-level parameter with the
+/// same name and location.
+fn merge_parameters(
+  path_item_parameters: &[ReferenceOr<Parameter>],
+  op_parameters: Vec<ReferenceOr<Parameter>>,
+  openapi_inline_mapping: &serde_yaml::Mapping,
+) -> Result<Vec<ReferenceOr<Parameter>>, CodegenError> {
+  let parameter_key = |parameter: &ReferenceOr<Parameter>| {
+    let parameter = match parameter {
+      ReferenceOr::Item(parameter) => Cow::Borrowed(parameter),
+      ReferenceOr::Reference { reference } => {
+        Cow::Owned(resolve_local_reference::<Parameter>(reference, openapi_inline_mapping)?.target)
+      }
+    };
+    let location = match parameter.as_ref() {
+      Parameter::Query { .. } => "query",
+      Parameter::Header { .. } => "header",
+      Parameter::Path { .. } => "path",
+      Parameter::Cookie { .. } => "cookie",
+    };
+    Ok::<_, CodegenError>((parameter.parameter_data_ref().name.clone(), location))
+  };
+
+  let op_parameter_keys = op_parameters
+    .iter()
+    .map(parameter_key)
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let mut merged_parameters = Vec::new();
+  for parameter in path_item_parameters {
+    if !op_parameter_keys.contains(&parameter_key(parameter)?) {
+      merged_parameters.push(parameter.clone());
+    }
+  }
+  merged_parameters.extend(op_parameters);
+  Ok(merged_parameters)
+}
+
 /// A generated single API operation (e.g., `GET /foo`).
 pub struct ApiOperation {
   /// Match case for the API dispatcher from `operation_id` to the handler wrapper.
@@ -100,6 +212,13 @@ pub struct ApiOperation {
   /// This function calls the user's handler.
   pub handler_wrapper: TokenStream,
 
+  /// Definition for the operation's typed request headers struct, if any (see
+  /// [`CodeGenerator::with_request_header_structs`]).
+  pub request_headers_struct: TokenStream,
+
+  /// Identifier for the operation's typed request headers struct, if any.
+  pub request_headers_ident: Option<Ident>,
+
   /// Definition for operation response type enum with one variant for each HTTP status code.
   pub response_type_enum: TokenStream,
 
@@ -108,67 +227,144 @@ pub struct ApiOperation {
 }
 
 impl CodeGenerator {
-  pub(crate) fn gen_api_operation(
+  /// Generates the request parameters of an API operation, followed by the request body (if any).
+  pub(crate) fn gen_request_parameters(
     &self,
-    mod_name: &str,
     operation: &PathOperation,
+    event_source: EventSource,
+    max_body_bytes: Option<usize>,
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> ApiOperation {
+  ) -> Result<Vec<RequestParameter>, CodegenError> {
     let PathOperation {
-      method,
-      request_path,
-      op,
+      request_path, op, ..
     } = operation;
 
-    let operation_id = op
-      .operation_id
-      .as_ref()
-      .unwrap_or_else(|| panic!("no operation_id for {request_path}"));
-
     let request_body = op
       .request_body
       .as_ref()
       .map(|request_body| match request_body {
-        ReferenceOr::Item(request) => Cow::Borrowed(request),
-        ReferenceOr::Reference { reference } => {
-          Cow::Owned(resolve_local_reference(reference, openapi_inline).target)
-        }
-      });
-
-    let body_parameter = request_body.and_then(|request_body| {
-      self.gen_request_body(
-        request_path,
-        request_body.as_ref(),
-        openapi_inline,
-        components_schemas,
-        generated_models,
-      )
-    });
+        ReferenceOr::Item(request) => Ok(Cow::Borrowed(request)),
+        ReferenceOr::Reference { reference } => Ok(Cow::Owned(
+          resolve_local_reference(reference, openapi_inline)?.target,
+        )),
+      })
+      .transpose()?;
+
+    let body_parameter = request_body
+      .map(|request_body| {
+        self.gen_request_body(
+          request_path,
+          request_body.as_ref(),
+          max_body_bytes,
+          openapi_inline,
+          components_schemas,
+          generated_models,
+        )
+      })
+      .transpose()?
+      .flatten();
 
-    let (param_call_values, log_params, param_doc_attrs, param_signatures, param_parse_assignments) =
+    Ok(
       op.parameters
         .iter()
-        .map(|parameter| match parameter {
-          ReferenceOr::Reference { reference } => self.gen_request_parameter(
-            &resolve_local_reference(reference, openapi_inline).target,
-            components_schemas,
-            generated_models,
-          ),
-          ReferenceOr::Item(parameter) => {
-            self.gen_request_parameter(parameter, components_schemas, generated_models)
-          }
+        .map(|parameter| {
+          Ok(match parameter {
+            ReferenceOr::Reference { reference } => self.gen_request_parameter(
+              request_path,
+              &resolve_local_reference(reference, openapi_inline)?.target,
+              event_source,
+              components_schemas,
+              generated_models,
+            ),
+            ReferenceOr::Item(parameter) => self.gen_request_parameter(
+              request_path,
+              parameter,
+              event_source,
+              components_schemas,
+              generated_models,
+            ),
+          })
         })
+        .collect::<Result<Vec<_>, CodegenError>>()?
+        .into_iter()
         .chain(body_parameter)
+        .collect(),
+    )
+  }
+
+  pub(crate) fn gen_api_operation(
+    &self,
+    mod_name: &str,
+    operation: &PathOperation,
+    openapi_inline: &serde_yaml::Mapping,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    generated_models: &HashMap<Ident, TokenStream>,
+  ) -> Result<ApiOperation, CodegenError> {
+    let PathOperation {
+      method,
+      request_path,
+      op,
+    } = operation;
+
+    // Operations in the OpenAPI definition's `paths` were already checked by
+    // `check_operation_ids`, but callbacks and webhooks weren't.
+    let operation_id = op.operation_id.as_ref().ok_or_else(|| {
+      CodegenError::InvalidSpec(vec![ValidationIssue::new(
+        self.openapi_path.display().to_string(),
+        format!("{method} {request_path} has no operationId"),
+      )])
+    })?;
+
+    let event_source = self.event_source(mod_name);
+    let request_type = event_source.request_type();
+    let request_context_type = event_source.request_context_type();
+    let handler_wrapper_extra_params = event_source.handler_wrapper_extra_params();
+    let handler_wrapper_extra_args = event_source.handler_wrapper_extra_args();
+
+    let request_parameters = self.gen_request_parameters(
+      operation,
+      event_source,
+      self.max_body_bytes(mod_name),
+      openapi_inline,
+      components_schemas,
+      generated_models,
+    )?;
+    let request_headers = self
+      .request_header_structs
+      .then(|| self.gen_request_headers(operation_id, &request_parameters))
+      .flatten();
+
+    // When header parameters are grouped into a struct, the struct argument takes the place of the
+    // first header parameter, and the remaining header parameters are omitted.
+    let mut request_headers_arg = request_headers.as_ref().map(|request_headers| {
+      (
+        quote! { request_headers, },
+        request_headers.doc_attr.clone(),
+        request_headers.signature.clone(),
+      )
+    });
+    let (param_call_values, log_params, param_doc_attrs, param_signatures, param_parse_assignments) =
+      request_parameters
+        .into_iter()
         .map(
           |RequestParameter {
              call_value,
+             client_path_value: _,
+             client_serialization: _,
              doc_attr,
+             header_field,
              log_param,
              signature,
              wrapper_parse_assignment,
            }| {
+            let (call_value, doc_attr, signature) =
+              if header_field.is_some() && request_headers.is_some() {
+                request_headers_arg.take().unwrap_or_default()
+              } else {
+                (call_value, doc_attr, signature)
+              };
             (
               call_value,
               log_param,
@@ -179,6 +375,15 @@ impl CodeGenerator {
           },
         )
         .unzip_n::<TokenStream, TokenStream, TokenStream, TokenStream, TokenStream>();
+    let (request_headers_struct, request_headers_assignment, request_headers_ident) =
+      match request_headers {
+        Some(request_headers) => (
+          request_headers.definition,
+          request_headers.assignment,
+          Some(request_headers.struct_ident),
+        ),
+        None => (quote! {}, quote! {}, None),
+      };
 
     let func_name_snake = operation_id.to_case(Case::Snake);
     let func_name_ident = self.identifier(&func_name_snake);
@@ -195,51 +400,116 @@ impl CodeGenerator {
       openapi_inline,
       components_schemas,
       generated_models,
-    );
+    )?;
 
-    let is_unauthenticated = op
+    // An empty security requirement (`{}`) allows anonymous requests. If the operation also
+    // lists other security requirements, authentication is optional.
+    let allows_anonymous = op
       .security
       .as_ref()
       .map(|security| security.iter().any(|sec| sec.is_empty()))
       .unwrap_or(false);
-    let (maybe_authenticate, auth_ok_proto_arg, auth_ok_doc_attr, auth_ok_call_arg, wrapper) =
-      if is_unauthenticated {
-        (
-          quote! {
-            log::debug!("Request does not require authentication");
-          },
-          quote! {},
-          quote! {},
-          quote! {},
-          quote! { wrap_handler_unauthed },
-        )
-      } else {
-        (
-          quote! {
-            log::trace!("Authenticating request");
-            let auth_ok = match middleware.authenticate(
-              #operation_id,
-              &request.headers,
-              &request.request_context,
-              &lambda_context,
-            ).await {
-              Ok(auth_ok) => auth_ok,
-              Err(err) => return err,
-            };
-          },
-          quote! {
-            auth_ok: Self::AuthOk,
-          },
-          quote! {
-            /// * `auth_ok` - Output of [`Middleware::authenticate`] representing the authenticated
-            ///   user's identity
-          },
+    // Operations without their own `security` inherit the top-level security requirements.
+    let root_security = openapi_inline.get("security").map(|security| {
+      serde_yaml::from_value::<Vec<SecurityRequirement>>(security.clone())
+        .expect("top-level security requirements should be valid")
+    });
+    let security_requirements = op
+      .security
+      .as_ref()
+      .or(root_security.as_ref())
+      .into_iter()
+      .flatten()
+      .filter(|sec| !sec.is_empty())
+      .map(|sec| {
+        let schemes = sec.iter().map(|(scheme_name, scopes)| {
           quote! {
-            auth_ok,
-          },
-          quote! { wrap_handler_authed },
-        )
-      };
+            SecuritySchemeRequirement { name: #scheme_name, scopes: &[#(#scopes),*] }
+          }
+        });
+        quote! { SecurityRequirement { schemes: &[#(#schemes),*] } }
+      })
+      .collect::<Vec<_>>();
+
+    let authenticate = quote! {
+      middleware.authenticate_with_security(
+        #operation_id,
+        &[#(#security_requirements),*],
+        &request.headers,
+        &request.request_context,
+        &lambda_context,
+      ).await
+    };
+    let (
+      maybe_authenticate,
+      auth_ok_proto_arg,
+      auth_ok_doc_attr,
+      auth_ok_call_arg,
+      auth_ok_wrapper_arg,
+      wrapper,
+    ) = if allows_anonymous && security_requirements.is_empty() {
+      (
+        quote! {
+          log::debug!("Request does not require authentication");
+        },
+        quote! {},
+        quote! {},
+        quote! {},
+        quote! {},
+        quote! { wrap_handler_unauthed },
+      )
+    } else if allows_anonymous {
+      (
+        quote! {
+          log::trace!("Authenticating request (optional)");
+          let auth_ok = match #authenticate {
+            Ok(auth_ok) => Some(auth_ok),
+            Err(_) => {
+              log::debug!("Request failed to authenticate; proceeding without authentication");
+              None
+            }
+          };
+        },
+        quote! {
+          auth_ok: Option<Self::AuthOk>,
+        },
+        quote! {
+          /// * `auth_ok` - Output of [`Middleware::authenticate`] representing the authenticated
+          ///   user's identity, or `None` if the request is not authenticated
+        },
+        quote! {
+          auth_ok,
+        },
+        // The wrapper hooks only distinguish between authenticated and unauthenticated requests,
+        // so the optional `auth_ok` is captured by the handler closure instead.
+        quote! {},
+        quote! { wrap_handler_unauthed },
+      )
+    } else {
+      (
+        quote! {
+          log::trace!("Authenticating request");
+          let auth_ok = match #authenticate {
+            Ok(auth_ok) => auth_ok,
+            Err(err) => return err,
+          };
+        },
+        quote! {
+          auth_ok: Self::AuthOk,
+        },
+        quote! {
+          /// * `auth_ok` - Output of [`Middleware::authenticate`] representing the authenticated
+          ///   user's identity
+        },
+        quote! {
+          auth_ok,
+        },
+        quote! {
+          auth_ok,
+        },
+        quote! { wrap_handler_authed },
+      )
+    };
 
     let description_doc_attr = op
       .description
@@ -268,56 +538,78 @@ impl CodeGenerator {
       ///   account/resources that invoked the Lambda function and Cognito identity information
       ///   about the client (if configured for the API Gateway).
       /// * `lambda_context` Lambda function execution context
+      /// * `operation` - Operation ID, HTTP method, and request path template of this endpoint
       #auth_ok_doc_attr
       async fn #func_name_ident(
         &self,
         #param_signatures
         headers: HeaderMap,
-        request_context: ApiGatewayProxyRequestContext,
+        request_context: #request_context_type,
         lambda_context: LambdaContext,
+        operation: OperationInfo,
         #auth_ok_proto_arg
       ) -> Result<(#response_type_ident, HeaderMap), Self::HandlerError>;
     };
 
+    let mock_response = if self.mock_handlers {
+      self.gen_mock_response(operation, &response_type_ident, openapi_inline)?
+    } else {
+      None
+    };
+    let handler_impl_body = match mock_response {
+      Some(mock_response) => quote! { Ok((#mock_response, HeaderMap::new())) },
+      None => quote! { todo!() },
+    };
+
     let handler_impl = quote! {
       async fn #func_name_ident(
         &self,
         #param_signatures
         headers: HeaderMap,
-        request_context: ApiGatewayProxyRequestContext,
+        request_context: #request_context_type,
         lambda_context: LambdaContext,
+        operation: OperationInfo,
         #auth_ok_proto_arg
       ) -> Result<(#response_type_ident, HeaderMap), Self::HandlerError> {
-        todo!()
+        #handler_impl_body
       }
     };
 
     let handler_wrapper = quote! {
       async fn #handler_wrapper_name_ident<A, M>(
         api: &A,
-        request: ApiGatewayProxyRequest,
+        request: #request_type,
         lambda_context: LambdaContext,
         middleware: &M,
+        #handler_wrapper_extra_params
       )-> HttpResponse
       where
-        A: Api<AuthOk = <M as Middleware>::AuthOk> + Sync,
-        M: Middleware + Sync,
+        A: Api<AuthOk = <M as Middleware<#request_context_type>>::AuthOk> + Sync,
+        M: Middleware<#request_context_type> + Sync,
       {
         log::info!(concat!("Handling HTTP ", #method_upper, " {} ({})"), #request_path, #operation_id);
 
+        let error_content_type = ErrorContentType::negotiate(&request.headers);
+
         #param_parse_assignments
         #log_params
+        #request_headers_assignment
 
         #maybe_authenticate
 
         middleware.#wrapper(
-          |headers, request_context, lambda_context, #auth_ok_call_arg| async move {
+          |headers, request_context, lambda_context, #auth_ok_wrapper_arg| async move {
             let (response, response_headers) = match api
               .#func_name_ident(
                 #param_call_values
                 headers,
                 request_context,
                 lambda_context,
+                OperationInfo {
+                  operation_id: #operation_id,
+                  method: #method_upper,
+                  path: #request_path,
+                },
                 #auth_ok_call_arg
               )
               .await
@@ -331,14 +623,14 @@ impl CodeGenerator {
 
             match response.into_http_response(response_headers) {
               Ok(response) => response,
-              Err(err) => api.respond_to_event_error(err).await,
+              Err(err) => api.respond_to_event_error(err, error_content_type).await,
             }
           },
           #operation_id,
           request.headers,
           request.request_context,
           lambda_context,
-          #auth_ok_call_arg
+          #auth_ok_wrapper_arg
         )
         .await
       }
@@ -350,16 +642,210 @@ impl CodeGenerator {
         request,
         lambda_context,
         middleware,
+        #handler_wrapper_extra_args
       ).await,
     };
 
-    ApiOperation {
+    Ok(ApiOperation {
       api_dispatcher_case,
       handler_impl,
       handler_prototype,
       handler_wrapper,
+      request_headers_ident,
+      request_headers_struct,
       response_type_enum,
       response_type_ident,
+    })
+  }
+
+  /// Generates a struct with one typed field for each header parameter of an operation, or `None`
+  /// if the operation doesn't have any header parameters.
+  fn gen_request_headers(
+    &self,
+    operation_id: &str,
+    request_parameters: &[RequestParameter],
+  ) -> Option<RequestHeaders> {
+    let header_fields = request_parameters
+      .iter()
+      .filter_map(|request_parameter| request_parameter.header_field.as_ref())
+      .collect::<Vec<_>>();
+    if header_fields.is_empty() {
+      return None;
     }
+
+    // Named after the operation (e.g., `GetPetRequestHeaders` for `getPet`).
+    let struct_ident = self.identifier(&format!(
+      "{}RequestHeaders",
+      operation_id.to_case(Case::Pascal)
+    ));
+    let struct_doc = format!("Header parameters of the `{operation_id}` operation.");
+
+    let definitions = header_fields
+      .iter()
+      .map(|header_field| &header_field.definition);
+    let idents = header_fields
+      .iter()
+      .map(|header_field| &header_field.ident)
+      .collect::<Vec<_>>();
+
+    // Sensitive headers (e.g., `format: password`) are redacted from the `Debug` output.
+    let (derives, debug_impl) = if header_fields
+      .iter()
+      .any(|header_field| header_field.is_sensitive)
+    {
+      let struct_name = struct_ident.to_string();
+      let debug_fields = header_fields.iter().map(
+        |RequestHeaderField {
+           ident,
+           is_sensitive,
+           ..
+         }| {
+          let field_name = ident.to_string();
+          if *is_sensitive {
+            quote! { .field(#field_name, &"***") }
+          } else {
+            quote! { .field(#field_name, &self.#ident) }
+          }
+        },
+      );
+      (
+        quote! { #[derive(Clone)] },
+        quote! {
+          impl std::fmt::Debug for #struct_ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+              f.debug_struct(#struct_name)
+                #(#debug_fields)*
+                .finish()
+            }
+          }
+        },
+      )
+    } else {
+      (quote! { #[derive(Clone, Debug)] }, quote! {})
+    };
+
+    Some(RequestHeaders {
+      assignment: quote! {
+        let request_headers = #struct_ident {
+          #(#idents,)*
+        };
+      },
+      definition: quote! {
+        #derives
+        #[doc = #struct_doc]
+        pub struct #struct_ident {
+          #(#definitions)*
+        }
+
+        #debug_impl
+      },
+      doc_attr: quote! {
+        /// * `request_headers` - Typed header parameters of the request
+      },
+      signature: quote! {
+        request_headers: #struct_ident,
+      },
+      struct_ident,
+    })
+  }
+}
+
+/// Typed header parameters of a single operation.
+struct RequestHeaders {
+  /// Statement that constructs the struct (bound to `request_headers`) from the parsed header
+  /// parameters in the handler wrapper.
+  assignment: TokenStream,
+  /// Struct definition.
+  definition: TokenStream,
+  /// #[doc = "..."] describing the handler argument.
+  doc_attr: TokenStream,
+  /// `request_headers: #struct_ident` for handler signature.
+  signature: TokenStream,
+  struct_ident: Ident,
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::operation::collect_callback_operations;
+  use crate::{CodeGenerator, DocCache};
+
+  use openapiv3::{Callback, OpenAPI};
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_collect_callback_operations() {
+    let openapi = serde_yaml::from_str::<OpenAPI>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /subscriptions:
+    post:
+      operationId: subscribe
+      responses:
+        "201":
+          description: Subscribed
+      callbacks:
+        onEvent:
+          "{$request.body#/callbackUrl}":
+            post:
+              operationId: onEvent
+              responses:
+                "200":
+                  description: Received
+components:
+  callbacks:
+    onCancel:
+      "{$request.body#/cancelUrl}":
+        delete:
+          operationId: onCancel
+          responses:
+            "204":
+              description: Received
+"##,
+    )
+    .unwrap();
+    let webhooks = serde_yaml::from_str::<Callback>(
+      r#"
+newPet:
+  post:
+    operationId: onNewPet
+    responses:
+      "200":
+        description: Received
+"#,
+    )
+    .unwrap();
+
+    let openapi_inline = CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+      .inline_openapi(openapi, webhooks, DocCache::new())
+      .unwrap();
+    let serde_yaml::Value::Mapping(openapi_inline_mapping) =
+      serde_yaml::to_value(&*openapi_inline).unwrap()
+    else {
+      panic!("OpenAPI spec should be a mapping");
+    };
+
+    assert_eq!(
+      collect_callback_operations(&openapi_inline, &openapi_inline_mapping)
+        .unwrap()
+        .iter()
+        .map(|op| {
+          format!(
+            "{} {} {}",
+            op.method,
+            op.request_path,
+            op.op.operation_id.as_deref().unwrap()
+          )
+        })
+        .collect::<Vec<_>>(),
+      vec![
+        "POST {$request.body#/callbackUrl} onEvent",
+        "DELETE {$request.body#/cancelUrl} onCancel",
+        "POST newPet onNewPet",
+      ]
+    );
   }
 }
@@ -1,11 +1,12 @@
 use crate::api::{is_array_param, is_plain_string_schema};
 use crate::model::GeneratedModels;
-use crate::CodeGenerator;
+use crate::{description_to_doc_attr, CodeGenerator, DateTimeBackend, EventSource};
 
 use convert_case::{Case, Casing};
 use indexmap::IndexMap;
 use openapiv3::{
-  ArrayType, Parameter, ParameterSchemaOrContent, ReferenceOr, Schema, SchemaKind, Type,
+  ArrayType, Parameter, ParameterData, ParameterSchemaOrContent, PathStyle, QueryStyle,
+  ReferenceOr, Schema, SchemaKind, StringFormat, Type,
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::quote;
@@ -17,9 +18,23 @@ pub struct RequestParameter {
   /// Value passed from handler wrapper to user handler implementation.
   pub call_value: TokenStream,
 
+  /// For path parameters, the parameter name and the expression for the (URL-encoded) value that
+  /// a generated client substitutes into the request URL.
+  pub client_path_value: Option<(String, TokenStream)>,
+
+  /// Statement that adds the parameter to the `request` (a `reqwest::RequestBuilder`) sent by a
+  /// generated client.
+  ///
+  /// Path parameters are instead substituted into the request URL by the client.
+  pub client_serialization: TokenStream,
+
   /// #[doc = "..."] describing the parameter.
   pub doc_attr: TokenStream,
 
+  /// For header parameters, the corresponding field of the operation's typed request headers
+  /// struct (see [`CodeGenerator::with_request_header_structs`]).
+  pub header_field: Option<RequestHeaderField>,
+
   /// Parameter name as snake_case.
   pub log_param: TokenStream,
 
@@ -30,10 +45,23 @@ pub struct RequestParameter {
   pub wrapper_parse_assignment: TokenStream,
 }
 
+/// A field of an operation's typed request headers struct.
+pub struct RequestHeaderField {
+  /// Field definition, including any doc comment.
+  pub definition: TokenStream,
+
+  pub ident: Ident,
+
+  /// Whether the header's value should be redacted from the struct's `Debug` output.
+  pub is_sensitive: bool,
+}
+
 impl CodeGenerator {
   pub(crate) fn gen_request_parameter(
     &self,
+    request_path: &str,
     param: &Parameter,
+    event_source: EventSource,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
   ) -> RequestParameter {
@@ -48,11 +76,51 @@ impl CodeGenerator {
     let param_name_ident = self.identifier(&param_name.to_case(Case::Snake));
     let (required_type, parse_type) = match &param_data.format {
       ParameterSchemaOrContent::Schema(ref_or_schema) => {
-        let (required_type, _) = self.inline_ref_or_schema(
-          ref_or_schema,
-          components_schemas,
-          GeneratedModels::Done(generated_models),
-        );
+        // Array parameters are parsed into a `Vec`, so we ignore any `uniqueItems`, `minItems`, or
+        // `maxItems` constraints (which API Gateway request validation can enforce instead).
+        // Since parameters aren't (de)serialized using serde, `date-time` items are supported here
+        // with any `DateTimeBackend`.
+        let (required_type, _) = match ref_or_schema {
+          ReferenceOr::Item(Schema {
+            schema_kind:
+              SchemaKind::Type(Type::Array(ArrayType {
+                items: Some(item_ref_or_schema),
+                ..
+              })),
+            ..
+          }) => {
+            let (item_type, _) = self.inline_ref_or_schema(
+              item_ref_or_schema,
+              components_schemas,
+              GeneratedModels::Done(generated_models),
+            );
+            let item_type = self.nullable_type(item_ref_or_schema, components_schemas, item_type);
+            (quote! { Vec<#item_type> }, None)
+          }
+          ReferenceOr::Item(
+            schema @ Schema {
+              schema_kind: SchemaKind::Type(Type::Array(_)),
+              ..
+            },
+          ) => self.inline_ref_or_schema::<Schema>(
+            &ReferenceOr::Item(Schema {
+              schema_kind: SchemaKind::Type(Type::Array(ArrayType {
+                items: None,
+                min_items: None,
+                max_items: None,
+                unique_items: false,
+              })),
+              schema_data: schema.schema_data.clone(),
+            }),
+            components_schemas,
+            GeneratedModels::Done(generated_models),
+          ),
+          _ => self.inline_ref_or_schema(
+            ref_or_schema,
+            components_schemas,
+            GeneratedModels::Done(generated_models),
+          ),
+        };
 
         // If it's anything other than a string or array of strings, we need to parse it.
         let parse_type = match ref_or_schema {
@@ -87,8 +155,33 @@ impl CodeGenerator {
       ParameterSchemaOrContent::Content(_) => unimplemented!("content parameter `{param_name}`"),
     };
 
+    // `time::Date` and `time::OffsetDateTime` don't implement `FromStr`, and the `Display`
+    // implementation of the latter doesn't produce RFC 3339 timestamps.
+    let time_format = match &param_data.format {
+      ParameterSchemaOrContent::Schema(ref_or_schema)
+        if self.datetime_backend == DateTimeBackend::Time =>
+      {
+        match ref_or_schema {
+          ReferenceOr::Item(Schema {
+            schema_kind:
+              SchemaKind::Type(Type::Array(ArrayType {
+                items: Some(item_ref_or_schema),
+                ..
+              })),
+            ..
+          }) => self.string_format(item_ref_or_schema, components_schemas),
+          _ => self.string_format(ref_or_schema, components_schemas),
+        }
+        .filter(|string_format| {
+          matches!(string_format, StringFormat::Date | StringFormat::DateTime)
+        })
+      }
+      _ => None,
+    };
+    let crate_import = self.crate_use_name();
+
     let param_type = if param_data.required {
-      required_type
+      required_type.clone()
     } else {
       quote! { Option<#required_type> }
     };
@@ -97,10 +190,26 @@ impl CodeGenerator {
       #param_name_ident: #param_type,
     };
 
-    let parse = if let Some(ref parse_type) = parse_type {
+    // Result<T, _> parsed from the parameter value `p`.
+    let parse_value = parse_type.as_ref().map(|parse_type| match time_format {
+      Some(StringFormat::Date) => quote! { #crate_import::__private::time::parse_date(&p) },
+      Some(StringFormat::DateTime) => {
+        quote! { #crate_import::__private::time::parse_date_time(&p) }
+      }
+      _ => quote! { p.parse::<#parse_type>() },
+    });
+
+    let is_sensitive = match &param_data.format {
+      ParameterSchemaOrContent::Schema(ref_or_schema) => {
+        self.is_sensitive(ref_or_schema, components_schemas)
+      }
+      ParameterSchemaOrContent::Content(_) => false,
+    };
+
+    let parse = if let Some(ref parse_value) = parse_value {
       let parse_error_variant = match param {
         Parameter::Query { .. } => quote! { InvalidRequestQueryParam },
-        Parameter::Header { .. } => unimplemented!("header newtypes"),
+        Parameter::Header { .. } => quote! { InvalidRequestHeaderParam },
         Parameter::Path { .. } => quote! { InvalidRequestPathParam },
         Parameter::Cookie { .. } => unimplemented!("cookie newtypes"),
       };
@@ -112,7 +221,7 @@ impl CodeGenerator {
           // something like serde_plain(), which could result in runtime errors from
           // trying to deserialize to a complex type (vs. FromStr which imposes no
           // requirements on the types for which it's implemented).
-          p.parse::<#parse_type>()
+          #parse_value
             .map_err(|err| {
               EventError::#parse_error_variant {
                 param_name: std::borrow::Cow::Borrowed(#param_name),
@@ -130,7 +239,18 @@ impl CodeGenerator {
       }
     };
 
+    let query_style = match param {
+      Parameter::Query {
+        style,
+        parameter_data,
+        ..
+      } => Some(query_param_style(parameter_data, style)),
+      _ => None,
+    };
+
     let param_parse = match param {
+      // Array header parameters are rejected by `find_unsupported_features` before any code is
+      // generated.
       Parameter::Header { .. } => {
         // Option<Result<String, _>>
         quote! {
@@ -151,13 +271,76 @@ impl CodeGenerator {
             })
         }
       }
+      Parameter::Path { .. } if is_greedy_path_param(request_path, param_name) => {
+        let path_parameters = event_source.path_parameters_expr();
+
+        // Option<Result<String, _>>
+        //
+        // Greedy path parameters (e.g., `{proxy+}`) capture the remainder of the request path,
+        // which is passed to the handler verbatim (i.e., without URL-decoding) so that encoded
+        // slashes remain distinguishable from path separators.
+        quote! {
+          #path_parameters
+            .get(#param_name)
+            .map(|param_value| param_value.as_str())
+            .map(#parse)
+        }
+      }
+      Parameter::Path {
+        parameter_data,
+        style,
+      } if *style != PathStyle::Simple || is_array_param(parameter_data) => {
+        let path_parameters = event_source.path_parameters_expr();
+        let (prefix, separator) = path_param_style(parameter_data, style);
+        let path_param_error = quote! {
+          |err| EventError::InvalidRequestPathParam {
+            param_name: std::borrow::Cow::Borrowed(#param_name),
+            source: Some(err),
+            backtrace: Backtrace::new(),
+          }
+        };
+
+        // Option<Result<_, _>>
+        //
+        // Array elements are split before URL-decoding (see below) so that encoded separators
+        // remain part of each element.
+        if is_array_param(parameter_data) {
+          quote! {
+            #path_parameters
+              .get(#param_name)
+              .map(|param_value| {
+                #crate_import::__private::params::split_path_param(
+                  param_value,
+                  #prefix,
+                  #separator,
+                )
+                .map_err(#path_param_error)
+                .and_then(|param_values| {
+                  param_values.into_iter().map(#parse).collect::<Result<Vec<_>, _>>()
+                })
+              })
+          }
+        } else {
+          quote! {
+            #path_parameters
+              .get(#param_name)
+              .map(|param_value| {
+                #crate_import::__private::params::decode_path_param(param_value, #prefix)
+                  .map_err(#path_param_error)
+                  .and_then(#parse)
+              })
+          }
+        }
+      }
       Parameter::Path { .. } => {
+        let path_parameters = event_source.path_parameters_expr();
+
         // Option<Result<String, _>>
         //
         // The API Gateway REST API Lambda proxy integration doesn't automatically URL-decode path
         // params, so we need to. See https://github.com/aws/aws-sam-cli/issues/771.
         quote! {
-          if let Some(param_value) = request.path_parameters.get(#param_name) {
+          if let Some(param_value) = #path_parameters.get(#param_name) {
             match urlencoding::decode(param_value) {
               Ok(decoded_param_value) => {
                 Some(decoded_param_value)
@@ -168,7 +351,8 @@ impl CodeGenerator {
                   param_name: std::borrow::Cow::Borrowed(#param_name),
                   source: Some(err.into()),
                   backtrace: Backtrace::new(),
-                }
+                },
+                error_content_type,
               ).await,
             }
           } else {
@@ -176,24 +360,75 @@ impl CodeGenerator {
           }
         }
       }
+      Parameter::Query { .. } if query_style == Some(QueryParamStyle::DeepObject) => {
+        // Option<Result<T, _>>
+        quote! {
+          #crate_import::__private::params::parse_deep_object::<#required_type>(
+            #param_name,
+            &request.query_string_parameters,
+          )
+          .map(|param_value| {
+            param_value.map_err(|err| EventError::InvalidRequestQueryParam {
+              param_name: std::borrow::Cow::Borrowed(#param_name),
+              source: Some(err.into()),
+              backtrace: Backtrace::new(),
+            })
+          })
+        }
+      }
       Parameter::Query { parameter_data, .. } => {
         // Unlike path parameters (see above), we don't need to URL-deoode query params.
         // "In general, REST APIs decode URL-encoded request parameters before passing them to backend
         // integrations." See:
         // https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html.
         if is_array_param(parameter_data) {
-          // Option<Result<Vec<String>, _>>
+          // HTTP API payload format version 2.0 combines duplicate query parameters into a single
+          // comma-separated value, which `aws_lambda_events` splits back into multiple values.
+          let multi_value_query_string_parameters = match event_source {
+            EventSource::ApiGatewayProxy | EventSource::ApplicationLoadBalancer => {
+              quote! { multi_value_query_string_parameters }
+            }
+            EventSource::ApiGatewayV2Http => quote! { query_string_parameters },
+          };
+
+          // Non-exploded arrays are serialized as a single delimited value (e.g., `tags=a,b`)
+          // rather than by repeating the parameter (e.g., `tags=a&tags=b`).
+          let split_values = match query_style {
+            Some(QueryParamStyle::Delimited(separator)) => quote! {
+              .flat_map(|param_value| {
+                #crate_import::__private::params::split_param_value(param_value, #separator)
+              })
+            },
+            _ => quote! { .copied() },
+          };
+
+          // Items that fail to parse are identified by their index (and value, unless the parameter
+          // is sensitive) so that clients can tell which item is invalid.
+          let parse_items = if let Some(ref parse_value) = parse_value {
+            quote! {
+              #crate_import::__private::params::parse_query_param_items(
+                #param_name,
+                param_values.iter()#split_values,
+                #is_sensitive,
+                |p| #parse_value.map_err(Into::into),
+              )
+            }
+          } else {
+            quote! {
+              param_values
+                .iter()
+                #split_values
+                .map(#parse)
+                .collect::<Result<Vec<_>, _>>()
+            }
+          };
+
+          // Option<Result<Vec<_>, _>>
           quote! {
             request
-              .multi_value_query_string_parameters
+              .#multi_value_query_string_parameters
               .all(#param_name)
-              .map(|param_values| {
-                param_values
-                  .iter()
-                  .copied()
-                  .map(#parse)
-                  .collect::<Result<Vec<_>, _>>()
-              })
+              .map(|param_values| #parse_items)
           }
         } else {
           // Option<Result<String, _>>
@@ -213,9 +448,10 @@ impl CodeGenerator {
         #[allow(clippy::bind_instead_of_map)]
         let #param_name_ident = match #param_parse {
           Some(Ok(param_value)) => param_value,
-          Some(Err(err)) => return api.respond_to_event_error(err).await,
+          Some(Err(err)) => return api.respond_to_event_error(err, error_content_type).await,
           None => return api.respond_to_event_error(
-            EventError::MissingRequestParam(std::borrow::Cow::Borrowed(#param_name), Backtrace::new())
+            EventError::MissingRequestParam(std::borrow::Cow::Borrowed(#param_name), Backtrace::new()),
+            error_content_type,
           ).await,
         };
       }
@@ -224,27 +460,235 @@ impl CodeGenerator {
         #[allow(clippy::bind_instead_of_map)]
         let #param_name_ident = match #param_parse.transpose() {
           Ok(param_value) => param_value,
-          Err(err) => return api.respond_to_event_error(err).await,
+          Err(err) => return api.respond_to_event_error(err, error_content_type).await,
         };
       }
     };
 
-    let log_param = quote! {
-      log::trace!(concat!("Request parameter `", #param_name, "`: {:#?}"), #param_name_ident);
+    // Log a warning whenever a client supplies a deprecated parameter so that its remaining usage
+    // can be observed before it's removed.
+    let is_deprecated = param_data.deprecated == Some(true);
+    let wrapper_parse_assignment = if is_deprecated {
+      let deprecation_warning = quote! {
+        log::warn!(concat!("Client supplied deprecated request parameter `", #param_name, "`"));
+      };
+      if param_data.required {
+        quote! {
+          #wrapper_parse_assignment
+          #deprecation_warning
+        }
+      } else {
+        quote! {
+          #wrapper_parse_assignment
+          if #param_name_ident.is_some() {
+            #deprecation_warning
+          }
+        }
+      }
+    } else {
+      wrapper_parse_assignment
+    };
+
+    let log_param = if is_sensitive {
+      quote! {
+        log::trace!(concat!("Request parameter `", #param_name, "`: ***"));
+      }
+    } else {
+      quote! {
+        log::trace!(concat!("Request parameter `", #param_name, "`: {:#?}"), #param_name_ident);
+      }
     };
 
+    let client_param_values = match (param_data.required, is_array_param(param_data)) {
+      (true, false) => quote! { std::iter::once(&#param_name_ident) },
+      (true, true) | (false, false) => quote! { #param_name_ident.iter() },
+      (false, true) => quote! { #param_name_ident.iter().flatten() },
+    };
+    let client_param_value = if time_format == Some(StringFormat::DateTime) {
+      quote! { #crate_import::__private::time::format_date_time(param_value) }
+    } else {
+      quote! { param_value.to_string() }
+    };
+    let client_param_lists = if param_data.required {
+      quote! { std::iter::once(&#param_name_ident) }
+    } else {
+      quote! { #param_name_ident.iter() }
+    };
+    let client_serialization = match param {
+      Parameter::Query { .. } if query_style == Some(QueryParamStyle::DeepObject) => quote! {
+        let request = #client_param_lists.try_fold(request, |request, param_value| {
+          #crate_import::__private::params::deep_object_query_pairs(#param_name, param_value)
+            .map(|pairs| request.query(&pairs))
+            .map_err(|source| ClientError::SerializeRequestParam {
+              param_name: #param_name,
+              source,
+            })
+        })?;
+      },
+      Parameter::Query { parameter_data, .. } if is_array_param(parameter_data) => {
+        match query_style {
+          Some(QueryParamStyle::Delimited(separator)) => quote! {
+            let request = #client_param_lists.fold(request, |request, param_values| {
+              request.query(&[(
+                #param_name,
+                param_values
+                  .iter()
+                  .map(|param_value| #client_param_value)
+                  .collect::<Vec<_>>()
+                  .join(#separator),
+              )])
+            });
+          },
+          _ => quote! {
+            let request = #client_param_values.fold(request, |request, param_value| {
+              request.query(&[(#param_name, #client_param_value)])
+            });
+          },
+        }
+      }
+      Parameter::Query { .. } => quote! {
+        let request = #client_param_values.fold(request, |request, param_value| {
+          request.query(&[(#param_name, #client_param_value)])
+        });
+      },
+      Parameter::Header { .. } => quote! {
+        let request = #client_param_values.fold(request, |request, param_value| {
+          request.header(#param_name, #client_param_value)
+        });
+      },
+      Parameter::Path { .. } => quote! {},
+      Parameter::Cookie { .. } => unimplemented!("cookie parameters"),
+    };
+
+    // Path parameters are always required, so scalar values aren't wrapped in an `Option`.
+    let client_path_scalar_value = if time_format == Some(StringFormat::DateTime) {
+      quote! { #crate_import::__private::time::format_date_time(&#param_name_ident) }
+    } else {
+      quote! { #param_name_ident.to_string() }
+    };
+    // Greedy path parameters are substituted verbatim since they may span multiple path segments.
+    let client_path_value = match param {
+      Parameter::Path { .. } if is_greedy_path_param(request_path, param_name) => {
+        Some(quote! { #param_name_ident })
+      }
+      Parameter::Path {
+        parameter_data,
+        style,
+      } if is_array_param(parameter_data) => {
+        let (prefix, separator) = path_param_style(parameter_data, style);
+        Some(quote! {
+          #prefix.to_owned()
+            + &#param_name_ident
+              .iter()
+              .map(|param_value| urlencoding::encode(&#client_param_value).into_owned())
+              .collect::<Vec<_>>()
+              .join(#separator)
+        })
+      }
+      Parameter::Path {
+        parameter_data,
+        style: style @ (PathStyle::Label | PathStyle::Matrix),
+      } => {
+        let (prefix, _) = path_param_style(parameter_data, style);
+        Some(quote! {
+          #prefix.to_owned() + &urlencoding::encode(&#client_path_scalar_value)
+        })
+      }
+      Parameter::Path { .. } => Some(quote! { urlencoding::encode(&#client_path_scalar_value) }),
+      Parameter::Query { .. } | Parameter::Header { .. } | Parameter::Cookie { .. } => None,
+    }
+    .map(|client_path_value| (param_name.to_string(), client_path_value));
+
     let param_desc = param_data.description.as_deref().unwrap_or("");
+    let param_desc = if is_deprecated {
+      format!("**Deprecated.** {param_desc}")
+    } else {
+      param_desc.to_string()
+    };
 
     let doc_attr = quote! {
       #[doc = concat!("* `", stringify!(#param_name_ident), "` - ", #param_desc)]
     };
 
+    let header_field = matches!(param, Parameter::Header { .. }).then(|| {
+      let field_doc_attr = if param_desc.is_empty() {
+        quote! {}
+      } else {
+        description_to_doc_attr(&param_desc)
+      };
+      RequestHeaderField {
+        definition: quote! {
+          #field_doc_attr
+          pub #param_name_ident: #param_type,
+        },
+        ident: param_name_ident.clone(),
+        is_sensitive,
+      }
+    });
+
     RequestParameter {
       call_value: quote! { #param_name_ident, },
+      client_path_value,
+      client_serialization,
       doc_attr,
+      header_field,
       log_param,
       signature,
       wrapper_parse_assignment,
     }
   }
 }
+
+/// Returns whether a path parameter is greedy (e.g., `{proxy+}`) in the specified request path.
+///
+/// Greedy path parameters match the remainder of the request path, including any `/` characters.
+pub(crate) fn is_greedy_path_param(request_path: &str, param_name: &str) -> bool {
+  request_path
+    .split('/')
+    .any(|segment| segment == format!("{{{param_name}+}}"))
+}
+
+/// How a query parameter's value is serialized, as determined by its `style` and `explode`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum QueryParamStyle {
+  /// Each array element is passed as a separate instance of the parameter (e.g., `tags=a&tags=b`).
+  Exploded,
+  /// Array elements are joined by the specified separator (e.g., `tags=a,b`).
+  Delimited(&'static str),
+  /// Each object property is passed as a separate parameter (e.g., `filter[color]=red`).
+  DeepObject,
+}
+
+fn query_param_style(parameter_data: &ParameterData, style: &QueryStyle) -> QueryParamStyle {
+  // `explode` defaults to true only for the `form` style.
+  let explode = parameter_data
+    .explode
+    .unwrap_or(matches!(style, QueryStyle::Form));
+  match style {
+    QueryStyle::DeepObject => QueryParamStyle::DeepObject,
+    _ if explode => QueryParamStyle::Exploded,
+    QueryStyle::Form => QueryParamStyle::Delimited(","),
+    QueryStyle::SpaceDelimited => QueryParamStyle::Delimited(" "),
+    QueryStyle::PipeDelimited => QueryParamStyle::Delimited("|"),
+  }
+}
+
+/// Returns the prefix preceding a path parameter's value (e.g., `.` for the `label` style) and the
+/// separator between array elements.
+fn path_param_style(parameter_data: &ParameterData, style: &PathStyle) -> (String, String) {
+  let explode = parameter_data.explode.unwrap_or(false);
+  match style {
+    PathStyle::Simple => (String::new(), ",".to_string()),
+    PathStyle::Label if explode => (".".to_string(), ".".to_string()),
+    PathStyle::Label => (".".to_string(), ",".to_string()),
+    PathStyle::Matrix => {
+      let prefix = format!(";{}=", parameter_data.name);
+      let separator = if explode {
+        prefix.clone()
+      } else {
+        ",".to_string()
+      };
+      (prefix, separator)
+    }
+  }
+}
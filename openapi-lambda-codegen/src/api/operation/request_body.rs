@@ -1,5 +1,5 @@
 use crate::api::operation::parameter::RequestParameter;
-use crate::CodeGenerator;
+use crate::{CodeGenerator, CodegenError};
 
 use indexmap::IndexMap;
 use openapiv3::{ReferenceOr, RequestBody, Schema};
@@ -15,12 +15,13 @@ impl CodeGenerator {
     &self,
     request_path: &str,
     request_body: &RequestBody,
+    max_body_bytes: Option<usize>,
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> Option<RequestParameter> {
+  ) -> Result<Option<RequestParameter>, CodegenError> {
     if request_body.content.is_empty() {
-      return None;
+      return Ok(None);
     } else if request_body.content.len() > 1 {
       // Shouldn't be too difficult to support this.
       unimplemented!("multiple request body MIME types for `{request_path}`");
@@ -29,6 +30,16 @@ impl CodeGenerator {
     // This should never fail since we filter out empty request bodies above.
     let (mime_type, body_type) = request_body.content.get_index(0).expect("no mime types");
 
+    // Media ranges (e.g., `image/*`) match any content type with the same top-level type, while
+    // `*/*` matches any content type.
+    let mime_type_matches = match mime_type.split_once('/') {
+      Some(("*", "*")) => quote! { Ok(_) },
+      Some((top_level_type, "*")) => quote! {
+        Ok(content_type) if content_type.type_().as_str() == #top_level_type
+      },
+      _ => quote! { Ok(content_type) if content_type.essence_str() == #mime_type },
+    };
+
     // NB: this is critical for CSRF prevention since any Content-Type header other than forms
     // or plaintext requires a preflight, and we reject all CORS preflights at the API
     // gateway.
@@ -42,15 +53,14 @@ impl CodeGenerator {
                 HeaderName::from_static(ContentType::name().as_str()),
                 Box::new(err),
                 Backtrace::new(),
-              )
+              ),
+              error_content_type,
             ).await,
         };
-        if !matches!(
-          content_type.parse::<Mime>(),
-          Ok(content_type) if content_type.essence_str() == #mime_type
-        ) {
+        if !matches!(content_type.parse::<Mime>(), #mime_type_matches) {
           return api.respond_to_event_error(
             EventError::UnexpectedContentType(content_type.to_owned(), Backtrace::new()),
+            error_content_type,
           ).await;
         }
       } else {
@@ -58,11 +68,32 @@ impl CodeGenerator {
           EventError::MissingRequestHeader(
             std::borrow::Cow::Borrowed(ContentType::name().as_str()),
             Backtrace::new(),
-          )
+          ),
+          error_content_type,
         ).await;
       }
     };
 
+    // Reject oversized request bodies before spending any effort decoding or deserializing them.
+    let check_body_size = max_body_bytes.map(|max_body_bytes| {
+      quote! {
+        if let Some(body) = &request.body {
+          let body_len = if request.is_base64_encoded {
+            // Exact decoded length of padded base64.
+            body.trim_end_matches('=').len() * 3 / 4
+          } else {
+            body.len()
+          };
+          if body_len > #max_body_bytes {
+            return api.respond_to_event_error(
+              EventError::BodyTooLarge(#max_body_bytes, Backtrace::new()),
+              error_content_type,
+            ).await;
+          }
+        }
+      }
+    });
+
     // Option<Vec<u8>>
     let decoded_body_opt = quote! {
       if request.is_base64_encoded {
@@ -75,85 +106,111 @@ impl CodeGenerator {
           .map_err(|err| EventError::InvalidBodyBase64(Box::new(err), Backtrace::new()))
         {
           Ok(body) => body,
-          Err(err) => return api.respond_to_event_error(err).await,
+          Err(err) => return api.respond_to_event_error(err, error_content_type).await,
         }
       } else {
         request.body.map(String::into_bytes)
       }
     };
 
-    let (signature, wrapper_parse_assignment) = if let Some(body_schema_or_ref) = &body_type.schema
-    {
-      let BodySchema {
-        required_type,
-        deserialize,
-        ..
-      } = self.gen_body_schema(
-        Some(body_schema_or_ref),
-        mime_type,
-        "request_body",
-        openapi_inline,
-        components_schemas,
-        generated_models,
-      );
-
-      if request_body.required {
+    let (signature, wrapper_parse_assignment, client_serialize) =
+      if let Some(body_schema_or_ref) = &body_type.schema {
+        let BodySchema {
+          required_type,
+          deserialize,
+          client_serialize,
+          ..
+        } = self.gen_body_schema(
+          Some(body_schema_or_ref),
+          mime_type,
+          "request_body",
+          openapi_inline,
+          components_schemas,
+          generated_models,
+        )?;
+
+        if request_body.required {
+          (
+            quote! {
+              request_body: #required_type,
+            },
+            quote! {
+              let request_body_opt = match #decoded_body_opt #deserialize {
+                Ok(body) => body,
+                Err(err) => return api.respond_to_event_error(err, error_content_type).await,
+              };
+              let request_body = if let Some(request_body) = request_body_opt {
+                request_body
+              } else {
+                return api
+                  .respond_to_event_error(
+                    EventError::MissingRequestBody(Backtrace::new()),
+                    error_content_type,
+                  )
+                  .await;
+              };
+            },
+            client_serialize,
+          )
+        } else {
+          (
+            quote! {
+              request_body: Option<#required_type>,
+            },
+            quote! {
+              let request_body = match #decoded_body_opt #deserialize {
+                Ok(body) => body,
+                Err(err) => return api.respond_to_event_error(err, error_content_type).await,
+              };
+            },
+            client_serialize,
+          )
+        }
+        // Body without schema (e.g., uploading binary data).
+      } else if request_body.required {
         (
           quote! {
-            request_body: #required_type,
+            request_body: Vec<u8>,
           },
           quote! {
-            let request_body_opt = match #decoded_body_opt #deserialize {
-              Ok(body) => body,
-              Err(err) => return api.respond_to_event_error(err).await,
-            };
-            let request_body = if let Some(request_body) = request_body_opt {
+            let request_body = if let Some(request_body) = #decoded_body_opt {
               request_body
             } else {
-              return api
-                .respond_to_event_error(EventError::MissingRequestBody(Backtrace::new()))
-                .await;
+              return api.respond_to_event_error(
+                EventError::MissingRequestBody(Backtrace::new()),
+                error_content_type,
+              ).await;
             };
           },
+          quote! { body },
         )
       } else {
         (
           quote! {
-            request_body: Option<#required_type>,
+            request_body: Option<Vec<u8>>,
           },
           quote! {
-            let request_body = match #decoded_body_opt #deserialize {
-              Ok(body) => body,
-              Err(err) => return api.respond_to_event_error(err).await,
-            };
+            let request_body = #decoded_body_opt;
           },
+          quote! { body },
         )
+      };
+
+    let client_serialization = if request_body.required {
+      quote! {
+        let request = {
+          let body = request_body;
+          request.header(CONTENT_TYPE, #mime_type).body(#client_serialize)
+        };
       }
-      // Body without schema (e.g., uploading binary data).
-    } else if request_body.required {
-      (
-        quote! {
-          request_body: Vec<u8>,
-        },
-        quote! {
-          let request_body = if let Some(request_body) = #decoded_body_opt {
-            request_body
-          } else {
-            return api.respond_to_event_error(
-              EventError::MissingRequestBody(Backtrace::new())
-            ).await;
-          };
-        },
-      )
     } else {
-      (
-        quote! {
-          request_body: Option<Vec<u8>>,
-        },
-        quote! {
-          let request_body = #decoded_body_opt;
-        },
-      )
+      quote! {
+        let request = if let Some(body) = request_body {
+          request.header(CONTENT_TYPE, #mime_type).body(#client_serialize)
+        } else {
+          request
+        };
+      }
     };
 
     let log_param = quote! { log::trace!("Request body: {request_body:#?}"); };
@@ -167,15 +224,19 @@ impl CodeGenerator {
       #[doc = concat!("* `request_body` - ", #param_desc)]
     };
 
-    Some(RequestParameter {
+    Ok(Some(RequestParameter {
       call_value: quote! { request_body, },
+      client_path_value: None,
+      client_serialization,
       doc_attr,
+      header_field: None,
       log_param,
       signature,
       wrapper_parse_assignment: quote! {
         #check_mime_type;
+        #check_body_size
         #wrapper_parse_assignment
       },
-    })
+    }))
   }
 }
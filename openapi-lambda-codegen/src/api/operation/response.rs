@@ -1,10 +1,14 @@
-use crate::api::body::BodySchema;
+use crate::api::body::{response_content_type, BodySchema, MultipartBodySchema};
+use crate::api::is_array_format;
 use crate::api::operation::PathOperation;
+use crate::model::GeneratedModels;
 use crate::reference::{resolve_local_reference, ResolvedReference};
-use crate::{description_to_doc_attr, CodeGenerator};
+use crate::{description_to_doc_attr, CodeGenerator, CodegenError};
 
+use convert_case::{Case, Casing};
 use indexmap::IndexMap;
-use openapiv3::{ReferenceOr, Schema, StatusCode};
+use itertools::Itertools;
+use openapiv3::{ParameterSchemaOrContent, ReferenceOr, Schema, StatusCode};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
@@ -21,18 +25,110 @@ impl CodeGenerator {
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> TokenStream {
+  ) -> Result<TokenStream, CodegenError> {
     let OperationResponses {
+      client_cases,
+      header_pair_cases,
+      header_structs,
       response_variants,
       response_cases,
+      ..
     } = self.gen_responses(
       op,
       response_type_ident,
+      None,
       openapi_inline,
       components_schemas,
       generated_models,
-    );
-    quote! {
+    )?;
+
+    let (streaming_variant, streaming_case) = if self.response_streaming(mod_name) {
+      (
+        quote! {
+          /// Response whose body is streamed to the client
+          ///
+          /// The status code and response headers are sent before the first chunk of the body.
+          /// Use the `Content-Type` response header to specify the MIME type of the body.
+          Streaming(StatusCode, ResponseStream),
+        },
+        quote! {
+          #response_type_ident::Streaming(status_code, response_stream) => {
+            return streaming_http_response(status_code, headers, response_stream);
+          }
+        },
+      )
+    } else {
+      (quote! {}, quote! {})
+    };
+
+    // Typed response headers are collected before `self` is consumed below.
+    let (header_pairs_fn, header_pairs_assignment, header_pairs_fold) =
+      if header_pair_cases.is_empty() {
+        (quote! {}, quote! {}, quote! {})
+      } else {
+        (
+          quote! {
+            fn typed_header_pairs(&self) -> Vec<(&'static str, String)> {
+              #[allow(unreachable_patterns)]
+              match self {
+                #header_pair_cases
+                _ => Vec::new(),
+              }
+            }
+          },
+          quote! {
+            let typed_header_pairs = self.typed_header_pairs();
+          },
+          quote! {
+            // As with `Content-Type`, a header supplied by the handler replaces the typed header of
+            // the same name.
+            let response_with_content_type = typed_header_pairs
+              .into_iter()
+              .filter(|(header_name, _)| !headers.contains_key(*header_name))
+              .fold(response_with_content_type, |response, (header_name, header_value)| {
+                response.header(header_name, header_value)
+              });
+          },
+        )
+      };
+
+    // Decoding responses requires the `ClientError` type, which is only available when the `client`
+    // feature is enabled (as it must be for generated clients).
+    let (from_http_fn, try_from_http_response_impl) = if self.client_mod_name.is_some() {
+      (
+        gen_from_http_fn(op, client_cases, None),
+        quote! {
+          /// Decodes an [`HttpResponse`] (e.g., one returned by an API handler in an integration
+          /// test) according to its HTTP status code.
+          ///
+          /// See [`from_http`](Self::from_http).
+          impl std::convert::TryFrom<HttpResponse> for #response_type_ident {
+            type Error = ClientError;
+
+            fn try_from(response: HttpResponse) -> Result<Self, Self::Error> {
+              let (parts, body) = response.into_parts();
+              let content_type = parts
+                .headers
+                .get(ContentType::name())
+                .and_then(|content_type| content_type.to_str().ok())
+                .map(String::from);
+              let body = match body {
+                Body::Empty => Vec::new(),
+                Body::Text(text) => text.into_bytes(),
+                Body::Binary(bytes) => bytes,
+              };
+              Self::from_http(parts.status, content_type, &parts.headers, body)
+            }
+          }
+        },
+      )
+    } else {
+      (quote! {}, quote! {})
+    };
+
+    Ok(quote! {
+      #header_structs
+
       #[allow(clippy::large_enum_variant)]
       #[derive(Clone, Debug)]
       #[doc = concat!(
@@ -41,23 +137,51 @@ impl CodeGenerator {
       )]
       pub enum #response_type_ident {
         #response_variants
+        #streaming_variant
+        /// Response sent with an explicit status code instead of the one declared by the OpenAPI
+        /// definition
+        ///
+        /// See [`with_status`](Self::with_status).
+        WithStatus(StatusCode, Box<Self>),
       }
       impl #response_type_ident {
+        /// Override the HTTP status code of this response.
+        ///
+        /// The body and headers of the response are otherwise unchanged.
+        pub fn with_status(self, status_code: StatusCode) -> Self {
+          Self::WithStatus(status_code, Box::new(self))
+        }
+
+        #header_pairs_fn
+
+        #from_http_fn
+
         pub(crate) fn into_http_response(
           self,
           headers: HeaderMap,
         ) -> Result<HttpResponse, EventError> {
+          #header_pairs_assignment
           let (status_code, content_type, body) = match self {
             #response_cases
+            #streaming_case
+            #response_type_ident::WithStatus(status_code, response) => {
+              let mut response = response.into_http_response(headers)?;
+              *response.status_mut() = status_code;
+              return Ok(response);
+            }
           };
 
           let response = Response::builder().status(status_code);
 
-          let response_with_content_type = if let Some(content_type) = content_type {
-            response.header(ContentType::name(), content_type.to_string())
-          } else {
-            response
+          // A `Content-Type` header supplied by the handler (e.g., to specify a `charset`) replaces
+          // the one inferred from the OpenAPI definition rather than being appended to it.
+          let response_with_content_type = match content_type {
+            Some(content_type) if !headers.contains_key(ContentType::name().as_str()) => {
+              response.header(ContentType::name(), content_type)
+            }
+            _ => response,
           };
+          #header_pairs_fold
 
           let response_with_headers = headers
             .iter()
@@ -70,22 +194,47 @@ impl CodeGenerator {
             .map_err(|err| EventError::HttpResponse(Box::new(err), Backtrace::new()))
         }
       }
-    }
+      /// Pairs the response with an empty set of response headers, which allows handlers that
+      /// don't set any response headers to return `Ok(response.into())`.
+      impl From<#response_type_ident> for (#response_type_ident, HeaderMap) {
+        fn from(response: #response_type_ident) -> Self {
+          (response, HeaderMap::new())
+        }
+      }
+      #try_from_http_response_impl
+    })
   }
 
-  fn gen_responses(
+  /// Generates the response variants of an operation's response type.
+  ///
+  /// If `client_error_type_ident` is specified, variants for error responses (i.e., `4XX` and `5XX`
+  /// status codes) are instead returned in
+  /// [`client_error_variants`](OperationResponses::client_error_variants), and the corresponding
+  /// `client_cases` return them as `ClientError::ErrorResponse` errors.
+  pub(crate) fn gen_responses(
     &self,
     op: &PathOperation,
     response_type_ident: &Ident,
+    client_error_type_ident: Option<&Ident>,
     openapi_inline: &serde_yaml::Mapping,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: &HashMap<Ident, TokenStream>,
-  ) -> OperationResponses {
-    let (response_variants, response_cases) = op
+  ) -> Result<OperationResponses, CodegenError> {
+    let (
+      response_variants,
+      client_error_variants,
+      response_cases,
+      client_cases,
+      header_structs,
+      header_pair_cases,
+    ) = op
       .op
       .responses
       .responses
       .iter()
+      // Generated clients match status codes in order, so specific status codes must precede
+      // status code ranges.
+      .sorted_by_key(|(status_code, _)| matches!(status_code, StatusCode::Range(_)))
       .map(|(status_code, response)| Some((Some(status_code), response)))
       .chain(std::iter::once(
         op.op
@@ -96,45 +245,139 @@ impl CodeGenerator {
       ))
       .flatten()
       .map(|(status_code_enum, ref_or_response)| {
-        let (status_code, variant_name) = if let Some(status_code_enum) = status_code_enum {
-          let StatusCodeTokens {
-            status_code,
-            variant_name,
-          } = status_code_tokens(status_code_enum);
-          (Some(status_code), variant_name)
-        } else {
-          (None, quote! { Default })
+        // Like the `Default` variant, variants for status code ranges (e.g., `2XX`) carry an
+        // explicit `StatusCode`.
+        let (status_code, variant_name, status_range) = match status_code_enum {
+          Some(StatusCode::Range(range)) => (None, status_range_variant_name(*range), Some(*range)),
+          Some(status_code_enum) => {
+            let StatusCodeTokens {
+              status_code,
+              variant_name,
+            } = status_code_tokens(status_code_enum);
+            (Some(status_code), variant_name, None)
+          }
+          None => (None, quote! { Default }, None),
+        };
+
+        // Expression used by generated clients to return a value of this variant.
+        let client_error_type_ident = client_error_type_ident.filter(|_| {
+          matches!(
+            status_code_enum,
+            Some(StatusCode::Code(400..) | StatusCode::Range(4..))
+          )
+        });
+        let client_result = |fields: TokenStream| match client_error_type_ident {
+          Some(error_type_ident) => quote! {
+            Err(ClientError::ErrorResponse {
+              status_code,
+              response: #error_type_ident::#variant_name #fields,
+            })
+          },
+          None => quote! { Ok(Self::#variant_name #fields) },
+        };
+
+        // Match arm pattern used by generated clients to select this variant.
+        let client_pattern = match (&status_code, status_range) {
+          (Some(status), _) => quote! { status_code if status_code == #status },
+          (None, Some(range)) => quote! { status_code if status_code.as_u16() / 100 == #range },
+          (None, None) => quote! { status_code },
         };
 
+        // Statement that rejects status codes outside of the declared range (if any).
+        let range_check = status_range
+          .map(|range| {
+            let range_str = format!("{range}XX");
+            quote! {
+              if status_code.as_u16() / 100 != #range {
+                return Err(EventError::ResponseStatusOutOfRange(
+                  status_code,
+                  #range_str,
+                  Backtrace::new(),
+                ));
+              }
+            }
+          })
+          .unwrap_or_default();
+
         let response = match ref_or_response {
           ReferenceOr::Item(response) => Cow::Borrowed(response),
           ReferenceOr::Reference { reference } => {
             let ResolvedReference { target, .. } =
-              resolve_local_reference::<openapiv3::Response>(reference, openapi_inline);
+              resolve_local_reference::<openapiv3::Response>(reference, openapi_inline)?;
             Cow::Owned(target)
           }
         };
 
-        let (response_variant, response_case) = match response.content.len() {
+        let response_headers = self.gen_response_headers(
+          &response,
+          response_type_ident,
+          &variant_name,
+          openapi_inline,
+          components_schemas,
+          generated_models,
+        )?;
+
+        // Trailing variant field (type, match pattern, and client value) holding the typed
+        // response headers, if any.
+        let (headers_type, headers_pattern, headers_client_value) = match &response_headers {
+          Some(ResponseHeaders {
+            struct_ident,
+            client_deserialize,
+            ..
+          }) => (
+            quote! { , #struct_ident },
+            quote! { , _ },
+            quote! { , #client_deserialize },
+          ),
+          None => (quote! {}, quote! {}, quote! {}),
+        };
+
+        let (response_variant, response_case, client_case) = match response.content.len() {
           0 => {
             if let Some(status) = status_code {
+              let (variant_fields, pattern_fields, client_fields) = match &response_headers {
+                Some(ResponseHeaders {
+                  struct_ident,
+                  client_deserialize,
+                  ..
+                }) => (
+                  quote! { (#struct_ident) },
+                  quote! { (_) },
+                  quote! { (#client_deserialize) },
+                ),
+                None => (quote! {}, quote! {}, quote! {}),
+              };
               (
                 quote! {
-                  #variant_name,
+                  #variant_name #variant_fields,
                 },
                 quote! {
-                  #response_type_ident::#variant_name =>
-                    (#status, Option::<&'static str>::None, Body::Empty),
+                  #response_type_ident::#variant_name #pattern_fields =>
+                    (#status, Option::<String>::None, Body::Empty),
+                },
+                {
+                  let client_result = client_result(client_fields);
+                  quote! {
+                    #client_pattern => #client_result,
+                  }
                 },
               )
             } else {
               (
                 quote! {
-                  #variant_name(StatusCode),
+                  #variant_name(StatusCode #headers_type),
                 },
                 quote! {
-                  #response_type_ident::#variant_name(status_code) =>
-                    (status_code, Option::<&'static str>::None, Body::Empty),
+                  #response_type_ident::#variant_name(status_code #headers_pattern) => {
+                    #range_check
+                    (status_code, Option::<String>::None, Body::Empty)
+                  }
+                },
+                {
+                  let client_result = client_result(quote! { (status_code #headers_client_value) });
+                  quote! {
+                    #client_pattern => #client_result,
+                  }
                 },
               )
             }
@@ -143,38 +386,103 @@ impl CodeGenerator {
             // This should never fail since we filter out empty request bodies above.
             let (mime_type, body_type) = response.content.get_index(0).expect("no mime types");
 
-            let BodySchema {
-              required_type: variant_body,
-              serialize: serialized_body,
-              ..
-            } = self.gen_body_schema(
-              body_type.schema.as_ref(),
-              mime_type,
-              &response_type_ident.to_string(),
-              openapi_inline,
-              components_schemas,
-              generated_models,
-            );
+            let status_or_var = status_code
+              .clone()
+              .unwrap_or_else(|| quote! { status_code });
 
-            if let Some(status) = status_code {
+            // Multipart bodies determine their own `Content-Type` since it includes the boundary.
+            // Generated clients don't parse multipart bodies, so they return such responses as
+            // `ClientError::UnexpectedResponse` errors.
+            let (variant_body, serialized_response, client_body) = if mime_type == "multipart/mixed"
+            {
+              let MultipartBodySchema {
+                required_type,
+                serialize,
+              } = self.gen_multipart_response_body_schema(
+                body_type,
+                &response_type_ident.to_string(),
+                openapi_inline,
+                components_schemas,
+                generated_models,
+              )?;
               (
+                required_type,
                 quote! {
-                  #variant_name(#variant_body),
+                  {
+                    let (content_type, body) = #serialize;
+                    (#status_or_var, Some(content_type), body)
+                  }
                 },
+                None,
+              )
+            } else {
+              let BodySchema {
+                required_type,
+                serialize,
+                client_deserialize,
+                ..
+              } = self.gen_body_schema(
+                body_type.schema.as_ref(),
+                mime_type,
+                &response_type_ident.to_string(),
+                openapi_inline,
+                components_schemas,
+                generated_models,
+              )?;
+              let content_type = response_content_type(mime_type);
+              (
+                required_type,
+                quote! { (#status_or_var, Some(#content_type.to_string()), #serialize) },
+                Some(quote! { (#client_deserialize)? }),
+              )
+            };
+
+            let client_case = match (client_body, status_code.is_some()) {
+              (None, _) => quote! {
+                #client_pattern => Err(ClientError::UnexpectedResponse {
+                  status_code,
+                  content_type,
+                  body,
+                }),
+              },
+              (Some(client_body), true) => {
+                let client_result = client_result(quote! { (#client_body #headers_client_value) });
                 quote! {
-                  #response_type_ident::#variant_name(body) =>
-                    (#status, Some(#mime_type), #serialized_body),
+                  #client_pattern => #client_result,
+                }
+              }
+              (Some(client_body), false) => {
+                let client_result =
+                  client_result(quote! { (status_code, #client_body #headers_client_value) });
+                quote! {
+                  #client_pattern => #client_result,
+                }
+              }
+            };
+
+            if status_code.is_some() {
+              (
+                quote! {
+                  #variant_name(#variant_body #headers_type),
+                },
+                quote! {
+                  #response_type_ident::#variant_name(body #headers_pattern) =>
+                    #serialized_response,
                 },
+                client_case,
               )
             } else {
               (
                 quote! {
-                  #variant_name(StatusCode, #variant_body),
+                  #variant_name(StatusCode, #variant_body #headers_type),
                 },
                 quote! {
-                  #response_type_ident::#variant_name(status_code, body) =>
-                    (status_code, Some(#mime_type), #serialized_body),
+                  #response_type_ident::#variant_name(status_code, body #headers_pattern) => {
+                    #range_check
+                    #serialized_response
+                  }
                 },
+                client_case,
               )
             }
           }
@@ -186,20 +494,280 @@ impl CodeGenerator {
 
         let doc_attr = description_to_doc_attr(&response.description);
 
-        (
-          quote! {
-            #doc_attr
-            #response_variant
-          },
+        let (header_struct, header_pair_case) = match response_headers {
+          Some(ResponseHeaders {
+            definition,
+            header_pairs,
+            ..
+          }) => (
+            definition,
+            quote! {
+              #response_type_ident::#variant_name(.., response_headers) => #header_pairs,
+            },
+          ),
+          None => (quote! {}, quote! {}),
+        };
+
+        let response_variant = quote! {
+          #doc_attr
+          #response_variant
+        };
+        let (response_variant, client_error_variant) = if client_error_type_ident.is_some() {
+          (quote! {}, response_variant)
+        } else {
+          (response_variant, quote! {})
+        };
+
+        Ok((
+          response_variant,
+          client_error_variant,
           response_case,
-        )
+          client_case,
+          header_struct,
+          header_pair_case,
+        ))
       })
-      .unzip::<_, _, TokenStream, TokenStream>();
+      .collect::<Result<Vec<_>, CodegenError>>()?
+      .into_iter()
+      .multiunzip::<(
+        TokenStream,
+        TokenStream,
+        TokenStream,
+        TokenStream,
+        TokenStream,
+        TokenStream,
+      )>();
 
-    OperationResponses {
+    Ok(OperationResponses {
+      client_cases,
+      client_error_variants,
+      header_pair_cases,
+      header_structs,
       response_cases,
       response_variants,
+    })
+  }
+
+  /// Generates a struct with one typed field for each header declared by the response, or `None`
+  /// if the response doesn't declare any headers.
+  fn gen_response_headers(
+    &self,
+    response: &openapiv3::Response,
+    response_type_ident: &Ident,
+    variant_name: &TokenStream,
+    openapi_inline: &serde_yaml::Mapping,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    generated_models: &HashMap<Ident, TokenStream>,
+  ) -> Result<Option<ResponseHeaders>, CodegenError> {
+    let headers = response
+      .headers
+      .iter()
+      // OpenAPI specifies that a `Content-Type` response header definition is ignored.
+      .filter(|(header_name, _)| !header_name.eq_ignore_ascii_case("content-type"))
+      .map(|(header_name, ref_or_header)| {
+        let header = match ref_or_header {
+          ReferenceOr::Item(header) => Cow::Borrowed(header),
+          ReferenceOr::Reference { reference } => {
+            let ResolvedReference { target, .. } =
+              resolve_local_reference::<openapiv3::Header>(reference, openapi_inline)?;
+            Cow::Owned(target)
+          }
+        };
+        Ok((header_name, header))
+      })
+      .collect::<Result<Vec<_>, CodegenError>>()?;
+    if headers.is_empty() {
+      return Ok(None);
     }
+
+    // Named after the operation (e.g., `GetPetOkHeaders` for `GetPetResponse::Ok`).
+    let response_type_name = response_type_ident.to_string();
+    let operation_name = response_type_name
+      .strip_suffix("Response")
+      .unwrap_or(&response_type_name);
+    let struct_ident = self.identifier(&format!("{operation_name}{variant_name}Headers"));
+    let struct_doc = format!("Headers of the [`{response_type_ident}::{variant_name}`] response.");
+
+    let (fields, client_fields, header_pair_values) = headers
+      .into_iter()
+      .map(|(header_name, header)| {
+        let ParameterSchemaOrContent::Schema(ref_or_schema) = &header.format else {
+          unimplemented!("content response header `{header_name}`");
+        };
+        if is_array_format(&header.format) {
+          unimplemented!("array response header `{header_name}`");
+        }
+        let (required_type, _) = self.inline_ref_or_schema(
+          ref_or_schema,
+          components_schemas,
+          GeneratedModels::Done(generated_models),
+        );
+
+        let field_ident = self.identifier(&header_name.to_case(Case::Snake));
+        let doc_attr = header
+          .description
+          .as_ref()
+          .map(description_to_doc_attr)
+          .unwrap_or_default();
+
+        let client_parse = quote! {
+          headers
+            .get(#header_name)
+            .map(|header_value| {
+              header_value
+                .to_str()
+                .map_err(|err| ClientError::InvalidResponseHeader {
+                  header_name: #header_name,
+                  source: Some(err.into()),
+                })
+                .and_then(|header_value| {
+                  header_value.parse::<#required_type>().map_err(|err| {
+                    ClientError::InvalidResponseHeader {
+                      header_name: #header_name,
+                      source: Some(err.into()),
+                    }
+                  })
+                })
+            })
+            .transpose()?
+        };
+
+        if header.required {
+          (
+            quote! {
+              #doc_attr
+              pub #field_ident: #required_type,
+            },
+            quote! {
+              #field_ident: #client_parse.ok_or(ClientError::InvalidResponseHeader {
+                header_name: #header_name,
+                source: None,
+              })?,
+            },
+            quote! {
+              .chain(std::iter::once((#header_name, response_headers.#field_ident.to_string())))
+            },
+          )
+        } else {
+          (
+            quote! {
+              #doc_attr
+              pub #field_ident: Option<#required_type>,
+            },
+            quote! {
+              #field_ident: #client_parse,
+            },
+            quote! {
+              .chain(
+                response_headers
+                  .#field_ident
+                  .iter()
+                  .map(|header_value| (#header_name, header_value.to_string()))
+              )
+            },
+          )
+        }
+      })
+      .multiunzip::<(TokenStream, TokenStream, TokenStream)>();
+
+    Ok(Some(ResponseHeaders {
+      client_deserialize: quote! {
+        #struct_ident {
+          #client_fields
+        }
+      },
+      definition: quote! {
+        #[derive(Clone, Debug)]
+        #[doc = #struct_doc]
+        pub struct #struct_ident {
+          #fields
+        }
+      },
+      header_pairs: quote! {
+        std::iter::empty()
+          #header_pair_values
+          .collect()
+      },
+      struct_ident,
+    }))
+  }
+
+  /// Generates an expression that constructs an example response for a mock handler, or `None`
+  /// if the operation has no suitable response or example.
+  ///
+  /// See [`CodeGenerator::generate_mock_handlers`].
+  pub(crate) fn gen_mock_response(
+    &self,
+    op: &PathOperation,
+    response_type_ident: &Ident,
+    openapi_inline: &serde_yaml::Mapping,
+  ) -> Result<Option<TokenStream>, CodegenError> {
+    let responses = &op.op.responses;
+    let (is_default, ref_or_response) = match responses.responses.get(&StatusCode::Code(200)) {
+      Some(response) => (false, response),
+      None => match &responses.default {
+        Some(response) => (true, response),
+        None => return Ok(None),
+      },
+    };
+
+    let response = match ref_or_response {
+      ReferenceOr::Item(response) => Cow::Borrowed(response),
+      ReferenceOr::Reference { reference } => {
+        let ResolvedReference { target, .. } =
+          resolve_local_reference::<openapiv3::Response>(reference, openapi_inline)?;
+        Cow::Owned(target)
+      }
+    };
+
+    // Typed response headers can't be populated from an example.
+    if response
+      .headers
+      .keys()
+      .any(|header_name| !header_name.eq_ignore_ascii_case("content-type"))
+    {
+      return Ok(None);
+    }
+
+    let body = match response.content.len() {
+      0 => None,
+      1 => {
+        let (mime_type, media_type) = response.content.get_index(0).expect("no mime types");
+        if mime_type != "application/json" {
+          return Ok(None);
+        }
+        // References to examples aren't resolved.
+        let Some(example) = media_type.example.as_ref().or_else(|| {
+          media_type
+            .examples
+            .values()
+            .find_map(|example| example.as_item()?.value.as_ref())
+        }) else {
+          return Ok(None);
+        };
+
+        let example_json = example.to_string();
+        let crate_import = self.crate_use_name();
+        Some(quote! {
+          #crate_import::__private::serde_json::from_str(#example_json)
+            .expect("example should match the response schema")
+        })
+      }
+      _ => return Ok(None),
+    };
+
+    let status_code = is_default.then(|| quote! { StatusCode::OK });
+    let args = status_code.into_iter().chain(body).collect::<Vec<_>>();
+    let variant_name = if is_default {
+      quote! { Default }
+    } else {
+      status_code_tokens(&StatusCode::Code(200)).variant_name
+    };
+    Ok(Some(if args.is_empty() {
+      quote! { #response_type_ident::#variant_name }
+    } else {
+      quote! { #response_type_ident::#variant_name(#(#args),*) }
+    }))
   }
 }
 
@@ -407,7 +975,7 @@ fn status_code_tokens(status_code_enum: &StatusCode) -> StatusCodeTokens {
       )
     }
 
-    StatusCode::Range(_) => unimplemented!("response status code ranges"),
+    StatusCode::Range(_) => unreachable!("status code ranges don't have a fixed status code"),
   };
 
   StatusCodeTokens {
@@ -416,7 +984,107 @@ fn status_code_tokens(status_code_enum: &StatusCode) -> StatusCodeTokens {
   }
 }
 
-struct OperationResponses {
+/// Returns the response variant name for a status code range (e.g., `Success` for `2XX`).
+fn status_range_variant_name(range: u16) -> TokenStream {
+  match range {
+    1 => quote! { Informational },
+    2 => quote! { Success },
+    3 => quote! { Redirection },
+    4 => quote! { ClientError },
+    5 => quote! { ServerError },
+    _ => panic!("invalid response status code range `{range}XX`"),
+  }
+}
+
+/// Generates the `from_http` method of a response type, which decodes a response according to its
+/// HTTP status code using the `client_cases` returned by [`CodeGenerator::gen_responses`].
+///
+/// If `client_error_type_ident` is specified, the `client_cases` must have been generated with the
+/// same error response type.
+///
+/// The generated method requires the `client` feature of the `openapi-lambda` crate.
+pub(crate) fn gen_from_http_fn(
+  op: &PathOperation,
+  client_cases: TokenStream,
+  client_error_type_ident: Option<&Ident>,
+) -> TokenStream {
+  // Responses with undeclared status codes can't be represented by the response type.
+  let unexpected_case = if op.op.responses.default.is_some() {
+    quote! {}
+  } else {
+    quote! {
+      status_code => Err(ClientError::UnexpectedResponse {
+        status_code,
+        content_type,
+        body,
+      }),
+    }
+  };
+
+  let (client_error_type, error_response_doc) = match client_error_type_ident {
+    Some(error_type_ident) => (
+      quote! { ClientError<#error_type_ident> },
+      quote! {
+        ///
+        /// Error responses (i.e., those with `4XX` and `5XX` status codes) declared by the OpenAPI
+        /// definition are returned as [`ClientError::ErrorResponse`] errors.
+      },
+    ),
+    None => (quote! { ClientError }, quote! {}),
+  };
+
+  quote! {
+    /// Decode an HTTP response according to its status code.
+    ///
+    /// Responses with status codes not declared for this operation by the OpenAPI definition (as
+    /// well as `multipart/mixed` responses) are returned as
+    /// [`ClientError::UnexpectedResponse`] errors.
+    #error_response_doc
+    ///
+    /// # Arguments
+    ///
+    /// * `status_code` - HTTP status code of the response
+    /// * `content_type` - Value of the `Content-Type` response header, if any
+    /// * `headers` - HTTP response headers
+    /// * `body` - Raw response body
+    #[allow(unused_variables)]
+    pub fn from_http(
+      status_code: StatusCode,
+      content_type: Option<String>,
+      headers: &HeaderMap,
+      body: Vec<u8>,
+    ) -> Result<Self, #client_error_type> {
+      match status_code {
+        #client_cases
+        #unexpected_case
+      }
+    }
+  }
+}
+
+pub(crate) struct OperationResponses {
+  /// Match arms used by generated clients to convert a `status_code`, `content_type`, and `body`
+  /// into the corresponding response variant.
+  pub client_cases: TokenStream,
+  /// Variants of a generated client's error response type (see [`CodeGenerator::gen_responses`]).
+  pub client_error_variants: TokenStream,
+  /// Match arms that convert each response variant with typed headers into a
+  /// `Vec<(&'static str, String)>` of header names and values.
+  pub header_pair_cases: TokenStream,
+  /// Definitions of the typed response header structs referenced by `response_variants`.
+  pub header_structs: TokenStream,
   pub response_cases: TokenStream,
   pub response_variants: TokenStream,
 }
+
+/// Typed headers declared by a single response.
+struct ResponseHeaders {
+  /// Expression that parses the struct from a generated client's response `headers`.
+  client_deserialize: TokenStream,
+  /// Struct definition.
+  definition: TokenStream,
+  /// Expression that converts the struct (bound to `response_headers`) into a
+  /// `Vec<(&'static str, String)>` of header names and values.
+  header_pairs: TokenStream,
+  struct_ident: Ident,
+}
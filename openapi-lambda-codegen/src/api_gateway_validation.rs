@@ -0,0 +1,348 @@
+use crate::apigw::OPENAPI_GW_FILENAME;
+use crate::validate::pointer_location;
+use crate::{CodeGenerator, ValidationIssue};
+
+use openapiv3::{
+  CookieStyle, HeaderStyle, OpenAPI, Parameter, ParameterData, PathStyle, QueryStyle, ReferenceOr,
+};
+use serde_json::Value;
+
+/// Whether to check the generated `openapi-apigw.yaml` for constructs that Amazon API Gateway
+/// rejects or ignores when importing a REST API.
+///
+/// See [`CodeGenerator::with_api_gateway_validation`](crate::CodeGenerator::with_api_gateway_validation).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ApiGatewayValidation {
+  /// Don't check the generated `openapi-apigw.yaml` (the default).
+  #[default]
+  None,
+  /// Log a warning for each problem found.
+  Warn,
+  /// Fail code generation with
+  /// [`CodegenError::ApiGatewayIncompatible`](crate::CodegenError::ApiGatewayIncompatible) if any
+  /// problems are found.
+  Error,
+}
+
+/// Schema keywords whose values are instances rather than schemas, and which therefore shouldn't
+/// be checked for unsupported schema keywords.
+const INSTANCE_KEYWORDS: [&str; 4] = ["default", "enum", "example", "examples"];
+
+impl CodeGenerator {
+  /// Find every construct in the generated `openapi-apigw.yaml` that API Gateway is known to
+  /// reject or silently ignore when importing a REST API. See
+  /// <https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html#api-gateway-known-issues-rest-apis>.
+  pub(crate) fn validate_openapi_apigw(&self, openapi: &OpenAPI) -> Vec<ValidationIssue> {
+    let apigw_path = self.out_dir.join(OPENAPI_GW_FILENAME);
+    let location = |tokens: &[&str]| {
+      pointer_location(
+        &apigw_path,
+        &tokens
+          .iter()
+          .map(|token| token.to_string())
+          .collect::<Vec<_>>(),
+      )
+    };
+    let mut issues = Vec::new();
+
+    for (path, path_item) in &openapi.paths.paths {
+      if let Err(message) = validate_path(path) {
+        issues.push(ValidationIssue::new(location(&["paths", path]), message));
+      }
+
+      let ReferenceOr::Item(path_item) = path_item else {
+        continue;
+      };
+      for (index, parameter) in path_item.parameters.iter().enumerate() {
+        if let Some(message) = parameter.as_item().and_then(validate_parameter_style) {
+          issues.push(ValidationIssue::new(
+            location(&["paths", path, "parameters", &index.to_string()]),
+            message,
+          ));
+        }
+      }
+      for (method, op) in path_item.iter() {
+        for (index, parameter) in op.parameters.iter().enumerate() {
+          if let Some(message) = parameter.as_item().and_then(validate_parameter_style) {
+            issues.push(ValidationIssue::new(
+              location(&["paths", path, method, "parameters", &index.to_string()]),
+              message,
+            ));
+          }
+        }
+      }
+    }
+
+    if let Some(components) = &openapi.components {
+      for (name, parameter) in &components.parameters {
+        if let Some(message) = parameter.as_item().and_then(validate_parameter_style) {
+          issues.push(ValidationIssue::new(
+            location(&["components", "parameters", name]),
+            message,
+          ));
+        }
+      }
+
+      for name in components.schemas.keys() {
+        if !name.chars().all(|c| c.is_ascii_alphanumeric()) {
+          issues.push(ValidationIssue::new(
+            location(&["components", "schemas", name]),
+            "API Gateway model names may only contain alphanumeric characters",
+          ));
+        }
+      }
+    }
+
+    let openapi_json = serde_json::to_value(openapi).expect("failed to serialize OpenAPI spec");
+    let mut pointer = Vec::new();
+    find_unsupported_schema_keywords(&openapi_json, &mut pointer, &mut |pointer| {
+      issues.push(ValidationIssue::new(
+        pointer_location(&apigw_path, pointer),
+        "API Gateway does not support `exclusiveMinimum`",
+      ))
+    });
+
+    issues
+  }
+}
+
+/// API Gateway resource paths consist of literal segments and path parameters, each of which must
+/// span an entire segment. A greedy path parameter (e.g., `{proxy+}`) must be the last segment.
+fn validate_path(path: &str) -> Result<(), String> {
+  let is_valid_char = |c: char| c.is_ascii_alphanumeric() || "-_.,:".contains(c);
+
+  let segments = path.split('/').filter(|segment| !segment.is_empty());
+  let num_segments = segments.clone().count();
+  for (index, segment) in segments.enumerate() {
+    if !segment.contains(['{', '}']) {
+      if !segment.chars().all(is_valid_char) {
+        return Err(format!(
+          "API Gateway path segment `{segment}` may only contain alphanumeric characters, hyphens, \
+           underscores, periods, commas, and colons"
+        ));
+      }
+      continue;
+    }
+
+    let Some(param_name) = segment
+      .strip_prefix('{')
+      .and_then(|segment| segment.strip_suffix('}'))
+      .filter(|param_name| !param_name.is_empty())
+    else {
+      return Err(format!(
+        "API Gateway path parameters must span an entire path segment: found `{segment}`"
+      ));
+    };
+
+    let param_name = match param_name.strip_suffix('+') {
+      Some(_) if index + 1 != num_segments => {
+        return Err(format!(
+          "API Gateway greedy path parameter `{segment}` must be the last path segment"
+        ))
+      }
+      Some(param_name) => param_name,
+      None => param_name,
+    };
+    if param_name.is_empty() || !param_name.chars().all(is_valid_char) {
+      return Err(format!(
+        "API Gateway path parameter name `{param_name}` may only contain alphanumeric characters, \
+         hyphens, underscores, periods, commas, and colons"
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+/// API Gateway ignores the serialization `style` and `explode` of each parameter and always uses
+/// the defaults for the parameter's location, so a non-default value won't match the requests that
+/// clients actually send.
+fn validate_parameter_style(parameter: &Parameter) -> Option<String> {
+  let (style, default_style, parameter_data): (Option<&str>, _, &ParameterData) = match parameter {
+    Parameter::Query {
+      style,
+      parameter_data,
+      ..
+    } => (
+      match style {
+        QueryStyle::Form => None,
+        QueryStyle::SpaceDelimited => Some("spaceDelimited"),
+        QueryStyle::PipeDelimited => Some("pipeDelimited"),
+        QueryStyle::DeepObject => Some("deepObject"),
+      },
+      "form",
+      parameter_data,
+    ),
+    Parameter::Path {
+      style,
+      parameter_data,
+    } => (
+      match style {
+        PathStyle::Simple => None,
+        PathStyle::Label => Some("label"),
+        PathStyle::Matrix => Some("matrix"),
+      },
+      "simple",
+      parameter_data,
+    ),
+    Parameter::Header {
+      style: HeaderStyle::Simple,
+      parameter_data,
+    } => (None, "simple", parameter_data),
+    Parameter::Cookie {
+      style: CookieStyle::Form,
+      parameter_data,
+    } => (None, "form", parameter_data),
+  };
+
+  if let Some(style) = style {
+    return Some(format!(
+      "API Gateway does not support parameter style `{style}` (only `{default_style}`)"
+    ));
+  }
+
+  let default_explode = default_style == "form";
+  match parameter_data.explode {
+    Some(explode) if explode != default_explode => Some(format!(
+      "API Gateway does not support `explode: {explode}` for parameters with style \
+       `{default_style}`"
+    )),
+    _ => None,
+  }
+}
+
+/// Recursively find every schema within `value` that uses `exclusiveMinimum`, invoking `report`
+/// with the JSON Pointer reference tokens of the offending keyword.
+fn find_unsupported_schema_keywords<F>(value: &Value, pointer: &mut Vec<String>, report: &mut F)
+where
+  F: FnMut(&[String]),
+{
+  match value {
+    Value::Object(object) => {
+      for (key, child) in object {
+        if INSTANCE_KEYWORDS.contains(&key.as_str()) || key.starts_with("x-") {
+          continue;
+        }
+
+        pointer.push(key.clone());
+        if key == "exclusiveMinimum" && *child == Value::Bool(true) {
+          report(pointer);
+        } else {
+          find_unsupported_schema_keywords(child, pointer, report);
+        }
+        pointer.pop();
+      }
+    }
+    Value::Array(values) => {
+      for (index, child) in values.iter().enumerate() {
+        pointer.push(index.to_string());
+        find_unsupported_schema_keywords(child, pointer, report);
+        pointer.pop();
+      }
+    }
+    Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::CodeGenerator;
+
+  use openapiv3::OpenAPI;
+
+  #[test]
+  fn test_validate_openapi_apigw() {
+    let openapi = serde_yaml::from_str::<OpenAPI>(
+      r##"
+openapi: 3.0.3
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo/{fooId}:
+    parameters:
+      - name: fooId
+        in: path
+        required: true
+        style: label
+        schema:
+          type: string
+    get:
+      operationId: getFoo
+      parameters:
+        - name: tags
+          in: query
+          explode: false
+          schema:
+            type: array
+            items:
+              type: string
+        - name: limit
+          in: query
+          schema:
+            type: integer
+            minimum: 0
+            exclusiveMinimum: true
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Foo_Bar"
+  /bar-{barId}/baz:
+    get:
+      operationId: getBar
+      responses:
+        "204":
+          description: Success
+  /{proxy+}/baz:
+    get:
+      operationId: getProxy
+      responses:
+        "204":
+          description: Success
+  /ok/{proxy+}:
+    get:
+      operationId: getOk
+      responses:
+        "204":
+          description: Success
+components:
+  schemas:
+    Foo_Bar:
+      type: object
+      properties:
+        exclusiveMinimum:
+          type: number
+          example:
+            exclusiveMinimum: true
+"##,
+    )
+    .unwrap();
+
+    let issues = CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+      .validate_openapi_apigw(&openapi)
+      .into_iter()
+      .map(|issue| issue.to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      issues,
+      vec![
+        ".openapi-lambda/openapi-apigw.yaml#/paths/~1foo~1{fooId}/parameters/0: API Gateway does \
+         not support parameter style `label` (only `simple`)",
+        ".openapi-lambda/openapi-apigw.yaml#/paths/~1foo~1{fooId}/get/parameters/0: API Gateway \
+         does not support `explode: false` for parameters with style `form`",
+        ".openapi-lambda/openapi-apigw.yaml#/paths/~1bar-{barId}~1baz: API Gateway path \
+         parameters must span an entire path segment: found `bar-{barId}`",
+        ".openapi-lambda/openapi-apigw.yaml#/paths/~1{proxy+}~1baz: API Gateway greedy path \
+         parameter `{proxy+}` must be the last path segment",
+        ".openapi-lambda/openapi-apigw.yaml#/components/schemas/Foo_Bar: API Gateway model names \
+         may only contain alphanumeric characters",
+        ".openapi-lambda/openapi-apigw.yaml#/paths/~1foo~1{fooId}/get/parameters/1/schema/\
+         exclusiveMinimum: API Gateway does not support `exclusiveMinimum`",
+      ]
+    );
+  }
+}
@@ -0,0 +1,23 @@
+/// Source from which Amazon API Gateway reads the API key of each request to an API that requires
+/// [API keys](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-api-usage-plans.html).
+///
+/// See [`CodeGenerator::with_api_key_source`](crate::CodeGenerator::with_api_key_source).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ApiKeySource {
+  /// Read the API key from the `X-API-Key` request header (API Gateway's default).
+  Header,
+  /// Read the API key from the `usageIdentifierKey` returned by a Lambda
+  /// [`Authorizer`](crate::Authorizer).
+  Authorizer,
+}
+
+impl ApiKeySource {
+  /// Value of the `x-amazon-apigateway-api-key-source` extension.
+  pub(crate) fn as_str(&self) -> &'static str {
+    match self {
+      ApiKeySource::Header => "HEADER",
+      ApiKeySource::Authorizer => "AUTHORIZER",
+    }
+  }
+}
@@ -1,26 +1,59 @@
+use crate::api::body::is_binary_mime_type;
+use crate::api::AUTHORIZER_CLAIMS_EXTENSION;
 use crate::inline::InlineApi;
-use crate::{ApiLambda, CodeGenerator};
+use crate::model::{
+  ENUM_DESCRIPTIONS_EXTENSION, ENUM_VARNAMES_EXTENSION, RUST_DEFAULT_RESPONSE_EXTENSION,
+  RUST_DERIVES_EXTENSION, RUST_MODULE_EXTENSION, RUST_RENAME_ALL_EXTENSION, RUST_TYPE_EXTENSION,
+};
+use crate::{
+  ApiGatewayValidation, ApiLambda, CodeGenerator, CodegenError, CorsConfig, EventSource,
+  ValidationIssue,
+};
 
-use log::warn;
+use http::Method;
+use itertools::Itertools;
+use log::{info, warn};
 use openapiv3::{
-  AdditionalProperties, Callback, Components, Header, MediaType, ObjectType, Operation, Parameter,
-  ParameterSchemaOrContent, PathItem, ReferenceOr, RequestBody, Response, Responses, Schema,
-  SchemaKind, Type,
+  APIKeyLocation, AdditionalProperties, Callback, Components, Header, MediaType, ObjectType,
+  OpenAPI, Operation, Parameter, ParameterSchemaOrContent, PathItem, ReferenceOr, RequestBody,
+  Response, Responses, Schema, SchemaKind, SecurityScheme, Type,
 };
 use serde_json::json;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-const API_GATEWAY_INTEGRATION_EXTENTION: &str = "x-amazon-apigateway-integration";
-const OPENAPI_GW_FILENAME: &str = "openapi-apigw.yaml";
+const API_GATEWAY_API_KEY_SOURCE_EXTENSION: &str = "x-amazon-apigateway-api-key-source";
+const API_GATEWAY_AUTHORIZER_EXTENSION: &str = "x-amazon-apigateway-authorizer";
+const API_GATEWAY_AUTH_TYPE_EXTENSION: &str = "x-amazon-apigateway-authtype";
+const API_GATEWAY_BINARY_MEDIA_TYPES_EXTENSION: &str = "x-amazon-apigateway-binary-media-types";
+pub(crate) const API_GATEWAY_INTEGRATION_EXTENTION: &str = "x-amazon-apigateway-integration";
+const API_GATEWAY_REQUEST_VALIDATOR_EXTENSION: &str = "x-amazon-apigateway-request-validator";
+const API_GATEWAY_REQUEST_VALIDATORS_EXTENSION: &str = "x-amazon-apigateway-request-validators";
+const API_KEY_HEADER: &str = "x-api-key";
+pub(crate) const OPENAPI_GW_FILENAME: &str = "openapi-apigw.yaml";
 
 impl CodeGenerator {
+  /// Generates the contents of `openapi-apigw.yaml`.
   pub(crate) fn gen_openapi_apigw(
     &self,
     openapi: InlineApi,
     operation_id_to_api_lambda: &HashMap<&str, &ApiLambda>,
-  ) {
-    let openapi_for_apigw = transform_openapi(openapi, operation_id_to_api_lambda);
+  ) -> Result<String, CodegenError> {
+    let openapi_for_apigw = self.transform_openapi(openapi, operation_id_to_api_lambda)?;
+
+    match self.api_gateway_validation {
+      ApiGatewayValidation::None => {}
+      ApiGatewayValidation::Warn => self
+        .validate_openapi_apigw(&openapi_for_apigw)
+        .iter()
+        .for_each(|issue| warn!("{issue}")),
+      ApiGatewayValidation::Error => {
+        let issues = self.validate_openapi_apigw(&openapi_for_apigw);
+        if !issues.is_empty() {
+          return Err(CodegenError::ApiGatewayIncompatible(issues));
+        }
+      }
+    }
 
     let mut yaml_bytes = Vec::new();
     serde_path_to_error::serialize(
@@ -29,91 +62,532 @@ impl CodeGenerator {
     )
     .expect("failed to serialize processed OpenAPI spec");
 
-    let openapi_apigw_path = self.out_dir.join(OPENAPI_GW_FILENAME);
-    std::fs::write(&openapi_apigw_path, &yaml_bytes).unwrap_or_else(|err| {
-      panic!(
-        "failed to write OpenAPI spec to `{}`: {err}",
-        openapi_apigw_path.display()
-      )
-    });
+    Ok(String::from_utf8(yaml_bytes).expect("serialized YAML should be UTF-8"))
   }
-}
 
-/// Process an OpenAPI definition and perform the following transformations:
-///  * Insert `x-amazon-apigateway-integration` extensions into each path item whose
-///    `operation_id` is mapped to an [`ApiLambda`].
-///  * Remove operations whose `operation_id` is not mapped to an [`ApiLambda`], and path items
-///    that are empty after removing unmapped operations.
-///  * Removes `discriminator` values and makes sure the corresponding fields are required. See
-///    <https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html#api-gateway-known-issues-rest-apis>.
-///    The serde deserializer will still follow the original schema and reject any invalid request
-///    schemas. Response schemas serialized by serde will likewise follow the original schema.
-fn transform_openapi(
-  mut openapi: InlineApi,
-  operation_id_to_api_lambda: &HashMap<&str, &ApiLambda>,
-) -> InlineApi {
-  if let Some(components) = &mut openapi.components {
-    transform_components(components);
-  }
-
-  let mut paths_to_remove = Vec::new();
-  let mut visited_operation_ids = HashSet::new();
-  for (path, path_item) in &mut openapi.paths.paths {
-    // Don't follow and update references here since we should reach the reference target directly
-    // (e.g., when visiting components above).
-    let ReferenceOr::Item(path_item) = path_item else {
-      continue;
+  /// Whether the operation's existing `x-amazon-apigateway-integration` extension is preserved
+  /// (see [`CodeGenerator::with_preserve_integrations`]), in which case API Gateway doesn't invoke
+  /// the generated Lambda handler for it.
+  pub(crate) fn is_integration_preserved(&self, op: &Operation) -> bool {
+    self.preserve_integrations
+      && op
+        .extensions
+        .contains_key(API_GATEWAY_INTEGRATION_EXTENTION)
+  }
+
+  /// Process an OpenAPI definition and perform the following transformations:
+  ///  * Insert `x-amazon-apigateway-integration` extensions into each path item whose
+  ///    `operation_id` is mapped to an [`ApiLambda`]. Integrations for API Lambdas handling HTTP API
+  ///    events use payload format version 2.0, and integrations for API Lambdas with response
+  ///    streaming enabled use the `STREAM` response transfer mode. If
+  ///    [`CodeGenerator::with_preserve_integrations`] is enabled, operations that already define an
+  ///    `x-amazon-apigateway-integration` extension are left unchanged.
+  ///  * Insert `OPTIONS` operations with API Gateway mock integrations that respond to CORS
+  ///    preflight requests for each path handled by a REST API Lambda configured with
+  ///    [`ApiLambda::with_cors`], unless the path already defines an `OPTIONS` operation.
+  ///  * Insert `x-amazon-apigateway-authorizer` extensions into each security scheme configured via
+  ///    [`CodeGenerator::with_authorizer`], and copy any top-level `security` requirements to each
+  ///    operation that doesn't define its own.
+  ///  * Move other `apiKey` security schemes to the `x-api-key` header so that API Gateway requires
+  ///    an API key for each operation referencing them, and insert the
+  ///    `x-amazon-apigateway-api-key-source` extension configured via
+  ///    [`CodeGenerator::with_api_key_source`].
+  ///  * Insert the `x-amazon-apigateway-binary-media-types` extension, either as configured via
+  ///    [`CodeGenerator::with_binary_media_types`] or inferred from the request and response body
+  ///    media types that the generated code treats as binary.
+  ///  * Insert the `x-amazon-apigateway-request-validators` extension and reference it from each
+  ///    operation handled by a REST API Lambda, as configured via
+  ///    [`CodeGenerator::with_request_validation`].
+  ///  * Remove operations whose `operation_id` is not mapped to an [`ApiLambda`] (unless their
+  ///    integration is preserved) or is mapped to an [`ApiLambda`] that handles Application Load
+  ///    Balancer events or streams responses to HTTP API (i.e., Lambda function URL) events, and path
+  ///    items that are empty after removing these operations.
+  ///  * Removes `discriminator` values and makes sure the corresponding fields are required. See
+  ///    <https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html#api-gateway-known-issues-rest-apis>.
+  ///    The serde deserializer will still follow the original schema and reject any invalid request
+  ///    schemas. Response schemas serialized by serde will likewise follow the original schema.
+  ///  * Removes `x-enum-descriptions`, `x-enum-varnames`, `x-rust-default-response`,
+  ///    `x-rust-derives`, `x-rust-rename-all`, `x-rust-type`, and `x-rust-module` schema extensions,
+  ///    which only affect the generated Rust models.
+  ///  * Removes `x-authorizer-claims` security scheme extensions, which only affect the generated
+  ///    Rust code.
+  fn transform_openapi(
+    &self,
+    mut openapi: InlineApi,
+    operation_id_to_api_lambda: &HashMap<&str, &ApiLambda>,
+  ) -> Result<InlineApi, CodegenError> {
+    if let Some(components) = &mut openapi.components {
+      transform_components(components);
+      self.transform_security_schemes(components)?;
+    }
+
+    if let Some(api_key_source) = self.api_key_source {
+      openapi.extensions.insert(
+        API_GATEWAY_API_KEY_SOURCE_EXTENSION.to_string(),
+        json!(api_key_source.as_str()),
+      );
+    }
+
+    // API Gateway applies authorizers and API key requirements per method, so inherit any
+    // top-level security requirements.
+    let has_api_key_schemes = openapi.components.as_ref().map_or(false, |components| {
+      components
+        .security_schemes
+        .iter()
+        .any(|(scheme_name, security_scheme)| {
+          !self.authorizers.contains_key(scheme_name)
+            && matches!(
+              security_scheme,
+              ReferenceOr::Item(SecurityScheme::APIKey { .. })
+            )
+        })
+    });
+    let root_security = if self.authorizers.is_empty() && !has_api_key_schemes {
+      None
+    } else {
+      openapi.security.clone()
     };
-    transform_path_item(path_item);
-
-    for (method, operation) in [
-      ("GET", &mut path_item.get),
-      ("PUT", &mut path_item.put),
-      ("POST", &mut path_item.post),
-      ("DELETE", &mut path_item.delete),
-      ("OPTIONS", &mut path_item.options),
-      ("HEAD", &mut path_item.head),
-      ("PATCH", &mut path_item.patch),
-      ("TRACE", &mut path_item.trace),
-    ]
-    .into_iter()
-    {
-      if let Some(op) = operation {
-        if let Some(operation_id) = &op.operation_id {
-          if !visited_operation_ids.insert(operation_id.to_owned()) {
-            panic!("duplicate operation_id `{operation_id}`");
+
+    // Request validators are only supported by REST APIs, so we set the validator for each
+    // operation handled by a REST API Lambda rather than setting a default for the whole API.
+    let request_validator = self.request_validation.validator();
+    if let Some((validator_name, validator)) = &request_validator {
+      openapi.extensions.insert(
+        API_GATEWAY_REQUEST_VALIDATORS_EXTENSION.to_string(),
+        json!({ *validator_name: validator }),
+      );
+    }
+
+    let mut paths_to_remove = Vec::new();
+    let mut visited_operation_ids = HashSet::new();
+    for (path, path_item) in &mut openapi.paths.paths {
+      // Don't follow and update references here since we should reach the reference target directly
+      // (e.g., when visiting components above).
+      let ReferenceOr::Item(path_item) = path_item else {
+        continue;
+      };
+      transform_path_item(path_item);
+
+      let mut path_cors = None;
+      for (method, operation) in [
+        ("GET", &mut path_item.get),
+        ("PUT", &mut path_item.put),
+        ("POST", &mut path_item.post),
+        ("DELETE", &mut path_item.delete),
+        ("OPTIONS", &mut path_item.options),
+        ("HEAD", &mut path_item.head),
+        ("PATCH", &mut path_item.patch),
+        ("TRACE", &mut path_item.trace),
+      ]
+      .into_iter()
+      {
+        if let Some(op) = operation {
+          let has_integration = op
+            .extensions
+            .contains_key(API_GATEWAY_INTEGRATION_EXTENTION);
+
+          if let Some(operation_id) = &op.operation_id {
+            if !visited_operation_ids.insert(operation_id.to_owned()) {
+              return Err(CodegenError::InvalidSpec(vec![ValidationIssue::new(
+                self.openapi_path.display().to_string(),
+                format!("duplicate operationId `{operation_id}`"),
+              )]));
+            }
+          }
+
+          if op.security.is_none() {
+            op.security = root_security.clone();
           }
-          if let Some(api_lambda) = operation_id_to_api_lambda.get(operation_id.as_str()) {
-            op.extensions.insert(
-              API_GATEWAY_INTEGRATION_EXTENTION.to_string(),
-              json!({
-                "httpMethod": "POST",
-                "type": "aws_proxy",
-                "uri": api_lambda.lambda_arn.apigw_invocation_arn()
-              }),
-            );
+
+          if self.is_integration_preserved(op) {
+            info!("preserving existing integration for endpoint: {method} {path}");
+          } else if let Some(operation_id) = &op.operation_id {
+            if let Some(api_lambda) = operation_id_to_api_lambda.get(operation_id.as_str()) {
+              if api_lambda.event_source == EventSource::ApplicationLoadBalancer {
+                info!(
+                  "removing endpoint handled by Application Load Balancer: {method} {path} \
+                 ({operation_id})"
+                );
+                *operation = None;
+                continue;
+              }
+
+              if api_lambda.response_streaming
+                && api_lambda.event_source == EventSource::ApiGatewayV2Http
+              {
+                info!(
+                  "removing endpoint handled by Lambda function URL with response streaming: \
+                 {method} {path} ({operation_id})"
+                );
+                *operation = None;
+                continue;
+              }
+
+              if has_integration {
+                warn!(
+                  "overwriting existing integration for endpoint: {method} {path} ({operation_id})"
+                );
+              }
+
+              let invocation_arn = api_lambda.apigw_invocation_arn(Some(op));
+              op.extensions.insert(
+                API_GATEWAY_INTEGRATION_EXTENTION.to_string(),
+                lambda_integration(api_lambda, invocation_arn),
+              );
+
+              if let (Some((validator_name, _)), EventSource::ApiGatewayProxy) =
+                (&request_validator, &api_lambda.event_source)
+              {
+                op.extensions.insert(
+                  API_GATEWAY_REQUEST_VALIDATOR_EXTENSION.to_string(),
+                  json!(validator_name),
+                );
+              }
+
+              if let (Some(cors), EventSource::ApiGatewayProxy) =
+                (&api_lambda.cors, &api_lambda.event_source)
+              {
+                match path_cors {
+                  Some(path_cors) if path_cors != cors => {
+                    return Err(CodegenError::InvalidConfig(format!(
+                      "conflicting CORS configurations for path `{path}`, whose operations are \
+                       handled by multiple API Lambdas"
+                    )));
+                  }
+                  _ => path_cors = Some(cors),
+                }
+              }
+            } else {
+              warn!("removing endpoint not mapped to any API: {method} {path} ({operation_id})");
+              *operation = None;
+            }
           } else {
-            warn!("removing endpoint not mapped to any API: {method} {path} ({operation_id})");
+            warn!("removing endpoint without operation_id: {method} {path}");
             *operation = None;
           }
-        } else {
-          warn!("removing endpoint without operation_id: {method} {path}");
-          *operation = None;
         }
       }
+
+      // Paths with an explicit `OPTIONS` operation are left to the user's integration instead.
+      if let (Some(cors), None) = (path_cors, &path_item.options) {
+        let allowed_methods = path_item
+          .iter()
+          .map(|(method, _)| method.to_uppercase())
+          .chain(std::iter::once("OPTIONS".to_string()))
+          .sorted()
+          .join(", ");
+        info!("adding CORS preflight integration for path: {path}");
+        path_item.options = Some(cors_preflight_operation(cors, &allowed_methods));
+      }
+
+      // If we remove all of the methods, we should remove the path altogether.
+      if path_item.iter().next().is_none() {
+        paths_to_remove.push(path.to_owned());
+      }
+    }
+
+    for path in &paths_to_remove {
+      openapi.paths.paths.shift_remove(path);
+    }
+
+    for api_lambda in self.api_lambdas.values() {
+      // These endpoints aren't served by API Gateway (see above).
+      if !api_lambda.is_api_gateway_integration() {
+        continue;
+      }
+
+      for route in &api_lambda.extra_routes {
+        let ReferenceOr::Item(path_item) = openapi
+          .paths
+          .paths
+          .entry(route.path.clone())
+          .or_insert_with(|| ReferenceOr::Item(PathItem::default()))
+        else {
+          return Err(CodegenError::InvalidConfig(format!(
+            "extra route path `{}` is defined in the OpenAPI definition as a reference",
+            route.path
+          )));
+        };
+        let operation = match route.method {
+          Method::GET => &mut path_item.get,
+          Method::PUT => &mut path_item.put,
+          Method::POST => &mut path_item.post,
+          Method::DELETE => &mut path_item.delete,
+          Method::OPTIONS => &mut path_item.options,
+          Method::HEAD => &mut path_item.head,
+          Method::PATCH => &mut path_item.patch,
+          Method::TRACE => &mut path_item.trace,
+          _ => unreachable!("extra routes should have been validated"),
+        };
+        if operation.is_some() {
+          return Err(CodegenError::InvalidConfig(format!(
+            "extra route {} {} conflicts with an endpoint in the OpenAPI definition",
+            route.method, route.path
+          )));
+        }
+
+        info!(
+          "adding integration for extra route: {} {}",
+          route.method, route.path
+        );
+        *operation = Some(extra_route_operation(api_lambda));
+      }
     }
 
-    // If we remove all of the methods, we should remove the path altogether.
-    if path_item.iter().next().is_none() {
-      paths_to_remove.push(path.to_owned());
+    let binary_media_types = self
+      .binary_media_types
+      .clone()
+      .unwrap_or_else(|| infer_binary_media_types(&openapi));
+    if !binary_media_types.is_empty() {
+      openapi.extensions.insert(
+        API_GATEWAY_BINARY_MEDIA_TYPES_EXTENSION.to_string(),
+        json!(binary_media_types),
+      );
     }
+
+    Ok(openapi)
+  }
+
+  /// Populates the security schemes configured via [`CodeGenerator::with_authorizer`] with the
+  /// corresponding API Gateway authorizer extensions.
+  fn transform_security_schemes(&self, components: &mut Components) -> Result<(), CodegenError> {
+    // API Gateway requires API keys to be passed in the `x-api-key` header (or returned by an
+    // authorizer), and it requires an API key for each method whose security requirements
+    // reference an `apiKey` security scheme with this name.
+    for security_scheme in components.security_schemes.values_mut() {
+      if let ReferenceOr::Item(
+        SecurityScheme::APIKey { extensions, .. }
+        | SecurityScheme::HTTP { extensions, .. }
+        | SecurityScheme::OAuth2 { extensions, .. }
+        | SecurityScheme::OpenIDConnect { extensions, .. },
+      ) = security_scheme
+      {
+        extensions.shift_remove(AUTHORIZER_CLAIMS_EXTENSION);
+      }
+    }
+
+    for (scheme_name, security_scheme) in &mut components.security_schemes {
+      if self.authorizers.contains_key(scheme_name) {
+        continue;
+      }
+      if let ReferenceOr::Item(SecurityScheme::APIKey { location, name, .. }) = security_scheme {
+        if *location != APIKeyLocation::Header || !name.eq_ignore_ascii_case(API_KEY_HEADER) {
+          warn!(
+            "API Gateway only supports API keys in the `{API_KEY_HEADER}` header; replacing \
+             location of API key security scheme `{scheme_name}`"
+          );
+          *location = APIKeyLocation::Header;
+          *name = API_KEY_HEADER.to_string();
+        }
+      }
+    }
+
+    for (scheme_name, authorizer) in &self.authorizers {
+      let Some(ReferenceOr::Item(security_scheme)) =
+        components.security_schemes.get_mut(scheme_name)
+      else {
+        return Err(CodegenError::InvalidConfig(format!(
+          "authorizer is configured for security scheme `{scheme_name}`, which is not defined in \
+           `components.securitySchemes`"
+        )));
+      };
+
+      // API Gateway only supports authorizers on `apiKey` security schemes, so we replace the
+      // scheme type (e.g., `http` or `oauth2`) while preserving its description and extensions.
+      let (description, mut extensions) = match std::mem::replace(
+        security_scheme,
+        SecurityScheme::APIKey {
+          location: APIKeyLocation::Header,
+          name: String::new(),
+          description: None,
+          extensions: Default::default(),
+        },
+      ) {
+        SecurityScheme::APIKey {
+          description,
+          extensions,
+          ..
+        }
+        | SecurityScheme::HTTP {
+          description,
+          extensions,
+          ..
+        }
+        | SecurityScheme::OAuth2 {
+          description,
+          extensions,
+          ..
+        }
+        | SecurityScheme::OpenIDConnect {
+          description,
+          extensions,
+          ..
+        } => (description, extensions),
+      };
+      extensions.insert(
+        API_GATEWAY_AUTH_TYPE_EXTENSION.to_string(),
+        json!(authorizer.auth_type()),
+      );
+      extensions.insert(
+        API_GATEWAY_AUTHORIZER_EXTENSION.to_string(),
+        authorizer.extension(),
+      );
+
+      *security_scheme = SecurityScheme::APIKey {
+        location: APIKeyLocation::Header,
+        name: authorizer.header_name().to_string(),
+        description,
+        extensions,
+      };
+    }
+
+    Ok(())
+  }
+}
+
+/// Returns the sorted media types of the request and response bodies that the generated code
+/// represents as raw byte strings.
+///
+/// Request bodies and responses defined in `components` are included even if no remaining
+/// operation references them, which is harmless since API Gateway only uses this list to decide
+/// whether to base64-encode request and response payloads.
+fn infer_binary_media_types(openapi: &OpenAPI) -> Vec<String> {
+  let operations = openapi
+    .paths
+    .paths
+    .values()
+    .filter_map(ReferenceOr::as_item)
+    .flat_map(|path_item| path_item.iter().map(|(_, op)| op))
+    .collect::<Vec<_>>();
+
+  let request_bodies = operations
+    .iter()
+    .filter_map(|op| op.request_body.as_ref())
+    .chain(
+      openapi
+        .components
+        .iter()
+        .flat_map(|components| components.request_bodies.values()),
+    )
+    .filter_map(ReferenceOr::as_item)
+    .flat_map(|request_body| request_body.content.keys());
+
+  let responses = operations
+    .iter()
+    .flat_map(|op| {
+      op.responses
+        .default
+        .iter()
+        .chain(op.responses.responses.values())
+    })
+    .chain(
+      openapi
+        .components
+        .iter()
+        .flat_map(|components| components.responses.values()),
+    )
+    .filter_map(ReferenceOr::as_item)
+    .flat_map(|response| response.content.keys());
+
+  request_bodies
+    .chain(responses)
+    .filter(|mime_type| is_binary_mime_type(mime_type))
+    .cloned()
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect()
+}
+
+/// Returns the `x-amazon-apigateway-integration` that invokes the Lambda function identified by
+/// `lambda_arn` for an endpoint handled by `api_lambda`.
+fn lambda_integration(
+  api_lambda: &ApiLambda,
+  invocation_arn: serde_json::Value,
+) -> serde_json::Value {
+  let mut integration = json!({
+    "httpMethod": "POST",
+    "type": "aws_proxy",
+    "uri": invocation_arn,
+  });
+  if api_lambda.event_source == EventSource::ApiGatewayV2Http {
+    integration["payloadFormatVersion"] = json!("2.0");
+  }
+  if api_lambda.response_streaming {
+    integration["responseTransferMode"] = json!("STREAM");
   }
+  integration
+}
+
+/// Returns an unauthenticated operation that invokes the Lambda function for an extra route
+/// registered via [`ApiLambda::with_extra_route`].
+fn extra_route_operation(api_lambda: &ApiLambda) -> Operation {
+  serde_json::from_value(json!({
+    "security": [],
+    "responses": {
+      "default": {
+        "description": "Response from extra route handler",
+      }
+    },
+    API_GATEWAY_INTEGRATION_EXTENTION: lambda_integration(
+      api_lambda,
+      api_lambda.apigw_invocation_arn(None),
+    ),
+  }))
+  .expect("invalid extra route operation")
+}
 
-  for path in &paths_to_remove {
-    openapi.paths.paths.remove(path);
+/// Returns an `OPTIONS` operation that responds to CORS preflight requests using an API Gateway
+/// mock integration, without invoking the Lambda function.
+fn cors_preflight_operation(cors: &CorsConfig, allowed_methods: &str) -> Operation {
+  let mut headers = vec![
+    ("Access-Control-Allow-Methods", allowed_methods.to_string()),
+    ("Access-Control-Allow-Origin", cors.allow_origin.clone()),
+    ("Allow", allowed_methods.to_string()),
+  ];
+  if let Some(allow_headers) = cors.allow_headers_value() {
+    headers.push(("Access-Control-Allow-Headers", allow_headers));
   }
+  if cors.allow_credentials {
+    headers.push(("Access-Control-Allow-Credentials", "true".to_string()));
+  }
+  if let Some(max_age) = cors.max_age {
+    headers.push(("Access-Control-Max-Age", max_age.as_secs().to_string()));
+  }
+  headers.sort();
 
-  openapi
+  serde_json::from_value(json!({
+    "summary": "CORS preflight",
+    "security": [],
+    "responses": {
+      "204": {
+        "description": "CORS preflight response",
+        "headers": headers
+          .iter()
+          .map(|(name, _)| (name.to_string(), json!({ "schema": { "type": "string" } })))
+          .collect::<serde_json::Map<_, _>>(),
+      }
+    },
+    API_GATEWAY_INTEGRATION_EXTENTION: {
+      "type": "mock",
+      "passthroughBehavior": "when_no_match",
+      "requestTemplates": {
+        "application/json": "{\"statusCode\": 204}"
+      },
+      "responses": {
+        "default": {
+          "statusCode": "204",
+          "responseParameters": headers
+            .iter()
+            .map(|(name, value)| {
+              // Static header values must be enclosed in single quotes.
+              (format!("method.response.header.{name}"), json!(format!("'{value}'")))
+            })
+            .collect::<serde_json::Map<_, _>>(),
+        }
+      }
+    }
+  }))
+  .expect("invalid CORS preflight operation")
 }
 
 fn transform_components(components: &mut Components) {
@@ -375,4 +849,777 @@ fn transform_schema(schema: &mut Schema) {
   }
 
   schema.schema_data.discriminator = None;
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(ENUM_DESCRIPTIONS_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(ENUM_VARNAMES_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(RUST_DEFAULT_RESPONSE_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(RUST_DERIVES_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(RUST_RENAME_ALL_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(RUST_TYPE_EXTENSION);
+  schema
+    .schema_data
+    .extensions
+    .shift_remove(RUST_MODULE_EXTENSION);
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::apigw::{
+    API_GATEWAY_API_KEY_SOURCE_EXTENSION, API_GATEWAY_AUTHORIZER_EXTENSION,
+    API_GATEWAY_AUTH_TYPE_EXTENSION, API_GATEWAY_BINARY_MEDIA_TYPES_EXTENSION,
+    API_GATEWAY_INTEGRATION_EXTENTION, API_GATEWAY_REQUEST_VALIDATORS_EXTENSION,
+    API_GATEWAY_REQUEST_VALIDATOR_EXTENSION,
+  };
+  use crate::{
+    ApiKeySource, ApiLambda, Authorizer, CodeGenerator, CodegenError, CorsConfig, DocCache,
+    EventSource, LambdaArn, Method, RequestValidation, UserPoolArn,
+  };
+
+  use openapiv3::{
+    APIKeyLocation, Callback, OpenAPI, SecurityRequirement, SecurityScheme, StatusCode,
+  };
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use std::collections::HashMap;
+  use std::time::Duration;
+
+  const OPENAPI_YAML: &str = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses: {}
+    post:
+      operationId: createFoo
+      responses: {}
+      x-amazon-apigateway-integration:
+        type: mock
+        requestTemplates:
+          application/json: '{"statusCode": 200}'
+  /bar:
+    get:
+      operationId: getBar
+      responses: {}
+      x-amazon-apigateway-integration:
+        type: http_proxy
+        httpMethod: GET
+        uri: https://example.com/bar
+"#;
+
+  fn transform(preserve_integrations: bool) -> OpenAPI {
+    transform_with_api_lambda(
+      preserve_integrations,
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    )
+  }
+
+  fn transform_with_api_lambda(preserve_integrations: bool, api_lambda: ApiLambda) -> OpenAPI {
+    transform_with_code_generator(
+      OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+        .with_preserve_integrations(preserve_integrations),
+      api_lambda,
+    )
+  }
+
+  fn transform_with_code_generator(
+    openapi_yaml: &str,
+    code_generator: CodeGenerator,
+    api_lambda: ApiLambda,
+  ) -> OpenAPI {
+    try_transform_with_code_generator(openapi_yaml, code_generator, api_lambda).unwrap()
+  }
+
+  fn try_transform_with_code_generator(
+    openapi_yaml: &str,
+    code_generator: CodeGenerator,
+    api_lambda: ApiLambda,
+  ) -> Result<OpenAPI, CodegenError> {
+    let openapi = serde_yaml::from_str::<OpenAPI>(openapi_yaml).unwrap();
+    let operation_id_to_api_lambda =
+      HashMap::from([("getFoo", &api_lambda), ("createFoo", &api_lambda)]);
+
+    let inline_openapi = code_generator
+      .inline_openapi(openapi, Callback::new(), DocCache::new())
+      .unwrap();
+    let transformed =
+      code_generator.transform_openapi(inline_openapi, &operation_id_to_api_lambda)?;
+    Ok((*transformed).clone())
+  }
+
+  fn integration(openapi: &OpenAPI, path: &str, method: &str) -> Option<serde_json::Value> {
+    let path_item = openapi.paths.paths.get(path)?.as_item().unwrap();
+    path_item
+      .iter()
+      .find(|(op_method, _)| *op_method == method)
+      .and_then(|(_, op)| op.extensions.get(API_GATEWAY_INTEGRATION_EXTENTION))
+      .cloned()
+  }
+
+  fn lambda_integration() -> serde_json::Value {
+    json!({
+      "httpMethod": "POST",
+      "type": "aws_proxy",
+      "uri": {
+        "Fn::Sub": "arn:aws:apigateway:${AWS::Region}:lambda:path/2015-03-31/functions/\
+          ${FooApiFunction.Alias}/invocations"
+      }
+    })
+  }
+
+  #[test]
+  fn test_preserve_integrations() {
+    let openapi = transform(true);
+
+    assert_eq!(
+      integration(&openapi, "/foo", "get"),
+      Some(lambda_integration())
+    );
+    assert_eq!(
+      integration(&openapi, "/foo", "post"),
+      Some(json!({
+        "type": "mock",
+        "requestTemplates": {
+          "application/json": "{\"statusCode\": 200}"
+        }
+      }))
+    );
+    // Endpoints with preserved integrations are kept even if they aren't mapped to an API Lambda.
+    assert_eq!(
+      integration(&openapi, "/bar", "get"),
+      Some(json!({
+        "type": "http_proxy",
+        "httpMethod": "GET",
+        "uri": "https://example.com/bar"
+      }))
+    );
+  }
+
+  #[test]
+  fn test_overwrite_integrations() {
+    let openapi = transform(false);
+
+    assert_eq!(
+      integration(&openapi, "/foo", "get"),
+      Some(lambda_integration())
+    );
+    assert_eq!(
+      integration(&openapi, "/foo", "post"),
+      Some(lambda_integration())
+    );
+    assert!(!openapi.paths.paths.contains_key("/bar"));
+  }
+
+  #[test]
+  fn test_arn_override() {
+    let openapi = transform_with_api_lambda(
+      false,
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")).with_arn_override(
+        |op| {
+          (op.operation_id.as_deref() == Some("createFoo"))
+            .then(|| LambdaArn::cloud_formation("CreateFooApiFunction.Alias"))
+        },
+      ),
+    );
+
+    assert_eq!(
+      integration(&openapi, "/foo", "get"),
+      Some(lambda_integration())
+    );
+    assert_eq!(
+      integration(&openapi, "/foo", "post"),
+      Some(json!({
+        "httpMethod": "POST",
+        "type": "aws_proxy",
+        "uri": {
+          "Fn::Sub": "arn:aws:apigateway:${AWS::Region}:lambda:path/2015-03-31/functions/\
+            ${CreateFooApiFunction.Alias}/invocations"
+        }
+      }))
+    );
+  }
+
+  #[test]
+  fn test_extra_routes() {
+    let openapi = transform_with_code_generator(
+      OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+        .add_api_lambda(
+          ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+            .with_extra_route(Method::GET, "/health", "crate::health_check")
+            .with_extra_route(Method::HEAD, "/foo", "crate::health_check"),
+        )
+        .add_api_lambda(
+          ApiLambda::new("alb", LambdaArn::cloud_formation("AlbApiFunction.Alias"))
+            .with_event_source(EventSource::ApplicationLoadBalancer)
+            .with_extra_route(Method::GET, "/alb-health", "crate::health_check"),
+        ),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    );
+
+    assert_eq!(
+      integration(&openapi, "/health", "get"),
+      Some(lambda_integration())
+    );
+    assert_eq!(
+      openapi.paths.paths["/health"]
+        .as_item()
+        .unwrap()
+        .get
+        .as_ref()
+        .unwrap()
+        .security,
+      Some(vec![])
+    );
+    // Extra routes may share a path with operations defined in the OpenAPI definition.
+    assert_eq!(
+      integration(&openapi, "/foo", "head"),
+      Some(lambda_integration())
+    );
+    assert_eq!(
+      integration(&openapi, "/foo", "get"),
+      Some(lambda_integration())
+    );
+    // Load balancers don't use API Gateway.
+    assert!(!openapi.paths.paths.contains_key("/alb-health"));
+  }
+
+  #[test]
+  fn test_extra_route_conflict() {
+    let err =
+      try_transform_with_code_generator(
+        OPENAPI_YAML,
+        CodeGenerator::new("openapi.yaml", ".openapi-lambda").add_api_lambda(
+          ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+            .with_extra_route(Method::GET, "/foo", "crate::health_check"),
+        ),
+        ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+      )
+      .unwrap_err();
+    assert_eq!(
+      err,
+      CodegenError::InvalidConfig(
+        "extra route GET /foo conflicts with an endpoint in the OpenAPI definition".to_string()
+      )
+    );
+  }
+
+  #[test]
+  fn test_cors_preflight() {
+    let openapi = transform_with_api_lambda(
+      false,
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")).with_cors(
+        CorsConfig::new("https://example.com")
+          .with_allow_credentials(true)
+          .with_allow_headers(["Authorization", "Content-Type"])
+          .with_max_age(Duration::from_secs(600)),
+      ),
+    );
+
+    assert_eq!(
+      integration(&openapi, "/foo", "options"),
+      Some(json!({
+        "type": "mock",
+        "passthroughBehavior": "when_no_match",
+        "requestTemplates": {
+          "application/json": "{\"statusCode\": 204}"
+        },
+        "responses": {
+          "default": {
+            "statusCode": "204",
+            "responseParameters": {
+              "method.response.header.Access-Control-Allow-Credentials": "'true'",
+              "method.response.header.Access-Control-Allow-Headers":
+                "'Authorization, Content-Type'",
+              "method.response.header.Access-Control-Allow-Methods": "'GET, OPTIONS, POST'",
+              "method.response.header.Access-Control-Allow-Origin": "'https://example.com'",
+              "method.response.header.Access-Control-Max-Age": "'600'",
+              "method.response.header.Allow": "'GET, OPTIONS, POST'",
+            }
+          }
+        }
+      }))
+    );
+
+    let options = openapi.paths.paths["/foo"]
+      .as_item()
+      .unwrap()
+      .options
+      .as_ref()
+      .unwrap();
+    assert_eq!(options.security, Some(vec![]));
+    assert_eq!(
+      options.responses.responses[&StatusCode::Code(204)]
+        .as_item()
+        .unwrap()
+        .headers
+        .keys()
+        .collect::<Vec<_>>(),
+      vec![
+        "Access-Control-Allow-Credentials",
+        "Access-Control-Allow-Headers",
+        "Access-Control-Allow-Methods",
+        "Access-Control-Allow-Origin",
+        "Access-Control-Max-Age",
+        "Allow",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_no_cors_preflight_without_cors_config() {
+    let openapi = transform(false);
+
+    assert!(openapi.paths.paths["/foo"]
+      .as_item()
+      .unwrap()
+      .options
+      .is_none());
+  }
+
+  const AUTHORIZER_OPENAPI_YAML: &str = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+security:
+  - bearerAuth: []
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses: {}
+    post:
+      operationId: createFoo
+      responses: {}
+      security:
+        - {}
+components:
+  securitySchemes:
+    bearerAuth:
+      type: http
+      scheme: bearer
+      description: Bearer token
+    cognitoAuth:
+      type: oauth2
+      flows:
+        implicit:
+          authorizationUrl: https://example.com/oauth2/authorize
+          scopes: {}
+"#;
+
+  fn transform_with_authorizers(authorizers: Vec<(&str, Authorizer)>) -> OpenAPI {
+    let code_generator = authorizers.into_iter().fold(
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda"),
+      |code_generator, (security_scheme, authorizer)| {
+        code_generator.with_authorizer(security_scheme, authorizer)
+      },
+    );
+    transform_with_code_generator(
+      AUTHORIZER_OPENAPI_YAML,
+      code_generator,
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    )
+  }
+
+  fn security_scheme(openapi: &OpenAPI, name: &str) -> SecurityScheme {
+    openapi.components.as_ref().unwrap().security_schemes[name]
+      .as_item()
+      .unwrap()
+      .clone()
+  }
+
+  fn operation_security(
+    openapi: &OpenAPI,
+    path: &str,
+    method: &str,
+  ) -> Option<Vec<SecurityRequirement>> {
+    openapi.paths.paths[path]
+      .as_item()
+      .unwrap()
+      .iter()
+      .find(|(op_method, _)| *op_method == method)
+      .unwrap()
+      .1
+      .security
+      .clone()
+  }
+
+  #[test]
+  fn test_lambda_authorizer() {
+    let openapi = transform_with_authorizers(vec![(
+      "bearerAuth",
+      Authorizer::lambda_token(LambdaArn::cloud_formation("AuthorizerFunction.Alias"))
+        .with_result_ttl(Duration::from_secs(60)),
+    )]);
+
+    let SecurityScheme::APIKey {
+      location,
+      name,
+      description,
+      extensions,
+    } = security_scheme(&openapi, "bearerAuth")
+    else {
+      panic!("expected apiKey security scheme");
+    };
+    assert_eq!(location, APIKeyLocation::Header);
+    assert_eq!(name, "Authorization");
+    assert_eq!(description.as_deref(), Some("Bearer token"));
+    assert_eq!(extensions[API_GATEWAY_AUTH_TYPE_EXTENSION], json!("custom"));
+    assert_eq!(
+      extensions[API_GATEWAY_AUTHORIZER_EXTENSION],
+      json!({
+        "type": "token",
+        "authorizerUri": {
+          "Fn::Sub": "arn:aws:apigateway:${AWS::Region}:lambda:path/2015-03-31/functions/\
+            ${AuthorizerFunction.Alias}/invocations"
+        },
+        "identitySource": "method.request.header.Authorization",
+        "authorizerResultTtlInSeconds": 60,
+      })
+    );
+
+    // Top-level security requirements are copied to each operation without its own.
+    let bearer_auth = SecurityRequirement::from([("bearerAuth".to_string(), vec![])]);
+    assert_eq!(
+      operation_security(&openapi, "/foo", "get"),
+      Some(vec![bearer_auth])
+    );
+    assert_eq!(
+      operation_security(&openapi, "/foo", "post"),
+      Some(vec![SecurityRequirement::new()])
+    );
+  }
+
+  #[test]
+  fn test_request_authorizer_identity_source() {
+    let openapi = transform_with_authorizers(vec![(
+      "bearerAuth",
+      Authorizer::lambda_request(LambdaArn::cloud_formation("AuthorizerFunction.Alias"))
+        .with_identity_source("method.request.header.X-Api-Token"),
+    )]);
+
+    let SecurityScheme::APIKey {
+      name, extensions, ..
+    } = security_scheme(&openapi, "bearerAuth")
+    else {
+      panic!("expected apiKey security scheme");
+    };
+    assert_eq!(name, "X-Api-Token");
+    assert_eq!(
+      extensions[API_GATEWAY_AUTHORIZER_EXTENSION]["type"],
+      json!("request")
+    );
+    assert_eq!(
+      extensions[API_GATEWAY_AUTHORIZER_EXTENSION]["identitySource"],
+      json!("method.request.header.X-Api-Token")
+    );
+  }
+
+  #[test]
+  fn test_cognito_authorizer() {
+    let openapi = transform_with_authorizers(vec![(
+      "cognitoAuth",
+      Authorizer::cognito_user_pools([
+        UserPoolArn::cloud_formation("UserPool"),
+        UserPoolArn::known("arn:aws:cognito-idp:us-east-1:1234567890:userpool/us-east-1_abc"),
+      ]),
+    )]);
+
+    let SecurityScheme::APIKey { extensions, .. } = security_scheme(&openapi, "cognitoAuth") else {
+      panic!("expected apiKey security scheme");
+    };
+    assert_eq!(
+      extensions[API_GATEWAY_AUTH_TYPE_EXTENSION],
+      json!("cognito_user_pools")
+    );
+    assert_eq!(
+      extensions[API_GATEWAY_AUTHORIZER_EXTENSION],
+      json!({
+        "type": "cognito_user_pools",
+        "providerARNs": [
+          { "Fn::Sub": "${UserPool.Arn}" },
+          "arn:aws:cognito-idp:us-east-1:1234567890:userpool/us-east-1_abc",
+        ],
+        "identitySource": "method.request.header.Authorization",
+      })
+    );
+
+    // Security schemes without an authorizer are left unchanged.
+    assert!(matches!(
+      security_scheme(&openapi, "bearerAuth"),
+      SecurityScheme::HTTP { .. }
+    ));
+  }
+
+  #[test]
+  fn test_no_authorizers() {
+    let openapi = transform_with_code_generator(
+      AUTHORIZER_OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda"),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    );
+
+    assert_eq!(operation_security(&openapi, "/foo", "get"), None);
+  }
+
+  #[test]
+  fn test_authorizer_missing_security_scheme() {
+    let err = try_transform_with_code_generator(
+      AUTHORIZER_OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda").with_authorizer(
+        "missingAuth",
+        Authorizer::lambda_token(LambdaArn::cloud_formation("AuthorizerFunction.Alias")),
+      ),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    )
+    .unwrap_err();
+    assert_eq!(
+      err,
+      CodegenError::InvalidConfig(
+        "authorizer is configured for security scheme `missingAuth`, which is not defined in \
+         `components.securitySchemes`"
+          .to_string()
+      )
+    );
+  }
+
+  const API_KEY_OPENAPI_YAML: &str = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+security:
+  - apiKeyAuth: []
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses: {}
+    post:
+      operationId: createFoo
+      responses: {}
+      security:
+        - {}
+components:
+  securitySchemes:
+    apiKeyAuth:
+      type: apiKey
+      in: query
+      name: api_key
+"#;
+
+  #[test]
+  fn test_api_key_required() {
+    let openapi = transform_with_code_generator(
+      API_KEY_OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+        .with_api_key_source(ApiKeySource::Authorizer),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    );
+
+    assert_eq!(
+      openapi.extensions.get(API_GATEWAY_API_KEY_SOURCE_EXTENSION),
+      Some(&json!("AUTHORIZER"))
+    );
+
+    let SecurityScheme::APIKey { location, name, .. } = security_scheme(&openapi, "apiKeyAuth")
+    else {
+      panic!("expected apiKey security scheme");
+    };
+    assert_eq!(location, APIKeyLocation::Header);
+    assert_eq!(name, "x-api-key");
+
+    assert_eq!(
+      operation_security(&openapi, "/foo", "get"),
+      Some(vec![SecurityRequirement::from([(
+        "apiKeyAuth".to_string(),
+        vec![]
+      )])])
+    );
+    assert_eq!(
+      operation_security(&openapi, "/foo", "post"),
+      Some(vec![SecurityRequirement::new()])
+    );
+  }
+
+  #[test]
+  fn test_default_api_key_source() {
+    let openapi = transform_with_code_generator(
+      API_KEY_OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda"),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    );
+
+    assert!(!openapi
+      .extensions
+      .contains_key(API_GATEWAY_API_KEY_SOURCE_EXTENSION));
+  }
+
+  const BINARY_OPENAPI_YAML: &str = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses:
+        "200":
+          description: Image
+          content:
+            image/png: {}
+        default:
+          description: Error
+          content:
+            application/json: {}
+            text/plain: {}
+    post:
+      operationId: createFoo
+      requestBody:
+        content:
+          application/octet-stream: {}
+      responses:
+        "200":
+          description: OK
+          content:
+            multipart/mixed:
+              schema:
+                type: object
+components:
+  responses:
+    FooThumbnail:
+      description: Thumbnail
+      content:
+        image/jpeg: {}
+"#;
+
+  fn binary_media_types(code_generator: CodeGenerator) -> Option<serde_json::Value> {
+    transform_with_code_generator(
+      BINARY_OPENAPI_YAML,
+      code_generator,
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    )
+    .extensions
+    .get(API_GATEWAY_BINARY_MEDIA_TYPES_EXTENSION)
+    .cloned()
+  }
+
+  #[test]
+  fn test_infer_binary_media_types() {
+    assert_eq!(
+      binary_media_types(CodeGenerator::new("openapi.yaml", ".openapi-lambda")),
+      Some(json!([
+        "application/octet-stream",
+        "image/jpeg",
+        "image/png",
+        "multipart/mixed"
+      ]))
+    );
+  }
+
+  #[test]
+  fn test_explicit_binary_media_types() {
+    assert_eq!(
+      binary_media_types(
+        CodeGenerator::new("openapi.yaml", ".openapi-lambda").with_binary_media_types(&["image/*"])
+      ),
+      Some(json!(["image/*"]))
+    );
+    assert_eq!(
+      binary_media_types(
+        CodeGenerator::new("openapi.yaml", ".openapi-lambda").with_binary_media_types(&[])
+      ),
+      None
+    );
+  }
+
+  fn request_validator(openapi: &OpenAPI, path: &str, method: &str) -> Option<serde_json::Value> {
+    openapi.paths.paths[path]
+      .as_item()
+      .unwrap()
+      .iter()
+      .find(|(op_method, _)| *op_method == method)
+      .unwrap()
+      .1
+      .extensions
+      .get(API_GATEWAY_REQUEST_VALIDATOR_EXTENSION)
+      .cloned()
+  }
+
+  #[test]
+  fn test_request_validation() {
+    let openapi = transform_with_code_generator(
+      OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+        .with_request_validation(RequestValidation::BodyOnly),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias")),
+    );
+
+    assert_eq!(
+      openapi
+        .extensions
+        .get(API_GATEWAY_REQUEST_VALIDATORS_EXTENSION),
+      Some(&json!({
+        "body-only": {
+          "validateRequestBody": true,
+          "validateRequestParameters": false,
+        }
+      }))
+    );
+    assert_eq!(
+      request_validator(&openapi, "/foo", "get"),
+      Some(json!("body-only"))
+    );
+    assert_eq!(
+      request_validator(&openapi, "/foo", "post"),
+      Some(json!("body-only"))
+    );
+  }
+
+  #[test]
+  fn test_request_validation_http_api() {
+    let openapi = transform_with_code_generator(
+      OPENAPI_YAML,
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+        .with_request_validation(RequestValidation::All),
+      ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+        .with_event_source(EventSource::ApiGatewayV2Http),
+    );
+
+    assert_eq!(request_validator(&openapi, "/foo", "get"), None);
+  }
+
+  #[test]
+  fn test_no_request_validation() {
+    let openapi = transform(false);
+
+    assert!(!openapi
+      .extensions
+      .contains_key(API_GATEWAY_REQUEST_VALIDATORS_EXTENSION));
+    assert_eq!(request_validator(&openapi, "/foo", "get"), None);
+  }
 }
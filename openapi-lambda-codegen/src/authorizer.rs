@@ -0,0 +1,188 @@
+use crate::{LambdaArn, LambdaArnImpl};
+
+use serde_json::json;
+
+use std::time::Duration;
+
+const AUTHORIZATION_HEADER_IDENTITY_SOURCE: &str = "method.request.header.Authorization";
+const IDENTITY_SOURCE_HEADER_PREFIX: &str = "method.request.header.";
+
+#[derive(Debug)]
+enum AuthorizerKind {
+  CognitoUserPools { user_pool_arns: Vec<UserPoolArn> },
+  LambdaRequest { lambda_arn: LambdaArnImpl },
+  LambdaToken { lambda_arn: LambdaArnImpl },
+}
+
+/// Amazon Resource Name (ARN) for an Amazon Cognito user pool.
+///
+/// Like [`LambdaArn`], this type represents an ARN either using variables (via the
+/// [`cloud_formation`](UserPoolArn::cloud_formation) method) or as a fully-resolved ARN (via the
+/// [`known`](UserPoolArn::known) method).
+#[derive(Debug)]
+pub struct UserPoolArn(serde_json::Value);
+
+impl UserPoolArn {
+  /// Construct a variable ARN that references the AWS CloudFormation logical ID of an
+  /// [`AWS::Cognito::UserPool`](https://docs.aws.amazon.com/AWSCloudFormation/latest/UserGuide/aws-resource-cognito-userpool.html)
+  /// resource.
+  ///
+  /// As with [`LambdaArn::cloud_formation`], the `openapi-apigw.yaml` file must be included in
+  /// your CloudFormation/SAM template with the `AWS::Include` transform so that the variable is
+  /// substituted during deployment.
+  pub fn cloud_formation<L>(logical_id: L) -> Self
+  where
+    L: Into<String>,
+  {
+    Self(json!({ "Fn::Sub": format!("${{{}.Arn}}", logical_id.into()) }))
+  }
+
+  /// Construct a fully-resolved user pool ARN (e.g.,
+  /// `arn:aws:cognito-idp:us-east-1:1234567890:userpool/us-east-1_abcdefghi`).
+  pub fn known<A>(arn: A) -> Self
+  where
+    A: Into<String>,
+  {
+    Self(serde_json::Value::String(arn.into()))
+  }
+}
+
+/// Amazon API Gateway authorizer for a security scheme defined in the OpenAPI definition.
+///
+/// Authorizers are registered via
+/// [`CodeGenerator::with_authorizer`](crate::CodeGenerator::with_authorizer), which populates the
+/// corresponding security scheme in the generated `openapi-apigw.yaml` with the
+/// [`x-amazon-apigateway-authorizer`](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-authorizer.html)
+/// and
+/// [`x-amazon-apigateway-authtype`](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-authtype.html)
+/// extensions. Authorizers are only supported by API Gateway REST APIs.
+///
+/// # Example
+///
+/// ```rust
+/// # use openapi_lambda_codegen::{Authorizer, LambdaArn};
+/// # use std::time::Duration;
+/// # let _ =
+/// Authorizer::lambda_token(LambdaArn::cloud_formation("AuthorizerFunction.Alias"))
+///   .with_result_ttl(Duration::from_secs(300))
+/// # ;
+/// ```
+#[derive(Debug)]
+pub struct Authorizer {
+  identity_source: Option<String>,
+  kind: AuthorizerKind,
+  result_ttl: Option<Duration>,
+}
+
+impl Authorizer {
+  /// Construct an authorizer that validates tokens issued by one or more Amazon Cognito user
+  /// pools.
+  ///
+  /// By default, the token is read from the `Authorization` request header.
+  pub fn cognito_user_pools<I>(user_pool_arns: I) -> Self
+  where
+    I: IntoIterator<Item = UserPoolArn>,
+  {
+    Self {
+      identity_source: Some(AUTHORIZATION_HEADER_IDENTITY_SOURCE.to_string()),
+      kind: AuthorizerKind::CognitoUserPools {
+        user_pool_arns: user_pool_arns.into_iter().collect(),
+      },
+      result_ttl: None,
+    }
+  }
+
+  /// Construct a Lambda authorizer of type `REQUEST`, which receives the request headers, query
+  /// string parameters, and other request context.
+  ///
+  /// By default, no identity source is specified, which disables caching of authorization results.
+  pub fn lambda_request(lambda_arn: LambdaArn) -> Self {
+    Self {
+      identity_source: None,
+      kind: AuthorizerKind::LambdaRequest {
+        lambda_arn: lambda_arn.0,
+      },
+      result_ttl: None,
+    }
+  }
+
+  /// Construct a Lambda authorizer of type `TOKEN`, which receives a bearer token from a single
+  /// request header.
+  ///
+  /// By default, the token is read from the `Authorization` request header.
+  pub fn lambda_token(lambda_arn: LambdaArn) -> Self {
+    Self {
+      identity_source: Some(AUTHORIZATION_HEADER_IDENTITY_SOURCE.to_string()),
+      kind: AuthorizerKind::LambdaToken {
+        lambda_arn: lambda_arn.0,
+      },
+      result_ttl: None,
+    }
+  }
+
+  /// Specify the
+  /// [identity source](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-authorizer.html)
+  /// from which API Gateway reads the caller's credentials (e.g.,
+  /// `method.request.header.X-Api-Token` or, for `REQUEST` authorizers, a comma-separated list
+  /// such as `method.request.header.Authorization, method.request.querystring.tenant`).
+  pub fn with_identity_source<S>(mut self, identity_source: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.identity_source = Some(identity_source.into());
+    self
+  }
+
+  /// Specify how long API Gateway caches authorization results.
+  ///
+  /// By default, API Gateway's default TTL (300 seconds) applies. The value is truncated to whole
+  /// seconds, and a duration of zero disables caching.
+  pub fn with_result_ttl(mut self, result_ttl: Duration) -> Self {
+    self.result_ttl = Some(result_ttl);
+    self
+  }
+
+  /// Name of the request header containing the caller's credentials, which API Gateway requires
+  /// as the `name` of the corresponding `apiKey` security scheme.
+  pub(crate) fn header_name(&self) -> &str {
+    self
+      .identity_source
+      .as_deref()
+      .and_then(|identity_source| identity_source.strip_prefix(IDENTITY_SOURCE_HEADER_PREFIX))
+      .filter(|header_name| !header_name.contains(','))
+      .unwrap_or("Authorization")
+  }
+
+  /// Value of the `x-amazon-apigateway-authtype` extension.
+  pub(crate) fn auth_type(&self) -> &'static str {
+    match self.kind {
+      AuthorizerKind::CognitoUserPools { .. } => "cognito_user_pools",
+      AuthorizerKind::LambdaRequest { .. } | AuthorizerKind::LambdaToken { .. } => "custom",
+    }
+  }
+
+  /// Value of the `x-amazon-apigateway-authorizer` extension.
+  pub(crate) fn extension(&self) -> serde_json::Value {
+    let mut authorizer = match &self.kind {
+      AuthorizerKind::CognitoUserPools { user_pool_arns } => json!({
+        "type": "cognito_user_pools",
+        "providerARNs": user_pool_arns.iter().map(|arn| &arn.0).collect::<Vec<_>>(),
+      }),
+      AuthorizerKind::LambdaRequest { lambda_arn } => json!({
+        "type": "request",
+        "authorizerUri": lambda_arn.apigw_invocation_arn(false),
+      }),
+      AuthorizerKind::LambdaToken { lambda_arn } => json!({
+        "type": "token",
+        "authorizerUri": lambda_arn.apigw_invocation_arn(false),
+      }),
+    };
+    if let Some(identity_source) = &self.identity_source {
+      authorizer["identitySource"] = json!(identity_source);
+    }
+    if let Some(result_ttl) = self.result_ttl {
+      authorizer["authorizerResultTtlInSeconds"] = json!(result_ttl.as_secs());
+    }
+    authorizer
+  }
+}
@@ -0,0 +1,119 @@
+use http::header::{ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_ORIGIN};
+use http::HeaderValue;
+
+use std::time::Duration;
+
+/// Cross-origin resource sharing (CORS) configuration for an [`ApiLambda`](crate::ApiLambda).
+///
+/// When an `ApiLambda` is configured with a `CorsConfig` (see
+/// [`ApiLambda::with_cors`](crate::ApiLambda::with_cors)), the generated dispatcher responds
+/// directly to
+/// [CORS preflight](https://developer.mozilla.org/en-US/docs/Glossary/Preflight_request) `OPTIONS`
+/// requests for each request path handled by the Lambda function, unless the OpenAPI definition
+/// already defines an `OPTIONS` operation for that path. The `Allow` and
+/// `Access-Control-Allow-Methods` response headers list the HTTP methods defined for the request
+/// path.
+/// Every other response also includes the `Access-Control-Allow-Origin` (and, if enabled,
+/// `Access-Control-Allow-Credentials`) response header.
+///
+/// For API Lambdas that handle REST API events, the generated `openapi-apigw.yaml` additionally
+/// defines an `OPTIONS` operation for each of these request paths backed by an API Gateway mock
+/// integration, which returns the same response headers without invoking the Lambda function.
+///
+/// # Example
+///
+/// ```rust
+/// # use openapi_lambda_codegen::CorsConfig;
+/// # use std::time::Duration;
+/// # let _ =
+/// CorsConfig::new("https://example.com")
+///   .with_allow_headers(["Authorization", "Content-Type"])
+///   .with_max_age(Duration::from_secs(3600))
+/// # ;
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CorsConfig {
+  pub(crate) allow_credentials: bool,
+  pub(crate) allow_headers: Vec<String>,
+  pub(crate) allow_origin: String,
+  pub(crate) max_age: Option<Duration>,
+}
+
+impl CorsConfig {
+  /// Construct a new `CorsConfig`.
+  ///
+  /// # Arguments
+  ///
+  /// * `allow_origin` - Value of the `Access-Control-Allow-Origin` response header (e.g.,
+  ///   `https://example.com` or `*`)
+  pub fn new<O>(allow_origin: O) -> Self
+  where
+    O: Into<String>,
+  {
+    Self {
+      allow_credentials: false,
+      allow_headers: Vec::new(),
+      allow_origin: allow_origin.into(),
+      max_age: None,
+    }
+  }
+
+  /// Set the `Access-Control-Allow-Credentials` response header to `true`.
+  ///
+  /// By default, this header is omitted.
+  pub fn with_allow_credentials(mut self, allow_credentials: bool) -> Self {
+    self.allow_credentials = allow_credentials;
+    self
+  }
+
+  /// Specify the request headers to include in the `Access-Control-Allow-Headers` response header.
+  ///
+  /// By default, this header is omitted.
+  pub fn with_allow_headers<I, H>(mut self, allow_headers: I) -> Self
+  where
+    I: IntoIterator<Item = H>,
+    H: Into<String>,
+  {
+    self.allow_headers = allow_headers.into_iter().map(Into::into).collect();
+    self
+  }
+
+  /// Specify how long browsers may cache preflight responses via the `Access-Control-Max-Age`
+  /// response header.
+  ///
+  /// By default, this header is omitted. The value is truncated to whole seconds.
+  pub fn with_max_age(mut self, max_age: Duration) -> Self {
+    self.max_age = Some(max_age);
+    self
+  }
+
+  /// Check that each configured value is a valid HTTP header value.
+  ///
+  /// The generated dispatcher constructs these headers at runtime, so invalid values must be
+  /// rejected during code generation.
+  pub(crate) fn validate(&self) -> Result<(), String> {
+    [
+      (ACCESS_CONTROL_ALLOW_ORIGIN, Some(self.allow_origin.clone())),
+      (ACCESS_CONTROL_ALLOW_HEADERS, self.allow_headers_value()),
+    ]
+    .into_iter()
+    .filter_map(|(header_name, value)| Some((header_name, value?)))
+    .try_for_each(|(header_name, value)| {
+      // `HeaderValue::from_static` (used by the generated code) only accepts visible ASCII.
+      HeaderValue::from_str(&value)
+        .ok()
+        .filter(|header_value| header_value.to_str().is_ok())
+        .map(|_| ())
+        .ok_or_else(|| format!("invalid `{header_name}` header value {value:?}"))
+    })
+  }
+
+  /// Value of the `Access-Control-Allow-Headers` response header, if any.
+  pub(crate) fn allow_headers_value(&self) -> Option<String> {
+    if self.allow_headers.is_empty() {
+      None
+    } else {
+      Some(self.allow_headers.join(", "))
+    }
+  }
+}
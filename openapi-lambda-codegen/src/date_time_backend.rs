@@ -0,0 +1,54 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Crate used to represent OpenAPI `date` and `date-time` string formats in the generated code.
+///
+/// The `openapi-lambda` crate feature of the same name must be enabled for the selected backend.
+///
+/// See [`CodeGenerator::with_datetime_backend`](crate::CodeGenerator::with_datetime_backend).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum DateTimeBackend {
+  /// Use [`chrono::NaiveDate`](https://docs.rs/chrono/latest/chrono/naive/struct.NaiveDate.html)
+  /// for `date` and
+  /// [`chrono::DateTime<Utc>`](https://docs.rs/chrono/latest/chrono/struct.DateTime.html) for
+  /// `date-time` (the default).
+  #[default]
+  Chrono,
+  /// Use [`time::Date`](https://docs.rs/time/latest/time/struct.Date.html) for `date` and
+  /// [`time::OffsetDateTime`](https://docs.rs/time/latest/time/struct.OffsetDateTime.html) for
+  /// `date-time`.
+  ///
+  /// Since the `time` crate's default serialization format for `OffsetDateTime` isn't RFC 3339,
+  /// `date-time` values are only supported as object properties (which are annotated with
+  /// `#[serde(with = "time::serde::rfc3339")]`) and request parameters. Other `date-time` schemas
+  /// (e.g., array items) are reported as
+  /// [`CodegenError::Unsupported`](crate::CodegenError::Unsupported).
+  Time,
+}
+
+impl DateTimeBackend {
+  /// Name of the crate re-exported by `openapi_lambda::models` for this backend.
+  pub(crate) fn crate_ident(&self) -> TokenStream {
+    match self {
+      DateTimeBackend::Chrono => quote! { chrono },
+      DateTimeBackend::Time => quote! { time },
+    }
+  }
+
+  /// Type representing the OpenAPI `date` string format.
+  pub(crate) fn date_type(&self) -> TokenStream {
+    match self {
+      DateTimeBackend::Chrono => quote! { chrono::NaiveDate },
+      DateTimeBackend::Time => quote! { time::Date },
+    }
+  }
+
+  /// Type representing the OpenAPI `date-time` string format.
+  pub(crate) fn date_time_type(&self) -> TokenStream {
+    match self {
+      DateTimeBackend::Chrono => quote! { chrono::DateTime<chrono::Utc> },
+      DateTimeBackend::Time => quote! { time::OffsetDateTime },
+    }
+  }
+}
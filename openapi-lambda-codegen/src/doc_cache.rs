@@ -0,0 +1,419 @@
+use crate::model::const_schemas_to_enums;
+use crate::reference::{parse_doc, reference_doc_path};
+
+use serde::{Deserialize, Serialize};
+use serde_yaml::value::{Tag, TaggedValue};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Cache of parsed OpenAPI documents, keyed by path.
+///
+/// If a cache directory is configured (see [`DocCache::with_disk_cache`]), each external document
+/// parsed during code generation is also persisted to disk so that subsequent builds can skip
+/// re-parsing documents whose contents haven't changed.
+#[derive(Debug, Default)]
+pub(crate) struct DocCache {
+  docs: HashMap<PathBuf, serde_yaml::Mapping>,
+  disk_cache_dir: Option<PathBuf>,
+}
+
+/// Parsed document persisted to the on-disk cache.
+#[derive(Deserialize, Serialize)]
+struct DiskCacheEntry {
+  /// Path of the source document, used to detect (unlikely) collisions between cache file names.
+  path: PathBuf,
+  /// Hash of the source document's contents when it was parsed.
+  contents_hash: u64,
+  /// Parsed document (see [`encode_value`]).
+  doc: serde_json::Value,
+}
+
+impl DocCache {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create an empty cache that also persists parsed documents to the specified directory.
+  pub fn with_disk_cache(disk_cache_dir: PathBuf) -> Self {
+    Self {
+      docs: HashMap::new(),
+      disk_cache_dir: Some(disk_cache_dir),
+    }
+  }
+
+  pub fn get(&self, doc_path: &Path) -> Option<&serde_yaml::Mapping> {
+    self.docs.get(doc_path)
+  }
+
+  pub fn insert(&mut self, doc_path: PathBuf, doc: serde_yaml::Mapping) {
+    self.docs.insert(doc_path, doc);
+  }
+
+  /// Return the parsed document at the specified path, reading and parsing it if it isn't already
+  /// cached.
+  pub fn load(&mut self, doc_path: &Path) -> Result<&serde_yaml::Mapping, String> {
+    if !self.docs.contains_key(doc_path) {
+      println!("cargo:rerun-if-changed={}", doc_path.display());
+      let contents = std::fs::read(doc_path)
+        .map_err(|err| format!("failed to open {}: {err}", doc_path.to_string_lossy()))?;
+      let contents_hash = hash_contents(&contents);
+
+      let doc = match self.read_disk_cache(doc_path, contents_hash) {
+        Some(doc) => doc,
+        None => {
+          let mut doc = parse_doc(doc_path, contents.as_slice()).map_err(|err| {
+            format!(
+              "failed to parse external OpenAPI doc {}: {err}",
+              doc_path.display()
+            )
+          })?;
+          const_schemas_to_enums(&mut doc);
+          self.write_disk_cache(doc_path, contents_hash, &doc);
+          doc
+        }
+      };
+      self.docs.insert(doc_path.to_path_buf(), doc);
+    }
+
+    Ok(
+      self
+        .docs
+        .get(doc_path)
+        .expect("document should have been cached above"),
+    )
+  }
+
+  /// Load every document referenced (directly or transitively) by the already-cached document at
+  /// `doc_path`, which emits `cargo:rerun-if-changed` for each of them (see [`DocCache::load`]).
+  ///
+  /// Code generation resolves references lazily, so it may never open documents that are only
+  /// referenced by other external documents (e.g., via schemas that no generated code uses).
+  /// Loading them up front ensures that editing any of them triggers a rebuild. Documents that
+  /// can't be loaded are skipped here, since code generation reports the error if it needs them.
+  pub fn load_transitive_references(&mut self, doc_path: &Path) {
+    let mut docs_to_visit = vec![doc_path.to_path_buf()];
+    let mut visited_docs = HashSet::new();
+    while let Some(doc_path) = docs_to_visit.pop() {
+      if !visited_docs.insert(doc_path.clone()) {
+        continue;
+      }
+      let doc = match self.load(&doc_path) {
+        Ok(doc) => doc,
+        Err(err) => {
+          log::debug!("skipping {}: {err}", doc_path.display());
+          continue;
+        }
+      };
+
+      let mut references = Vec::new();
+      collect_references(&serde_yaml::Value::Mapping(doc.clone()), &mut references);
+      docs_to_visit.extend(
+        references
+          .iter()
+          .filter_map(|reference| reference_doc_path(&doc_path, reference).ok()),
+      );
+    }
+  }
+
+  fn disk_cache_path(&self, doc_path: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    doc_path.hash(&mut hasher);
+    self
+      .disk_cache_dir
+      .as_ref()
+      .map(|dir| dir.join(format!("{:016x}.json", hasher.finish())))
+  }
+
+  fn read_disk_cache(&self, doc_path: &Path, contents_hash: u64) -> Option<serde_yaml::Mapping> {
+    let cache_path = self.disk_cache_path(doc_path)?;
+    let entry = serde_json::from_slice::<DiskCacheEntry>(&std::fs::read(&cache_path).ok()?).ok()?;
+    if entry.path != doc_path || entry.contents_hash != contents_hash {
+      return None;
+    }
+
+    match decode_value(entry.doc) {
+      Some(serde_yaml::Value::Mapping(doc)) => {
+        log::debug!(
+          "using cached {} from {}",
+          doc_path.display(),
+          cache_path.display()
+        );
+        Some(doc)
+      }
+      _ => None,
+    }
+  }
+
+  /// Persist a parsed document to the on-disk cache (if enabled).
+  ///
+  /// Failures are logged but otherwise ignored since the cache is only an optimization.
+  fn write_disk_cache(&self, doc_path: &Path, contents_hash: u64, doc: &serde_yaml::Mapping) {
+    let (Some(disk_cache_dir), Some(cache_path)) =
+      (&self.disk_cache_dir, self.disk_cache_path(doc_path))
+    else {
+      return;
+    };
+
+    let entry = DiskCacheEntry {
+      path: doc_path.to_path_buf(),
+      contents_hash,
+      doc: encode_value(&serde_yaml::Value::Mapping(doc.clone())),
+    };
+    if let Err(err) = std::fs::create_dir_all(disk_cache_dir).and_then(|()| {
+      std::fs::write(
+        &cache_path,
+        serde_json::to_vec(&entry).expect("failed to serialize cache entry"),
+      )
+    }) {
+      log::warn!("failed to write {}: {err}", cache_path.display());
+    }
+  }
+}
+
+impl FromIterator<(PathBuf, serde_yaml::Mapping)> for DocCache {
+  fn from_iter<I>(iter: I) -> Self
+  where
+    I: IntoIterator<Item = (PathBuf, serde_yaml::Mapping)>,
+  {
+    Self {
+      docs: iter.into_iter().collect(),
+      disk_cache_dir: None,
+    }
+  }
+}
+
+/// Recursively collect every `$ref` string within `value`.
+fn collect_references(value: &serde_yaml::Value, references: &mut Vec<String>) {
+  match value {
+    serde_yaml::Value::Mapping(mapping) => {
+      for (key, child) in mapping {
+        match (key.as_str(), child) {
+          (Some("$ref"), serde_yaml::Value::String(reference)) => {
+            references.push(reference.to_owned())
+          }
+          _ => collect_references(child, references),
+        }
+      }
+    }
+    serde_yaml::Value::Sequence(sequence) => sequence
+      .iter()
+      .for_each(|child| collect_references(child, references)),
+    serde_yaml::Value::Tagged(tagged) => collect_references(&tagged.value, references),
+    serde_yaml::Value::Null
+    | serde_yaml::Value::Bool(_)
+    | serde_yaml::Value::Number(_)
+    | serde_yaml::Value::String(_) => {}
+  }
+}
+
+fn hash_contents(contents: &[u8]) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  // Invalidate cache entries written by other versions of this crate, which may transform parsed
+  // documents differently.
+  env!("CARGO_PKG_VERSION").hash(&mut hasher);
+  contents.hash(&mut hasher);
+  hasher.finish()
+}
+
+/// Encode a YAML value as JSON, which is much faster to parse than YAML.
+///
+/// Unlike JSON objects, YAML mappings may have non-string keys (e.g., unquoted response status
+/// codes), so mappings are encoded as lists of key-value pairs to preserve the original keys. JSON
+/// objects are used only to encode mappings, tagged values, and non-finite numbers.
+fn encode_value(value: &serde_yaml::Value) -> serde_json::Value {
+  match value {
+    serde_yaml::Value::Null => serde_json::Value::Null,
+    serde_yaml::Value::Bool(value) => serde_json::Value::Bool(*value),
+    serde_yaml::Value::Number(number) => {
+      if let Some(number) = number.as_u64() {
+        serde_json::Value::from(number)
+      } else if let Some(number) = number.as_i64() {
+        serde_json::Value::from(number)
+      } else if let Some(number) = number.as_f64().and_then(serde_json::Number::from_f64) {
+        serde_json::Value::Number(number)
+      } else {
+        serde_json::json!({ "number": number.to_string() })
+      }
+    }
+    serde_yaml::Value::String(value) => serde_json::Value::String(value.to_owned()),
+    serde_yaml::Value::Sequence(values) => values.iter().map(encode_value).collect(),
+    serde_yaml::Value::Mapping(mapping) => serde_json::json!({
+      "mapping": mapping
+        .iter()
+        .map(|(key, value)| serde_json::json!([encode_value(key), encode_value(value)]))
+        .collect::<Vec<_>>(),
+    }),
+    serde_yaml::Value::Tagged(tagged) => serde_json::json!({
+      "tag": tagged.tag.to_string(),
+      "value": encode_value(&tagged.value),
+    }),
+  }
+}
+
+/// Decode a YAML value encoded by [`encode_value`], returning `None` if the encoding is invalid.
+fn decode_value(value: serde_json::Value) -> Option<serde_yaml::Value> {
+  Some(match value {
+    serde_json::Value::Null => serde_yaml::Value::Null,
+    serde_json::Value::Bool(value) => serde_yaml::Value::Bool(value),
+    serde_json::Value::Number(number) => {
+      if let Some(number) = number.as_u64() {
+        serde_yaml::Value::from(number)
+      } else if let Some(number) = number.as_i64() {
+        serde_yaml::Value::from(number)
+      } else {
+        serde_yaml::Value::from(number.as_f64()?)
+      }
+    }
+    serde_json::Value::String(value) => serde_yaml::Value::String(value),
+    serde_json::Value::Array(values) => serde_yaml::Value::Sequence(
+      values
+        .into_iter()
+        .map(decode_value)
+        .collect::<Option<_>>()?,
+    ),
+    serde_json::Value::Object(mut object) => {
+      if let Some(serde_json::Value::Array(entries)) = object.remove("mapping") {
+        serde_yaml::Value::Mapping(
+          entries
+            .into_iter()
+            .map(|entry| match entry {
+              serde_json::Value::Array(pair) if pair.len() == 2 => {
+                let mut pair = pair.into_iter();
+                Some((decode_value(pair.next()?)?, decode_value(pair.next()?)?))
+              }
+              _ => None,
+            })
+            .collect::<Option<_>>()?,
+        )
+      } else if let (Some(serde_json::Value::String(tag)), Some(value)) =
+        (object.remove("tag"), object.remove("value"))
+      {
+        serde_yaml::Value::Tagged(Box::new(TaggedValue {
+          tag: Tag::new(tag),
+          value: decode_value(value)?,
+        }))
+      } else if let Some(serde_json::Value::String(number)) = object.remove("number") {
+        serde_yaml::Value::Number(number.parse().ok()?)
+      } else {
+        return None;
+      }
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::doc_cache::{decode_value, encode_value, DiskCacheEntry, DocCache};
+
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_encode_decode() {
+    let value = serde_yaml::from_str::<serde_yaml::Value>(
+      r#"
+responses:
+  200:
+    description: OK
+  "404":
+    description: Not found
+  default:
+    description: Default
+true: yes
+~: null
+numbers: [1, -2, 3.5, .inf, -.inf, .nan, 18446744073709551615]
+nested:
+  - {a: [b, c]}
+  - []
+  - {}
+tagged: !Thing {x: 1}
+"#,
+    )
+    .unwrap();
+
+    let decoded = decode_value(
+      serde_json::from_slice(&serde_json::to_vec(&encode_value(&value)).unwrap()).unwrap(),
+    )
+    .unwrap();
+    // NaN isn't equal to itself, so compare the serialized values instead.
+    assert_eq!(
+      serde_yaml::to_string(&decoded).unwrap(),
+      serde_yaml::to_string(&value).unwrap()
+    );
+    let responses = decoded["responses"].as_mapping().unwrap();
+    assert!(responses.contains_key(serde_yaml::Value::from(200)));
+    assert!(responses.contains_key("404"));
+  }
+
+  #[test]
+  fn test_disk_cache() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let cache_dir = dir.join("cache");
+    let doc_path = dir.join("other.yaml");
+    std::fs::write(&doc_path, "Foo:\n  type: string\n").unwrap();
+
+    let first = DocCache::with_disk_cache(cache_dir.clone())
+      .load(&doc_path)
+      .cloned();
+
+    // Replace the cached document to verify that the cache is used instead of the original file.
+    let cache_paths = std::fs::read_dir(&cache_dir)
+      .unwrap()
+      .map(|entry| entry.unwrap().path())
+      .collect::<Vec<_>>();
+    let mut entry =
+      serde_json::from_slice::<DiskCacheEntry>(&std::fs::read(&cache_paths[0]).unwrap()).unwrap();
+    entry.doc = encode_value(&serde_yaml::from_str("Foo:\n  type: boolean\n").unwrap());
+    std::fs::write(&cache_paths[0], serde_json::to_vec(&entry).unwrap()).unwrap();
+    let cached = DocCache::with_disk_cache(cache_dir.clone())
+      .load(&doc_path)
+      .cloned();
+
+    // Changing the document's contents should invalidate the cached entry.
+    std::fs::write(&doc_path, "Foo:\n  type: integer\n").unwrap();
+    let modified = DocCache::with_disk_cache(cache_dir)
+      .load(&doc_path)
+      .cloned();
+
+    assert_eq!(cache_paths.len(), 1);
+    assert_eq!(first.unwrap()["Foo"]["type"], "string");
+    assert_eq!(cached.unwrap()["Foo"]["type"], "boolean");
+    assert_eq!(modified.unwrap()["Foo"]["type"], "integer");
+  }
+
+  #[test]
+  fn test_load_transitive_references() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    std::fs::create_dir_all(dir.join("nested")).unwrap();
+    let root_path = dir.join("openapi.yaml");
+    std::fs::write(
+      dir.join("a.yaml"),
+      "A:\n  $ref: \"nested/b.yaml#/B\"\nMissing:\n  $ref: \"missing.yaml#/Missing\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("nested/b.yaml"),
+      "B:\n  items:\n    - $ref: \"../c.yaml#/C\"\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("c.yaml"), "C:\n  type: string\n").unwrap();
+    std::fs::write(dir.join("unreferenced.yaml"), "D:\n  type: string\n").unwrap();
+
+    let mut cache = DocCache::new();
+    cache.insert(
+      root_path.clone(),
+      serde_yaml::from_str("Root:\n  $ref: \"a.yaml#/A\"\n").unwrap(),
+    );
+    cache.load_transitive_references(&root_path);
+
+    assert!(cache.get(&dir.join("a.yaml")).is_some());
+    assert!(cache.get(&dir.join("nested/b.yaml")).is_some());
+    assert!(cache.get(&dir.join("nested/../c.yaml")).is_some());
+    assert!(cache.get(&dir.join("missing.yaml")).is_none());
+    assert!(cache.get(&dir.join("unreferenced.yaml")).is_none());
+  }
+}
@@ -0,0 +1,102 @@
+use crate::ValidationIssue;
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Error that occurred while generating code from an OpenAPI definition.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum CodegenError {
+  /// The generated `openapi-apigw.yaml` uses constructs that Amazon API Gateway rejects or ignores
+  /// (see
+  /// [`CodeGenerator::with_api_gateway_validation`](crate::CodeGenerator::with_api_gateway_validation)).
+  ApiGatewayIncompatible(Vec<ValidationIssue>),
+  /// The [`CodeGenerator`](crate::CodeGenerator) or [`ApiLambda`](crate::ApiLambda) configuration
+  /// is invalid (e.g., a [`CorsConfig`](crate::CorsConfig) value that isn't a valid HTTP header
+  /// value).
+  InvalidConfig(String),
+  /// The generated code couldn't be parsed or formatted (e.g., due to an invalid
+  /// [`HandlerTemplate`](crate::HandlerTemplate) or a `rustfmt` failure).
+  InvalidGeneratedCode(String),
+  /// The OpenAPI definition could not be parsed or failed validation (see
+  /// [`CodeGenerator::with_spec_validation`](crate::CodeGenerator::with_spec_validation)).
+  InvalidSpec(Vec<ValidationIssue>),
+  /// A `$ref` could not be resolved (e.g., it's malformed, its target doesn't exist, or its target
+  /// isn't a valid object of the expected type).
+  InvalidReference(String),
+  /// Failed to read or write a file or directory.
+  Io {
+    /// Path of the file or directory.
+    path: PathBuf,
+    /// Kind of I/O error.
+    kind: ErrorKind,
+    /// Description of the I/O error.
+    message: String,
+  },
+  /// The `OUT_DIR` environment variable isn't set (see
+  /// [`CodeGenerator::try_generate`](crate::CodeGenerator::try_generate)).
+  MissingOutDir,
+  /// The OpenAPI definition uses features that code generation doesn't support (e.g., `anyOf`
+  /// schemas), each listed along with its location in the OpenAPI definition.
+  Unsupported(Vec<ValidationIssue>),
+}
+
+impl CodegenError {
+  pub(crate) fn io(path: PathBuf, err: std::io::Error) -> Self {
+    CodegenError::Io {
+      path,
+      kind: err.kind(),
+      message: err.to_string(),
+    }
+  }
+}
+
+impl Display for CodegenError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CodegenError::ApiGatewayIncompatible(issues) => {
+        write!(
+          f,
+          "generated API Gateway OpenAPI spec has {} compatibility issue(s):",
+          issues.len()
+        )?;
+        issues
+          .iter()
+          .try_for_each(|issue| write!(f, "\n  * {issue}"))
+      }
+      CodegenError::InvalidConfig(message) => write!(f, "invalid configuration: {message}"),
+      CodegenError::InvalidGeneratedCode(message) => {
+        write!(f, "invalid generated code: {message}")
+      }
+      CodegenError::InvalidSpec(issues) => {
+        write!(f, "OpenAPI spec has {} validation issue(s):", issues.len())?;
+        issues
+          .iter()
+          .try_for_each(|issue| write!(f, "\n  * {issue}"))
+      }
+      CodegenError::InvalidReference(message) => write!(f, "{message}"),
+      CodegenError::Io { path, message, .. } => {
+        write!(f, "I/O error accessing `{}`: {message}", path.display())
+      }
+      CodegenError::MissingOutDir => write!(
+        f,
+        "`OUT_DIR` environment variable is not set; `try_generate` must be called from a Cargo \
+         build script"
+      ),
+      CodegenError::Unsupported(issues) => {
+        write!(
+          f,
+          "OpenAPI spec uses {} unsupported feature(s):",
+          issues.len()
+        )?;
+        issues
+          .iter()
+          .try_for_each(|issue| write!(f, "\n  * {issue}"))
+      }
+    }
+  }
+}
+
+impl Error for CodegenError {}
@@ -0,0 +1,187 @@
+use crate::api::operation::PathOperation;
+
+use itertools::Itertools;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Type of Lambda event handled by an API Lambda function.
+///
+/// The event source determines the request and response types accepted and returned by the
+/// generated `dispatch_request` method, as well as the request context type passed to the
+/// [`Middleware`](https://docs.rs/openapi-lambda/latest/openapi_lambda/trait.Middleware.html) and
+/// to each API handler method.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum EventSource {
+  /// Amazon API Gateway REST API using the Lambda proxy integration (`ApiGatewayProxyRequest`).
+  ///
+  /// Use [`run_lambda`](https://docs.rs/openapi-lambda/latest/openapi_lambda/fn.run_lambda.html)
+  /// to start the Lambda runtime.
+  #[default]
+  ApiGatewayProxy,
+  /// Amazon API Gateway HTTP API using payload format version 2.0 (`ApiGatewayV2httpRequest`).
+  ///
+  /// Requests are dispatched based on the `routeKey` of each request, so each route must be
+  /// defined using the HTTP method and path of the corresponding OpenAPI operation (as in the
+  /// generated `openapi-apigw.yaml`). Requests with the `$default` route key (e.g., from Lambda
+  /// function URLs) are instead dispatched based on the HTTP method and path of each request. Use
+  /// [`run_lambda_v2`](https://docs.rs/openapi-lambda/latest/openapi_lambda/fn.run_lambda_v2.html)
+  /// to start the Lambda runtime.
+  ApiGatewayV2Http,
+  /// Application Load Balancer (`AlbTargetGroupRequest`).
+  ///
+  /// Load balancer events don't identify the OpenAPI operation, so requests are dispatched based
+  /// on the HTTP method and path of each request. Operations handled by these API Lambdas are
+  /// omitted from the generated `openapi-apigw.yaml`. Use
+  /// [`run_lambda_alb`](https://docs.rs/openapi-lambda/latest/openapi_lambda/fn.run_lambda_alb.html)
+  /// to start the Lambda runtime.
+  ApplicationLoadBalancer,
+}
+
+impl EventSource {
+  /// Lambda event payload type.
+  pub(crate) fn request_type(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy => quote! { ApiGatewayProxyRequest },
+      EventSource::ApiGatewayV2Http => quote! { ApiGatewayV2httpRequest },
+      EventSource::ApplicationLoadBalancer => quote! { AlbTargetGroupRequest },
+    }
+  }
+
+  /// Lambda response type.
+  pub(crate) fn response_type(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy => quote! { ApiGatewayProxyResponse },
+      EventSource::ApiGatewayV2Http => quote! { ApiGatewayV2httpResponse },
+      EventSource::ApplicationLoadBalancer => quote! { AlbTargetGroupResponse },
+    }
+  }
+
+  /// Request context type passed to the middleware and API handlers.
+  pub(crate) fn request_context_type(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy => quote! { ApiGatewayProxyRequestContext },
+      EventSource::ApiGatewayV2Http => quote! { ApiGatewayV2httpRequestContext },
+      EventSource::ApplicationLoadBalancer => quote! { AlbTargetGroupRequestContext },
+    }
+  }
+
+  /// Function that converts an `HttpResponse` into the Lambda response type.
+  pub(crate) fn response_conversion_fn(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy => quote! { http_response_to_apigw },
+      EventSource::ApiGatewayV2Http => quote! { http_response_to_apigw_v2 },
+      EventSource::ApplicationLoadBalancer => quote! { http_response_to_alb },
+    }
+  }
+
+  /// Expression evaluating to the request's HTTP method as a `&str`.
+  pub(crate) fn http_method_expr(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy | EventSource::ApplicationLoadBalancer => {
+        quote! { request.http_method.as_str() }
+      }
+      EventSource::ApiGatewayV2Http => quote! { request.request_context.http.method.as_str() },
+    }
+  }
+
+  /// Expression evaluating to the request's path (e.g., `/foo/123`) as a `&str`.
+  pub(crate) fn request_path_expr(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy | EventSource::ApplicationLoadBalancer => {
+        quote! { request.path.as_deref().unwrap_or_default() }
+      }
+      EventSource::ApiGatewayV2Http => quote! { request.raw_path.as_deref().unwrap_or_default() },
+    }
+  }
+
+  /// Expression evaluating to the request's path after stripping any base path (e.g., an HTTP
+  /// API stage name), which is matched against the operations' request paths.
+  pub(crate) fn routing_path_expr(&self, base_path: Option<&str>) -> TokenStream {
+    let request_path = self.request_path_expr();
+    match base_path {
+      Some(base_path) => quote! { routing::strip_base_path(#request_path, #base_path) },
+      None => request_path,
+    }
+  }
+
+  /// Expression evaluating to the OpenAPI path template (e.g., `/foo/{foo_id}`) matching the
+  /// request as an `Option<&str>`.
+  ///
+  /// API Gateway identifies the path template via the resource (REST APIs) or route key (HTTP
+  /// APIs) of each request. If these don't correspond to any of the operations (e.g., for
+  /// `/{proxy+}` resources, `$default` HTTP API routes, and Lambda function URLs), or if the event
+  /// doesn't identify one at all (e.g., load balancer events), the request path is matched against
+  /// the path template of each operation instead.
+  pub(crate) fn path_template_expr(
+    &self,
+    operations: &[&PathOperation],
+    base_path: Option<&str>,
+  ) -> TokenStream {
+    let templates = operations
+      .iter()
+      .map(|op| op.request_path.as_str())
+      .sorted()
+      .dedup();
+    let routing_path = self.routing_path_expr(base_path);
+    let match_path = quote! { routing::match_path(TEMPLATES, #routing_path) };
+
+    let event_template = match self {
+      EventSource::ApiGatewayProxy => Some(quote! { request.resource.as_deref() }),
+      // Route keys have the form `<METHOD> <PATH>` (e.g., `OPTIONS /foo/{foo_id}`).
+      EventSource::ApiGatewayV2Http => Some(quote! {
+        request
+          .route_key
+          .as_deref()
+          .and_then(|route_key| route_key.split_once(' '))
+          .map(|(_, route_path)| route_path)
+      }),
+      EventSource::ApplicationLoadBalancer => None,
+    };
+    let path_template = match event_template {
+      Some(event_template) => quote! {
+        #event_template
+          .filter(|template| TEMPLATES.contains(template))
+          .or_else(|| #match_path)
+      },
+      None => match_path,
+    };
+
+    quote! {
+      {
+        const TEMPLATES: &[&str] = &[#(#templates),*];
+        #path_template
+      }
+    }
+  }
+
+  /// Additional parameters accepted by each generated handler wrapper function.
+  pub(crate) fn handler_wrapper_extra_params(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy | EventSource::ApiGatewayV2Http => quote! {},
+      // Load balancer events don't include path parameters, so the dispatcher extracts them
+      // while matching the request path.
+      EventSource::ApplicationLoadBalancer => quote! {
+        path_parameters: std::collections::HashMap<String, String>,
+      },
+    }
+  }
+
+  /// Additional arguments passed by the dispatcher to each generated handler wrapper function.
+  pub(crate) fn handler_wrapper_extra_args(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy | EventSource::ApiGatewayV2Http => quote! {},
+      EventSource::ApplicationLoadBalancer => quote! { path_parameters, },
+    }
+  }
+
+  /// Expression evaluating to the request's path parameters as a `HashMap<String, String>`.
+  pub(crate) fn path_parameters_expr(&self) -> TokenStream {
+    match self {
+      EventSource::ApiGatewayProxy | EventSource::ApiGatewayV2Http => {
+        quote! { request.path_parameters }
+      }
+      EventSource::ApplicationLoadBalancer => quote! { path_parameters },
+    }
+  }
+}
@@ -0,0 +1,140 @@
+use crate::error::CodegenError;
+
+use std::path::PathBuf;
+
+/// Template for the `<MODULE_NAME>_handler.rs` file generated for an API Lambda.
+///
+/// By default, the generated handler file contains a minimal placeholder implementation of the
+/// `Api` trait. A custom template can instead include project-specific boilerplate such as license
+/// headers, tracing setup, or a handler state struct. The following placeholders are substituted
+/// in the template:
+///  * `{mod_name}` - Name of the API Lambda module (e.g., `backend`)
+///  * `{mod_name_pascal}` - Name of the default handler struct (e.g., `BackendApiHandler`)
+///  * `{api_mod_imports}` - Comma-separated list of the response types defined in the API Lambda
+///    module (e.g., `CreatePetResponse, GetPetByIdResponse`), suitable for importing alongside the
+///    module's `Api` trait
+///  * `{handler_impls}` - Placeholder implementation of each `Api` trait method
+///
+/// All other text, including any other curly braces, is emitted verbatim. The resulting file is
+/// formatted with `rustfmt` if available.
+///
+/// # Example
+///
+/// ```rust
+/// # use openapi_lambda_codegen::HandlerTemplate;
+/// # let _ =
+/// HandlerTemplate::Inline(
+///   r#"
+///   use crate::{mod_name}::{Api, {api_mod_imports}};
+///   use openapi_lambda::{
+///     ApiGatewayProxyRequestContext, HeaderMap, HttpResponse, LambdaContext, OperationInfo,
+///   };
+///   use openapi_lambda::async_trait::async_trait;
+///
+///   pub struct {mod_name_pascal};
+///
+///   #[async_trait]
+///   impl Api for {mod_name_pascal} {
+///     type AuthOk = ();
+///     type HandlerError = ();
+///
+///     async fn respond_to_handler_error(&self, _err: Self::HandlerError) -> HttpResponse {
+///       todo!()
+///     }
+///
+///     {handler_impls}
+///   }
+///   "#
+///   .to_string(),
+/// )
+/// # ;
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HandlerTemplate {
+  /// Read the template from the specified file.
+  ///
+  /// Relative paths are resolved relative to the current working directory, which is the crate
+  /// root when the code generator is invoked from `build.rs`.
+  File(PathBuf),
+  /// Use the specified string as the template.
+  Inline(String),
+}
+
+impl HandlerTemplate {
+  /// Substitutes the specified placeholder values into the template.
+  pub(crate) fn render(&self, vars: &[(&str, &str)]) -> Result<String, CodegenError> {
+    let template = match self {
+      HandlerTemplate::File(path) => {
+        println!("cargo:rerun-if-changed={}", path.display());
+        std::fs::read_to_string(path).map_err(|err| CodegenError::io(path.clone(), err))?
+      }
+      HandlerTemplate::Inline(template) => template.clone(),
+    };
+
+    // Substitute in a single pass so that placeholder-like text in substituted values (e.g., in
+    // doc comments) is left untouched.
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(brace_idx) = rest.find('{') {
+      rendered.push_str(&rest[..brace_idx]);
+      rest = &rest[brace_idx..];
+      match vars.iter().find(|(name, _)| {
+        rest
+          .strip_prefix('{')
+          .and_then(|after_brace| after_brace.strip_prefix(name))
+          .map_or(false, |after_name| after_name.starts_with('}'))
+      }) {
+        Some((name, value)) => {
+          rendered.push_str(value);
+          rest = &rest[name.len() + 2..];
+        }
+        None => {
+          rendered.push('{');
+          rest = &rest[1..];
+        }
+      }
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::HandlerTemplate;
+
+  #[test]
+  fn test_render() {
+    let template = HandlerTemplate::Inline(
+      "use crate::{mod_name}::{Api, {api_mod_imports}};\n\
+       impl Api for {mod_name_pascal} { {handler_impls} }\n\
+       {unknown}"
+        .to_string(),
+    );
+    assert_eq!(
+      template
+        .render(&[
+          ("mod_name", "backend"),
+          ("mod_name_pascal", "BackendApiHandler"),
+          ("api_mod_imports", "GetFooResponse"),
+          ("handler_impls", "/// Returns `{mod_name}`.\nfn foo() {}"),
+        ])
+        .unwrap(),
+      "use crate::backend::{Api, GetFooResponse};\n\
+       impl Api for BackendApiHandler { /// Returns `{mod_name}`.\nfn foo() {} }\n\
+       {unknown}"
+    );
+  }
+
+  #[test]
+  fn test_render_missing_file() {
+    let err = HandlerTemplate::File("does-not-exist.rs.tmpl".into())
+      .render(&[])
+      .unwrap_err();
+    assert!(
+      matches!(err, crate::CodegenError::Io { ref path, .. } if path.to_str() == Some("does-not-exist.rs.tmpl")),
+      "unexpected error {err:?}"
+    );
+  }
+}
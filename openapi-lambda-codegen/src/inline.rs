@@ -1,5 +1,6 @@
-use crate::reference::{resolve_reference, ResolvedReference};
-use crate::{CodeGenerator, DocCache};
+use crate::model::RUST_TYPE_EXTENSION;
+use crate::reference::{escape_pointer_token, resolve_reference, ResolvedReference};
+use crate::{CodeGenerator, CodegenError, DocCache};
 
 use indexmap::IndexMap;
 use openapiv3::{
@@ -15,19 +16,25 @@ use std::path::Path;
 
 // An OpenAPI definition with only local references (i.e., within the same file).
 #[derive(Debug)]
-pub(crate) struct InlineApi(OpenAPI);
+pub(crate) struct InlineApi {
+  openapi: OpenAPI,
+  /// Top-level `webhooks` (OpenAPI 3.1), which `openapiv3` doesn't support.
+  ///
+  /// Webhooks map a webhook name to a path item, so they share the same structure as a callback.
+  pub webhooks: Callback,
+}
 
 impl Deref for InlineApi {
   type Target = OpenAPI;
 
   fn deref(&self) -> &Self::Target {
-    &self.0
+    &self.openapi
   }
 }
 
 impl DerefMut for InlineApi {
   fn deref_mut(&mut self) -> &mut Self::Target {
-    &mut self.0
+    &mut self.openapi
   }
 }
 
@@ -37,14 +44,19 @@ impl CodeGenerator {
   /// The resulting OpenAPI definition is contained in a single file. Schemas are a special case:
   /// rather than inlining foreign schema references, we add them to `openapi.components.schemas`
   /// and replace the foreign reference with a local reference in order to preserve the schema name.
-  /// If there is already a non-identical schema with the same name, we inline it instead.
+  /// If there is already a non-identical schema with the same name, we inline it instead. Foreign
+  /// references to responses and parameters are handled the same way (via
+  /// `openapi.components.responses` and `openapi.components.parameters`) so that operations sharing
+  /// them also share any models generated for them. The top-level `webhooks` (see
+  /// [`InlineApi::webhooks`]) are inlined likewise.
   pub(crate) fn inline_openapi(
     &self,
     mut openapi: OpenAPI,
+    mut webhooks: Callback,
     mut cached_external_docs: DocCache,
-  ) -> InlineApi {
+  ) -> Result<InlineApi, CodegenError> {
     let components = if let Some(components) = &mut openapi.components {
-      self.inline_components(components, &mut cached_external_docs);
+      self.inline_components(components, &mut cached_external_docs)?;
       components
     } else {
       openapi.components.insert(Components::default())
@@ -60,23 +72,38 @@ impl CodeGenerator {
             parent_doc_path,
             path_item,
             &mut components.schemas,
+            &mut components.parameters,
+            &mut components.responses,
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
-    InlineApi(openapi)
+    self.inline_callback(
+      &self.openapi_path,
+      &mut webhooks,
+      &mut components.schemas,
+      &mut components.parameters,
+      &mut components.responses,
+      &mut cached_external_docs,
+    )?;
+
+    Ok(InlineApi { openapi, webhooks })
   }
 
-  fn inline_components(&self, components: &mut Components, cached_external_docs: &mut DocCache) {
+  fn inline_components(
+    &self,
+    components: &mut Components,
+    cached_external_docs: &mut DocCache,
+  ) -> Result<(), CodegenError> {
     for (_, security_scheme) in &mut components.security_schemes {
       self.inline_reference_or_item(
         &self.openapi_path,
         security_scheme,
         cached_external_docs,
-        |_, _, _| (),
-      );
+        |_, _, _| Ok(()),
+      )?;
     }
 
     for (_, response) in &mut components.responses {
@@ -92,7 +119,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     for (_, parameter) in &mut components.parameters {
@@ -108,7 +135,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     for (_, example) in &mut components.examples {
@@ -116,8 +143,8 @@ impl CodeGenerator {
         &self.openapi_path,
         example,
         cached_external_docs,
-        |_, _, _| (),
-      );
+        |_, _, _| Ok(()),
+      )?;
     }
 
     for (_, request_body) in &mut components.request_bodies {
@@ -133,7 +160,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     for (_, header) in &mut components.headers {
@@ -149,7 +176,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     // We can't borrow components.schemas mutably twice, so we create a temporary copy for importing
@@ -162,7 +189,7 @@ impl CodeGenerator {
         schema,
         &mut inlined_schemas,
         cached_external_docs,
-      )
+      )?;
     }
 
     for (name, schema) in inlined_schemas {
@@ -175,7 +202,12 @@ impl CodeGenerator {
     }
 
     for (_, link) in &mut components.links {
-      self.inline_reference_or_item(&self.openapi_path, link, cached_external_docs, |_, _, _| ())
+      self.inline_reference_or_item(
+        &self.openapi_path,
+        link,
+        cached_external_docs,
+        |_, _, _| Ok(()),
+      )?;
     }
 
     for (_, callback) in &mut components.callbacks {
@@ -188,13 +220,17 @@ impl CodeGenerator {
             parent_doc_path,
             callback,
             &mut components.schemas,
+            &mut components.parameters,
+            &mut components.responses,
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     // We just leave `components.extensions` alone for now.
+
+    Ok(())
   }
 
   // NB: Don't use this for schemas because the inlining loses the name of the reference target.
@@ -206,8 +242,9 @@ impl CodeGenerator {
     reference_or: &mut ReferenceOr<T>,
     cached_external_docs: &mut DocCache,
     mut inline_fn: F,
-  ) where
-    F: FnMut(&Path, &mut T, &mut DocCache),
+  ) -> Result<(), CodegenError>
+  where
+    F: FnMut(&Path, &mut T, &mut DocCache) -> Result<(), CodegenError>,
     T: DeserializeOwned,
   {
     match reference_or {
@@ -219,13 +256,13 @@ impl CodeGenerator {
             mut target,
             ..
           },
-        ) = resolve_reference::<T>(parent_doc_path, reference, cached_external_docs);
+        ) = resolve_reference::<T>(parent_doc_path, reference, cached_external_docs)?;
 
         // If the reference target is in the root OpenAPI spec, don't update it here since we'll
         // process it directly. As much as possible, we try to leave local references in place
         // so that the size of the final OpenAPI spec doesn't due to excessive inlining.
         if target_doc_path != *self.openapi_path {
-          inline_fn(&target_doc_path, &mut target, cached_external_docs);
+          inline_fn(&target_doc_path, &mut target, cached_external_docs)?;
 
           // NB: This drops the name of the reference target, which seems fine for non-schema
           // references, although it may increase the size of the final OpenAPI spec. If this
@@ -242,9 +279,97 @@ impl CodeGenerator {
         }
       }
       ReferenceOr::Item(item) => {
-        inline_fn(parent_doc_path, item, cached_external_docs);
+        inline_fn(parent_doc_path, item, cached_external_docs)?;
       }
     }
+
+    Ok(())
+  }
+
+  /// Like [`inline_reference_or_item`](Self::inline_reference_or_item), but preserves the names of
+  /// foreign reference targets by copying them into `components_items` (i.e., the
+  /// `components_field` of `openapi.components`, such as `responses`) and replacing the foreign
+  /// reference with a local one.
+  ///
+  /// Only targets within a map of reusable objects (e.g., `components/responses/NotFound` or
+  /// `responses/NotFound`) have meaningful names. Other targets (e.g., the responses of a foreign
+  /// operation), as well as targets whose name is already used by a non-identical component, are
+  /// inlined instead.
+  fn inline_reference_or_component<F, T>(
+    &self,
+    parent_doc_path: &Path,
+    reference_or: &mut ReferenceOr<T>,
+    components_field: &str,
+    components_items: &mut IndexMap<String, ReferenceOr<T>>,
+    cached_external_docs: &mut DocCache,
+    mut inline_fn: F,
+  ) -> Result<(), CodegenError>
+  where
+    F: FnMut(&Path, &mut T, &mut DocCache) -> Result<(), CodegenError>,
+    T: DeserializeOwned + PartialEq,
+  {
+    let ReferenceOr::Reference { reference } = reference_or else {
+      return self.inline_reference_or_item(
+        parent_doc_path,
+        reference_or,
+        cached_external_docs,
+        inline_fn,
+      );
+    };
+
+    let (
+      target_doc_path,
+      ResolvedReference {
+        root_rel_ref: rel_ref,
+        mut target,
+        target_name,
+      },
+    ) = resolve_reference::<T>(parent_doc_path, reference, cached_external_docs)?;
+
+    // As in `inline_reference_or_item`, references back to the root definition become local.
+    if target_doc_path == *self.openapi_path {
+      *reference_or = ReferenceOr::Reference {
+        reference: format!("#/{rel_ref}"),
+      };
+      return Ok(());
+    }
+
+    inline_fn(&target_doc_path, &mut target, cached_external_docs)?;
+
+    let is_named_component = rel_ref
+      .strip_prefix("components/")
+      .unwrap_or(rel_ref)
+      .split_once('/')
+      .map_or(false, |(field, name)| {
+        field == components_field && !name.contains('/')
+      });
+    if !is_named_component {
+      *reference_or = ReferenceOr::Item(target);
+      return Ok(());
+    }
+
+    let local_reference = format!(
+      "#/components/{components_field}/{}",
+      escape_pointer_token(&target_name)
+    );
+    match components_items.get(target_name.as_ref()) {
+      Some(ReferenceOr::Item(existing_item_with_name)) if *existing_item_with_name == target => {
+        *reference_or = ReferenceOr::Reference {
+          reference: local_reference,
+        };
+      }
+      Some(_) => {
+        *reference_or = ReferenceOr::Item(target);
+      }
+      None => {
+        components_items.insert(target_name.into_owned(), ReferenceOr::Item(target));
+        *reference_or = ReferenceOr::Reference {
+          reference: local_reference,
+        };
+      }
+    }
+
+    Ok(())
   }
 
   fn inline_reference_or_schema<T>(
@@ -253,7 +378,8 @@ impl CodeGenerator {
     reference_or_schema: &mut ReferenceOr<T>,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) where
+  ) -> Result<(), CodegenError>
+  where
     T: BorrowMut<Schema> + std::fmt::Debug + From<Schema>,
   {
     match reference_or_schema {
@@ -265,18 +391,25 @@ impl CodeGenerator {
             mut target,
             target_name,
           },
-        ) = resolve_reference::<Schema>(parent_doc_path, reference, cached_external_docs);
+        ) = resolve_reference::<Schema>(parent_doc_path, reference, cached_external_docs)?;
 
         // If the reference target is in the root OpenAPI spec, don't update it here since we'll
         // process it directly. As much as possible, we try to leave local references in place
         // so that the size of the final OpenAPI spec doesn't due to excessive inlining.
         if target_doc_path != *self.openapi_path {
+          if let Some(rust_type) = self.mapped_schema_ref_type(&target_doc_path, rel_ref) {
+            target.schema_data.extensions.insert(
+              RUST_TYPE_EXTENSION.to_string(),
+              serde_json::Value::String(rust_type.to_string()),
+            );
+          }
+
           self.inline_schema(
             &target_doc_path,
             &mut target,
             components_schemas,
             cached_external_docs,
-          );
+          )?;
 
           // To preserve schema names (which will become Rust type names in the generated code),
           // we insert them into #/components/schemas (if there isn't already a schema with the
@@ -284,21 +417,25 @@ impl CodeGenerator {
           // conflicting schema with the same name, we just inline it and handle name conflict
           // resolution later, when generating the models.
 
-          match components_schemas.get(target_name) {
+          let local_reference = format!(
+            "#/components/schemas/{}",
+            escape_pointer_token(&target_name)
+          );
+          match components_schemas.get(target_name.as_ref()) {
             Some(ReferenceOr::Item(existing_schema_with_name))
               if *existing_schema_with_name == target =>
             {
               *reference_or_schema = ReferenceOr::Reference {
-                reference: format!("#/components/schemas/{target_name}"),
+                reference: local_reference,
               };
             }
             Some(_) => {
               *reference_or_schema = ReferenceOr::Item(T::from(target));
             }
             None => {
-              components_schemas.insert(target_name.to_string(), ReferenceOr::Item(target));
+              components_schemas.insert(target_name.into_owned(), ReferenceOr::Item(target));
               *reference_or_schema = ReferenceOr::Reference {
-                reference: format!("#/components/schemas/{target_name}"),
+                reference: local_reference,
               };
             }
           }
@@ -317,9 +454,30 @@ impl CodeGenerator {
           item.borrow_mut(),
           components_schemas,
           cached_external_docs,
-        );
+        )?;
       }
     }
+
+    Ok(())
+  }
+
+  /// Returns the Rust type to which the target of a foreign schema reference is mapped via
+  /// [`CodeGenerator::map_schema_ref`], if any.
+  fn mapped_schema_ref_type(&self, target_doc_path: &Path, root_rel_ref: &str) -> Option<&str> {
+    self
+      .schema_ref_types
+      .iter()
+      .find_map(|(reference, rust_type)| {
+        let (rel_path, rel_ref) = reference.split_once("#/")?;
+        let doc_path = self.openapi_path.parent()?.join(rel_path);
+        // Canonicalize the paths since references from different documents may refer to the same
+        // document via different relative paths.
+        let is_same_doc = match (doc_path.canonicalize(), target_doc_path.canonicalize()) {
+          (Ok(doc_path), Ok(target_doc_path)) => doc_path == target_doc_path,
+          _ => doc_path == target_doc_path,
+        };
+        (is_same_doc && rel_ref == root_rel_ref).then_some(rust_type.as_str())
+      })
   }
 
   fn inline_callback(
@@ -327,16 +485,22 @@ impl CodeGenerator {
     parent_doc_path: &Path,
     callback: &mut Callback,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+    components_parameters: &mut IndexMap<String, ReferenceOr<Parameter>>,
+    components_responses: &mut IndexMap<String, ReferenceOr<Response>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     for (_, path_item) in callback {
       self.inline_path_item(
         parent_doc_path,
         path_item,
         components_schemas,
+        components_parameters,
+        components_responses,
         cached_external_docs,
-      )
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_header(
@@ -345,17 +509,24 @@ impl CodeGenerator {
     header: &mut Header,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     self.inline_parameter_schema_or_content(
       parent_doc_path,
       &mut header.format,
       components_schemas,
       cached_external_docs,
-    );
+    )?;
 
     for (_, example) in &mut header.examples {
-      self.inline_reference_or_item(parent_doc_path, example, cached_external_docs, |_, _, _| ())
+      self.inline_reference_or_item(
+        parent_doc_path,
+        example,
+        cached_external_docs,
+        |_, _, _| Ok(()),
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_media_type(
@@ -364,19 +535,26 @@ impl CodeGenerator {
     media_type: &mut MediaType,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     if let Some(schema) = &mut media_type.schema {
       self.inline_reference_or_schema(
         parent_doc_path,
         schema,
         components_schemas,
         cached_external_docs,
-      )
+      )?;
     }
 
     for (_, example) in &mut media_type.examples {
-      self.inline_reference_or_item(parent_doc_path, example, cached_external_docs, |_, _, _| ())
+      self.inline_reference_or_item(
+        parent_doc_path,
+        example,
+        cached_external_docs,
+        |_, _, _| Ok(()),
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_operation(
@@ -384,12 +562,16 @@ impl CodeGenerator {
     parent_doc_path: &Path,
     operation: &mut Operation,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+    components_parameters: &mut IndexMap<String, ReferenceOr<Parameter>>,
+    components_responses: &mut IndexMap<String, ReferenceOr<Response>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     for parameter in &mut operation.parameters {
-      self.inline_reference_or_item(
+      self.inline_reference_or_component(
         parent_doc_path,
         parameter,
+        "parameters",
+        components_parameters,
         cached_external_docs,
         |parent_doc_path, parameter, cached_external_docs| {
           self.inline_parameter(
@@ -399,7 +581,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     if let Some(request_body) = &mut operation.request_body {
@@ -415,15 +597,29 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     self.inline_responses(
       parent_doc_path,
       &mut operation.responses,
       components_schemas,
+      components_responses,
       cached_external_docs,
-    );
+    )?;
+
+    for (_, callback) in &mut operation.callbacks {
+      self.inline_callback(
+        parent_doc_path,
+        callback,
+        components_schemas,
+        components_parameters,
+        components_responses,
+        cached_external_docs,
+      )?;
+    }
+
+    Ok(())
   }
 
   fn inline_parameter(
@@ -432,7 +628,7 @@ impl CodeGenerator {
     parameter: &mut Parameter,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     let parameter_data = match parameter {
       Parameter::Query { parameter_data, .. }
       | Parameter::Header { parameter_data, .. }
@@ -445,11 +641,18 @@ impl CodeGenerator {
       &mut parameter_data.format,
       components_schemas,
       cached_external_docs,
-    );
+    )?;
 
     for (_, example) in &mut parameter_data.examples {
-      self.inline_reference_or_item(parent_doc_path, example, cached_external_docs, |_, _, _| ())
+      self.inline_reference_or_item(
+        parent_doc_path,
+        example,
+        cached_external_docs,
+        |_, _, _| Ok(()),
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_parameter_schema_or_content(
@@ -458,14 +661,14 @@ impl CodeGenerator {
     parameter_schema_or_content: &mut ParameterSchemaOrContent,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     match parameter_schema_or_content {
       ParameterSchemaOrContent::Schema(schema) => self.inline_reference_or_schema(
         parent_doc_path,
         schema,
         components_schemas,
         cached_external_docs,
-      ),
+      )?,
       ParameterSchemaOrContent::Content(content) => {
         for (_, media_type) in content {
           self.inline_media_type(
@@ -473,10 +676,12 @@ impl CodeGenerator {
             media_type,
             components_schemas,
             cached_external_docs,
-          )
+          )?;
         }
       }
     }
+
+    Ok(())
   }
 
   fn inline_path_item(
@@ -484,8 +689,10 @@ impl CodeGenerator {
     parent_doc_path: &Path,
     path_item: &mut PathItem,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+    components_parameters: &mut IndexMap<String, ReferenceOr<Parameter>>,
+    components_responses: &mut IndexMap<String, ReferenceOr<Response>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     path_item
       .get
       .iter_mut()
@@ -496,19 +703,23 @@ impl CodeGenerator {
       .chain(path_item.head.iter_mut())
       .chain(path_item.patch.iter_mut())
       .chain(path_item.trace.iter_mut())
-      .for_each(|operation| {
+      .try_for_each(|operation| {
         self.inline_operation(
           parent_doc_path,
           operation,
           components_schemas,
+          components_parameters,
+          components_responses,
           cached_external_docs,
         )
-      });
+      })?;
 
     for parameter in &mut path_item.parameters {
-      self.inline_reference_or_item(
+      self.inline_reference_or_component(
         parent_doc_path,
         parameter,
+        "parameters",
+        components_parameters,
         cached_external_docs,
         |parent_doc_path, parameter, cached_external_docs| {
           self.inline_parameter(
@@ -518,8 +729,10 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_request_body(
@@ -528,15 +741,17 @@ impl CodeGenerator {
     request_body: &mut RequestBody,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     for (_, media_type) in &mut request_body.content {
       self.inline_media_type(
         parent_doc_path,
         media_type,
         components_schemas,
         cached_external_docs,
-      )
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_response(
@@ -545,7 +760,7 @@ impl CodeGenerator {
     response: &mut Response,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     for (_, header) in &mut response.headers {
       self.inline_reference_or_item(
         parent_doc_path,
@@ -559,7 +774,7 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      )
+      )?;
     }
 
     for (_, media_type) in &mut response.content {
@@ -568,12 +783,16 @@ impl CodeGenerator {
         media_type,
         components_schemas,
         cached_external_docs,
-      )
+      )?;
     }
 
     for (_, link) in &mut response.links {
-      self.inline_reference_or_item(parent_doc_path, link, cached_external_docs, |_, _, _| ())
+      self.inline_reference_or_item(parent_doc_path, link, cached_external_docs, |_, _, _| {
+        Ok(())
+      })?;
     }
+
+    Ok(())
   }
 
   fn inline_responses(
@@ -581,12 +800,15 @@ impl CodeGenerator {
     parent_doc_path: &Path,
     responses: &mut Responses,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+    components_responses: &mut IndexMap<String, ReferenceOr<Response>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     if let Some(default) = &mut responses.default {
-      self.inline_reference_or_item(
+      self.inline_reference_or_component(
         parent_doc_path,
         default,
+        "responses",
+        components_responses,
         cached_external_docs,
         |parent_doc_path, response, cached_external_docs| {
           self.inline_response(
@@ -596,13 +818,15 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
 
     for (_, response) in &mut responses.responses {
-      self.inline_reference_or_item(
+      self.inline_reference_or_component(
         parent_doc_path,
         response,
+        "responses",
+        components_responses,
         cached_external_docs,
         |parent_doc_path, response, cached_external_docs| {
           self.inline_response(
@@ -612,8 +836,10 @@ impl CodeGenerator {
             cached_external_docs,
           )
         },
-      );
+      )?;
     }
+
+    Ok(())
   }
 
   fn inline_schema(
@@ -622,7 +848,7 @@ impl CodeGenerator {
     schema: &mut Schema,
     components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
     cached_external_docs: &mut DocCache,
-  ) {
+  ) -> Result<(), CodegenError> {
     match &mut schema.schema_kind {
       SchemaKind::Type(schema_type) => match schema_type {
         Type::Object(object) => {
@@ -632,7 +858,7 @@ impl CodeGenerator {
               property,
               components_schemas,
               cached_external_docs,
-            )
+            )?;
           }
 
           if let Some(AdditionalProperties::Schema(additional_properties)) =
@@ -643,7 +869,7 @@ impl CodeGenerator {
               additional_properties,
               components_schemas,
               cached_external_docs,
-            )
+            )?;
           }
         }
         Type::Array(array) => {
@@ -653,27 +879,29 @@ impl CodeGenerator {
               items,
               components_schemas,
               cached_external_docs,
-            )
+            )?;
           }
         }
         Type::String(_) | Type::Number(_) | Type::Integer(_) | Type::Boolean { .. } => {}
       },
       SchemaKind::OneOf { one_of: inner }
       | SchemaKind::AllOf { all_of: inner }
-      | SchemaKind::AnyOf { any_of: inner } => inner.iter_mut().for_each(|inner_schema_or_ref| {
-        self.inline_reference_or_schema(
-          parent_doc_path,
-          inner_schema_or_ref,
-          components_schemas,
-          cached_external_docs,
-        )
-      }),
+      | SchemaKind::AnyOf { any_of: inner } => {
+        inner.iter_mut().try_for_each(|inner_schema_or_ref| {
+          self.inline_reference_or_schema(
+            parent_doc_path,
+            inner_schema_or_ref,
+            components_schemas,
+            cached_external_docs,
+          )
+        })?
+      }
       SchemaKind::Not { not } => self.inline_reference_or_schema(
         parent_doc_path,
         not,
         components_schemas,
         cached_external_docs,
-      ),
+      )?,
       SchemaKind::Any(any) => {
         for (_, schema) in &mut any.properties {
           self.inline_reference_or_schema(
@@ -681,7 +909,7 @@ impl CodeGenerator {
             schema,
             components_schemas,
             cached_external_docs,
-          )
+          )?;
         }
 
         if let Some(AdditionalProperties::Schema(additional_properties)) =
@@ -692,7 +920,7 @@ impl CodeGenerator {
             additional_properties,
             components_schemas,
             cached_external_docs,
-          )
+          )?;
         }
 
         if let Some(items) = &mut any.items {
@@ -701,7 +929,7 @@ impl CodeGenerator {
             items,
             components_schemas,
             cached_external_docs,
-          )
+          )?;
         }
 
         any
@@ -709,14 +937,14 @@ impl CodeGenerator {
           .iter_mut()
           .chain(any.all_of.iter_mut())
           .chain(any.any_of.iter_mut())
-          .for_each(|inner_schema_or_ref| {
+          .try_for_each(|inner_schema_or_ref| {
             self.inline_reference_or_schema(
               parent_doc_path,
               inner_schema_or_ref,
               components_schemas,
               cached_external_docs,
             )
-          });
+          })?;
 
         if let Some(not) = &mut any.not {
           self.inline_reference_or_schema(
@@ -724,7 +952,7 @@ impl CodeGenerator {
             not,
             components_schemas,
             cached_external_docs,
-          )
+          )?;
         }
       }
     }
@@ -740,7 +968,7 @@ impl CodeGenerator {
           &mut temp_ref,
           components_schemas,
           cached_external_docs,
-        );
+        )?;
 
         match temp_ref {
           ReferenceOr::Reference { reference } => {
@@ -749,11 +977,16 @@ impl CodeGenerator {
           // Since we already inlined all of the oneOf variant schemas above, this should only
           // happen if the mapping points to a schema that isn't listed under oneOf/anyOf (which is
           // an error in the OpenAPI definition).
-          ReferenceOr::Item(inlined) => panic!(
-            "discriminator-mapped reference {schema_ref} unexpectedly inlined to schema {inlined:#?}",
-          ),
+          ReferenceOr::Item(inlined) => {
+            return Err(CodegenError::InvalidReference(format!(
+              "discriminator-mapped reference {schema_ref} unexpectedly inlined to schema \
+               {inlined:#?}",
+            )))
+          }
         }
       }
     }
+
+    Ok(())
   }
 }
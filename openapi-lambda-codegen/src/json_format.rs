@@ -0,0 +1,73 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Formatting of JSON response bodies produced by the generated code.
+///
+/// By default, JSON response bodies are compact, and object keys appear in the order in which the
+/// corresponding model serializes them (i.e., declaration order for generated structs).
+///
+/// See [`CodeGenerator::with_json_format`](crate::CodeGenerator::with_json_format).
+///
+/// # Example
+///
+/// ```rust
+/// # use openapi_lambda_codegen::JsonFormat;
+/// # let _ =
+/// JsonFormat::new().with_pretty(true).with_sorted_keys(true)
+/// # ;
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct JsonFormat {
+  pub(crate) pretty: bool,
+  pub(crate) sorted_keys: bool,
+}
+
+impl JsonFormat {
+  /// Construct a new `JsonFormat` that produces compact JSON with keys in their natural order.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Pretty-print JSON response bodies with two-space indentation.
+  ///
+  /// This is useful for debugging but increases the size of each response.
+  pub fn with_pretty(mut self, pretty: bool) -> Self {
+    self.pretty = pretty;
+    self
+  }
+
+  /// Sort the keys of every JSON object in response bodies lexicographically, which produces
+  /// canonical output (e.g., for signing or caching responses).
+  ///
+  /// Sorting keys requires serializing each response body into an intermediate
+  /// `serde_json::Value` before writing it out, which roughly doubles the cost of serialization
+  /// and allocates a copy of the entire response body.
+  pub fn with_sorted_keys(mut self, sorted_keys: bool) -> Self {
+    self.sorted_keys = sorted_keys;
+    self
+  }
+
+  /// Function definition or import that brings a `to_json` function into scope in the generated
+  /// API modules.
+  pub(crate) fn gen_to_json_fn(&self, crate_import: &syn::Path) -> TokenStream {
+    if *self == JsonFormat::default() {
+      return quote! {
+        use #crate_import::__private::encoding::to_json;
+      };
+    }
+
+    let pretty = self.pretty;
+    let sorted_keys = self.sorted_keys;
+    quote! {
+      #[allow(dead_code)]
+      fn to_json<T>(
+        value: &T,
+      ) -> Result<String, serde_path_to_error::Error<serde_json::Error>>
+      where
+        T: #crate_import::__private::serde::Serialize,
+      {
+        #crate_import::__private::encoding::to_json_with_format(value, #pretty, #sorted_keys)
+      }
+    }
+  }
+}
@@ -2,35 +2,96 @@
 #![allow(clippy::too_many_arguments)]
 #![warn(missing_docs)]
 
-use crate::api::operation::collect_operations;
+use crate::api::operation::{collect_callback_operations, collect_operations};
+use crate::api::ApiModule;
+use crate::apigw::OPENAPI_GW_FILENAME;
+use crate::doc_cache::DocCache;
+use crate::model::{const_schemas_to_enums, schema_rust_module};
+use crate::reference::parse_doc;
+use crate::validate::conflicting_path_templates;
 
+use convert_case::{Case, Casing};
 use indexmap::IndexMap;
 use itertools::Itertools;
-use openapiv3::{OpenAPI, Operation};
-use proc_macro2::{Ident, Span, TokenStream};
+use openapiv3::{Callback, Info, OpenAPI, Operation};
+use proc_macro2::TokenStream;
 use quote::quote;
 use serde_json::json;
 use syn::parse2;
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
-use std::io::Write;
+use std::io::{ErrorKind, Read, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 mod api;
+mod api_gateway_validation;
+mod api_key;
 mod apigw;
+mod authorizer;
+mod cors;
+mod date_time_backend;
+mod doc_cache;
+mod error;
+mod event_source;
+mod handler_template;
 mod inline;
+mod json_format;
+mod manifest;
+mod map_type;
 mod model;
+mod model_modules;
 mod reference;
+mod request_validation;
+mod set_type;
+mod unsupported;
+mod validate;
 
 // Re-export since `Operation` is part of the public API (for filters), and that includes references
 // to other `openapiv3` types.
 pub use openapiv3;
 
-/// Cache of parsed OpenAPI documents.
-type DocCache = HashMap<PathBuf, serde_yaml::Mapping>;
+// Re-export since `Method` is part of the public API (for route filters).
+pub use http::Method;
+
+pub use api_gateway_validation::ApiGatewayValidation;
+pub use api_key::ApiKeySource;
+pub use authorizer::{Authorizer, UserPoolArn};
+pub use cors::CorsConfig;
+pub use date_time_backend::DateTimeBackend;
+pub use error::CodegenError;
+pub use event_source::EventSource;
+pub use handler_template::HandlerTemplate;
+pub use json_format::JsonFormat;
+pub use map_type::MapType;
+pub use model_modules::ModelModules;
+pub use request_validation::RequestValidation;
+pub use set_type::SetType;
+pub use validate::ValidationIssue;
+
+/// Name of the module generated by [`CodeGenerator::generate_callbacks`].
+const CALLBACKS_MOD_NAME: &str = "callbacks";
+
+/// File name (within the [`CodeGenerator::with_base_dir`] directory) used to resolve references
+/// and report issues for an OpenAPI definition passed to [`CodeGenerator::from_str`] or
+/// [`CodeGenerator::from_reader`].
+const IN_MEMORY_OPENAPI_FILENAME: &str = "openapi.yaml";
+
+/// Schema formats defined by the OpenAPI specification, which the `openapiv3` crate parses into
+/// known variants (see [`CodeGenerator::map_format_to_type`]).
+const STANDARD_FORMATS: [&str; 9] = [
+  "binary",
+  "byte",
+  "date",
+  "date-time",
+  "double",
+  "float",
+  "int32",
+  "int64",
+  "password",
+];
 
 #[derive(Debug)]
 enum LambdaArnImpl {
@@ -63,13 +124,22 @@ enum LambdaArnImpl {
 }
 
 impl LambdaArnImpl {
-  pub fn apigw_invocation_arn(&self) -> serde_json::Value {
+  /// Returns the URI used by API Gateway to invoke the Lambda function.
+  ///
+  /// Response streaming requires API Gateway to invoke the function using the
+  /// `InvokeWithResponseStream` API instead of the `Invoke` API.
+  pub fn apigw_invocation_arn(&self, response_streaming: bool) -> serde_json::Value {
+    let (api_version, invocation_path) = if response_streaming {
+      ("2021-11-15", "response-streaming-invocations")
+    } else {
+      ("2015-03-31", "invocations")
+    };
     match self {
       LambdaArnImpl::CloudFormation { logical_id } => {
         json!({
           "Fn::Sub": format!(
-            "arn:aws:apigateway:${{AWS::Region}}:lambda:path/2015-03-31/functions/${{{logical_id}}}\
-             /invocations",
+            "arn:aws:apigateway:${{AWS::Region}}:lambda:path/{api_version}/functions/\
+             ${{{logical_id}}}/{invocation_path}",
           )
         })
       }
@@ -80,8 +150,8 @@ impl LambdaArnImpl {
         function_name,
         alias_or_version,
       } => serde_json::Value::String(format!(
-        "arn:aws:apigateway:{api_gateway_region}:lambda:path/2015-03-31/functions/arn:aws\
-         :lambda:{function_region}:{account_id}:function:{function_name}{}/invocations",
+        "arn:aws:apigateway:{api_gateway_region}:lambda:path/{api_version}/functions/arn:aws\
+         :lambda:{function_region}:{account_id}:function:{function_name}{}/{invocation_path}",
         alias_or_version
           .as_ref()
           .map(|alias| Cow::Owned(format!(":{alias}")))
@@ -206,7 +276,47 @@ impl LambdaArn {
   }
 }
 
+type ArnOverride = Box<dyn Fn(&Operation) -> Option<LambdaArn> + 'static>;
 type OpFilter = Box<dyn Fn(&Operation) -> bool + 'static>;
+type RouteFilter = Box<dyn Fn(&Method, &str, &Operation) -> bool + 'static>;
+
+/// Endpoint handled by an [`ApiLambda`] outside of the OpenAPI definition (see
+/// [`ApiLambda::with_extra_route`]).
+struct ExtraRoute {
+  method: Method,
+  path: String,
+  /// Rust expression evaluating to a function or closure that returns an `HttpResponse`.
+  handler: String,
+}
+
+impl ExtraRoute {
+  /// Returns an error if the route can't be added to the generated code or `openapi-apigw.yaml`.
+  fn validate(&self) -> Result<(), String> {
+    if ![
+      Method::GET,
+      Method::PUT,
+      Method::POST,
+      Method::DELETE,
+      Method::OPTIONS,
+      Method::HEAD,
+      Method::PATCH,
+      Method::TRACE,
+    ]
+    .contains(&self.method)
+    {
+      return Err(format!("unsupported HTTP method `{}`", self.method));
+    }
+    if !self.path.starts_with('/') || self.path.contains(['{', '}']) {
+      return Err(format!(
+        "path `{}` must be a literal path starting with `/`",
+        self.path
+      ));
+    }
+    syn::parse_str::<syn::Expr>(&self.handler)
+      .map(|_| ())
+      .map_err(|err| format!("invalid handler `{}`: {err}", self.handler))
+  }
+}
 
 /// Builder for generating code for a single API Lambda function.
 ///
@@ -219,8 +329,9 @@ type OpFilter = Box<dyn Fn(&Operation) -> bool + 'static>;
 /// between performance and implementation/deployment complexity (i.e., more Lambda functions to
 /// manage).
 ///
-/// Use the [`with_op_filter`](ApiLambda::with_op_filter) method to specify a closure that
-/// associates API endpoints with the corresponding Lambda function.
+/// Use the [`with_op_filter`](ApiLambda::with_op_filter) or
+/// [`with_route_filter`](ApiLambda::with_route_filter) method to specify a closure that associates
+/// API endpoints with the corresponding Lambda function.
 ///
 /// # Example
 ///
@@ -231,9 +342,19 @@ type OpFilter = Box<dyn Fn(&Operation) -> bool + 'static>;
 /// # ;
 /// ```
 pub struct ApiLambda {
+  arn_override: Option<ArnOverride>,
+  base_path: Option<String>,
+  cors: Option<CorsConfig>,
+  event_source: EventSource,
+  extra_routes: Vec<ExtraRoute>,
+  handler_template: Option<HandlerTemplate>,
   mod_name: String,
   lambda_arn: LambdaArnImpl,
+  max_body_bytes: Option<usize>,
   op_filter: Option<OpFilter>,
+  response_compression: bool,
+  response_streaming: bool,
+  route_filter: Option<RouteFilter>,
 }
 
 impl ApiLambda {
@@ -249,12 +370,199 @@ impl ApiLambda {
     M: Into<String>,
   {
     Self {
+      arn_override: None,
+      base_path: None,
+      cors: None,
+      event_source: EventSource::default(),
+      extra_routes: Vec::new(),
+      handler_template: None,
       lambda_arn: lambda_arn.0,
+      max_body_bytes: None,
       mod_name: mod_name.into(),
       op_filter: None,
+      response_compression: false,
+      response_streaming: false,
+      route_filter: None,
     }
   }
 
+  /// Override the Lambda function that API Gateway invokes for specific API endpoints.
+  ///
+  /// By default, API Gateway invokes the Lambda function identified by the `lambda_arn` passed to
+  /// [`ApiLambda::new`] for every API endpoint handled by this `ApiLambda`. This method allows a
+  /// subset of those endpoints (e.g., a slow report generator requiring more memory or a longer
+  /// timeout) to be routed to a differently-configured Lambda function that runs the same
+  /// generated code. For each API endpoint, the `x-amazon-apigateway-integration` in the
+  /// generated `openapi-apigw.yaml` uses the ARN returned by the closure, or the default ARN if the
+  /// closure returns `None`.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_arn_override(|op| {
+  ///     (op.operation_id.as_deref() == Some("generateReport"))
+  ///       .then(|| LambdaArn::cloud_formation("ReportApiFunction.Alias"))
+  ///   })
+  /// # ;
+  /// ```
+  pub fn with_arn_override<F>(mut self, arn_override: F) -> Self
+  where
+    F: Fn(&Operation) -> Option<LambdaArn> + 'static,
+  {
+    self.arn_override = Some(Box::new(arn_override));
+    self
+  }
+
+  /// Specify the base path to strip from request paths before matching them against the request
+  /// paths defined in the OpenAPI definition.
+  ///
+  /// The generated dispatcher matches the path of each request against the request paths defined
+  /// in the OpenAPI definition whenever the Lambda event doesn't otherwise identify the operation
+  /// (e.g., for Application Load Balancers, Lambda function URLs, and `$default` HTTP API routes).
+  /// Depending on the deployment, these paths may include a prefix such as an HTTP API stage name
+  /// (e.g., `/prod`) or an API mapping of a custom domain name. Requests whose paths don't begin
+  /// with the base path are matched unchanged.
+  ///
+  /// By default, the base path is the path of the first URL in the OpenAPI definition's `servers`
+  /// (e.g., `/v1` for `https://api.example.com/v1`), using the default value of any server
+  /// variables. Pass an empty string to disable base path stripping.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_base_path("/prod")
+  /// # ;
+  /// ```
+  pub fn with_base_path<B>(mut self, base_path: B) -> Self
+  where
+    B: Into<String>,
+  {
+    self.base_path = Some(base_path.into());
+    self
+  }
+
+  /// Enable cross-origin resource sharing (CORS) for the API endpoints handled by this Lambda
+  /// function.
+  ///
+  /// See [`CorsConfig`] for further details.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, CorsConfig, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_cors(CorsConfig::new("https://example.com"))
+  /// # ;
+  /// ```
+  pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+    self.cors = Some(cors);
+    self
+  }
+
+  /// Specify the type of Lambda event handled by this Lambda function.
+  ///
+  /// By default, the generated code handles events from Amazon API Gateway REST APIs
+  /// ([`EventSource::ApiGatewayProxy`]). See [`EventSource`] for further details.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, EventSource, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_event_source(EventSource::ApiGatewayV2Http)
+  /// # ;
+  /// ```
+  pub fn with_event_source(mut self, event_source: EventSource) -> Self {
+    self.event_source = event_source;
+    self
+  }
+
+  /// Route requests for an additional endpoint that isn't defined in the OpenAPI definition (e.g.,
+  /// a load balancer health check) to this Lambda function.
+  ///
+  /// Requests whose HTTP method and path (after stripping any base path; see
+  /// [`with_base_path`](ApiLambda::with_base_path)) match the route bypass the generated `Api`
+  /// trait and [`Middleware`](https://docs.rs/openapi-lambda/latest/openapi_lambda/trait.Middleware.html)
+  /// altogether: the dispatcher responds with the `HttpResponse` returned by `handler`, which is a
+  /// Rust expression evaluating to a function or closure that takes no arguments (e.g.,
+  /// `crate::health::health_check`). The expression is evaluated within the generated API module,
+  /// so paths should be absolute (e.g., `crate::...`).
+  ///
+  /// The generated `openapi-apigw.yaml` includes an unauthenticated (`security: []`) endpoint
+  /// for each extra route that invokes this Lambda function, unless the Lambda function handles
+  /// Application Load Balancer events (see [`EventSource`]). Each `path` must be a literal path
+  /// (without path parameters) whose HTTP `method` isn't also defined for that path in the OpenAPI
+  /// definition.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn, Method};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_extra_route(Method::GET, "/health", "crate::health::health_check")
+  /// # ;
+  /// ```
+  pub fn with_extra_route<P, H>(mut self, method: Method, path: P, handler: H) -> Self
+  where
+    P: Into<String>,
+    H: Into<String>,
+  {
+    self.extra_routes.push(ExtraRoute {
+      method,
+      path: path.into(),
+      handler: handler.into(),
+    });
+    self
+  }
+
+  /// Use a custom template for the generated `<MODULE_NAME>_handler.rs` file.
+  ///
+  /// See [`HandlerTemplate`] for the supported placeholders.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, HandlerTemplate, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_handler_template(HandlerTemplate::File("handler.rs.tmpl".into()))
+  /// # ;
+  /// ```
+  pub fn with_handler_template(mut self, handler_template: HandlerTemplate) -> Self {
+    self.handler_template = Some(handler_template);
+    self
+  }
+
+  /// Reject request bodies larger than the specified number of bytes.
+  ///
+  /// When set, the generated handler wrappers respond with `413 Payload Too Large` (see
+  /// `EventError::BodyTooLarge`) before decoding or deserializing any request body whose (decoded)
+  /// size exceeds `max_body_bytes`. By default, request bodies of any size are accepted, subject to
+  /// the limits imposed by AWS.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_max_body_bytes(1024 * 1024)
+  /// # ;
+  /// ```
+  pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+    self.max_body_bytes = Some(max_body_bytes);
+    self
+  }
+
   /// Define a filter to associate a subset of API endpoints with this Lambda function.
   ///
   /// Use this method when *not* implementing a "mono-Lambda" that handles all API endpoints. By
@@ -282,6 +590,157 @@ impl ApiLambda {
     self.op_filter = Some(Box::new(op_filter));
     self
   }
+
+  /// Define a filter to associate a subset of API endpoints with this Lambda function based on
+  /// each endpoint's HTTP method and request path.
+  ///
+  /// This method is similar to [`with_op_filter`](ApiLambda::with_op_filter), but the closure also
+  /// receives the HTTP method and request path of each endpoint, which makes it possible to split
+  /// API endpoints between Lambda functions by path prefix. If both filters are specified, an
+  /// endpoint is only included if both filters return `true`.
+  ///
+  /// # Arguments
+  ///
+  /// * `route_filter` - Closure that returns `true` or `false` to indicate whether the OpenAPI
+  ///   [`Operation`] (endpoint) with the given HTTP method and request path (e.g., `/pet/{petId}`)
+  ///   will be handled by the corresponding Lambda function
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("admin", LambdaArn::cloud_formation("AdminApiFunction.Alias"))
+  ///   // Only include API endpoints under `/admin`.
+  ///   .with_route_filter(|_method, path, _op| path.starts_with("/admin/"))
+  /// # ;
+  /// ```
+  pub fn with_route_filter<F>(mut self, route_filter: F) -> Self
+  where
+    F: Fn(&Method, &str, &Operation) -> bool + 'static,
+  {
+    self.route_filter = Some(Box::new(route_filter));
+    self
+  }
+
+  /// Whether the API endpoint with the given HTTP method and request path is handled by this
+  /// Lambda function.
+  pub(crate) fn includes_operation(
+    &self,
+    method: &Method,
+    request_path: &str,
+    op: &Operation,
+  ) -> bool {
+    self
+      .op_filter
+      .as_ref()
+      .map_or(true, |op_filter| (*op_filter)(op))
+      && self.route_filter.as_ref().map_or(true, |route_filter| {
+        (*route_filter)(method, request_path, op)
+      })
+  }
+
+  /// Whether Amazon API Gateway invokes this Lambda function (i.e., it isn't invoked by an
+  /// Application Load Balancer or via a Lambda function URL with response streaming).
+  pub(crate) fn is_api_gateway_integration(&self) -> bool {
+    !(self.event_source == EventSource::ApplicationLoadBalancer
+      || (self.response_streaming && self.event_source == EventSource::ApiGatewayV2Http))
+  }
+
+  /// Returns the URI used by API Gateway to invoke this Lambda function for the given operation,
+  /// taking into account any [`with_arn_override`](ApiLambda::with_arn_override) closure.
+  ///
+  /// Extra routes (see [`with_extra_route`](ApiLambda::with_extra_route)) have no operation and
+  /// always use the default ARN.
+  pub(crate) fn apigw_invocation_arn(&self, op: Option<&Operation>) -> serde_json::Value {
+    let arn_override = self
+      .arn_override
+      .as_ref()
+      .zip(op)
+      .and_then(|(arn_override, op)| (*arn_override)(op));
+    arn_override
+      .as_ref()
+      .map_or(&self.lambda_arn, |arn_override| &arn_override.0)
+      .apigw_invocation_arn(self.response_streaming)
+  }
+
+  /// Compress response bodies returned by the API handlers using `gzip` or `deflate`, depending on
+  /// the request's `Accept-Encoding` header.
+  ///
+  /// Compressed responses include a `Content-Encoding` header and are base64-encoded within the
+  /// Lambda response (i.e., `isBase64Encoded: true`). Empty bodies and responses that already
+  /// include a `Content-Encoding` header are returned uncompressed. This option requires enabling
+  /// the `compression` feature of the `openapi-lambda` crate.
+  ///
+  /// Compression is disabled by default to avoid compressing responses twice when Amazon API
+  /// Gateway's own
+  /// [payload compression](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-gzip-compression-decompression.html)
+  /// is enabled. Note that API Gateway REST APIs ([`EventSource::ApiGatewayProxy`]) only decode
+  /// base64-encoded response bodies to binary if the request's `Accept` header matches one of the
+  /// API's binary media types (see [`CodeGenerator::with_binary_media_types`]), so such APIs
+  /// typically need to include `*/*` as a binary media type.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_response_compression(true)
+  /// # ;
+  /// ```
+  pub fn with_response_compression(mut self, response_compression: bool) -> Self {
+    self.response_compression = response_compression;
+    self
+  }
+
+  /// Stream responses to clients using the
+  /// [Lambda response streaming](https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html)
+  /// API.
+  ///
+  /// When enabled, the response type of each operation gains a `Streaming` variant containing a
+  /// [`ResponseStream`](https://docs.rs/openapi-lambda/latest/openapi_lambda/struct.ResponseStream.html),
+  /// which is useful for large response bodies and server-sent events. The generated
+  /// `dispatch_request` method returns a
+  /// [`StreamingResponse`](https://docs.rs/openapi-lambda/latest/openapi_lambda/type.StreamingResponse.html),
+  /// and the Lambda runtime must be started with
+  /// [`run_lambda_streaming`](https://docs.rs/openapi-lambda/latest/openapi_lambda/fn.run_lambda_streaming.html).
+  ///
+  /// Response streaming is supported by Amazon API Gateway REST APIs
+  /// ([`EventSource::ApiGatewayProxy`]) and by Lambda function URLs configured with the
+  /// `RESPONSE_STREAM` invoke mode ([`EventSource::ApiGatewayV2Http`]). Amazon API Gateway HTTP
+  /// APIs don't support response streaming, so operations handled by streaming API Lambdas with
+  /// the [`EventSource::ApiGatewayV2Http`] event source are omitted from the generated
+  /// `openapi-apigw.yaml`. Application Load Balancers don't support response streaming either.
+  ///
+  /// # Example
+  ///
+  /// ```rust
+  /// # use openapi_lambda_codegen::{ApiLambda, LambdaArn};
+  /// # let _ =
+  /// ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   .with_response_streaming(true)
+  /// # ;
+  /// ```
+  pub fn with_response_streaming(mut self, response_streaming: bool) -> Self {
+    self.response_streaming = response_streaming;
+    self
+  }
+}
+
+/// Code generated by [`CodeGenerator::generate_to_string`].
+#[derive(Clone, Debug)]
+pub struct GeneratedArtifacts {
+  /// Generated Rust code, which [`CodeGenerator::generate`] writes to `out.rs`.
+  pub out_rs: TokenStream,
+  /// OpenAPI definition for Amazon API Gateway, which [`CodeGenerator::generate`] writes to
+  /// `openapi-apigw.yaml`.
+  pub openapi_apigw_yaml: String,
+  /// Formatted source code of each placeholder handler, keyed by module name (e.g., `backend` for
+  /// `backend_handler.rs`).
+  pub handlers: BTreeMap<String, String>,
+  /// JSON route manifest, if enabled via [`CodeGenerator::with_manifest`].
+  pub manifest_json: Option<String>,
 }
 
 /// OpenAPI Lambda code generator.
@@ -311,6 +770,9 @@ impl ApiLambda {
 ///    module (`<MODULE_NAME>_handler`) in `src/lib.rs`, and replace each instance of `todo!()` in
 ///    the trait implementation.
 ///
+/// To generate code without writing any files (e.g., outside of a Cargo build script), use
+/// [`generate_to_string`](CodeGenerator::generate_to_string) instead.
+///
 /// # Examples
 ///
 /// ## Mono-Lambda
@@ -345,9 +807,33 @@ impl ApiLambda {
 ///   .generate();
 /// ```
 pub struct CodeGenerator {
+  api_gateway_validation: ApiGatewayValidation,
+  api_key_source: Option<ApiKeySource>,
   api_lambdas: IndexMap<String, ApiLambda>,
+  authorizers: IndexMap<String, Authorizer>,
+  binary_media_types: Option<Vec<String>>,
+  builders: bool,
+  callbacks: bool,
+  client_mod_name: Option<String>,
+  crate_name: String,
+  datetime_backend: DateTimeBackend,
+  format_types: IndexMap<String, String>,
+  json_format: JsonFormat,
+  manifest_path: Option<PathBuf>,
+  map_type: MapType,
+  mock_handlers: bool,
+  model_derives: Vec<String>,
+  model_modules: ModelModules,
+  openapi_contents: Option<Vec<u8>>,
   openapi_path: PathBuf,
   out_dir: PathBuf,
+  preserve_integrations: bool,
+  request_header_structs: bool,
+  request_validation: RequestValidation,
+  rustfmt: bool,
+  schema_ref_types: IndexMap<String, String>,
+  set_type: SetType,
+  spec_validation: bool,
 }
 
 impl CodeGenerator {
@@ -355,80 +841,1091 @@ impl CodeGenerator {
   ///
   /// # Arguments
   ///
-  /// * `openapi_path` - Input path to OpenAPI definition in YAML format
+  /// * `openapi_path` - Input path to OpenAPI definition in YAML format, or in JSON format if the
+  ///   path has a `.json` extension (external documents referenced via `$ref` are parsed likewise)
   /// * `out_dir` - Output directory path in which `openapi-apigw.yaml` and one
   ///   `<MODULE_NAME>_handler.rs` file for each call to
-  ///    [`add_api_lambda`](CodeGenerator::add_api_lambda) will be written
+  ///   [`add_api_lambda`](CodeGenerator::add_api_lambda) will be written
   pub fn new<P, O>(openapi_path: P, out_dir: O) -> Self
   where
     P: Into<PathBuf>,
     O: Into<PathBuf>,
   {
     Self {
+      api_gateway_validation: ApiGatewayValidation::None,
+      api_key_source: None,
       api_lambdas: IndexMap::new(),
+      authorizers: IndexMap::new(),
+      binary_media_types: None,
+      builders: false,
+      callbacks: false,
+      client_mod_name: None,
+      crate_name: "openapi_lambda".to_string(),
+      datetime_backend: DateTimeBackend::default(),
+      format_types: IndexMap::new(),
+      json_format: JsonFormat::default(),
+      manifest_path: None,
+      map_type: MapType::default(),
+      mock_handlers: false,
+      model_derives: Vec::new(),
+      model_modules: ModelModules::default(),
+      openapi_contents: None,
       openapi_path: openapi_path.into(),
       out_dir: out_dir.into(),
+      preserve_integrations: false,
+      request_header_structs: false,
+      request_validation: RequestValidation::None,
+      rustfmt: true,
+      schema_ref_types: IndexMap::new(),
+      set_type: SetType::default(),
+      spec_validation: false,
     }
   }
 
-  /// Register an API Lambda function for code generation.
+  /// Construct a new `CodeGenerator` from an in-memory OpenAPI definition (e.g., one embedded in
+  /// the build script via [`include_str!`] or produced by an earlier build step).
   ///
-  /// Each call to this method will result in a module being generated that contains an `Api` trait
-  /// with methods for the corresponding API endpoints. See [`ApiLambda`] for further details.
-  pub fn add_api_lambda(mut self, builder: ApiLambda) -> Self {
-    if self.api_lambdas.contains_key(&builder.mod_name) {
-      panic!(
-        "API Lambda module names must be unique: found duplicate `{}`",
-        builder.mod_name
-      )
+  /// Since the definition has no file path, external documents referenced via `$ref` are resolved
+  /// relative to the current directory (the package root when running in a Cargo build script)
+  /// unless a different directory is provided via
+  /// [`with_base_dir`](CodeGenerator::with_base_dir). Issues found in the definition are reported
+  /// as if it were located at `openapi.yaml` within that directory.
+  ///
+  /// # Arguments
+  ///
+  /// * `openapi` - OpenAPI definition in YAML format (or JSON format, which YAML parsers also
+  ///   accept)
+  /// * `out_dir` - Output directory path (see [`new`](CodeGenerator::new))
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// # fn generate_openapi_yaml() -> String { unimplemented!() }
+  /// CodeGenerator::from_str(generate_openapi_yaml(), ".openapi-lambda")
+  ///   .with_base_dir("spec")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn from_str<S, O>(openapi: S, out_dir: O) -> Self
+  where
+    S: Into<String>,
+    O: Into<PathBuf>,
+  {
+    Self {
+      openapi_contents: Some(openapi.into().into_bytes()),
+      ..Self::new(IN_MEMORY_OPENAPI_FILENAME, out_dir)
     }
+  }
 
-    self.api_lambdas.insert(builder.mod_name.clone(), builder);
+  /// Construct a new `CodeGenerator` by reading an OpenAPI definition from `reader`.
+  ///
+  /// The entire definition is read immediately, and any I/O error is returned. See
+  /// [`from_str`](CodeGenerator::from_str) for how external references are resolved.
+  ///
+  /// # Arguments
+  ///
+  /// * `reader` - Source of the OpenAPI definition in YAML format (or JSON format, which YAML
+  ///   parsers also accept)
+  /// * `out_dir` - Output directory path (see [`new`](CodeGenerator::new))
+  pub fn from_reader<R, O>(mut reader: R, out_dir: O) -> std::io::Result<Self>
+  where
+    R: Read,
+    O: Into<PathBuf>,
+  {
+    let mut openapi_contents = Vec::new();
+    reader.read_to_end(&mut openapi_contents)?;
+    Ok(Self {
+      openapi_contents: Some(openapi_contents),
+      ..Self::new(IN_MEMORY_OPENAPI_FILENAME, out_dir)
+    })
+  }
+
+  /// Check the generated `openapi-apigw.yaml` for constructs that Amazon API Gateway rejects or
+  /// silently ignores when importing a REST API (see [`ApiGatewayValidation`]).
+  ///
+  /// The following problems are reported, each along with its location in `openapi-apigw.yaml`:
+  ///  * Paths containing characters that API Gateway doesn't allow, path parameters that don't
+  ///    span an entire path segment (e.g., `/foo-{fooId}`), and greedy path parameters (e.g.,
+  ///    `{proxy+}`) that aren't the last path segment.
+  ///  * Schema names in `components.schemas` that aren't alphanumeric, since API Gateway creates a
+  ///    model with the same name for each schema.
+  ///  * Parameters with a non-default `style` or `explode`, which API Gateway ignores.
+  ///  * Schemas that use `exclusiveMinimum`.
+  ///
+  /// See [API Gateway known issues](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html#api-gateway-known-issues-rest-apis)
+  /// for details. By default, no validation is performed.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiGatewayValidation, ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_api_gateway_validation(ApiGatewayValidation::Error)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_api_gateway_validation(
+    mut self,
+    api_gateway_validation: ApiGatewayValidation,
+  ) -> Self {
+    self.api_gateway_validation = api_gateway_validation;
     self
   }
 
-  /// Emit generated code.
-  pub fn generate(self) {
-    let cargo_out_dir = std::env::var("OUT_DIR").expect("OUT_DIR env not set");
-    log::info!("writing Rust codegen to {cargo_out_dir}");
-    log::info!("writing OpenAPI codegen to {}", self.out_dir.display());
+  /// Source of the API keys for operations that require them (see [`ApiKeySource`]).
+  ///
+  /// Amazon API Gateway requires an API key for each operation whose `security` requirements
+  /// (or the top-level `security` requirements, if the operation doesn't define its own)
+  /// reference an `apiKey` security scheme. Since API Gateway only accepts API keys in the
+  /// `x-api-key` request header, such security schemes are moved to this header in the generated
+  /// `openapi-apigw.yaml`.
+  ///
+  /// By default, the `x-amazon-apigateway-api-key-source` extension is omitted, and API Gateway
+  /// reads API keys from the request header.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiKeySource, ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_api_key_source(ApiKeySource::Authorizer)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_api_key_source(mut self, api_key_source: ApiKeySource) -> Self {
+    self.api_key_source = Some(api_key_source);
+    self
+  }
 
-    if !self.out_dir.exists() {
-      std::fs::create_dir_all(&self.out_dir).unwrap_or_else(|err| {
-        panic!(
-          "failed to create directory `{}`: {err}",
-          self.out_dir.display()
-        )
-      });
+  /// Use an Amazon API Gateway [`Authorizer`] for the security scheme named `security_scheme` in
+  /// the OpenAPI definition's `components.securitySchemes`.
+  ///
+  /// The security scheme in the generated `openapi-apigw.yaml` is replaced with an `apiKey` scheme
+  /// (as required by API Gateway) that includes the
+  /// [`x-amazon-apigateway-authorizer`](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-authorizer.html)
+  /// extension. Operations that don't define their own `security` requirements inherit the
+  /// top-level `security` requirements of the OpenAPI definition, since API Gateway configures
+  /// authorizers for each method individually. Operations with an empty security requirement
+  /// (`security: [{}]`) remain unauthenticated.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, Authorizer, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_authorizer(
+  ///     "bearerAuth",
+  ///     Authorizer::lambda_token(LambdaArn::cloud_formation("AuthorizerFunction.Alias")),
+  ///   )
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if an authorizer has already been configured for `security_scheme`. Code generation
+  /// panics if the OpenAPI definition does not define the security scheme.
+  pub fn with_authorizer<S>(mut self, security_scheme: S, authorizer: Authorizer) -> Self
+  where
+    S: Into<String>,
+  {
+    let security_scheme = security_scheme.into();
+    if self.authorizers.contains_key(&security_scheme) {
+      panic!("duplicate authorizer for security scheme `{security_scheme}`");
     }
+    self.authorizers.insert(security_scheme, authorizer);
+    self
+  }
 
-    let openapi_file = File::open(&self.openapi_path)
-      .unwrap_or_else(|err| panic!("failed to open {}: {err}", self.openapi_path.display()));
-
-    let openapi_yaml: serde_yaml::Mapping =
-      serde_path_to_error::deserialize(serde_yaml::Deserializer::from_reader(&openapi_file))
-        .unwrap_or_else(|err| panic!("Failed to parse OpenAPI spec as YAML: {err}"));
+  /// Directory relative to which external documents referenced via `$ref` are resolved for an
+  /// OpenAPI definition passed to [`from_str`](CodeGenerator::from_str) or
+  /// [`from_reader`](CodeGenerator::from_reader).
+  ///
+  /// Defaults to the current directory. This has no effect on a `CodeGenerator` constructed via
+  /// [`new`](CodeGenerator::new), which resolves references relative to the directory containing
+  /// the OpenAPI definition.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// # fn generate_openapi_yaml() -> String { unimplemented!() }
+  /// CodeGenerator::from_str(generate_openapi_yaml(), ".openapi-lambda")
+  ///   .with_base_dir("spec")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_base_dir<B>(mut self, base_dir: B) -> Self
+  where
+    B: AsRef<Path>,
+  {
+    if self.openapi_contents.is_some() {
+      self.openapi_path = base_dir.as_ref().join(IN_MEMORY_OPENAPI_FILENAME);
+    }
+    self
+  }
 
-    let mut cached_external_docs = DocCache::new();
+  /// Media types for which Amazon API Gateway should treat request and response payloads as binary
+  /// data (see
+  /// [`x-amazon-apigateway-binary-media-types`](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-binary-media-types.html)).
+  ///
+  /// By default, the generated `openapi-apigw.yaml` lists every request and response body media
+  /// type that the generated code represents as raw bytes (i.e., anything other than
+  /// `application/json` and `text/*`). Specifying the media types explicitly (e.g., to use a
+  /// wildcard such as `image/*`) replaces this inferred list, and an empty list omits the
+  /// extension altogether.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_binary_media_types(&["application/octet-stream", "image/*"])
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_binary_media_types(mut self, binary_media_types: &[&str]) -> Self {
+    self.binary_media_types = Some(
+      binary_media_types
+        .iter()
+        .map(|media_type| media_type.to_string())
+        .collect(),
+    );
+    self
+  }
 
-    // Clippy in 1.70.0 raises a false positive here.
-    #[allow(clippy::redundant_clone)]
-    cached_external_docs.insert(self.openapi_path.to_path_buf(), openapi_yaml.clone());
+  /// Generate a builder for each object model.
+  ///
+  /// For each object model `Foo`, the generated code includes a `FooBuilder` struct (constructed
+  /// via `Foo::builder()`) with one setter method per property. Optional properties may be left
+  /// unset, while `FooBuilder::build()` returns an `openapi_lambda::models::MissingField` error if
+  /// any required property hasn't been set. `readOnly` properties with an `x-rust-default-response`
+  /// extension are instead populated by evaluating the extension's Rust expression. Builders are not
+  /// generated for models composed via `allOf`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_builders()
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_builders(mut self) -> Self {
+    self.builders = true;
+    self
+  }
 
-    println!("cargo:rerun-if-changed={}", self.openapi_path.display());
+  /// Path used by the generated code to refer to the `openapi-lambda` crate.
+  ///
+  /// This is similar to serde's [`crate`](https://serde.rs/container-attrs.html#crate) attribute
+  /// and is useful when the `openapi-lambda` crate is re-exported by another crate (e.g., a
+  /// workspace facade crate) rather than being a direct dependency. Defaults to `openapi_lambda`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_crate_name("my_facade::openapi_lambda")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if `crate_name` is not a valid Rust path.
+  pub fn with_crate_name(mut self, crate_name: &str) -> Self {
+    if let Err(err) = syn::parse_str::<syn::Path>(crate_name) {
+      panic!("invalid crate name `{crate_name}`: {err}");
+    }
+    self.crate_name = crate_name.to_string();
+    self
+  }
+
+  /// Crate used to represent OpenAPI `date` and `date-time` string formats (see
+  /// [`DateTimeBackend`]).
+  ///
+  /// By default, the generated code uses the `chrono` crate. The corresponding feature of the
+  /// `openapi-lambda` crate (`chrono` or `time`) must be enabled.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, DateTimeBackend, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_datetime_backend(DateTimeBackend::Time)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_datetime_backend(mut self, datetime_backend: DateTimeBackend) -> Self {
+    self.datetime_backend = datetime_backend;
+    self
+  }
+
+  /// Formatting of JSON response bodies (see [`JsonFormat`]).
+  ///
+  /// By default, JSON response bodies are compact and aren't sorted. Pretty-printing and sorting
+  /// keys are useful for debugging or for producing canonical output, but both increase the cost
+  /// of serializing each response (sorting keys, in particular, requires an additional copy of the
+  /// response body).
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, JsonFormat, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_json_format(JsonFormat::new().with_pretty(true))
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_json_format(mut self, json_format: JsonFormat) -> Self {
+    self.json_format = json_format;
+    self
+  }
+
+  /// Write a machine-readable JSON manifest of the API routes handled by each [`ApiLambda`].
+  ///
+  /// The manifest is intended for infrastructure-as-code tools other than CloudFormation (e.g.,
+  /// Terraform or the AWS CDK), which can use it to configure API Gateway integrations without
+  /// parsing the generated `openapi-apigw.yaml`. Relative paths are resolved relative to the output
+  /// directory passed to [`CodeGenerator::new`].
+  ///
+  /// The manifest contains a `routes` array with one entry for each operation handled by an
+  /// [`ApiLambda`] (in the order the operations appear in the OpenAPI definition), followed by one
+  /// entry for each extra route registered via [`ApiLambda::with_extra_route`]. Each entry
+  /// contains the following fields:
+  ///  * `method` - HTTP method (e.g., `GET`)
+  ///  * `path` - Request path template (e.g., `/pets/{petId}`)
+  ///  * `operation_id` - Operation ID, or `null` for extra routes
+  ///  * `module` - Name of the [`ApiLambda`] module that handles the route
+  ///  * `integration_uri` - URI that API Gateway uses to invoke the Lambda function, exactly as it
+  ///    appears in `openapi-apigw.yaml` (i.e., an object containing an `Fn::Sub` expression when
+  ///    using [`LambdaArn::cloud_formation`]). Routes that API Gateway doesn't serve (e.g., those
+  ///    handled by an Application Load Balancer) have a `null` URI.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   // Writes `.openapi-lambda/routes.json`.
+  ///   .with_manifest("routes.json")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_manifest<P>(mut self, manifest_path: P) -> Self
+  where
+    P: Into<PathBuf>,
+  {
+    self.manifest_path = Some(manifest_path.into());
+    self
+  }
+
+  /// Rust type used to represent objects with `additionalProperties` (see [`MapType`]).
+  ///
+  /// By default, the generated code uses `openapi_lambda::models::IndexMap`, which preserves the
+  /// order in which the object's keys were inserted (or deserialized). Serialized objects list
+  /// their declared `properties` first (in declaration order), followed by any additional
+  /// properties in map order, so output is deterministic for a given input.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn, MapType};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_map_type(MapType::BTreeMap)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_map_type(mut self, map_type: MapType) -> Self {
+    self.map_type = map_type;
+    self
+  }
+
+  /// Additional traits to derive for every generated model (e.g., `Hash` or
+  /// `schemars::JsonSchema`).
+  ///
+  /// These derives are emitted alongside the `Clone`, `Debug`, `Deserialize`, and `Serialize`
+  /// derives that every model has (as well as `PartialEq` and `Eq` for most models whose fields
+  /// implement them). Individual schemas may specify further derives via an
+  /// `x-rust-derives` extension containing an array of trait paths, such as:
+  ///
+  /// ```yaml
+  /// components:
+  ///   schemas:
+  ///     Pet:
+  ///       type: object
+  ///       x-rust-derives: [Eq, Hash]
+  ///       properties:
+  ///         id:
+  ///           type: integer
+  /// ```
+  ///
+  /// Derives already present on a model are not duplicated. Note that deriving a trait for a
+  /// model generally requires that each of the model's fields also implement the trait.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_model_derives(&["Hash"])
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if any of the `model_derives` is not a valid Rust path.
+  pub fn with_model_derives(mut self, model_derives: &[&str]) -> Self {
+    for derive in model_derives {
+      if let Err(err) = syn::parse_str::<syn::Path>(derive) {
+        panic!("invalid model derive `{derive}`: {err}");
+      }
+    }
+    self.model_derives = model_derives
+      .iter()
+      .map(|derive| derive.to_string())
+      .collect();
+    self
+  }
+
+  /// Layout of the generated `models` module (see [`ModelModules`]).
+  ///
+  /// By default, every model is generated directly within the `models` module. For large APIs,
+  /// [`ModelModules::ByTag`] instead groups models into submodules such as `models::pet`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn, ModelModules};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_model_modules(ModelModules::ByTag)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_model_modules(mut self, model_modules: ModelModules) -> Self {
+    self.model_modules = model_modules;
+    self
+  }
+
+  /// Preserve any
+  /// [`x-amazon-apigateway-integration`](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-swagger-extensions-integration.html)
+  /// extensions already present in the input OpenAPI definition.
+  ///
+  /// By default, the generated `openapi-apigw.yaml` replaces the integration of each operation
+  /// mapped to an [`ApiLambda`], and it omits operations not mapped to any [`ApiLambda`]. When
+  /// this option is enabled, operations that define their own integration (e.g., a mock
+  /// integration or an HTTP proxy to another service) are instead included verbatim, which allows
+  /// custom integrations to be mixed with generated ones. Since API Gateway doesn't invoke the
+  /// Lambda function for these operations, no handler methods are generated for them, even if
+  /// they're mapped to an [`ApiLambda`]. Models are still generated.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_preserve_integrations(true)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_preserve_integrations(mut self, preserve_integrations: bool) -> Self {
+    self.preserve_integrations = preserve_integrations;
+    self
+  }
+
+  /// Pass each operation's header parameters to its handler as a single typed struct.
+  ///
+  /// By default, each header parameter (`in: header`) is passed to the handler method as a separate
+  /// argument. When this option is enabled, the header parameters of each operation are instead
+  /// grouped into a generated struct named after the operation (e.g., `GetPetRequestHeaders` for
+  /// the `getPet` operation), with one field per header parameter. Required headers use the
+  /// corresponding Rust type, while optional headers are wrapped in `Option`. The handler wrapper
+  /// still parses each header (responding with `400 Bad Request` if a header is missing, isn't
+  /// valid UTF-8, or fails to parse) before invoking the handler, which also continues to receive
+  /// the raw `HeaderMap`. Generated clients are unaffected.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_request_header_structs()
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_request_header_structs(mut self) -> Self {
+    self.request_header_structs = true;
+    self
+  }
+
+  /// Have Amazon API Gateway validate requests before invoking the Lambda function (see
+  /// [`RequestValidation`]).
+  ///
+  /// API Gateway rejects invalid requests with a `400 Bad Request` response, which avoids invoking
+  /// the Lambda function for obviously malformed requests. Request validators are only supported
+  /// by REST APIs, so this option has no effect on operations handled by other event sources.
+  ///
+  /// Note that request bodies are validated against the simplified schemas in the generated
+  /// `openapi-apigw.yaml`, which omit the `discriminator` of any polymorphic schemas (see
+  /// [API Gateway known issues](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-known-issues.html#api-gateway-known-issues-rest-apis)).
+  /// The generated code still deserializes each request according to the original schema.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn, RequestValidation};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_request_validation(RequestValidation::All)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_request_validation(mut self, request_validation: RequestValidation) -> Self {
+    self.request_validation = request_validation;
+    self
+  }
+
+  /// Format the generated `<MODULE_NAME>_handler.rs` files using `rustfmt` (enabled by default).
+  ///
+  /// If this option is disabled or `rustfmt` is not installed (e.g., in minimal CI images), the
+  /// handler files are instead formatted using
+  /// [`prettyplease`](https://docs.rs/prettyplease/latest/prettyplease/), which omits the
+  /// explanatory comments included in the handler templates.
+  pub fn with_rustfmt(mut self, rustfmt: bool) -> Self {
+    self.rustfmt = rustfmt;
+    self
+  }
+
+  /// Rust type used to represent arrays with `uniqueItems: true` (see [`SetType`]).
+  ///
+  /// By default, the generated code uses `IndexSet`, which preserves the order of the array's
+  /// items. Arrays whose item type doesn't implement the traits required by the set type instead
+  /// use `openapi_lambda::models::UniqueVec`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn, SetType};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .with_set_type(SetType::BTreeSet)
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  pub fn with_set_type(mut self, set_type: SetType) -> Self {
+    self.set_type = set_type;
+    self
+  }
+
+  /// Validate the OpenAPI definition before generating any code.
+  ///
+  /// When enabled, [`generate`](CodeGenerator::generate) runs
+  /// [`validate_spec`](CodeGenerator::validate_spec) first and panics with a list of every issue
+  /// found, rather than stopping at the first problem encountered during code generation.
+  pub fn with_spec_validation(mut self, spec_validation: bool) -> Self {
+    self.spec_validation = spec_validation;
+    self
+  }
+
+  /// Use an existing Rust type for the target of a foreign schema reference instead of generating a
+  /// model for it.
+  ///
+  /// By default, each schema referenced from another OpenAPI document is copied into
+  /// `components/schemas` and a model is generated for it. This method instead maps the target
+  /// of `reference` (e.g., a shared schema for which a hand-written or separately generated type
+  /// already exists) to the Rust type at `rust_path`. The referenced schema is still included in
+  /// the generated `openapi-apigw.yaml`. The type must implement the traits required by the
+  /// generated code that uses it (at least `Clone`, `Debug`, `Deserialize`, and `Serialize`).
+  ///
+  /// The `reference` must be relative to the OpenAPI definition passed to
+  /// [`CodeGenerator::new`] (e.g., `common.yaml#/components/schemas/Money`), although it matches
+  /// references to the same schema from any document.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .map_schema_ref("common.yaml#/components/schemas/Money", "my_common::Money")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if `reference` doesn't refer to a schema in another document or if `rust_path` is not
+  /// a valid Rust type.
+  pub fn map_schema_ref(mut self, reference: &str, rust_path: &str) -> Self {
+    match reference.split_once("#/") {
+      Some((rel_path, _)) if !rel_path.is_empty() => {}
+      _ => panic!("invalid foreign schema reference `{reference}`"),
+    }
+    if let Err(err) = syn::parse_str::<syn::Type>(rust_path) {
+      panic!("invalid Rust type `{rust_path}` for schema reference `{reference}`: {err}");
+    }
+    self
+      .schema_ref_types
+      .insert(reference.to_string(), rust_path.to_string());
+    self
+  }
+
+  /// Map a custom schema `format` (e.g., `format: bar-id`) to the Rust type at `rust_path`.
+  ///
+  /// By default, custom formats (i.e., those not defined by the OpenAPI specification) are treated
+  /// as verbatim Rust types. This method instead lets the OpenAPI definition use a descriptive
+  /// format name while generating the mapped type for every model field, request body, and request
+  /// parameter of that format. The type must implement the traits required by the generated code
+  /// that uses it: `Clone`, `Debug`, `Deserialize`, and `Serialize` for models and bodies, and
+  /// `FromStr` (and `Display` for generated clients) for request parameters.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .map_format_to_type("bar-id", "crate::types::BarId")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if `format` is defined by the OpenAPI specification (e.g., `date-time`), if it has
+  /// already been mapped, or if `rust_path` is not a valid Rust type.
+  pub fn map_format_to_type(mut self, format: &str, rust_path: &str) -> Self {
+    if STANDARD_FORMATS.contains(&format) {
+      panic!("cannot map standard format `{format}` to a Rust type");
+    }
+    if let Err(err) = syn::parse_str::<syn::Type>(rust_path) {
+      panic!("invalid Rust type `{rust_path}` for format `{format}`: {err}");
+    }
+    if self.format_types.contains_key(format) {
+      panic!("duplicate type mapping for format `{format}`");
+    }
+    self
+      .format_types
+      .insert(format.to_string(), rust_path.to_string());
+    self
+  }
+
+  /// Register an API Lambda function for code generation.
+  ///
+  /// Each call to this method will result in a module being generated that contains an `Api` trait
+  /// with methods for the corresponding API endpoints. See [`ApiLambda`] for further details.
+  pub fn add_api_lambda(mut self, builder: ApiLambda) -> Self {
+    if self.api_lambdas.contains_key(&builder.mod_name)
+      || self.client_mod_name.as_ref() == Some(&builder.mod_name)
+      || (self.callbacks && builder.mod_name == CALLBACKS_MOD_NAME)
+    {
+      panic!(
+        "API Lambda module names must be unique: found duplicate `{}`",
+        builder.mod_name
+      )
+    }
+
+    if builder.response_streaming && builder.event_source == EventSource::ApplicationLoadBalancer {
+      panic!(
+        "API Lambda `{}` enables response streaming, which is not supported for Application Load \
+         Balancer events",
+        builder.mod_name
+      )
+    }
+
+    self.api_lambdas.insert(builder.mod_name.clone(), builder);
+    self
+  }
+
+  /// Generate an asynchronous API client in a module named `mod_name`.
+  ///
+  /// The generated module contains a `Client` struct with one method for each operation in the
+  /// OpenAPI definition (regardless of any API Lambda [op filters](ApiLambda::with_op_filter)).
+  /// Each method accepts the same parameters as the corresponding `Api` trait method, followed by
+  /// any additional request headers, and returns the operation's response type or a
+  /// `ClientError`. Requests are sent using [`reqwest`](https://docs.rs/reqwest), and responses
+  /// are deserialized according to their HTTP status code. Declared error responses (i.e., those
+  /// with `4XX` and `5XX` status codes) are returned as `ClientError::ErrorResponse` errors holding
+  /// a variant of the operation's generated error response type (e.g., `GetPetErrorResponse`).
+  /// Request and response bodies reuse the types in the generated `models` module. Parameters are
+  /// serialized using their `Display` implementations, which custom parameter types (i.e., string
+  /// schemas whose `format` is a Rust type path) must provide.
+  ///
+  /// The response types of the generated API modules additionally gain a `from_http` constructor
+  /// and a `TryFrom<HttpResponse>` implementation, which decode an HTTP response into the
+  /// corresponding response variant (i.e., the inverse of serializing a handler's response).
+  ///
+  /// The client requires enabling the `client` feature of the `openapi-lambda` crate.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate_client("client")
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if `mod_name` is `models` or the module name of an API Lambda.
+  pub fn generate_client<M>(mut self, mod_name: M) -> Self
+  where
+    M: Into<String>,
+  {
+    let mod_name = mod_name.into();
+    if mod_name == "models"
+      || self.api_lambdas.contains_key(&mod_name)
+      || (self.callbacks && mod_name == CALLBACKS_MOD_NAME)
+    {
+      panic!("client module name `{mod_name}` conflicts with another generated module");
+    }
+    self.client_mod_name = Some(mod_name);
+    self
+  }
+
+  /// Generate an API module named `callbacks` for the operations defined by
+  /// [callbacks](https://spec.openapis.org/oas/v3.0.3#callback-object) and OpenAPI 3.1
+  /// [webhooks](https://spec.openapis.org/oas/v3.1.0#oas-webhooks).
+  ///
+  /// Callbacks and webhooks describe requests that the API sends to its clients. Implementing the
+  /// generated `callbacks::Api` trait (along with the `callbacks_handler.rs` placeholder written to
+  /// `out_dir`) allows a Lambda function to receive these requests, with typed request bodies and
+  /// response types just like other operations. Each callback and webhook operation must have an
+  /// `operationId`.
+  ///
+  /// Since the URL of each callback is determined at runtime, callback operations are not included
+  /// in the generated `openapi-apigw.yaml`. The generated `dispatch_request` method handles
+  /// [`EventSource::ApiGatewayProxy`] events and identifies each operation using the
+  /// `operationName` of the API Gateway request context, so the API Gateway method that receives
+  /// each callback must specify the corresponding `operationId`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::CodeGenerator;
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .generate_callbacks()
+  ///   .generate();
+  /// ```
+  ///
+  /// # Panics
+  ///
+  /// Panics if `callbacks` is the module name of an API Lambda or generated client.
+  pub fn generate_callbacks(mut self) -> Self {
+    if self.api_lambdas.contains_key(CALLBACKS_MOD_NAME)
+      || self.client_mod_name.as_deref() == Some(CALLBACKS_MOD_NAME)
+    {
+      panic!(
+        "callbacks module name `{CALLBACKS_MOD_NAME}` conflicts with another generated module"
+      );
+    }
+    self.callbacks = true;
+    self
+  }
+
+  /// Generate handlers that return example responses instead of calling `todo!()`.
+  ///
+  /// Each method of the generated `<MODULE_NAME>_handler.rs` files returns the operation's
+  /// `200` response (or, if there isn't one, its `default` response with status code `200 OK`).
+  /// Responses with an `application/json` body use the `example` (or the first of the `examples`)
+  /// declared in the OpenAPI definition for that media type, deserialized into the corresponding
+  /// model, while responses without a body need no example. Methods for which no such response or
+  /// example exists still call `todo!()`.
+  ///
+  /// This is useful for prototyping, contract testing, or letting front-end developers use a
+  /// realistic stub before the API is implemented. Only the handler files are affected, not the
+  /// generated `out.rs`.
+  ///
+  /// # Example
+  ///
+  /// ```rust,no_run
+  /// # use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+  /// CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+  ///   .add_api_lambda(
+  ///     ApiLambda::new("backend", LambdaArn::cloud_formation("BackendApiFunction.Alias"))
+  ///   )
+  ///   .generate_mock_handlers()
+  ///   .generate();
+  /// ```
+  pub fn generate_mock_handlers(mut self) -> Self {
+    self.mock_handlers = true;
+    self
+  }
+
+  /// Emit generated code.
+  ///
+  /// Writes `out.rs` to the directory referenced by the `OUT_DIR` environment variable set by
+  /// Cargo, and writes `openapi-apigw.yaml` and the `<MODULE_NAME>_handler.rs` files to the output
+  /// directory passed to [`CodeGenerator::new`].
+  ///
+  /// Emits `cargo:rerun-if-changed` for the OpenAPI definition and for every document it references
+  /// via `$ref` (directly or transitively), even if code generation doesn't otherwise use the
+  /// referenced objects, so that editing any of them re-runs the build script.
+  ///
+  /// # Panics
+  ///
+  /// Panics if code generation fails. See [`try_generate`](CodeGenerator::try_generate) for a
+  /// non-panicking alternative.
+  pub fn generate(self) {
+    if let Err(err) = self.try_generate() {
+      panic!("{err}");
+    }
+  }
+
+  /// Emit generated code, returning an error if code generation fails.
+  ///
+  /// Errors include OpenAPI definitions that can't be parsed or fail validation, invalid
+  /// references, schemas that code generation doesn't support (e.g., `anyOf`), invalid
+  /// configuration, and failures to read or write files. Returns [`CodegenError::MissingOutDir`] if
+  /// the `OUT_DIR` environment variable set by Cargo for build scripts isn't set.
+  pub fn try_generate(self) -> Result<(), CodegenError> {
+    let cargo_out_dir = std::env::var("OUT_DIR").map_err(|_| CodegenError::MissingOutDir)?;
+    let out_dir = self.out_dir.clone();
+    log::info!("writing Rust codegen to {cargo_out_dir}");
+    log::info!("writing OpenAPI codegen to {}", out_dir.display());
+
+    let manifest_path = self
+      .manifest_path
+      .as_ref()
+      .map(|manifest_path| out_dir.join(manifest_path));
+    let GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      handlers,
+      manifest_json,
+    } = self.try_generate_to_string()?;
+
+    if !out_dir.exists() {
+      std::fs::create_dir_all(&out_dir).map_err(|err| CodegenError::io(out_dir.clone(), err))?;
+    }
+
+    let openapi_apigw_path = out_dir.join(OPENAPI_GW_FILENAME);
+    std::fs::write(&openapi_apigw_path, openapi_apigw_yaml)
+      .map_err(|err| CodegenError::io(openapi_apigw_path, err))?;
+
+    for (mod_name, handler) in handlers {
+      let handler_path = out_dir.join(format!("{mod_name}_handler.rs"));
+      log::info!("Writing `{mod_name}` handler to {}", handler_path.display());
+      std::fs::write(&handler_path, handler).map_err(|err| CodegenError::io(handler_path, err))?;
+    }
+
+    if let (Some(manifest_path), Some(manifest_json)) = (manifest_path, manifest_json) {
+      log::info!("Writing route manifest to {}", manifest_path.display());
+      if let Some(manifest_dir) = manifest_path.parent().filter(|dir| !dir.exists()) {
+        std::fs::create_dir_all(manifest_dir)
+          .map_err(|err| CodegenError::io(manifest_dir.to_path_buf(), err))?;
+      }
+      std::fs::write(&manifest_path, manifest_json)
+        .map_err(|err| CodegenError::io(manifest_path, err))?;
+    }
+
+    let out_rs_file = parse2(out_rs.clone()).map_err(|err| {
+      CodegenError::InvalidGeneratedCode(format!("failed to parse out.rs: {err}\n{out_rs}"))
+    })?;
+    let out_rs_path = Path::new(&cargo_out_dir).join("out.rs");
+    File::create(&out_rs_path)
+      .and_then(|mut out_rs| out_rs.write_all(prettyplease::unparse(&out_rs_file).as_bytes()))
+      .map_err(|err| CodegenError::io(out_rs_path, err))
+  }
+
+  /// Generate code without writing any files.
+  ///
+  /// Unlike [`generate`](CodeGenerator::generate), this method doesn't require the `OUT_DIR`
+  /// environment variable to be set, which makes it usable outside of Cargo build scripts (e.g.,
+  /// in tests or with other build systems). If `OUT_DIR` is set, parsed external documents are
+  /// still cached there across builds.
+  ///
+  /// # Panics
+  ///
+  /// Panics if code generation fails. See
+  /// [`try_generate_to_string`](CodeGenerator::try_generate_to_string) for a non-panicking
+  /// alternative.
+  pub fn generate_to_string(self) -> GeneratedArtifacts {
+    self
+      .try_generate_to_string()
+      .unwrap_or_else(|err| panic!("{err}"))
+  }
+
+  /// Generate code without writing any files, returning an error if code generation fails.
+  ///
+  /// See [`generate_to_string`](CodeGenerator::generate_to_string) and
+  /// [`try_generate`](CodeGenerator::try_generate).
+  pub fn try_generate_to_string(self) -> Result<GeneratedArtifacts, CodegenError> {
+    for api_lambda in self.api_lambdas.values() {
+      if let Some(cors) = &api_lambda.cors {
+        cors.validate().map_err(|err| {
+          CodegenError::InvalidConfig(format!(
+            "CORS configuration for API Lambda `{}`: {err}",
+            api_lambda.mod_name
+          ))
+        })?;
+      }
+      for route in &api_lambda.extra_routes {
+        route.validate().map_err(|err| {
+          CodegenError::InvalidConfig(format!(
+            "extra route {} {} for API Lambda `{}`: {err}",
+            route.method, route.path, api_lambda.mod_name
+          ))
+        })?;
+      }
+    }
+
+    let mut openapi_yaml = match &self.openapi_contents {
+      Some(openapi_contents) => parse_doc(&self.openapi_path, openapi_contents.as_slice()),
+      None => {
+        let openapi_file = File::open(&self.openapi_path)
+          .map_err(|err| CodegenError::io(self.openapi_path.clone(), err))?;
+        parse_doc(&self.openapi_path, &openapi_file)
+      }
+    }
+    .map_err(|err| {
+      CodegenError::InvalidSpec(vec![ValidationIssue::new(
+        self.openapi_path.display().to_string(),
+        err,
+      )])
+    })?;
+    const_schemas_to_enums(&mut openapi_yaml);
+
+    // Persist parsed external documents across builds to avoid re-parsing unchanged files.
+    let mut cached_external_docs = std::env::var_os("OUT_DIR")
+      .map(|cargo_out_dir| {
+        DocCache::with_disk_cache(Path::new(&cargo_out_dir).join("openapi-lambda-doc-cache"))
+      })
+      .unwrap_or_default();
+
+    // Clippy in 1.70.0 raises a false positive here.
+    #[allow(clippy::redundant_clone)]
+    cached_external_docs.insert(self.openapi_path.to_path_buf(), openapi_yaml.clone());
+
+    // An in-memory definition has no file to watch, but the documents it references still do.
+    if self.openapi_contents.is_none() {
+      println!("cargo:rerun-if-changed={}", self.openapi_path.display());
+    }
+    cached_external_docs.load_transitive_references(&self.openapi_path);
+
+    // `openapiv3` doesn't support OpenAPI 3.1 webhooks, so we parse them separately. Each webhook
+    // maps a name to a path item, just like a callback maps an expression to a path item.
+    let webhooks = openapi_yaml
+      .remove("webhooks")
+      .filter(|_| self.callbacks)
+      .map(|webhooks| {
+        serde_path_to_error::deserialize::<_, Callback>(webhooks).map_err(|err| {
+          CodegenError::InvalidSpec(vec![ValidationIssue::new(
+            format!("{}#/webhooks", self.openapi_path.display()),
+            format!("failed to parse webhooks: {err}"),
+          )])
+        })
+      })
+      .transpose()?
+      .unwrap_or_default();
+
+    if self.spec_validation {
+      let issues = self.validate_openapi_yaml(
+        &self.openapi_path,
+        openapi_yaml.clone(),
+        &mut cached_external_docs,
+      );
+      if !issues.is_empty() {
+        return Err(CodegenError::InvalidSpec(issues));
+      }
+    }
+
+    let openapi: OpenAPI = serde_path_to_error::deserialize(serde_yaml::Value::Mapping(
+      openapi_yaml,
+    ))
+    .map_err(|err| {
+      CodegenError::InvalidSpec(vec![ValidationIssue::new(
+        self.openapi_path.display().to_string(),
+        format!("failed to parse OpenAPI definition: {err}"),
+      )])
+    })?;
 
-    let openapi: OpenAPI =
-      serde_path_to_error::deserialize(serde_yaml::Value::Mapping(openapi_yaml))
-        .unwrap_or_else(|err| panic!("Failed to parse OpenAPI spec: {err}"));
+    // API Gateway rejects paths that differ only in path parameter names, so catch them early
+    // even when spec validation is disabled.
+    let conflicting_paths = conflicting_path_templates(
+      &self.openapi_path,
+      openapi.paths.paths.keys().map(String::as_str),
+    );
+    if !conflicting_paths.is_empty() {
+      return Err(CodegenError::InvalidSpec(conflicting_paths));
+    }
 
     let crate_import = self.crate_use_name();
+    let datetime_crate = self.datetime_backend.crate_ident();
 
     // Merge any references to other OpenAPI files into the root OpenAPI definition, and replace
     // any unnamed schemas that require named models to represent in Rust (e.g., enums) with named
     // schemas in components.schemas. This simplifies the rest of the code generation process since
     // we don't have to visit other files or worry about conflicting schema names.
-    let (openapi_inline, models) =
-      self.generate_models(self.inline_openapi(openapi, cached_external_docs));
+    let openapi_inline = self.inline_openapi(openapi, webhooks, cached_external_docs)?;
+
+    let unsupported_features = self.find_unsupported_features(&openapi_inline);
+    if !unsupported_features.is_empty() {
+      return Err(CodegenError::Unsupported(unsupported_features));
+    }
+
+    let api_metadata_out = gen_api_metadata(&openapi_inline.info);
+
+    let (openapi_inline, models) = self.generate_models(openapi_inline)?;
+
+    // Submodule containing each model when using `ModelModules::ByTag`.
+    let model_modules = openapi_inline
+      .components
+      .iter()
+      .flat_map(|components| &components.schemas)
+      .filter_map(|(schema_name, schema)| {
+        let module = schema_rust_module(schema.as_item()?)?;
+        Some((
+          self.identifier(&schema_name.to_case(Case::Pascal)),
+          self.identifier(module),
+        ))
+      })
+      .collect::<HashMap<_, _>>();
 
     let openapi_inline_mapping =
       serde_path_to_error::serialize(&*openapi_inline, serde_yaml::value::Serializer)
@@ -437,20 +1934,15 @@ impl CodeGenerator {
       panic!("OpenAPI spec should be a mapping: {:#?}", &*openapi_inline);
     };
 
-    let operations = collect_operations(&openapi_inline, &openapi_inline_mapping);
+    let operations = collect_operations(&openapi_inline, &openapi_inline_mapping)?;
+    self.check_operation_ids(&operations)?;
     let operations_by_api_lambda = self
       .api_lambdas
       .values()
       .flat_map(|api_lambda| {
         operations
           .iter()
-          .filter(|op| {
-            api_lambda
-              .op_filter
-              .as_ref()
-              .map(|op_filter| (*op_filter)(&op.op))
-              .unwrap_or(true)
-          })
+          .filter(|op| api_lambda.includes_operation(&op.method, &op.request_path, &op.op))
           .map(|op| (&api_lambda.mod_name, op))
       })
       .into_group_map();
@@ -464,13 +1956,18 @@ impl CodeGenerator {
       })
       .into_group_map()
       .into_iter()
-      .for_each(|((method, request_path), mod_names)| {
-        if mod_names.len() > 1 {
-          panic!(
-            "endpoint {method} {request_path} is mapped to multiple API Lambdas: {mod_names:?}"
-          );
-        }
-      });
+      .sorted_by_key(|((method, request_path), _)| (method.as_str(), *request_path))
+      .find(|(_, mod_names)| mod_names.len() > 1)
+      .map_or(Ok(()), |((method, request_path), mod_names)| {
+        Err(CodegenError::InvalidConfig(format!(
+          "endpoint {method} {request_path} is mapped to multiple API Lambdas: {}",
+          mod_names
+            .into_iter()
+            .sorted()
+            .map(|mod_name| format!("`{mod_name}`"))
+            .join(", ")
+        )))
+      })?;
 
     let operation_id_to_api_lambda = operations_by_api_lambda
       .iter()
@@ -480,7 +1977,7 @@ impl CodeGenerator {
             op.op
               .operation_id
               .as_ref()
-              .unwrap_or_else(|| panic!("no operation_id for {} {}", op.method, op.request_path))
+              .expect("operation_id should have been checked above")
               .as_str(),
             self
               .api_lambdas
@@ -496,83 +1993,299 @@ impl CodeGenerator {
       .as_ref()
       .map(|components| Cow::Borrowed(&components.schemas))
       .unwrap_or_else(|| Cow::Owned(IndexMap::new()));
+    let mut handlers = BTreeMap::new();
     let apis_out = operations_by_api_lambda
       .iter()
       .sorted_by_key(|(mod_name, _)| **mod_name)
       .map(|(mod_name, ops)| {
-        self.gen_api_module(
+        let ApiModule { module, handler } = self.gen_api_module(
           mod_name,
           ops,
           &openapi_inline_mapping,
           &components_schemas,
           &models,
+        )?;
+        handlers.insert(mod_name.to_string(), handler);
+        Ok(module)
+      })
+      .collect::<Result<TokenStream, CodegenError>>()?;
+
+    let callbacks_out = if self.callbacks {
+      let callback_operations =
+        collect_callback_operations(&openapi_inline, &openapi_inline_mapping)?;
+      let ApiModule { module, handler } = self.gen_api_module(
+        CALLBACKS_MOD_NAME,
+        &callback_operations.iter().collect::<Vec<_>>(),
+        &openapi_inline_mapping,
+        &components_schemas,
+        &models,
+      )?;
+      handlers.insert(CALLBACKS_MOD_NAME.to_string(), handler);
+      Some(module)
+    } else {
+      None
+    };
+
+    let client_out = self
+      .client_mod_name
+      .as_ref()
+      .map(|client_mod_name| {
+        self.gen_client_module(
+          client_mod_name,
+          &operations,
+          &openapi_inline_mapping,
+          &components_schemas,
+          &models,
         )
       })
-      .collect::<TokenStream>();
+      .transpose()?;
 
-    self.gen_openapi_apigw(openapi_inline, &operation_id_to_api_lambda);
+    let manifest_json = self
+      .manifest_path
+      .is_some()
+      .then(|| self.gen_manifest(&operations, &operation_id_to_api_lambda));
 
-    let models_out = models
-      .into_iter()
-      .sorted_by(|(ident_a, _), (ident_b, _)| ident_a.cmp(ident_b))
-      .map(|(_, model)| model)
-      .collect::<TokenStream>();
+    let openapi_apigw_yaml = self.gen_openapi_apigw(openapi_inline, &operation_id_to_api_lambda)?;
+
+    let models_imports = quote! {
+      use #crate_import::__private::anyhow::{self, anyhow};
+      use #crate_import::__private::serde::{Deserialize, Serialize};
+      use #crate_import::models::#datetime_crate;
+    };
+    let models_out = if model_modules.is_empty() {
+      let models_out = models
+        .into_iter()
+        .sorted_by(|(ident_a, _), (ident_b, _)| ident_a.cmp(ident_b))
+        .map(|(_, model)| model)
+        .collect::<TokenStream>();
+
+      quote! {
+        #models_imports
+
+        #models_out
+      }
+    } else {
+      models
+        .into_iter()
+        .map(|(model_ident, model)| {
+          let module_ident = model_modules
+            .get(&model_ident)
+            .unwrap_or_else(|| panic!("no models submodule for model `{model_ident}`"));
+          (module_ident.clone(), (model_ident, model))
+        })
+        .into_group_map()
+        .into_iter()
+        .sorted_by(|(module_a, _), (module_b, _)| module_a.cmp(module_b))
+        .map(|(module_ident, module_models)| {
+          let module_models_out = module_models
+            .into_iter()
+            .sorted_by(|(ident_a, _), (ident_b, _)| ident_a.cmp(ident_b))
+            .map(|(_, model)| model)
+            .collect::<TokenStream>();
+
+          quote! {
+            pub mod #module_ident {
+              #models_imports
+
+              #module_models_out
+            }
+          }
+        })
+        .collect::<TokenStream>()
+    };
+
+    let out_rs = quote! {
+      #api_metadata_out
 
-    let out_rs_path = Path::new(&cargo_out_dir).join("out.rs");
-    let out_tok = quote! {
       pub mod models {
         #![allow(unused_imports)]
         #![allow(clippy::large_enum_variant)]
 
-        use #crate_import::__private::anyhow::{self, anyhow};
-        use #crate_import::__private::serde::{Deserialize, Serialize};
-        use #crate_import::models::chrono;
-
         #models_out
       }
 
       #apis_out
+
+      #callbacks_out
+
+      #client_out
     };
-    File::create(&out_rs_path)
-      .unwrap_or_else(|err| panic!("failed to create {}: {err}", out_rs_path.to_string_lossy()))
-      .write_all(
-        prettyplease::unparse(
-          &parse2(out_tok.clone())
-            .unwrap_or_else(|err| panic!("failed to parse generated code: {err}\n{out_tok}")),
-        )
-        .as_bytes(),
-      )
-      .unwrap_or_else(|err| {
-        panic!(
-          "failed to write to {}: {err}",
-          out_rs_path.to_string_lossy()
-        )
-      });
+
+    Ok(GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      handlers,
+      manifest_json,
+    })
   }
 
-  /// Name of this crate to use for `use` imports.
-  fn crate_use_name(&self) -> Ident {
-    // TODO: support import customization similar to serde's `crate` attribute:
-    // https://serde.rs/container-attrs.html#crate. This also requires a custom model.mustache
-    // since that file embeds the #[serde(crate = "...")] attributes.
-    Ident::new("openapi_lambda", Span::call_site())
+  /// Type of Lambda event handled by the specified API Lambda module.
+  fn event_source(&self, mod_name: &str) -> EventSource {
+    self
+      .api_lambdas
+      .get(mod_name)
+      .map(|api_lambda| api_lambda.event_source)
+      .unwrap_or_default()
   }
 
-  fn rustfmt(&self, path: &Path) {
-    let rustfmt_result = Command::new("rustfmt")
-      .args(["--edition".as_ref(), "2021".as_ref(), path.as_os_str()])
-      .output()
-      .unwrap_or_else(|err| panic!("failed to run rustfmt: {err}"));
+  /// Maximum request body size (in bytes) accepted by the specified API Lambda module, if any.
+  fn max_body_bytes(&self, mod_name: &str) -> Option<usize> {
+    self
+      .api_lambdas
+      .get(mod_name)
+      .and_then(|api_lambda| api_lambda.max_body_bytes)
+  }
 
-    if !rustfmt_result.status.success() {
-      panic!(
-        "rustfmt failed with status {}:\n{}",
-        rustfmt_result.status,
-        String::from_utf8_lossy(rustfmt_result.stdout.as_slice())
-          + String::from_utf8_lossy(rustfmt_result.stderr.as_slice())
-      );
+  /// Base path to strip from request paths before matching them against the request paths of the
+  /// specified API Lambda module's operations, if any (see [`ApiLambda::with_base_path`]).
+  fn base_path(&self, mod_name: &str, openapi_inline: &serde_yaml::Mapping) -> Option<String> {
+    let base_path = match self
+      .api_lambdas
+      .get(mod_name)
+      .and_then(|api_lambda| api_lambda.base_path.clone())
+    {
+      Some(base_path) => base_path,
+      None => servers_base_path(openapi_inline)?,
+    };
+
+    let base_path = base_path.trim_end_matches('/');
+    if base_path.is_empty() {
+      None
+    } else if base_path.starts_with('/') {
+      Some(base_path.to_string())
+    } else {
+      Some(format!("/{base_path}"))
     }
   }
+
+  /// Custom handler template for the specified API Lambda module, if any.
+  fn handler_template(&self, mod_name: &str) -> Option<&HandlerTemplate> {
+    self
+      .api_lambdas
+      .get(mod_name)
+      .and_then(|api_lambda| api_lambda.handler_template.as_ref())
+  }
+
+  /// Whether the specified API Lambda module compresses its responses.
+  fn response_compression(&self, mod_name: &str) -> bool {
+    self
+      .api_lambdas
+      .get(mod_name)
+      .map(|api_lambda| api_lambda.response_compression)
+      .unwrap_or_default()
+  }
+
+  /// Whether the specified API Lambda module streams its responses.
+  fn response_streaming(&self, mod_name: &str) -> bool {
+    self
+      .api_lambdas
+      .get(mod_name)
+      .map(|api_lambda| api_lambda.response_streaming)
+      .unwrap_or_default()
+  }
+
+  /// Path of this crate to use for `use` imports.
+  fn crate_use_name(&self) -> syn::Path {
+    syn::parse_str(&self.crate_name).expect("crate name should have been validated")
+  }
+
+  /// Format generated Rust source code.
+  ///
+  /// Uses `rustfmt` if enabled and installed, and otherwise falls back to `prettyplease`.
+  fn format_rust_source(&self, file_name: &str, source: &str) -> Result<String, CodegenError> {
+    if self.rustfmt {
+      match Command::new("rustfmt")
+        .args(["--edition", "2021"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+      {
+        Ok(mut rustfmt) => {
+          rustfmt
+            .stdin
+            .take()
+            .expect("rustfmt stdin should be piped")
+            .write_all(source.as_bytes())
+            .map_err(|err| CodegenError::io(PathBuf::from("rustfmt"), err))?;
+          let rustfmt_result = rustfmt
+            .wait_with_output()
+            .map_err(|err| CodegenError::io(PathBuf::from("rustfmt"), err))?;
+          if !rustfmt_result.status.success() {
+            return Err(CodegenError::InvalidGeneratedCode(format!(
+              "rustfmt failed to format {file_name} with status {}:\n{}",
+              rustfmt_result.status,
+              String::from_utf8_lossy(rustfmt_result.stdout.as_slice())
+                + String::from_utf8_lossy(rustfmt_result.stderr.as_slice())
+            )));
+          }
+          return String::from_utf8(rustfmt_result.stdout).map_err(|err| {
+            CodegenError::InvalidGeneratedCode(format!(
+              "rustfmt output for {file_name} is not valid UTF-8: {err}"
+            ))
+          });
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+          log::warn!("rustfmt not found; formatting {file_name} with prettyplease instead");
+        }
+        Err(err) => return Err(CodegenError::io(PathBuf::from("rustfmt"), err)),
+      }
+    }
+
+    let file = syn::parse_file(source).map_err(|err| {
+      CodegenError::InvalidGeneratedCode(format!("failed to parse {file_name}: {err}\n{source}"))
+    })?;
+    Ok(prettyplease::unparse(&file))
+  }
+}
+
+/// Returns the path of the first URL in the OpenAPI definition's `servers`, substituting the default
+/// value of each server variable.
+fn servers_base_path(openapi_inline: &serde_yaml::Mapping) -> Option<String> {
+  let server = openapi_inline.get("servers")?.as_sequence()?.first()?;
+  let url = server.get("url")?.as_str()?;
+  let url = server
+    .get("variables")
+    .and_then(serde_yaml::Value::as_mapping)
+    .into_iter()
+    .flatten()
+    .filter_map(|(name, variable)| Some((name.as_str()?, variable.get("default")?.as_str()?)))
+    .fold(url.to_string(), |url, (name, default)| {
+      url.replace(&format!("{{{name}}}"), default)
+    });
+
+  // Absolute URLs include a scheme and host, while relative URLs (e.g., `/v1`) consist of only the
+  // path.
+  let path = match url.split_once("://") {
+    Some((_, authority_and_path)) => authority_and_path
+      .find('/')
+      .map(|path_start| &authority_and_path[path_start..])
+      .unwrap_or_default(),
+    None => url.as_str(),
+  };
+  Some(path.to_string())
+}
+
+/// Generates constants exposing the `info` block of the OpenAPI definition (e.g., so that handlers
+/// can report the API version without duplicating it).
+fn gen_api_metadata(info: &Info) -> TokenStream {
+  let title = &info.title;
+  let version = &info.version;
+  let description = match &info.description {
+    Some(description) => quote! { Some(#description) },
+    None => quote! { None },
+  };
+
+  quote! {
+    /// Title of the API (from `info.title` in the OpenAPI definition).
+    pub const API_TITLE: &str = #title;
+
+    /// Version of the API (from `info.version` in the OpenAPI definition).
+    pub const API_VERSION: &str = #version;
+
+    /// Description of the API (from `info.description` in the OpenAPI definition), if any.
+    pub const API_DESCRIPTION: Option<&str> = #description;
+  }
 }
 
 fn description_to_doc_attr<S>(description: &S) -> TokenStream
@@ -589,3 +2302,1449 @@ where
     })
     .collect()
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::{
+    ApiLambda, CodeGenerator, CodegenError, CorsConfig, DateTimeBackend, EventSource,
+    GeneratedArtifacts, HandlerTemplate, JsonFormat, LambdaArn, Method, ModelModules,
+    ValidationIssue,
+  };
+
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_generate_to_string() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    let openapi_path = dir.join("openapi.yaml");
+    let out_dir = dir.join(".openapi-lambda");
+    std::fs::write(
+      &openapi_path,
+      r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses:
+        "204":
+          description: No content
+"#,
+    )
+    .unwrap();
+
+    let GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      handlers,
+      manifest_json,
+    } = CodeGenerator::new(&openapi_path, &out_dir)
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+    let out_dir_exists = out_dir.exists();
+
+    // Nothing should be written to the output directory.
+    assert!(!out_dir_exists);
+    assert!(pretty_print(out_rs).contains("pub mod backend {"));
+    assert!(openapi_apigw_yaml.contains("x-amazon-apigateway-integration"));
+    assert_eq!(handlers.keys().collect::<Vec<_>>(), vec!["backend"]);
+    assert!(handlers["backend"].contains("pub struct BackendApiHandler"));
+    assert_eq!(manifest_json, None);
+  }
+
+  #[test]
+  fn test_manifest() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo/{foo_id}:
+    parameters:
+      - name: foo_id
+        in: path
+        required: true
+        schema:
+          type: string
+    get:
+      operationId: getFoo
+      responses:
+        "204":
+          description: No content
+    delete:
+      operationId: deleteFoo
+      responses:
+        "204":
+          description: No content
+  /bar:
+    get:
+      operationId: getBar
+      tags: [bar]
+      responses:
+        "204":
+          description: No content
+"#;
+    let manifest_json = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .with_manifest("routes.json")
+      .add_api_lambda(
+        ApiLambda::new(
+          "backend",
+          LambdaArn::known("us-east-1", "123456789012", "us-east-1", "backend", None),
+        )
+        .with_op_filter(|op| op.tags.is_empty())
+        .with_arn_override(|op| {
+          (op.operation_id.as_deref() == Some("deleteFoo"))
+            .then(|| LambdaArn::cloud_formation("DeleteFooFunction.Alias"))
+        })
+        .with_extra_route(Method::GET, "/health", "crate::health_check"),
+      )
+      .add_api_lambda(
+        ApiLambda::new("bar", LambdaArn::cloud_formation("BarFunction.Alias"))
+          .with_op_filter(|op| op.tags.iter().any(|tag| tag == "bar"))
+          .with_event_source(EventSource::ApplicationLoadBalancer),
+      )
+      .generate_to_string()
+      .manifest_json
+      .expect("manifest should be generated");
+
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&manifest_json).unwrap(),
+      serde_json::json!({
+        "routes": [
+          {
+            "method": "GET",
+            "path": "/foo/{foo_id}",
+            "operation_id": "getFoo",
+            "module": "backend",
+            "integration_uri": "arn:aws:apigateway:us-east-1:lambda:path/2015-03-31/functions/\
+              arn:aws:lambda:us-east-1:123456789012:function:backend/invocations",
+          },
+          {
+            "method": "DELETE",
+            "path": "/foo/{foo_id}",
+            "operation_id": "deleteFoo",
+            "module": "backend",
+            "integration_uri": {
+              "Fn::Sub": "arn:aws:apigateway:${AWS::Region}:lambda:path/2015-03-31/functions/\
+                ${DeleteFooFunction.Alias}/invocations",
+            },
+          },
+          {
+            "method": "GET",
+            "path": "/bar",
+            "operation_id": "getBar",
+            "module": "bar",
+            "integration_uri": null,
+          },
+          {
+            "method": "GET",
+            "path": "/health",
+            "operation_id": null,
+            "module": "backend",
+            "integration_uri": "arn:aws:apigateway:us-east-1:lambda:path/2015-03-31/functions/\
+              arn:aws:lambda:us-east-1:123456789012:function:backend/invocations",
+          },
+        ]
+      })
+    );
+  }
+
+  #[test]
+  fn test_datetime_backend_time_parameters() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /events/{since}:
+    get:
+      operationId: listEvents
+      parameters:
+        - name: since
+          in: path
+          required: true
+          schema:
+            type: string
+            format: date-time
+        - name: until
+          in: query
+          schema:
+            type: array
+            items:
+              type: string
+              format: date-time
+      responses:
+        "204":
+          description: Success
+"#;
+
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .with_datetime_backend(DateTimeBackend::Time)
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_client("client")
+      .try_generate_to_string()
+      .unwrap();
+
+    let out_rs = pretty_print(out_rs);
+    for expected in [
+      "since: time::OffsetDateTime,",
+      "until: Option<Vec<time::OffsetDateTime>>,",
+      "openapi_lambda::__private::time::parse_date_time(&p)",
+      r#"urlencoding::encode(& openapi_lambda::__private::time::format_date_time(&
+                since))"#,
+      r#"                                        "until",
+                                        openapi_lambda::__private::time::format_date_time(
+                                            param_value,
+                                        ),"#,
+    ] {
+      assert!(out_rs.contains(expected), "missing `{expected}`");
+    }
+  }
+
+  #[test]
+  fn test_from_str() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(
+      dir.join("schemas.yaml"),
+      r#"
+Foo:
+  type: object
+  properties:
+    bar:
+      type: string
+"#,
+    )
+    .unwrap();
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "schemas.yaml#/Foo"
+"#;
+    let generator = |code_generator: CodeGenerator| {
+      code_generator
+        .with_base_dir(dir)
+        .with_spec_validation(true)
+        .add_api_lambda(ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        ))
+        .try_generate_to_string()
+    };
+
+    let from_str = generator(CodeGenerator::from_str(
+      openapi,
+      dir.join(".openapi-lambda"),
+    ));
+    let from_reader = generator(
+      CodeGenerator::from_reader(openapi.as_bytes(), dir.join(".openapi-lambda")).unwrap(),
+    );
+    // Without a base directory, the external reference can't be resolved.
+    let missing_base_dir = CodeGenerator::from_str(openapi, dir.join(".openapi-lambda"))
+      .validate_spec()
+      .unwrap_err();
+
+    let from_str = from_str.unwrap();
+    assert!(from_str.out_rs.to_string().contains("pub struct Foo"));
+    assert_eq!(
+      from_str.out_rs.to_string(),
+      from_reader.unwrap().out_rs.to_string()
+    );
+    assert_eq!(missing_base_dir.len(), 1);
+    assert!(missing_base_dir[0]
+      .to_string()
+      .starts_with("openapi.yaml#/paths/~1foo/get/responses/200/content/application~1json/schema"));
+  }
+
+  #[test]
+  fn test_conflicting_path_templates() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      responses: {}
+  /pets/{petId}:
+    delete:
+      operationId: deletePet
+      responses: {}
+"#;
+
+    // Conflicting paths are rejected even without spec validation.
+    let Err(CodegenError::InvalidSpec(issues)) =
+      CodeGenerator::from_str(openapi, ".openapi-lambda").try_generate_to_string()
+    else {
+      panic!("expected conflicting paths to be rejected");
+    };
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1pets~1{petId}: path `/pets/{petId}` conflicts with `/pets/{id}` \
+         since they differ only in path parameter names"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_array_header_parameter_unsupported() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      parameters:
+        - name: X-Ids
+          in: header
+          schema:
+            type: array
+            items:
+              type: string
+      responses: {}
+"#;
+
+    let Err(CodegenError::Unsupported(issues)) =
+      CodeGenerator::from_str(openapi, ".openapi-lambda")
+        .add_api_lambda(ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        ))
+        .try_generate_to_string()
+    else {
+      panic!("expected array header parameter to be unsupported");
+    };
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec!["openapi.yaml#/paths/~1foo/get/parameters/0: array header parameters are not supported"]
+    );
+  }
+
+  #[test]
+  fn test_invalid_cors_config() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths: {}
+"#;
+
+    let err = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(
+        ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        )
+        .with_cors(CorsConfig::new("https://example.com\nX-Injected: true")),
+      )
+      .try_generate_to_string()
+      .unwrap_err();
+    assert_eq!(
+      err,
+      CodegenError::InvalidConfig(
+        "CORS configuration for API Lambda `backend`: invalid `access-control-allow-origin` \
+         header value \"https://example.com\\nX-Injected: true\""
+          .to_string()
+      )
+    );
+  }
+
+  const FOO_BAR_OPENAPI_YAML: &str = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      tags: [foo]
+      responses:
+        "204":
+          description: Success
+  /bar:
+    get:
+      operationId: getBar
+      tags: [bar]
+      responses:
+        "204":
+          description: Success
+"#;
+
+  #[test]
+  fn test_operation_id_errors() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses: {}
+    post:
+      responses: {}
+  /bar:
+    get:
+      operationId: getFoo
+      responses: {}
+"#;
+
+    let Err(CodegenError::InvalidSpec(issues)) =
+      CodeGenerator::from_str(openapi, ".openapi-lambda")
+        .add_api_lambda(ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        ))
+        .try_generate_to_string()
+    else {
+      panic!("expected operationId errors");
+    };
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1foo/post: POST /foo is handled by API Lambda `backend` but has no \
+         operationId",
+        "openapi.yaml#/paths/~1bar/get: duplicate operationId `getFoo` (also used by \
+         openapi.yaml#/paths/~1foo/get)",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_invalid_operation_response_default() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    post:
+      operationId: createFoo
+      responses:
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  id:
+                    type: string
+                    x-rust-default-response: crate::new_id()
+"#;
+
+    let Err(CodegenError::InvalidSpec(issues)) =
+      CodeGenerator::from_str(openapi, ".openapi-lambda")
+        .add_api_lambda(ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        ))
+        .try_generate_to_string()
+    else {
+      panic!("expected invalid `x-rust-default-response`");
+    };
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1foo/post/responses/201/content/application~1json/schema/properties/\
+         id: `x-rust-default-response` is only supported for `readOnly` schemas",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_endpoint_mapped_to_multiple_api_lambdas() {
+    let err = CodeGenerator::from_str(FOO_BAR_OPENAPI_YAML, ".openapi-lambda")
+      .add_api_lambda(ApiLambda::new(
+        "foo",
+        LambdaArn::cloud_formation("FooApiFunction.Alias"),
+      ))
+      .add_api_lambda(
+        ApiLambda::new("bar", LambdaArn::cloud_formation("BarApiFunction.Alias"))
+          .with_op_filter(|op| op.tags.iter().any(|tag| tag == "bar")),
+      )
+      .try_generate_to_string()
+      .unwrap_err();
+    assert_eq!(
+      err,
+      CodegenError::InvalidConfig(
+        "endpoint GET /bar is mapped to multiple API Lambdas: `bar`, `foo`".to_string()
+      )
+    );
+  }
+
+  #[test]
+  fn test_conflicting_cors_configs() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses: {}
+    post:
+      operationId: createFoo
+      responses: {}
+"#;
+
+    let err = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(
+        ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+          .with_route_filter(|method, _, _| method == Method::GET)
+          .with_cors(CorsConfig::new("https://foo.example.com")),
+      )
+      .add_api_lambda(
+        ApiLambda::new("bar", LambdaArn::cloud_formation("BarApiFunction.Alias"))
+          .with_route_filter(|method, _, _| method == Method::POST)
+          .with_cors(CorsConfig::new("https://bar.example.com")),
+      )
+      .try_generate_to_string()
+      .unwrap_err();
+    assert_eq!(
+      err,
+      CodegenError::InvalidConfig(
+        "conflicting CORS configurations for path `/foo`, whose operations are handled by \
+         multiple API Lambdas"
+          .to_string()
+      )
+    );
+  }
+
+  #[test]
+  fn test_invalid_extra_routes() {
+    for (method, path, handler, expected_err) in [
+      (
+        Method::CONNECT,
+        "/health",
+        "crate::health_check",
+        "extra route CONNECT /health for API Lambda `foo`: unsupported HTTP method `CONNECT`",
+      ),
+      (
+        Method::GET,
+        "/health/{id}",
+        "crate::health_check",
+        "extra route GET /health/{id} for API Lambda `foo`: path `/health/{id}` must be a \
+         literal path starting with `/`",
+      ),
+      (
+        Method::GET,
+        "/health",
+        "crate::health_check(",
+        "extra route GET /health for API Lambda `foo`: invalid handler `crate::health_check(`: \
+         cannot parse string into token stream",
+      ),
+    ] {
+      let err = CodeGenerator::from_str(FOO_BAR_OPENAPI_YAML, ".openapi-lambda")
+        .add_api_lambda(
+          ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+            .with_extra_route(method, path, handler),
+        )
+        .try_generate_to_string()
+        .unwrap_err();
+      assert_eq!(err, CodegenError::InvalidConfig(expected_err.to_string()));
+    }
+  }
+
+  #[test]
+  fn test_invalid_handler_template() {
+    let Err(CodegenError::InvalidGeneratedCode(message)) =
+      CodeGenerator::from_str(FOO_BAR_OPENAPI_YAML, ".openapi-lambda")
+        .with_rustfmt(false)
+        .add_api_lambda(
+          ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
+            .with_handler_template(HandlerTemplate::Inline(
+              "pub struct {mod_name_pascal} {".to_string(),
+            )),
+        )
+        .try_generate_to_string()
+    else {
+      panic!("expected invalid handler template to produce invalid code");
+    };
+    assert!(
+      message.starts_with("failed to parse foo_handler.rs: "),
+      "{message}"
+    );
+  }
+
+  #[test]
+  fn test_map_schema_ref() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(
+      dir.join("openapi.yaml"),
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Foo"
+components:
+  schemas:
+    Foo:
+      type: object
+      properties:
+        price:
+          $ref: "common.yaml#/components/schemas/Money"
+        currency:
+          $ref: "common.yaml#/components/schemas/Currency"
+"##,
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("common.yaml"),
+      r##"
+components:
+  schemas:
+    Money:
+      type: object
+      properties:
+        amount:
+          type: string
+        currency:
+          $ref: "#/components/schemas/Currency"
+      required:
+        - amount
+        - currency
+    Currency:
+      type: string
+      enum:
+        - USD
+        - EUR
+"##,
+    )
+    .unwrap();
+
+    let GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      ..
+    } = CodeGenerator::new(dir.join("openapi.yaml"), dir.join(".openapi-lambda"))
+      .map_schema_ref("common.yaml#/components/schemas/Money", "my_common::Money")
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+
+    let out_rs = pretty_print(out_rs);
+    assert!(out_rs.contains("pub price: Option<my_common::Money>,"));
+    assert!(!out_rs.contains("pub struct Money"));
+    // Unmapped foreign schemas still generate models.
+    assert!(out_rs.contains("pub enum Currency {"));
+
+    // The mapped schema is still included in the API Gateway definition.
+    assert!(openapi_apigw_yaml.contains("Money:"));
+    assert!(!openapi_apigw_yaml.contains("x-rust-type"));
+  }
+
+  #[test]
+  #[should_panic(expected = "invalid foreign schema reference `#/components/schemas/Money`")]
+  fn test_map_local_schema_ref() {
+    CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+      .map_schema_ref("#/components/schemas/Money", "my_common::Money");
+  }
+
+  #[test]
+  fn test_foreign_response_and_parameter_refs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(
+      dir.join("openapi.yaml"),
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      parameters:
+        - $ref: "common.yaml#/components/parameters/Locale"
+      responses:
+        "204":
+          description: Success
+        "404":
+          $ref: "common.yaml#/components/responses/NotFound"
+  /bar:
+    get:
+      operationId: getBar
+      parameters:
+        - $ref: "common.yaml#/components/parameters/Locale"
+      responses:
+        "204":
+          description: Success
+        "404":
+          $ref: "common.yaml#/components/responses/NotFound"
+        default:
+          $ref: "common.yaml#/paths/~1error/get/responses/default"
+"##,
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("common.yaml"),
+      r##"
+paths:
+  /error:
+    get:
+      responses:
+        default:
+          description: Error
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  code:
+                    type: integer
+components:
+  parameters:
+    Locale:
+      name: locale
+      in: query
+      schema:
+        type: string
+        enum:
+          - en
+          - fr
+  responses:
+    NotFound:
+      description: Not found
+      content:
+        application/json:
+          schema:
+            type: object
+            properties:
+              message:
+                type: string
+"##,
+    )
+    .unwrap();
+
+    let GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      ..
+    } = CodeGenerator::new(dir.join("openapi.yaml"), dir.join(".openapi-lambda"))
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+
+    // Both operations share the models named after the foreign components.
+    let out_rs = pretty_print(out_rs);
+    assert_eq!(out_rs.matches("pub enum LocaleParam {").count(), 1);
+    assert_eq!(
+      out_rs
+        .matches("pub struct NotFoundResponseJsonResponseBody {")
+        .count(),
+      1
+    );
+    assert!(!out_rs.contains("GetFooLocaleParam"));
+    assert!(!out_rs.contains("GetFoo404Response"));
+
+    // Foreign responses outside of a map of reusable responses are still inlined.
+    assert!(out_rs.contains("pub struct GetBarDefaultResponseJsonResponseBody {"));
+
+    assert!(openapi_apigw_yaml.contains("$ref: '#/components/responses/NotFound'"));
+    assert!(openapi_apigw_yaml.contains("$ref: '#/components/parameters/Locale'"));
+  }
+
+  #[test]
+  fn test_model_modules_by_tag() {
+    let openapi = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      tags: [pet]
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/PetList"
+  /orders:
+    get:
+      operationId: listOrders
+      tags: [Pet Store]
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Order"
+components:
+  schemas:
+    PetList:
+      type: array
+      items:
+        $ref: "#/components/schemas/Pet"
+    Pet:
+      type: object
+      properties:
+        category:
+          $ref: "#/components/schemas/Category"
+    Order:
+      type: object
+      properties:
+        category:
+          $ref: "#/components/schemas/Category"
+    Category:
+      type: object
+      properties:
+        name:
+          type: string
+    Unused:
+      type: object
+      properties:
+        name:
+          type: string
+"##;
+
+    let GeneratedArtifacts {
+      out_rs,
+      openapi_apigw_yaml,
+      ..
+    } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .with_model_modules(ModelModules::ByTag)
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+
+    let out_rs = pretty_print(out_rs);
+    for (module, models) in [
+      ("common", &["Category", "Unused"][..]),
+      ("pet", &["Pet"][..]),
+      ("pet_store", &["Order"][..]),
+    ] {
+      let module_start = out_rs
+        .find(&format!("pub mod {module} {{"))
+        .unwrap_or_else(|| panic!("missing module `{module}`"));
+      let module_out = out_rs[module_start + 1..].split("pub mod ").next().unwrap();
+      for model in models {
+        assert!(
+          module_out.contains(&format!("pub struct {model} {{")),
+          "`{model}` should be in `{module}`"
+        );
+      }
+    }
+    assert!(out_rs.contains("pub category: Option<crate::models::common::Category>,"));
+    assert!(out_rs.contains("Vec<crate::models::pet::Pet>"));
+    assert!(out_rs.contains("crate::models::pet_store::Order"));
+    assert!(!openapi_apigw_yaml.contains("x-rust-module"));
+  }
+
+  #[test]
+  fn test_inline_one_of_bodies() {
+    let openapi = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              oneOf:
+                - $ref: "#/components/schemas/Cat"
+                - $ref: "#/components/schemas/Dog"
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                oneOf:
+                  - $ref: "#/components/schemas/Cat"
+                  - type: object
+                    properties:
+                      error:
+                        type: string
+components:
+  schemas:
+    Cat:
+      type: object
+      properties:
+        meow:
+          type: boolean
+    Dog:
+      type: object
+      properties:
+        bark:
+          type: boolean
+"##;
+
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_client("client")
+      .try_generate_to_string()
+      .unwrap();
+
+    // The inline `oneOf` schemas are hoisted into named models.
+    let out_rs = pretty_print(out_rs);
+    for expected in [
+      "pub enum CreatePetJsonRequestBody {",
+      "pub enum CreatePet200ResponseJsonResponseBody {",
+      "pub struct CreatePet200ResponseJsonResponseBodyOneOf {",
+      "request_body: crate::models::CreatePetJsonRequestBody,",
+      "Ok(crate::models::CreatePet200ResponseJsonResponseBody),",
+    ] {
+      assert!(out_rs.contains(expected), "missing `{expected}`");
+    }
+  }
+
+  #[test]
+  fn test_inline_any_of_bodies_unsupported() {
+    let openapi = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    post:
+      operationId: createPet
+      requestBody:
+        content:
+          application/json:
+            schema:
+              anyOf:
+                - $ref: "#/components/schemas/Cat"
+                - $ref: "#/components/schemas/Dog"
+      responses:
+        "204":
+          description: Success
+components:
+  schemas:
+    Cat:
+      type: object
+      properties:
+        meow:
+          type: boolean
+    Dog:
+      type: object
+      properties:
+        bark:
+          type: boolean
+"##;
+
+    // Unlike `oneOf`, `anyOf` isn't supported in any position, including request and response
+    // bodies.
+    let Err(CodegenError::Unsupported(issues)) =
+      CodeGenerator::from_str(openapi, ".openapi-lambda")
+        .add_api_lambda(ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        ))
+        .try_generate_to_string()
+    else {
+      panic!("expected inline `anyOf` request body to be unsupported");
+    };
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1pets/post/requestBody/content/application~1json/schema: `anyOf` \
+         schemas are not supported"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_deprecated_parameters() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      parameters:
+        - name: page
+          in: query
+          deprecated: true
+          description: Page number
+          schema:
+            type: integer
+        - name: x-legacy-client
+          in: header
+          required: true
+          deprecated: true
+          schema:
+            type: string
+        - name: limit
+          in: query
+          schema:
+            type: integer
+      responses:
+        "204":
+          description: Success
+"#;
+
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+
+    let out_rs = pretty_print(out_rs);
+    for expected in [
+      r#"#[doc = concat!("* `", stringify!(page), "` - ", "**Deprecated.** Page number")]"#,
+      r#"#[doc = concat!("* `", stringify!(limit), "` - ", "")]"#,
+      r#"        if page.is_some() {
+            log::warn!(
+                concat!("Client supplied deprecated request parameter `", "page", "`")
+            );
+        }"#,
+      r#"        log::warn!(
+            concat!("Client supplied deprecated request parameter `", "x-legacy-client",
+            "`")
+        );"#,
+    ] {
+      assert!(out_rs.contains(expected), "missing `{expected}`");
+    }
+    assert!(!out_rs.contains(r#"deprecated request parameter `", "limit""#));
+  }
+
+  #[test]
+  fn test_allof_inline_one_of() {
+    let openapi = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pet:
+    post:
+      operationId: createPet
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Pet"
+      responses:
+        "204":
+          description: Success
+components:
+  schemas:
+    Pet:
+      allOf:
+        - type: object
+          properties:
+            name:
+              type: string
+          required:
+            - name
+        - oneOf:
+            - $ref: "#/components/schemas/Cat"
+            - $ref: "#/components/schemas/Dog"
+          discriminator:
+            propertyName: petType
+    Cat:
+      type: object
+      properties:
+        petType:
+          type: string
+        lives:
+          type: integer
+      required:
+        - petType
+    Dog:
+      type: object
+      properties:
+        petType:
+          type: string
+        goodBoy:
+          type: boolean
+      required:
+        - petType
+"##;
+
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_to_string();
+
+    let out_rs = pretty_print(out_rs);
+    assert!(out_rs.contains(
+      "    pub struct Pet {
+        pub name: String,
+        #[serde(flatten)]
+        pub pet_one_of: crate::models::PetOneOf,
+    }"
+    ));
+    assert!(out_rs.contains("pub enum PetOneOf {"));
+  }
+
+  #[test]
+  fn test_enum_and_primitive_bodies() {
+    let openapi = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /status:
+    put:
+      operationId: setStatus
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/Status"
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "#/components/schemas/Status"
+  /count:
+    put:
+      operationId: setCount
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              type: integer
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                type: string
+                enum:
+                  - updated
+                  - unchanged
+        "201":
+          description: Created
+          content:
+            application/json:
+              schema:
+                type: boolean
+components:
+  schemas:
+    Status:
+      type: string
+      enum:
+        - active
+        - inactive
+"##;
+
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ))
+      .generate_client("client")
+      .generate_to_string();
+
+    let out_rs = pretty_print(out_rs);
+    for expected in [
+      "request_body: crate::models::Status,",
+      "                _,
+                crate::models::Status,
+            >(&mut serde_json::Deserializer::from_slice(&decoded_body)))",
+      "Ok(crate::models::Status),",
+      "request_body: i64,",
+      "                _,
+                i64,
+            >(&mut serde_json::Deserializer::from_slice(&decoded_body)))",
+      "pub enum SetCount200ResponseJsonResponseBody {",
+      "Ok(crate::models::SetCount200ResponseJsonResponseBody),",
+      "Created(bool),",
+    ] {
+      assert!(out_rs.contains(expected), "missing `{expected}`");
+    }
+  }
+
+  #[test]
+  fn test_mono_lambda_cors_preflight() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "204":
+          description: Success
+    post:
+      operationId: createPet
+      responses:
+        "204":
+          description: Success
+  /pets/{petId}:
+    parameters:
+      - name: petId
+        in: path
+        required: true
+        schema:
+          type: string
+    get:
+      operationId: getPet
+      responses:
+        "204":
+          description: Success
+    delete:
+      operationId: deletePet
+      responses:
+        "204":
+          description: Success
+  /custom:
+    get:
+      operationId: getCustom
+      responses:
+        "204":
+          description: Success
+    options:
+      operationId: customOptions
+      responses:
+        "204":
+          description: Success
+"#;
+
+    // A single API Lambda handles every operation, so its dispatcher answers the preflight
+    // requests for all of the paths.
+    let GeneratedArtifacts { out_rs, .. } = CodeGenerator::from_str(openapi, ".openapi-lambda")
+      .add_api_lambda(
+        ApiLambda::new(
+          "backend",
+          LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+        )
+        .with_cors(
+          CorsConfig::new("https://example.com")
+            .with_allow_headers(["Authorization"])
+            .with_allow_credentials(true)
+            .with_max_age(std::time::Duration::from_secs(300)),
+        ),
+      )
+      .try_generate_to_string()
+      .unwrap();
+
+    let out_rs = pretty_print(out_rs);
+    for expected in [
+      r#"Some("/pets") => Some("GET, OPTIONS, POST"),"#,
+      r#"Some("/pets/{petId}") => Some("DELETE, GET, OPTIONS"),"#,
+      r#""https://example.com","#,
+      r#"Some("Authorization"),"#,
+      "Some(300u64),",
+    ] {
+      assert!(out_rs.contains(expected), "missing `{expected}`");
+    }
+
+    // The explicit `OPTIONS` operation is dispatched to the user's handler instead.
+    assert!(!out_rs.contains(r#"Some("/custom") => Some("#));
+    assert!(out_rs.contains("async fn custom_options("));
+  }
+
+  #[test]
+  fn test_format_rust_source_without_rustfmt() {
+    let formatted = CodeGenerator::new("openapi.yaml", ".openapi-lambda")
+      .with_rustfmt(false)
+      .format_rust_source(
+        "foo_handler.rs",
+        "pub struct FooApiHandler { state: () } impl FooApiHandler { pub fn new(state: ()) -> \
+         Self { Self { state } } }",
+      )
+      .unwrap();
+
+    assert_eq!(
+      formatted,
+      "pub struct FooApiHandler {\n    state: (),\n}\nimpl FooApiHandler {\n    pub fn \
+       new(state: ()) -> Self {\n        Self { state }\n    }\n}\n"
+    );
+  }
+
+  #[test]
+  fn test_json_format() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  name:
+                    type: string
+"#;
+
+    let generate = |json_format| {
+      pretty_print(
+        CodeGenerator::from_str(openapi, ".openapi-lambda")
+          .with_json_format(json_format)
+          .add_api_lambda(ApiLambda::new(
+            "backend",
+            LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+          ))
+          .generate_to_string()
+          .out_rs,
+      )
+    };
+    let default_out_rs = generate(JsonFormat::new());
+    let pretty_out_rs = generate(JsonFormat::new().with_pretty(true).with_sorted_keys(true));
+
+    assert!(default_out_rs.contains("use openapi_lambda::__private::encoding::to_json;"));
+    assert!(!default_out_rs.contains("to_json_with_format"));
+    assert!(pretty_out_rs
+      .contains("openapi_lambda::__private::encoding::to_json_with_format(value, true, true)"));
+    assert!(!pretty_out_rs.contains("use openapi_lambda::__private::encoding::to_json;"));
+  }
+
+  #[test]
+  fn test_servers_base_path() {
+    let openapi = r#"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+servers:
+  - url: https://api.example.com/{stage}/
+    variables:
+      stage:
+        default: v1
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        "204":
+          description: Success
+"#;
+
+    let generate = |api_lambda: ApiLambda| {
+      pretty_print(
+        CodeGenerator::from_str(openapi, ".openapi-lambda")
+          .add_api_lambda(api_lambda.with_event_source(EventSource::ApplicationLoadBalancer))
+          .generate_to_string()
+          .out_rs,
+      )
+    };
+    let api_lambda = || {
+      ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      )
+    };
+    let servers_out_rs = generate(api_lambda());
+    let override_out_rs = generate(api_lambda().with_base_path("prod"));
+    let disabled_out_rs = generate(api_lambda().with_base_path(""));
+
+    assert!(servers_out_rs
+      .contains(r#"routing::strip_base_path(request.path.as_deref().unwrap_or_default(), "/v1")"#));
+    assert!(override_out_rs.contains(
+      r#"            routing::strip_base_path(
+                request.path.as_deref().unwrap_or_default(),
+                "/prod",
+            ),"#
+    ));
+    assert!(!disabled_out_rs.contains("strip_base_path"));
+  }
+
+  /// Formats generated code using `prettyplease`, which is more readable (and stable) to match
+  /// against than the raw token stream.
+  fn pretty_print(out_rs: proc_macro2::TokenStream) -> String {
+    prettyplease::unparse(&syn::parse2(out_rs).unwrap())
+  }
+}
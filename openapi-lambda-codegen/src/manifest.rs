@@ -0,0 +1,70 @@
+use crate::api::operation::PathOperation;
+use crate::apigw::API_GATEWAY_INTEGRATION_EXTENTION;
+use crate::{ApiLambda, CodeGenerator};
+
+use serde_json::json;
+
+use std::collections::HashMap;
+
+impl CodeGenerator {
+  /// Generates the JSON route manifest written to the path passed to
+  /// [`with_manifest`](CodeGenerator::with_manifest).
+  ///
+  /// The manifest lists each operation handled by an [`ApiLambda`] (in the order the operations
+  /// appear in the OpenAPI definition), followed by each extra route registered via
+  /// [`ApiLambda::with_extra_route`].
+  pub(crate) fn gen_manifest(
+    &self,
+    operations: &[PathOperation],
+    operation_id_to_api_lambda: &HashMap<&str, &ApiLambda>,
+  ) -> String {
+    let operation_routes = operations.iter().filter_map(|operation| {
+      let operation_id = operation.op.operation_id.as_deref()?;
+      let api_lambda = operation_id_to_api_lambda.get(operation_id)?;
+
+      let preserved_integration = operation
+        .op
+        .extensions
+        .get(API_GATEWAY_INTEGRATION_EXTENTION)
+        .filter(|_| self.is_integration_preserved(&operation.op));
+      let integration_uri = if let Some(integration) = preserved_integration {
+        integration.get("uri").cloned().unwrap_or_default()
+      } else if api_lambda.is_api_gateway_integration() {
+        api_lambda.apigw_invocation_arn(Some(&operation.op))
+      } else {
+        serde_json::Value::Null
+      };
+
+      Some(json!({
+        "method": operation.method.as_str(),
+        "path": operation.request_path,
+        "operation_id": operation_id,
+        "module": api_lambda.mod_name,
+        "integration_uri": integration_uri,
+      }))
+    });
+
+    let extra_routes = self.api_lambdas.values().flat_map(|api_lambda| {
+      api_lambda.extra_routes.iter().map(|route| {
+        let integration_uri = if api_lambda.is_api_gateway_integration() {
+          api_lambda.apigw_invocation_arn(None)
+        } else {
+          serde_json::Value::Null
+        };
+
+        json!({
+          "method": route.method.as_str(),
+          "path": route.path,
+          "operation_id": null,
+          "module": api_lambda.mod_name,
+          "integration_uri": integration_uri,
+        })
+      })
+    });
+
+    let manifest = json!({
+      "routes": operation_routes.chain(extra_routes).collect::<Vec<_>>(),
+    });
+    serde_json::to_string_pretty(&manifest).expect("failed to serialize manifest") + "\n"
+  }
+}
@@ -0,0 +1,33 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Rust type used to represent objects with `additionalProperties`.
+///
+/// See [`CodeGenerator::with_map_type`](crate::CodeGenerator::with_map_type).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum MapType {
+  /// Use [`std::collections::HashMap`], which doesn't preserve key order.
+  HashMap,
+  /// Use [`std::collections::BTreeMap`], which sorts entries by key.
+  BTreeMap,
+  /// Use [`IndexMap`](https://docs.rs/indexmap/latest/indexmap/map/struct.IndexMap.html)
+  /// (re-exported as `openapi_lambda::models::IndexMap`), which preserves insertion order (the
+  /// default).
+  ///
+  /// Deserializing and then re-serializing an object reproduces its additional properties in their
+  /// original order (e.g., when round-tripping JSON payloads that are signed or snapshot-tested).
+  #[default]
+  IndexMap,
+}
+
+impl MapType {
+  /// Type of a map from `String` keys to `value_type` values.
+  pub(crate) fn map_type(&self, crate_import: &syn::Path, value_type: TokenStream) -> TokenStream {
+    match self {
+      MapType::HashMap => quote! { std::collections::HashMap<String, #value_type> },
+      MapType::BTreeMap => quote! { std::collections::BTreeMap<String, #value_type> },
+      MapType::IndexMap => quote! { #crate_import::models::IndexMap<String, #value_type> },
+    }
+  }
+}
@@ -0,0 +1,333 @@
+use crate::model::schema_rust_type;
+use crate::reference::unescape_pointer_token;
+use crate::CodeGenerator;
+
+use indexmap::IndexMap;
+use openapiv3::{
+  AdditionalProperties, ArrayType, IntegerFormat, IntegerType, NumberType, ObjectType, ReferenceOr,
+  Schema, SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
+};
+
+use std::borrow::Borrow;
+
+impl CodeGenerator {
+  /// Checks that a schema `default` deserializes into the Rust type generated for the schema.
+  ///
+  /// Generated `Default` impls parse these values at runtime and panic if parsing fails, so any
+  /// mismatch needs to be reported during code generation instead. Values of types that aren't
+  /// generated by this crate (e.g., custom `format`s and schemas mapped to existing Rust types) are
+  /// assumed to be valid.
+  pub(in crate::model) fn default_value_error(
+    &self,
+    value: &serde_json::Value,
+    schema: &Schema,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Result<(), String> {
+    if schema_rust_type(schema).is_some() {
+      return Ok(());
+    }
+    if value.is_null() {
+      return if schema.schema_data.nullable
+        || matches!(
+          schema.schema_kind,
+          SchemaKind::AnyOf { .. } | SchemaKind::Not { .. } | SchemaKind::Any(_)
+        ) {
+        Ok(())
+      } else {
+        Err("found `null`, but the schema is not `nullable`".to_string())
+      };
+    }
+
+    match &schema.schema_kind {
+      SchemaKind::Type(Type::Boolean(_)) => {
+        if value.is_boolean() {
+          Ok(())
+        } else {
+          Err(format!("expected a boolean, but found {value}"))
+        }
+      }
+      SchemaKind::Type(Type::Integer(integer)) => integer_default_error(value, integer),
+      SchemaKind::Type(Type::Number(number)) => number_default_error(value, number),
+      SchemaKind::Type(Type::String(string)) => string_default_error(value, string),
+      SchemaKind::Type(Type::Array(ArrayType { items, .. })) => {
+        let Some(elements) = value.as_array() else {
+          return Err(format!("expected an array, but found {value}"));
+        };
+        let Some(items) = items else {
+          return Ok(());
+        };
+        elements.iter().enumerate().try_for_each(|(idx, element)| {
+          self
+            .ref_or_schema_default_error(element, items, components_schemas)
+            .map_err(|err| format!("item {idx}: {err}"))
+        })
+      }
+      SchemaKind::Type(Type::Object(object)) => {
+        self.object_default_error(value, object, None, components_schemas)
+      }
+      SchemaKind::OneOf { one_of } => match &schema.schema_data.discriminator {
+        Some(discriminator) => {
+          let tag_field = &discriminator.property_name;
+          let Some(tag_value) = value.get(tag_field).and_then(serde_json::Value::as_str) else {
+            return Err(format!(
+              "expected an object with a string `{tag_field}` property, but found {value}"
+            ));
+          };
+          let variant_name = if discriminator.mapping.is_empty() {
+            Some(tag_value.to_string())
+          } else {
+            discriminator
+              .mapping
+              .get(tag_value)
+              .and_then(|reference| local_schema_name(reference))
+          };
+          let Some(variant) = variant_name.as_deref().and_then(|variant_name| {
+            one_of.iter().find(|variant| {
+              matches!(
+                variant,
+                ReferenceOr::Reference { reference }
+                  if local_schema_name(reference).as_deref() == Some(variant_name)
+              )
+            })
+          }) else {
+            return Err(format!(
+              "`{tag_field}` value `{tag_value}` does not identify a `oneOf` variant"
+            ));
+          };
+          let Some(variant_schema) = self.resolve_default_schema(variant, components_schemas)
+          else {
+            return Ok(());
+          };
+          match &variant_schema.schema_kind {
+            SchemaKind::Type(Type::Object(object))
+              if schema_rust_type(variant_schema).is_none() =>
+            {
+              self.object_default_error(value, object, Some(tag_field), components_schemas)
+            }
+            _ => Ok(()),
+          }
+        }
+        None => {
+          if one_of.iter().any(|variant| {
+            self
+              .ref_or_schema_default_error(value, variant, components_schemas)
+              .is_ok()
+          }) {
+            Ok(())
+          } else {
+            Err(format!("{value} does not match any `oneOf` variant"))
+          }
+        }
+      },
+      SchemaKind::AllOf { all_of } => all_of.iter().try_for_each(|component| {
+        self.ref_or_schema_default_error(value, component, components_schemas)
+      }),
+      SchemaKind::AnyOf { .. } | SchemaKind::Not { .. } | SchemaKind::Any(_) => Ok(()),
+    }
+  }
+
+  fn ref_or_schema_default_error<T>(
+    &self,
+    value: &serde_json::Value,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Result<(), String>
+  where
+    T: Borrow<Schema>,
+  {
+    match self.resolve_default_schema(ref_or_schema, components_schemas) {
+      Some(schema) => self.default_value_error(value, schema, components_schemas),
+      // Invalid references are reported elsewhere.
+      None => Ok(()),
+    }
+  }
+
+  fn resolve_default_schema<'a, T>(
+    &self,
+    ref_or_schema: &'a ReferenceOr<T>,
+    components_schemas: &'a IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<&'a Schema>
+  where
+    T: Borrow<Schema>,
+  {
+    match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(local_schema_name(reference)?.as_str())
+        .and_then(ReferenceOr::as_item),
+      ReferenceOr::Item(schema) => Some(schema.borrow()),
+    }
+  }
+
+  fn object_default_error(
+    &self,
+    value: &serde_json::Value,
+    object: &ObjectType,
+    tag_field_to_exclude: Option<&str>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Result<(), String> {
+    let Some(fields) = value.as_object() else {
+      return Err(format!("expected an object, but found {value}"));
+    };
+
+    if let Some(missing) = object.required.iter().find(|property_name| {
+      Some(property_name.as_str()) != tag_field_to_exclude
+        && object.properties.contains_key(*property_name)
+        && !fields.contains_key(*property_name)
+    }) {
+      return Err(format!("missing required property `{missing}`"));
+    }
+
+    fields.iter().try_for_each(|(field_name, field_value)| {
+      // Optional properties are represented as `Option`s, which deserialize `null` as `None`.
+      if Some(field_name.as_str()) == tag_field_to_exclude
+        || (field_value.is_null() && !object.required.contains(field_name))
+      {
+        return Ok(());
+      }
+      if let Some(property) = object.properties.get(field_name) {
+        self.ref_or_schema_default_error(field_value, property, components_schemas)
+      } else if let Some(AdditionalProperties::Schema(additional_properties)) =
+        &object.additional_properties
+      {
+        self.ref_or_schema_default_error(field_value, additional_properties, components_schemas)
+      } else {
+        Ok(())
+      }
+      .map_err(|err| format!("property `{field_name}`: {err}"))
+    })
+  }
+}
+
+/// Returns the name of the schema targeted by a `#/components/schemas/` reference.
+fn local_schema_name(reference: &str) -> Option<String> {
+  reference
+    .strip_prefix("#/components/schemas/")
+    .map(|name| unescape_pointer_token(name).into_owned())
+}
+
+fn integer_default_error(value: &serde_json::Value, integer: &IntegerType) -> Result<(), String> {
+  // Custom formats may be mapped to any Rust type.
+  if let VariantOrUnknownOrEmpty::Unknown(_) = &integer.format {
+    return Ok(());
+  }
+  let Some(number) = value.as_i64() else {
+    return Err(format!("expected an integer, but found {value}"));
+  };
+  if integer.format == VariantOrUnknownOrEmpty::Item(IntegerFormat::Int32)
+    && i32::try_from(number).is_err()
+  {
+    return Err(format!("{value} does not fit in an `i32`"));
+  }
+  if !integer.enumeration.is_empty() && !integer.enumeration.contains(&Some(number)) {
+    return Err(format!("{value} is not an `enum` value"));
+  }
+  Ok(())
+}
+
+fn number_default_error(value: &serde_json::Value, number: &NumberType) -> Result<(), String> {
+  if let VariantOrUnknownOrEmpty::Unknown(_) = &number.format {
+    return Ok(());
+  }
+  let Some(float) = value.as_f64() else {
+    return Err(format!("expected a number, but found {value}"));
+  };
+  if !number.enumeration.is_empty() && !number.enumeration.contains(&Some(float)) {
+    return Err(format!("{value} is not an `enum` value"));
+  }
+  Ok(())
+}
+
+fn string_default_error(value: &serde_json::Value, string: &StringType) -> Result<(), String> {
+  if let VariantOrUnknownOrEmpty::Unknown(_) = &string.format {
+    return Ok(());
+  }
+  let Some(text) = value.as_str() else {
+    return Err(format!("expected a string, but found {value}"));
+  };
+  if !string.enumeration.is_empty() && !string.enumeration.contains(&Some(text.to_string())) {
+    return Err(format!("{value} is not an `enum` value"));
+  }
+  match &string.format {
+    VariantOrUnknownOrEmpty::Item(StringFormat::Date) if !is_full_date(text) => {
+      Err(format!("{value} is not an RFC 3339 `full-date`"))
+    }
+    VariantOrUnknownOrEmpty::Item(StringFormat::DateTime) if !is_date_time(text) => {
+      Err(format!("{value} is not an RFC 3339 `date-time`"))
+    }
+    _ => Ok(()),
+  }
+}
+
+/// Parses a fixed-width run of ASCII digits.
+fn parse_digits(digits: &str) -> Option<u32> {
+  (!digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()))
+    .then(|| digits.parse().ok())
+    .flatten()
+}
+
+/// Returns true iff the string is an RFC 3339 `full-date` (e.g., `2024-02-29`).
+fn is_full_date(date: &str) -> bool {
+  let (Some(year), Some(month), Some(day)) = (
+    date.get(0..4).and_then(parse_digits),
+    date.get(5..7).and_then(parse_digits),
+    date.get(8..10).and_then(parse_digits),
+  ) else {
+    return false;
+  };
+  let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+  let days_in_month = match month {
+    1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+    4 | 6 | 9 | 11 => 30,
+    2 if is_leap_year => 29,
+    2 => 28,
+    _ => return false,
+  };
+  date.len() == 10 && &date[4..5] == "-" && &date[7..8] == "-" && (1..=days_in_month).contains(&day)
+}
+
+/// Returns true iff the string is an RFC 3339 `date-time` (e.g., `2024-02-29T12:00:00.5+01:00`).
+fn is_date_time(date_time: &str) -> bool {
+  let Some((date, time)) = date_time.split_once(['T', 't']) else {
+    return false;
+  };
+  let (partial_time, offset) = if let Some(partial_time) = time.strip_suffix(['Z', 'z']) {
+    (partial_time, None)
+  } else {
+    let Some(offset_start) = time.rfind(['+', '-']) else {
+      return false;
+    };
+    (&time[..offset_start], Some(&time[offset_start + 1..]))
+  };
+  let is_time = |time: &str, max_hour: u32, max_second: u32| {
+    let mut parts = time.splitn(3, ':');
+    let (Some(hour), Some(minute)) = (
+      parts
+        .next()
+        .filter(|hour| hour.len() == 2)
+        .and_then(parse_digits),
+      parts
+        .next()
+        .filter(|minute| minute.len() == 2)
+        .and_then(parse_digits),
+    ) else {
+      return false;
+    };
+    let second_ok = match parts.next() {
+      Some(second) => {
+        let (whole, fraction) = second.split_once('.').unwrap_or((second, "0"));
+        whole.len() == 2
+          && parse_digits(whole).map_or(false, |second| second <= max_second)
+          && !fraction.is_empty()
+          && fraction.bytes().all(|b| b.is_ascii_digit())
+      }
+      None => max_second == 0,
+    };
+    hour <= max_hour && minute <= 59 && second_ok
+  };
+
+  is_full_date(date)
+    && is_time(partial_time, 23, 60)
+    && offset.map_or(true, |offset| {
+      offset.len() == 5 && !offset.contains('.') && is_time(offset, 23, 0)
+    })
+}
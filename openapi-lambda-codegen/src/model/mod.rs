@@ -1,30 +1,223 @@
 #![allow(clippy::too_many_arguments)]
 
 use crate::inline::InlineApi;
-use crate::{description_to_doc_attr, CodeGenerator};
+use crate::model::rename_rule::RenameRule;
+use crate::reference::unescape_pointer_token;
+use crate::set_type::SetItemTrait;
+use crate::{
+  description_to_doc_attr, CodeGenerator, CodegenError, DateTimeBackend, MapType, ModelModules,
+  SetType,
+};
 
 use convert_case::{Case, Casing};
 use indexmap::{IndexMap, IndexSet};
-use itertools::Either;
+use itertools::{Either, Itertools};
 use openapiv3::{
   AdditionalProperties, AnySchema, ArrayType, BooleanType, Components, Discriminator,
   IntegerFormat, IntegerType, NumberFormat, NumberType, ObjectType, ReferenceOr, Schema,
-  SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
+  SchemaData, SchemaKind, StringFormat, StringType, Type, VariantOrUnknownOrEmpty,
 };
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use unzip_n::unzip_n;
 
 use std::borrow::{Borrow, Cow};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
+mod default_value;
+mod modules;
 mod name_model_schemas;
+mod rename_rule;
+
+/// Returns true iff the schema is an object schema that specifies an `enum` of permitted values.
+///
+/// The `openapiv3` crate doesn't support `enum` for object types, so these schemas are parsed as
+/// `any` schemas.
+pub(crate) fn is_object_enum_schema(any: &AnySchema) -> bool {
+  any.typ.as_deref() == Some("object") && !any.enumeration.is_empty()
+}
 
 #[cfg(test)]
 mod tests;
 
+/// Replace each JSON Schema `const` keyword in a raw OpenAPI document with the equivalent
+/// single-value `enum`.
+///
+/// The `openapiv3` crate silently ignores `const`, so this must happen before the document is
+/// deserialized. Schemas without a `type` are assigned one based on the type of the `const` value.
+/// Keywords whose values aren't schemas (e.g., `example`) are left unchanged.
+pub(crate) fn const_schemas_to_enums(doc: &mut serde_yaml::Mapping) {
+  fn visit(value: &mut serde_yaml::Value) {
+    match value {
+      serde_yaml::Value::Mapping(mapping) => {
+        if let Some(const_value) = mapping
+          .get("const")
+          .filter(|const_value| {
+            matches!(
+              const_value,
+              serde_yaml::Value::Bool(_)
+                | serde_yaml::Value::Number(_)
+                | serde_yaml::Value::String(_)
+            )
+          })
+          .cloned()
+        {
+          mapping.remove("const");
+          if mapping.contains_key("enum") {
+            // Keep the conflicting value so that the issue can be reported along with the schema's
+            // location once the document has been deserialized.
+            mapping.insert(CONFLICTING_CONST_EXTENSION.into(), const_value);
+          } else {
+            if !mapping.contains_key("type") {
+              let typ = match &const_value {
+                serde_yaml::Value::Bool(_) => "boolean",
+                serde_yaml::Value::Number(number) if number.is_f64() => "number",
+                serde_yaml::Value::Number(_) => "integer",
+                _ => "string",
+              };
+              mapping.insert("type".into(), typ.into());
+            }
+            mapping.insert("enum".into(), vec![const_value].into());
+          }
+        }
+
+        mapping
+          .iter_mut()
+          .filter(|(key, _)| {
+            !matches!(
+              key.as_str(),
+              Some("default" | "enum" | "example" | "examples")
+            )
+          })
+          .for_each(|(_, child)| visit(child));
+      }
+      serde_yaml::Value::Sequence(sequence) => sequence.iter_mut().for_each(visit),
+      _ => {}
+    }
+  }
+
+  doc.iter_mut().for_each(|(_, value)| visit(value));
+}
+
+/// Returns the name of an identifier without any `r#` prefix, which serde uses when renaming fields.
+fn unraw_ident(ident: &Ident) -> String {
+  let ident = ident.to_string();
+  ident
+    .strip_prefix("r#")
+    .map(str::to_string)
+    .unwrap_or(ident)
+}
+
+/// Returns the name of the untagged `oneOf` enum variant representing an inline primitive schema.
+fn primitive_variant_name(model_ident: &Ident, schema: &Schema) -> &'static str {
+  match &schema.schema_kind {
+    SchemaKind::Type(Type::String(_)) => "String",
+    SchemaKind::Type(Type::Integer(_)) => "Integer",
+    SchemaKind::Type(Type::Number(_)) => "Number",
+    SchemaKind::Type(Type::Boolean(_)) => "Boolean",
+    _ => panic!(
+      "unexpected inline schema in `oneOf` schema `{model_ident}`: enum variants must be either \
+       references to named schemas or primitive (`string`, `integer`, `number`, or `boolean`) \
+       schemas: {schema:#?}",
+    ),
+  }
+}
+
+/// Returns true iff the `additionalProperties` of an object schema produce an
+/// `additional_properties` field in the generated struct.
+fn has_additional_properties(additional_properties: Option<&AdditionalProperties>) -> bool {
+  !matches!(
+    additional_properties,
+    None | Some(AdditionalProperties::Any(false))
+  )
+}
+
+/// Schema extension holding the `const` value of a schema that also specifies `enum`. This is
+/// added by [`const_schemas_to_enums`] so that the conflict can be reported as an invalid schema.
+pub(crate) const CONFLICTING_CONST_EXTENSION: &str = "x-rust-conflicting-const";
+
+/// Schema extension mapping string `enum` values to descriptions of the corresponding variants.
+pub(crate) const ENUM_DESCRIPTIONS_EXTENSION: &str = "x-enum-descriptions";
+
+/// Schema extension listing variant names for string `enum` values, in the same order as `enum`.
+pub(crate) const ENUM_VARNAMES_EXTENSION: &str = "x-enum-varnames";
+
+/// Schema extension listing additional derives for the generated model.
+pub(crate) const RUST_DERIVES_EXTENSION: &str = "x-rust-derives";
+
+/// Schema extension specifying a Rust expression that generates the value of a `readOnly` property
+/// (e.g., `uuid::Uuid::new_v4()`) when constructing a model via its `Default` impl or builder.
+pub(crate) const RUST_DEFAULT_RESPONSE_EXTENSION: &str = "x-rust-default-response";
+
+/// Schema extension specifying the serde `rename_all` rule (e.g., `camelCase`) for the properties
+/// of an object model, overriding the rule inferred from the property names.
+pub(crate) const RUST_RENAME_ALL_EXTENSION: &str = "x-rust-rename-all";
+
+/// Schema extension specifying an existing Rust type to use for the schema instead of generating
+/// a model. This is added to the targets of foreign references mapped via
+/// [`CodeGenerator::map_schema_ref`].
+pub(crate) const RUST_TYPE_EXTENSION: &str = "x-rust-type";
+
+/// Schema extension specifying the `models` submodule containing the generated model. This is
+/// added to every schema in `components.schemas` when using [`ModelModules::ByTag`].
+pub(crate) const RUST_MODULE_EXTENSION: &str = "x-rust-module";
+
+/// Returns the existing Rust type specified by the schema's [`RUST_TYPE_EXTENSION`], if any.
+pub(crate) fn schema_rust_type(schema: &Schema) -> Option<&str> {
+  schema
+    .schema_data
+    .extensions
+    .get(RUST_TYPE_EXTENSION)
+    .and_then(serde_json::Value::as_str)
+}
+
+/// Returns the `models` submodule specified by the schema's [`RUST_MODULE_EXTENSION`], if any.
+pub(crate) fn schema_rust_module(schema: &Schema) -> Option<&str> {
+  schema
+    .schema_data
+    .extensions
+    .get(RUST_MODULE_EXTENSION)
+    .and_then(serde_json::Value::as_str)
+}
+
 unzip_n!(3);
 
+/// How to implement `Default` for an object model (see [`CodeGenerator::object_default`]).
+enum ObjectDefault {
+  /// Derive `Default`, since every property is optional.
+  Derive,
+  /// Use a manual `impl Default`, since some required properties specify a schema `default`.
+  Impl(TokenStream),
+  /// Don't implement `Default`, since some required properties don't specify a schema `default`.
+  None,
+}
+
+/// Comparison traits implemented by the Rust type representing a schema (see
+/// [`CodeGenerator::schema_comparison`]).
+///
+/// Variants are ordered such that the comparison supported by a composite type (e.g., a struct) is
+/// the minimum of those supported by its fields.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+enum Comparison {
+  /// Neither `PartialEq` nor `Eq` is known to be implemented (e.g., for existing Rust types).
+  None,
+  /// Only `PartialEq` is implemented (e.g., for floating-point numbers).
+  PartialEq,
+  /// Both `PartialEq` and `Eq` are implemented.
+  Eq,
+}
+
+impl Comparison {
+  /// Derives implementing the supported comparison traits.
+  fn derives(self) -> &'static [&'static str] {
+    match self {
+      Comparison::None => &[],
+      Comparison::PartialEq => &["PartialEq"],
+      Comparison::Eq => &["PartialEq", "Eq"],
+    }
+  }
+}
+
 /// Used by [`CodeGenerator::inline_ref_or_schema`] to determine whether to inline schema references
 /// or generate code that points to a separate generated model. During model generation, the
 /// `InProgress` variant is used, since the referenced schema may not have been processed yet, and
@@ -39,23 +232,200 @@ pub(crate) enum GeneratedModels<'a> {
   Done(&'a HashMap<Ident, TokenStream>),
 }
 
+/// Component of an `allOf` schema after resolving references and flattening nested `allOf`
+/// schemas.
+enum ComposedComponent<'a> {
+  /// Object whose properties become fields of the composed model.
+  Object(&'a ObjectType),
+  /// Reference to a named `oneOf` schema, which becomes a `#[serde(flatten)]` enum field of the
+  /// composed model.
+  OneOf {
+    schema_name: Cow<'a, str>,
+    reference: &'a ReferenceOr<Schema>,
+  },
+}
+
 impl CodeGenerator {
   /// Generate models and update OpenAPI with unnamed models replaced by references to new, named
   /// models inserted into `components/schemas/`.
+  ///
+  /// Returns an error listing every invalid schema (e.g., one with a malformed extension) before
+  /// generating any models.
   pub(crate) fn generate_models(
     &self,
     mut openapi: InlineApi,
-  ) -> (InlineApi, HashMap<Ident, TokenStream>) {
+  ) -> Result<(InlineApi, HashMap<Ident, TokenStream>), CodegenError> {
+    // Schemas are checked before they're moved so that issues point to their original locations.
+    let invalid_schemas = self.find_invalid_schemas(&openapi);
+    if !invalid_schemas.is_empty() {
+      return Err(CodegenError::InvalidSpec(invalid_schemas));
+    }
+
     // Moves all schemas for which we need to generate Rust models into openapi.components.schemas.
-    name_model_schemas::visit_openapi(&mut openapi);
+    name_model_schemas::visit_openapi(&mut openapi, self.callbacks);
+
+    if self.model_modules == ModelModules::ByTag {
+      modules::assign_model_modules(&mut openapi, self.callbacks);
+    }
 
     // If there are still no components, then there are no models to generate.
     let Some(components) = &openapi.components else {
-      return (openapi, HashMap::new());
+      return Ok((openapi, HashMap::new()));
     };
 
     let models = self.generate_components(components);
-    (openapi, models)
+    Ok((openapi, models))
+  }
+
+  /// Returns a description of each way in which a schema's `default` or extensions are invalid.
+  ///
+  /// These schemas are reported by `find_invalid_schemas` before any models are generated.
+  pub(crate) fn schema_errors(
+    &self,
+    schema: &Schema,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if let Some(default) = &schema.schema_data.default {
+      if let Err(err) = self.default_value_error(default, schema, components_schemas) {
+        errors.push(format!("`default` does not match the schema: {err}"));
+      }
+    }
+
+    match schema.schema_data.extensions.get(RUST_DERIVES_EXTENSION) {
+      None => {}
+      Some(serde_json::Value::Array(derives)) => {
+        for derive in derives {
+          match derive.as_str() {
+            Some(derive) => {
+              if let Err(err) = syn::parse_str::<syn::Path>(derive) {
+                errors.push(format!(
+                  "`{RUST_DERIVES_EXTENSION}` contains `{derive}`, which is not a valid Rust \
+                   path: {err}"
+                ));
+              }
+            }
+            None => errors.push(format!(
+              "`{RUST_DERIVES_EXTENSION}` must be an array of strings, but found {derive}"
+            )),
+          }
+        }
+      }
+      Some(other) => errors.push(format!(
+        "`{RUST_DERIVES_EXTENSION}` must be an array of strings, but found {other}"
+      )),
+    }
+
+    if let Some(rename_all) = schema.schema_data.extensions.get(RUST_RENAME_ALL_EXTENSION) {
+      if rename_all
+        .as_str()
+        .and_then(|rename_all| rename_all.parse::<RenameRule>().ok())
+        .is_none()
+      {
+        errors.push(format!(
+          "`{RUST_RENAME_ALL_EXTENSION}` must be one of {}, but found {rename_all}",
+          RenameRule::ALL
+            .iter()
+            .map(|rule| format!("\"{}\"", rule.as_str()))
+            .join(", ")
+        ));
+      }
+    }
+
+    if let Some(default) = schema
+      .schema_data
+      .extensions
+      .get(RUST_DEFAULT_RESPONSE_EXTENSION)
+    {
+      match default.as_str() {
+        None => errors.push(format!(
+          "`{RUST_DEFAULT_RESPONSE_EXTENSION}` must be a string, but found {default}"
+        )),
+        Some(default) => {
+          if let Err(err) = syn::parse_str::<syn::Expr>(default) {
+            errors.push(format!(
+              "`{RUST_DEFAULT_RESPONSE_EXTENSION}` must be a Rust expression, but found \
+               `{default}`: {err}"
+            ));
+          }
+        }
+      }
+      if !schema.schema_data.read_only {
+        errors.push(format!(
+          "`{RUST_DEFAULT_RESPONSE_EXTENSION}` is only supported for `readOnly` schemas"
+        ));
+      }
+    }
+
+    if let SchemaKind::Type(Type::String(StringType { enumeration, .. })) = &schema.schema_kind {
+      match schema
+        .schema_data
+        .extensions
+        .get(ENUM_DESCRIPTIONS_EXTENSION)
+      {
+        None => {}
+        Some(serde_json::Value::Object(descriptions)) => {
+          for (variant, description) in descriptions {
+            if !enumeration.contains(&Some(variant.to_string())) {
+              errors.push(format!(
+                "`{ENUM_DESCRIPTIONS_EXTENSION}` describes `{variant}`, which is not an enum value"
+              ));
+            } else if !description.is_string() {
+              errors.push(format!(
+                "`{ENUM_DESCRIPTIONS_EXTENSION}` must map enum values to strings, but found \
+                 {description} for `{variant}`"
+              ));
+            }
+          }
+        }
+        Some(other) => errors.push(format!(
+          "`{ENUM_DESCRIPTIONS_EXTENSION}` must be an object mapping enum values to descriptions, \
+           but found {other}"
+        )),
+      }
+
+      match schema.schema_data.extensions.get(ENUM_VARNAMES_EXTENSION) {
+        None => {}
+        Some(serde_json::Value::Array(names)) => {
+          if names.len() != enumeration.len() {
+            errors.push(format!(
+              "`{ENUM_VARNAMES_EXTENSION}` has {} names, but `enum` has {} values",
+              names.len(),
+              enumeration.len(),
+            ));
+          }
+          let mut used_variant_idents = HashSet::new();
+          for name in names {
+            let Some(name) = name.as_str() else {
+              errors.push(format!(
+                "`{ENUM_VARNAMES_EXTENSION}` must be an array of strings, but found {name}"
+              ));
+              continue;
+            };
+            match self.variant_name_ident(name) {
+              None => errors.push(format!(
+                "`{ENUM_VARNAMES_EXTENSION}` contains `{name}`, which is not a valid Rust \
+                 identifier"
+              )),
+              Some(variant_ident) => {
+                if !used_variant_idents.insert(variant_ident.to_string()) {
+                  errors.push(format!(
+                    "`{ENUM_VARNAMES_EXTENSION}` contains `{name}`, which duplicates the name of \
+                     another variant (`{variant_ident}`)"
+                  ));
+                }
+              }
+            }
+          }
+        }
+        Some(other) => errors.push(format!(
+          "`{ENUM_VARNAMES_EXTENSION}` must be an array of strings, but found {other}"
+        )),
+      }
+    }
+
+    errors
   }
 
   fn generate_components(&self, components: &Components) -> HashMap<Ident, TokenStream> {
@@ -105,6 +475,11 @@ impl CodeGenerator {
       return true;
     }
 
+    // Schemas mapped to existing Rust types are always referenced via `inline_type`.
+    if schema_rust_type(schema).is_some() {
+      return false;
+    }
+
     // Prevent infinite recursion.
     if models_in_progress.contains(&model_ident) {
       panic!("dependency cycle detected between models: {models_in_progress:#?}");
@@ -115,14 +490,19 @@ impl CodeGenerator {
       SchemaKind::Type(schema_type) => match schema_type {
         Type::Object(object) => self.generate_object_model(
           &model_ident,
+          &schema.schema_data,
           object,
           components_schemas,
           models,
           models_in_progress,
         ),
         Type::Array(_) => None,
-        Type::String(string) => self.generate_string_model(&model_ident, string),
-        Type::Integer(integer) => self.generate_integer_model(&model_ident, integer),
+        Type::String(string) => {
+          self.generate_string_model(&model_ident, &schema.schema_data, string)
+        }
+        Type::Integer(integer) => {
+          self.generate_integer_model(&model_ident, &schema.schema_data, integer)
+        }
         Type::Number(number) => self.generate_number_model(&model_ident, number),
         Type::Boolean(boolean) => self.generate_boolean_model(&model_ident, boolean),
       },
@@ -130,6 +510,7 @@ impl CodeGenerator {
         if let Some(discriminator) = &schema.schema_data.discriminator {
           Some(self.generate_tagged_enum_model(
             &model_ident,
+            &schema.schema_data,
             one_of,
             discriminator,
             components_schemas,
@@ -139,6 +520,7 @@ impl CodeGenerator {
         } else {
           Some(self.generate_untagged_enum_model(
             &model_ident,
+            &schema.schema_data,
             one_of,
             components_schemas,
             models,
@@ -151,6 +533,7 @@ impl CodeGenerator {
       }
       SchemaKind::AllOf { all_of } => Some(self.generate_composed_object_model(
         &model_ident,
+        &schema.schema_data,
         all_of,
         components_schemas,
         models,
@@ -159,6 +542,9 @@ impl CodeGenerator {
       SchemaKind::Not { .. } => {
         unimplemented!("`not` schema {schema:#?}");
       }
+      SchemaKind::Any(any) if is_object_enum_schema(any) => {
+        Some(self.generate_object_enum_model(&model_ident, &schema.schema_data, &any.enumeration))
+      }
       SchemaKind::Any(any) => {
         if *any != AnySchema::default() {
           unimplemented!("`any` schema: {any:#?}");
@@ -169,7 +555,7 @@ impl CodeGenerator {
       }
     };
 
-    models_in_progress.remove(&model_ident);
+    models_in_progress.shift_remove(&model_ident);
 
     if let Some(model) = model {
       let model_with_docs = if let Some(description) = &schema.schema_data.description {
@@ -195,6 +581,7 @@ impl CodeGenerator {
     required: &[String],
     is_enum_variant: bool,
     tag_field_to_exclude: Option<&str>,
+    rename_rule: Option<RenameRule>,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
     models_in_progress: &mut IndexSet<Ident>,
@@ -205,10 +592,7 @@ impl CodeGenerator {
       // determine the enum variant, it's not available to the variant's body. The OpenAPI spec
       // explicitly requires the discriminator field to be present in each `oneOf` component,
       // although we assume it's a string and don't enforce that it's explicitly defined.
-      .filter(|(property_name, _)| {
-        !tag_field_to_exclude
-          .is_some_and(|tag_field_to_exclude| tag_field_to_exclude == property_name.as_str())
-      })
+      .filter(|(property_name, _)| tag_field_to_exclude != Some(property_name.as_str()))
       .map(|(property_name, ref_or_schema)| {
         let property_ident = self.identifier(&property_name.to_case(Case::Snake));
         let (property_type_inner, property_description) = self.inline_ref_or_schema(
@@ -220,7 +604,12 @@ impl CodeGenerator {
           },
         );
 
-        let serde_rename = if property_ident != property_name {
+        let needs_rename = if let Some(rename_rule) = rename_rule {
+          rename_rule.apply_to_field(&unraw_ident(&property_ident)) != *property_name
+        } else {
+          property_ident != property_name
+        };
+        let serde_rename = if needs_rename {
           Some(quote! { rename = #property_name })
         } else {
           None
@@ -237,19 +626,73 @@ impl CodeGenerator {
         } else {
           quote! {}
         };
+        let nullable = self.is_nullable(ref_or_schema, components_schemas);
+        // The `time` crate doesn't serialize `OffsetDateTime` as RFC 3339 by default.
+        let rfc3339_with = (self.datetime_backend == DateTimeBackend::Time
+          && self.string_format(ref_or_schema, components_schemas) == Some(StringFormat::DateTime))
+        .then(|| format!("{}::models::time::serde::rfc3339", self.crate_name));
         if required.contains(property_name) {
+          let serde_with = rfc3339_with.map(|rfc3339_with| {
+            let rfc3339_with = if nullable {
+              format!("{rfc3339_with}::option")
+            } else {
+              rfc3339_with
+            };
+            quote! { with = #rfc3339_with }
+          });
+          let serde_attrs = match (serde_rename, serde_with) {
+            (Some(rename), Some(with)) => quote! { #[serde(#rename, #with)] },
+            (Some(attr), None) | (None, Some(attr)) => quote! { #[serde(#attr)] },
+            (None, None) => quote! {},
+          };
+          // Required nullable properties serialize `None` as `null`.
+          let property_type = if nullable {
+            quote! { Option<#property_type_inner> }
+          } else {
+            property_type_inner
+          };
+          quote! {
+            #doc_attr
+            #serde_attrs
+            #r#pub #property_ident: #property_type,
+          }
+        } else if nullable {
+          // Optional nullable properties distinguish between a missing property (`None`) and a
+          // `null` value (`Some(None)`).
+          let double_option = if rfc3339_with.is_some() {
+            format!(
+              "{}::__private::time::rfc3339_double_option",
+              self.crate_name
+            )
+          } else {
+            format!("{}::__private::double_option", self.crate_name)
+          };
           let serde_attrs = serde_rename
-            .map(|rename| quote! { #[serde(#rename)] })
+            .map(|rename| quote! { #rename, })
             .unwrap_or_default();
           quote! {
             #doc_attr
-            #serde_attrs
-            #r#pub #property_ident: #property_type_inner,
+            #[serde(
+              #serde_attrs
+              default,
+              skip_serializing_if = "Option::is_none",
+              with = #double_option
+            )]
+            #r#pub #property_ident: Option<Option<#property_type_inner>>,
           }
         } else {
-          let serde_attrs = serde_rename
-            .map(|rename| quote! { #rename, skip_serializing_if = "Option::is_none" })
-            .unwrap_or_else(|| quote! { skip_serializing_if = "Option::is_none" });
+          let serde_rename = serde_rename.map(|rename| quote! { #rename, });
+          let serde_attrs = if let Some(rfc3339_with) = rfc3339_with {
+            let rfc3339_with = format!("{rfc3339_with}::option");
+            quote! {
+              #serde_rename
+              default,
+              skip_serializing_if = "Option::is_none",
+              with = #rfc3339_with
+            }
+          } else {
+            quote! { #serde_rename skip_serializing_if = "Option::is_none" }
+          };
           quote! {
             #doc_attr
             #[serde(#serde_attrs)]
@@ -270,22 +713,29 @@ impl CodeGenerator {
     let additional_property_type = match additional_properties.as_ref() {
       None | Some(AdditionalProperties::Any(false)) => None,
       Some(AdditionalProperties::Any(true)) => Some(self.inline_any_type()),
-      Some(AdditionalProperties::Schema(ref_or_schema)) => Some(
-        self
-          .inline_ref_or_schema(
-            ref_or_schema,
-            components_schemas,
-            GeneratedModels::InProgress {
-              models,
-              models_in_progress,
-            },
-          )
-          .0,
-      ),
+      Some(AdditionalProperties::Schema(ref_or_schema)) => Some({
+        self.check_nested_date_time(ref_or_schema, components_schemas, "additionalProperties");
+        self.nullable_type(
+          ref_or_schema,
+          components_schemas,
+          self
+            .inline_ref_or_schema(
+              ref_or_schema,
+              components_schemas,
+              GeneratedModels::InProgress {
+                models,
+                models_in_progress,
+              },
+            )
+            .0,
+        )
+      }),
     };
 
     additional_property_type.map(|additional_property_type| {
-      quote! { std::collections::HashMap<String, #additional_property_type> }
+      self
+        .map_type
+        .map_type(&self.crate_use_name(), additional_property_type)
     })
   }
 
@@ -294,6 +744,7 @@ impl CodeGenerator {
     object: &ObjectType,
     is_enum_variant: bool,
     tag_field_to_exclude: Option<&str>,
+    rename_rule: Option<RenameRule>,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
     models_in_progress: &mut IndexSet<Ident>,
@@ -310,6 +761,7 @@ impl CodeGenerator {
       required,
       is_enum_variant,
       tag_field_to_exclude,
+      rename_rule,
       components_schemas,
       models,
       models_in_progress,
@@ -358,6 +810,7 @@ impl CodeGenerator {
   fn generate_object_model(
     &self,
     model_ident: &Ident,
+    schema_data: &SchemaData,
     object: &ObjectType,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
@@ -367,90 +820,511 @@ impl CodeGenerator {
       return None;
     }
 
+    let rename_rule = self.object_rename_rule(schema_data, object);
     let struct_body = self.generate_object_struct_body(
       object,
       false,
       None,
+      rename_rule,
       components_schemas,
       models,
       models_in_progress,
     );
     let serde_crate_attr = self.serde_crate_attr();
+    let debug_impl = self.generate_redacted_debug_impl(
+      model_ident,
+      &object.properties,
+      &[],
+      has_additional_properties(object.additional_properties.as_ref()),
+      components_schemas,
+    );
+    let object_default = self.object_default(model_ident, object, components_schemas);
+    let base_derives = ["Clone"]
+      .into_iter()
+      .chain(debug_impl.is_none().then_some("Debug"))
+      .chain(matches!(object_default, ObjectDefault::Derive).then_some("Default"))
+      .chain(["Deserialize", "Serialize"])
+      .chain(
+        self
+          .object_comparison(object, components_schemas)
+          .derives()
+          .iter()
+          .copied(),
+      )
+      .collect::<Vec<_>>();
+    let derive_attr = self.derive_attr(&base_derives, schema_data);
+    let default_impl = match object_default {
+      ObjectDefault::Impl(default_impl) => Some(default_impl),
+      ObjectDefault::Derive | ObjectDefault::None => None,
+    };
+    let builder = self.builders.then(|| {
+      self.generate_builder(
+        model_ident,
+        object,
+        components_schemas,
+        models,
+        models_in_progress,
+      )
+    });
+    let rename_all_attr = rename_rule.map(|rename_rule| {
+      let rename_all = rename_rule.as_str();
+      quote! { , rename_all = #rename_all }
+    });
     Some(quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
-      #[serde(#serde_crate_attr)]
+      #derive_attr
+      #[serde(#serde_crate_attr #rename_all_attr)]
       pub struct #model_ident #struct_body
+
+      #debug_impl
+
+      #default_impl
+
+      #builder
     })
   }
 
-  fn flatten_composed_object_components<'a, I>(
-    &'a self,
-    model_ident: &'a Ident,
-    components: I,
-    components_schemas: &'a IndexMap<String, ReferenceOr<Schema>>,
-  ) -> Box<dyn Iterator<Item = &'a ObjectType> + 'a>
-  where
-    I: IntoIterator<Item = &'a ReferenceOr<Schema>> + 'a,
-  {
-    Box::new(components.into_iter().flat_map(
-      move |component: &ReferenceOr<Schema>| match component {
-        ReferenceOr::Item(schema) => match &schema.schema_kind {
-          SchemaKind::Type(Type::Object(object)) => Box::new(std::iter::once(object)),
-          SchemaKind::AllOf { all_of } => {
-            self.flatten_composed_object_components(model_ident, all_of, components_schemas)
-          }
-          SchemaKind::Type(_)
-          | SchemaKind::OneOf { .. }
-          | SchemaKind::AnyOf { .. }
-          | SchemaKind::Not { .. }
-          | SchemaKind::Any(_) => {
-            panic!(
-              "unexpected `allOf` component type (must be object or nested `allOf`): {schema:#?}",
+  /// Determines the serde `rename_all` rule, if any, for the properties of an object model.
+  ///
+  /// The schema's [`RUST_RENAME_ALL_EXTENSION`] takes precedence, in which case any properties that
+  /// don't follow the rule are still renamed individually. Otherwise, a rule is inferred only if it
+  /// produces every property name from the corresponding field identifier and at least one
+  /// property would otherwise require an individual `rename`.
+  fn object_rename_rule(
+    &self,
+    schema_data: &SchemaData,
+    object: &ObjectType,
+  ) -> Option<RenameRule> {
+    if let Some(rename_all) = schema_data.extensions.get(RUST_RENAME_ALL_EXTENSION) {
+      return Some(
+        rename_all
+          .as_str()
+          .and_then(|rename_all| rename_all.parse().ok())
+          .unwrap_or_else(|| panic!("`{RUST_RENAME_ALL_EXTENSION}` should have been validated")),
+      );
+    }
+
+    let fields = object
+      .properties
+      .keys()
+      .map(|property_name| {
+        let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+        (unraw_ident(&property_ident), property_name)
+      })
+      .collect::<Vec<_>>();
+    if fields
+      .iter()
+      .all(|(field, property_name)| field == *property_name)
+    {
+      return None;
+    }
+
+    RenameRule::INFERRED.into_iter().find(|rename_rule| {
+      fields
+        .iter()
+        .all(|(field, property_name)| rename_rule.apply_to_field(field) == **property_name)
+    })
+  }
+
+  /// Determines how to implement `Default` for an object model.
+  ///
+  /// Optional properties default to `None` (i.e., omitted, which implies any schema `default`),
+  /// while required properties use their schema `default`, if any. Properties with a
+  /// [`RUST_DEFAULT_RESPONSE_EXTENSION`] instead default to the value of the specified expression.
+  fn object_default(
+    &self,
+    model_ident: &Ident,
+    object: &ObjectType,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> ObjectDefault {
+    let crate_import = self.crate_use_name();
+    let response_defaults = object
+      .properties
+      .iter()
+      .filter_map(|(property_name, ref_or_schema)| {
+        self
+          .response_default(ref_or_schema, components_schemas)
+          .map(|default| (property_name, default))
+      })
+      .collect::<Vec<_>>();
+    let required_defaults = object
+      .properties
+      .iter()
+      .filter(|(property_name, _)| object.required.contains(property_name))
+      .map(|(property_name, ref_or_schema)| {
+        if let Some((_, default)) = response_defaults
+          .iter()
+          .find(|(response_name, _)| *response_name == property_name)
+        {
+          return Some((property_name, default.clone()));
+        }
+
+        // The `time` crate doesn't deserialize `OffsetDateTime` from RFC 3339 by default, so we
+        // can't use `serde_json` to parse `date-time` defaults.
+        if self.datetime_backend == DateTimeBackend::Time
+          && self.string_format(ref_or_schema, components_schemas) == Some(StringFormat::DateTime)
+        {
+          return None;
+        }
+        self
+          .schema_default(ref_or_schema, components_schemas)
+          .map(|default| {
+            let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+            let default_json = default.to_string();
+            let expect_msg = format!("invalid default value for `{model_ident}.{property_ident}`");
+            (
+              property_name,
+              quote! {
+                #crate_import::models::serde_json::from_str(#default_json).expect(#expect_msg)
+              },
             )
+          })
+      })
+      .collect::<Option<Vec<_>>>();
+    let Some(required_defaults) = required_defaults else {
+      return ObjectDefault::None;
+    };
+    if required_defaults.is_empty() && response_defaults.is_empty() {
+      return ObjectDefault::Derive;
+    }
+
+    let fields = object
+      .properties
+      .iter()
+      .map(|(property_name, _)| {
+        let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+        if let Some((_, default)) = required_defaults
+          .iter()
+          .find(|(required_name, _)| *required_name == property_name)
+        {
+          quote! { #property_ident: #default, }
+        } else if let Some((_, default)) = response_defaults
+          .iter()
+          .find(|(response_name, _)| *response_name == property_name)
+        {
+          quote! { #property_ident: Some(#default), }
+        } else {
+          quote! { #property_ident: None, }
+        }
+      })
+      .chain(
+        has_additional_properties(object.additional_properties.as_ref())
+          .then(|| quote! { additional_properties: Default::default(), }),
+      )
+      .collect::<TokenStream>();
+
+    ObjectDefault::Impl(quote! {
+      impl Default for #model_ident {
+        fn default() -> Self {
+          Self {
+            #fields
           }
-        },
-        ReferenceOr::Reference { reference } => {
-          let target_schema_name = self.reference_schema_name(reference);
-          let Some(target) = components_schemas.get(target_schema_name) else {
-            panic!("invalid schema reference `{reference}` from model `{model_ident}`");
-          };
-          self.flatten_composed_object_components(model_ident, [target], components_schemas)
         }
-      },
-    ))
+      }
+    })
   }
 
-  fn generate_composed_object_struct_body(
+  /// Generates a `<Model>Builder` struct for an object model, along with a `<Model>::builder()`
+  /// constructor.
+  ///
+  /// Each property has a setter accepting the property's value (without the `Option` wrapper for
+  /// optional properties). The builder's `build()` method returns a `MissingField` error if any
+  /// required property hasn't been set.
+  fn generate_builder(
     &self,
     model_ident: &Ident,
-    components: &[ReferenceOr<Schema>],
-    is_enum_variant: bool,
-    tag_field_to_exclude: Option<&str>,
+    object: &ObjectType,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
     models_in_progress: &mut IndexSet<Ident>,
   ) -> TokenStream {
-    let (properties, additional_properties) = self
-      .flatten_composed_object_components(model_ident, components, components_schemas)
-      .fold(
-        (TokenStream::default(), None),
-        |(mut properties_acc, model_additional_properties), component| {
-          let ObjectType {
-            properties,
-            required,
-            additional_properties,
-            ..
-          } = component;
+    let crate_import = self.crate_use_name();
+    let builder_ident = Ident::new(&format!("{model_ident}Builder"), Span::call_site());
 
-          let fields = self.generate_object_struct_properties(
-            properties,
-            required,
-            is_enum_variant,
-            tag_field_to_exclude,
-            components_schemas,
+    let (builder_fields, setters, build_fields): (Vec<_>, Vec<_>, Vec<_>) = object
+      .properties
+      .iter()
+      .map(|(property_name, ref_or_schema)| {
+        let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+        let field_name = property_ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+        let (property_type_inner, _) = self.inline_ref_or_schema(
+          ref_or_schema,
+          components_schemas,
+          GeneratedModels::InProgress {
             models,
             models_in_progress,
-          );
+          },
+        );
+        // Setters accept the value of the property, which is `null`able if the schema is nullable.
+        let value_type = if self.is_nullable(ref_or_schema, components_schemas) {
+          quote! { Option<#property_type_inner> }
+        } else {
+          property_type_inner
+        };
+        let response_default = self.response_default(ref_or_schema, components_schemas);
+        let build_field = match (object.required.contains(property_name), response_default) {
+          // Server-generated properties are populated automatically if they haven't been set.
+          (true, Some(response_default)) => quote! {
+            #property_ident: if let Some(value) = self.#property_ident {
+              value
+            } else {
+              #response_default
+            },
+          },
+          (false, Some(response_default)) => quote! {
+            #property_ident: if self.#property_ident.is_some() {
+              self.#property_ident
+            } else {
+              Some(#response_default)
+            },
+          },
+          (true, None) => quote! {
+            #property_ident: self.#property_ident.ok_or(#crate_import::models::MissingField {
+              field_name: #field_name,
+            })?,
+          },
+          (false, None) => quote! { #property_ident: self.#property_ident, },
+        };
+
+        (
+          quote! { #property_ident: Option<#value_type>, },
+          quote! {
+            pub fn #property_ident(mut self, value: #value_type) -> Self {
+              self.#property_ident = Some(value);
+              self
+            }
+          },
+          build_field,
+        )
+      })
+      .multiunzip();
+
+    let additional_properties_type = self.generate_object_struct_additional_properties_type(
+      object.additional_properties.as_ref(),
+      components_schemas,
+      models,
+      models_in_progress,
+    );
+    let (additional_properties_field, additional_properties_setter, additional_properties_build) =
+      if let Some(additional_properties_type) = additional_properties_type {
+        (
+          quote! { additional_properties: #additional_properties_type, },
+          quote! {
+            pub fn additional_properties(mut self, value: #additional_properties_type) -> Self {
+              self.additional_properties = value;
+              self
+            }
+          },
+          quote! { additional_properties: self.additional_properties, },
+        )
+      } else {
+        (quote! {}, quote! {}, quote! {})
+      };
+
+    let builder_doc = format!("Builder for [`{model_ident}`].");
+    quote! {
+      impl #model_ident {
+        /// Returns a builder for constructing this model.
+        pub fn builder() -> #builder_ident {
+          #builder_ident::default()
+        }
+      }
+
+      #[doc = #builder_doc]
+      #[derive(Clone, Default)]
+      pub struct #builder_ident {
+        #(#builder_fields)*
+        #additional_properties_field
+      }
+
+      impl #builder_ident {
+        #(#setters)*
+        #additional_properties_setter
+
+        /// Builds the model, returning an error if any required field hasn't been set.
+        pub fn build(self) -> Result<#model_ident, #crate_import::models::MissingField> {
+          Ok(#model_ident {
+            #(#build_fields)*
+            #additional_properties_build
+          })
+        }
+      }
+    }
+  }
+
+  /// Generates a `Debug` impl for a struct model that redacts the values of any sensitive
+  /// properties (see [`CodeGenerator::is_sensitive`]).
+  ///
+  /// Any `flattened_fields` (e.g., `oneOf` components of an `allOf` schema) are formatted using
+  /// their own `Debug` impls.
+  ///
+  /// Returns `None` if none of the properties are sensitive, in which case `Debug` should be
+  /// derived as usual.
+  fn generate_redacted_debug_impl(
+    &self,
+    model_ident: &Ident,
+    properties: &IndexMap<String, ReferenceOr<Box<Schema>>>,
+    flattened_fields: &[Ident],
+    has_additional_properties: bool,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<TokenStream> {
+    if !properties
+      .values()
+      .any(|ref_or_schema| self.is_sensitive(ref_or_schema, components_schemas))
+    {
+      return None;
+    }
+
+    let fields = properties
+      .iter()
+      .map(|(property_name, ref_or_schema)| {
+        let property_ident = self.identifier(&property_name.to_case(Case::Snake));
+        let field_name = property_ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+        if self.is_sensitive(ref_or_schema, components_schemas) {
+          quote! { .field(#field_name, &"***") }
+        } else {
+          quote! { .field(#field_name, &self.#property_ident) }
+        }
+      })
+      .chain(flattened_fields.iter().map(|field_ident| {
+        let field_name = field_ident.to_string();
+        let field_name = field_name.trim_start_matches("r#");
+        quote! { .field(#field_name, &self.#field_ident) }
+      }))
+      .chain(
+        has_additional_properties
+          .then(|| quote! { .field("additional_properties", &self.additional_properties) }),
+      )
+      .collect::<TokenStream>();
+
+    let model_name = model_ident.to_string();
+    Some(quote! {
+      impl std::fmt::Debug for #model_ident {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          f.debug_struct(#model_name)
+            #fields
+            .finish()
+        }
+      }
+    })
+  }
+
+  fn flatten_composed_object_components<'a, I>(
+    &'a self,
+    model_ident: &'a Ident,
+    components: I,
+    components_schemas: &'a IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Box<dyn Iterator<Item = ComposedComponent<'a>> + 'a>
+  where
+    I: IntoIterator<Item = &'a ReferenceOr<Schema>> + 'a,
+  {
+    Box::new(components.into_iter().flat_map(
+      move |component: &ReferenceOr<Schema>| match component {
+        ReferenceOr::Item(schema) => match &schema.schema_kind {
+          SchemaKind::Type(Type::Object(object)) => {
+            Box::new(std::iter::once(ComposedComponent::Object(object)))
+          }
+          SchemaKind::AllOf { all_of } => {
+            self.flatten_composed_object_components(model_ident, all_of, components_schemas)
+          }
+          // Inline `oneOf` components are normally replaced with references to named schemas
+          // before we generate any models (see `name_model_schemas`).
+          SchemaKind::OneOf { .. } => panic!(
+            "unexpected inline `oneOf` component in `allOf` schema `{model_ident}`: must be a \
+             reference to a named schema: {schema:#?}",
+          ),
+          SchemaKind::Type(_)
+          | SchemaKind::AnyOf { .. }
+          | SchemaKind::Not { .. }
+          | SchemaKind::Any(_) => {
+            panic!(
+              "unexpected component type in `allOf` schema `{model_ident}` (must be object, \
+               `oneOf`, or nested `allOf`): {schema:#?}",
+            )
+          }
+        },
+        ReferenceOr::Reference { reference } => {
+          let target_schema_name = self.reference_schema_name(reference);
+          let Some(target) = components_schemas.get(target_schema_name.as_ref()) else {
+            panic!("invalid schema reference `{reference}` from model `{model_ident}`");
+          };
+          match target {
+            ReferenceOr::Item(Schema {
+              schema_kind: SchemaKind::OneOf { .. },
+              ..
+            }) => Box::new(std::iter::once(ComposedComponent::OneOf {
+              schema_name: target_schema_name,
+              reference: component,
+            })),
+            _ => self.flatten_composed_object_components(model_ident, [target], components_schemas),
+          }
+        }
+      },
+    ))
+  }
+
+  fn generate_composed_object_struct_body(
+    &self,
+    model_ident: &Ident,
+    components: &[ReferenceOr<Schema>],
+    is_enum_variant: bool,
+    tag_field_to_exclude: Option<&str>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    models: &mut HashMap<Ident, TokenStream>,
+    models_in_progress: &mut IndexSet<Ident>,
+  ) -> TokenStream {
+    let (properties, additional_properties) = self
+      .flatten_composed_object_components(model_ident, components, components_schemas)
+      .fold(
+        (TokenStream::default(), None),
+        |(mut properties_acc, model_additional_properties), component| {
+          let component = match component {
+            ComposedComponent::Object(object) => object,
+            ComposedComponent::OneOf {
+              schema_name,
+              reference,
+            } => {
+              let field_ident = self.identifier(&schema_name.to_case(Case::Snake));
+              let (field_type, _) = self.inline_ref_or_schema(
+                reference,
+                components_schemas,
+                GeneratedModels::InProgress {
+                  models,
+                  models_in_progress,
+                },
+              );
+              let r#pub = if is_enum_variant {
+                quote! {}
+              } else {
+                quote! { pub }
+              };
+
+              // The enum variants' properties are (de)serialized alongside the other fields.
+              properties_acc.extend(quote! {
+                #[serde(flatten)]
+                #r#pub #field_ident: #field_type,
+              });
+              return (properties_acc, model_additional_properties);
+            }
+          };
+          let ObjectType {
+            properties,
+            required,
+            additional_properties,
+            ..
+          } = component;
+
+          let fields = self.generate_object_struct_properties(
+            properties,
+            required,
+            is_enum_variant,
+            tag_field_to_exclude,
+            None,
+            components_schemas,
+            models,
+            models_in_progress,
+          );
 
           if model_additional_properties.is_some() && additional_properties.is_some() {
             panic!(
@@ -501,6 +1375,7 @@ impl CodeGenerator {
   fn generate_composed_object_model(
     &self,
     model_ident: &Ident,
+    schema_data: &SchemaData,
     components: &[ReferenceOr<Schema>],
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
@@ -516,16 +1391,64 @@ impl CodeGenerator {
       models_in_progress,
     );
     let serde_crate_attr = self.serde_crate_attr();
+    let (properties, flattened_fields, has_additional_properties) = self
+      .flatten_composed_object_components(model_ident, components, components_schemas)
+      .fold(
+        (IndexMap::new(), Vec::new(), false),
+        |(mut properties_acc, mut flattened_fields_acc, has_additional_properties_acc),
+         component| match component {
+          ComposedComponent::Object(object) => {
+            properties_acc.extend(object.properties.clone());
+            (
+              properties_acc,
+              flattened_fields_acc,
+              has_additional_properties_acc
+                || has_additional_properties(object.additional_properties.as_ref()),
+            )
+          }
+          ComposedComponent::OneOf { schema_name, .. } => {
+            flattened_fields_acc.push(self.identifier(&schema_name.to_case(Case::Snake)));
+            (
+              properties_acc,
+              flattened_fields_acc,
+              has_additional_properties_acc,
+            )
+          }
+        },
+      );
+    let debug_impl = self.generate_redacted_debug_impl(
+      model_ident,
+      &properties,
+      &flattened_fields,
+      has_additional_properties,
+      components_schemas,
+    );
+    let base_derives = ["Clone"]
+      .into_iter()
+      .chain(debug_impl.is_none().then_some("Debug"))
+      .chain(["Deserialize", "Serialize"])
+      .chain(
+        self
+          .schemas_comparison(components, components_schemas)
+          .derives()
+          .iter()
+          .copied(),
+      )
+      .collect::<Vec<_>>();
+    let derive_attr = self.derive_attr(&base_derives, schema_data);
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #derive_attr
       #[serde(#serde_crate_attr)]
       pub struct #model_ident #struct_body
+
+      #debug_impl
     }
   }
 
   fn generate_tagged_enum_model(
     &self,
     model_ident: &Ident,
+    schema_data: &SchemaData,
     variants: &[ReferenceOr<Schema>],
     discriminator: &Discriminator,
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
@@ -549,7 +1472,8 @@ impl CodeGenerator {
         };
 
         let target_schema_name = self.reference_schema_name(reference);
-        let Some(ReferenceOr::Item(target)) = components_schemas.get(target_schema_name) else {
+        let Some(ReferenceOr::Item(target)) = components_schemas.get(target_schema_name.as_ref())
+        else {
           panic!(
             "invalid schema reference `{reference}` from model `{model_ident}`: target schema does \
              not exist",
@@ -568,7 +1492,7 @@ impl CodeGenerator {
           .map(|(tag_value, variant_ref)| {
             let variant_name = self.reference_schema_name(variant_ref);
 
-            let Some(variant_schema) = variants_by_name.get(variant_name) else {
+            let Some(variant_schema) = variants_by_name.get(variant_name.as_ref()) else {
               panic!(
                 "`oneOf` type `{model_ident}` maps discriminator value `{tag_value}` to unknown \
                  type `{variant_name}`"
@@ -583,7 +1507,11 @@ impl CodeGenerator {
         variants_by_name
           .iter()
           .map(|(variant_name, variant_schema)| {
-            (variant_name, variant_name.as_str(), variant_schema)
+            (
+              variant_name,
+              Cow::Borrowed(variant_name.as_str()),
+              variant_schema,
+            )
           }),
       )
     }
@@ -614,8 +1542,19 @@ impl CodeGenerator {
     .collect::<TokenStream>();
 
     let serde_crate_attr = self.serde_crate_attr();
+    let base_derives = ["Clone", "Debug", "Deserialize", "Serialize"]
+      .into_iter()
+      .chain(
+        self
+          .schemas_comparison(variants, components_schemas)
+          .derives()
+          .iter()
+          .copied(),
+      )
+      .collect::<Vec<_>>();
+    let derive_attr = self.derive_attr(&base_derives, schema_data);
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #derive_attr
       #[serde(#serde_crate_attr, tag = #tag_field)]
       pub enum #model_ident {
         #variants_tok
@@ -647,6 +1586,7 @@ impl CodeGenerator {
             object,
             true,
             tag_field_to_exclude,
+            None,
             components_schemas,
             models,
             models_in_progress,
@@ -683,34 +1623,78 @@ impl CodeGenerator {
   fn generate_untagged_enum_model(
     &self,
     model_ident: &Ident,
+    schema_data: &SchemaData,
     variants: &[ReferenceOr<Schema>],
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     models: &mut HashMap<Ident, TokenStream>,
     models_in_progress: &mut IndexSet<Ident>,
   ) -> TokenStream {
+    // Referenced variants are named after the target schema, while inline primitive variants are
+    // named after their type (e.g., `String`). Duplicate names get a numeric suffix (e.g.,
+    // `String1` and `String2`).
+    let variant_names = variants
+      .iter()
+      .map(|variant| match variant {
+        ReferenceOr::Reference { reference } => {
+          self.reference_schema_name(reference).to_case(Case::Pascal)
+        }
+        ReferenceOr::Item(variant_schema) => {
+          primitive_variant_name(model_ident, variant_schema).to_owned()
+        }
+      })
+      .collect::<Vec<_>>();
+    let variant_name_counts = variant_names.iter().counts();
+    let mut variant_name_occurrences = HashMap::<&str, usize>::new();
+    let variant_idents = variant_names
+      .iter()
+      .map(|variant_name| {
+        if variant_name_counts[variant_name] > 1 {
+          let occurrence = variant_name_occurrences
+            .entry(variant_name)
+            .and_modify(|occurrence| *occurrence += 1)
+            .or_insert(1);
+          self.identifier(&format!("{variant_name}{occurrence}"))
+        } else {
+          self.identifier(variant_name)
+        }
+      })
+      .collect::<Vec<_>>();
+
     let variants_tok = variants
       .iter()
-      .map(|variant| {
+      .zip_eq(&variant_idents)
+      .map(|(variant, variant_ident)| {
         let ReferenceOr::Reference { reference } = variant else {
-          panic!(
-            "unexpected inline schema in `oneOf` schema `{model_ident}`: enum variants must be \
-             references to named schemas: {variant:#?}",
-          )
+          let (type_tok, description) = self.inline_ref_or_schema(
+            variant,
+            components_schemas,
+            GeneratedModels::InProgress {
+              models,
+              models_in_progress,
+            },
+          );
+          let type_tok = self.nullable_type(variant, components_schemas, type_tok);
+          let doc_attr = description
+            .map(|description| description_to_doc_attr(&description))
+            .unwrap_or_default();
+          return quote! {
+            #doc_attr
+            #variant_ident(#type_tok),
+          };
         };
 
         let variant_name = self.reference_schema_name(reference);
-        let Some(ReferenceOr::Item(variant_schema)) = components_schemas.get(variant_name) else {
+        let Some(ReferenceOr::Item(variant_schema)) = components_schemas.get(variant_name.as_ref())
+        else {
           panic!(
             "invalid schema reference `{reference}` from model `{model_ident}`: target schema does \
              not exist",
           );
         };
 
-        let variant_ident = self.identifier(&variant_name.to_case(Case::Pascal));
-
         self.generate_enum_variant(
           model_ident,
-          &variant_ident,
+          variant_ident,
           variant_schema,
           None,
           components_schemas,
@@ -721,8 +1705,19 @@ impl CodeGenerator {
       .collect::<TokenStream>();
 
     let serde_crate_attr = self.serde_crate_attr();
+    let base_derives = ["Clone", "Debug", "Deserialize", "Serialize"]
+      .into_iter()
+      .chain(
+        self
+          .schemas_comparison(variants, components_schemas)
+          .derives()
+          .iter()
+          .copied(),
+      )
+      .collect::<Vec<_>>();
+    let derive_attr = self.derive_attr(&base_derives, schema_data);
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #derive_attr
       #[serde(#serde_crate_attr, untagged)]
       pub enum #model_ident {
         #variants_tok
@@ -752,6 +1747,7 @@ impl CodeGenerator {
   fn generate_integer_model(
     &self,
     model_ident: &Ident,
+    schema_data: &SchemaData,
     integer: &IntegerType,
   ) -> Option<TokenStream> {
     let IntegerType { enumeration, .. } = integer;
@@ -765,8 +1761,80 @@ impl CodeGenerator {
       unimplemented!("nullable enum {model_ident}: {enumeration:#?}");
     }
 
-    // See https://serde.rs/enum-number.html.
-    unimplemented!("integer enum {model_ident}: {enumeration:#?}");
+    let [Some(value)] = enumeration.as_slice() else {
+      // See https://serde.rs/enum-number.html.
+      unimplemented!("integer enum {model_ident}: {enumeration:#?}");
+    };
+
+    // A single permitted value (e.g., from a `const` schema) is represented as a unit struct that
+    // only (de)serializes that value.
+    let integer_type = self.inline_integer(&IntegerType {
+      enumeration: Vec::new(),
+      ..integer.clone()
+    });
+    let value = proc_macro2::Literal::i64_unsuffixed(*value);
+    let crate_import = self.crate_use_name();
+    let derive_attr = self.derive_attr(
+      &[
+        "Clone",
+        "Copy",
+        "Debug",
+        "Default",
+        "PartialEq",
+        "Eq",
+        "Hash",
+      ],
+      schema_data,
+    );
+    Some(quote! {
+      #derive_attr
+      pub struct #model_ident;
+      impl #model_ident {
+        /// The only value permitted by this type.
+        pub const VALUE: #integer_type = #value;
+      }
+      impl Serialize for #model_ident {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+          S: #crate_import::__private::serde::Serializer,
+        {
+          Self::VALUE.serialize(serializer)
+        }
+      }
+      impl<'de> Deserialize<'de> for #model_ident {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+          D: #crate_import::__private::serde::Deserializer<'de>,
+        {
+          let value = #integer_type::deserialize(deserializer)?;
+          if value == Self::VALUE {
+            Ok(Self)
+          } else {
+            Err(#crate_import::__private::serde::de::Error::custom(format!(
+              "expected `{}`, but found `{}`",
+              Self::VALUE,
+              value
+            )))
+          }
+        }
+      }
+      impl std::fmt::Display for #model_ident {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", Self::VALUE)
+        }
+      }
+      impl std::str::FromStr for #model_ident {
+        type Err = anyhow::Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          if s.parse::<#integer_type>()? == Self::VALUE {
+            Ok(Self)
+          } else {
+            Err(anyhow!("expected `{}`, but found `{}`", Self::VALUE, s))
+          }
+        }
+      }
+    })
   }
 
   fn generate_number_model(&self, model_ident: &Ident, number: &NumberType) -> Option<TokenStream> {
@@ -785,7 +1853,87 @@ impl CodeGenerator {
     unimplemented!("number enum {model_ident}: {enumeration:#?}");
   }
 
-  fn generate_string_model(&self, model_ident: &Ident, string: &StringType) -> Option<TokenStream> {
+  /// Generates a model for an object schema that also specifies an `enum` of permitted object
+  /// values.
+  ///
+  /// Since the permitted values can be arbitrary JSON, the model wraps a `serde_json::Value` and
+  /// rejects any value not listed in the `enum` during deserialization.
+  fn generate_object_enum_model(
+    &self,
+    model_ident: &Ident,
+    schema_data: &SchemaData,
+    enumeration: &[serde_json::Value],
+  ) -> TokenStream {
+    let crate_import = self.crate_use_name();
+    let serde_crate_attr = self.serde_crate_attr();
+    let value_type = self.inline_any_type();
+    let value_type_str = value_type.to_string().replace(' ', "");
+
+    let allowed_values_json = enumeration.iter().map(|value| value.to_string());
+
+    let derive_attr = self.derive_attr(
+      &["Clone", "Debug", "Deserialize", "Serialize", "PartialEq"],
+      schema_data,
+    );
+    quote! {
+      #derive_attr
+      #[serde(#serde_crate_attr, try_from = #value_type_str, into = #value_type_str)]
+      pub struct #model_ident(#value_type);
+      impl #model_ident {
+        /// JSON encodings of the values permitted by the schema's `enum`.
+        pub const ALLOWED_VALUES_JSON: &'static [&'static str] = &[#(#allowed_values_json),*];
+
+        /// Returns the underlying JSON value.
+        pub fn as_value(&self) -> &#value_type {
+          &self.0
+        }
+
+        /// Returns the underlying JSON value.
+        pub fn into_value(self) -> #value_type {
+          self.0
+        }
+      }
+      impl std::convert::TryFrom<#value_type> for #model_ident {
+        type Error = anyhow::Error;
+
+        fn try_from(value: #value_type) -> Result<Self, Self::Error> {
+          static ALLOWED_VALUES: #crate_import::__private::once_cell::sync::Lazy<
+            Vec<#value_type>,
+          > = #crate_import::__private::once_cell::sync::Lazy::new(|| {
+            #model_ident::ALLOWED_VALUES_JSON
+              .iter()
+              .map(|allowed_value_json| {
+                #crate_import::models::serde_json::from_str(allowed_value_json)
+                  .expect("allowed value should be valid JSON")
+              })
+              .collect()
+          });
+          if ALLOWED_VALUES.contains(&value) {
+            Ok(Self(value))
+          } else {
+            Err(anyhow!("value is not one of the permitted `enum` values: {}", value))
+          }
+        }
+      }
+      impl From<#model_ident> for #value_type {
+        fn from(value: #model_ident) -> Self {
+          value.0
+        }
+      }
+      impl AsRef<#value_type> for #model_ident {
+        fn as_ref(&self) -> &#value_type {
+          &self.0
+        }
+      }
+    }
+  }
+
+  fn generate_string_model(
+    &self,
+    model_ident: &Ident,
+    schema_data: &SchemaData,
+    string: &StringType,
+  ) -> Option<TokenStream> {
     let StringType {
       enumeration,
       // TODO: Support patterned strings with regex validation during deserialization.
@@ -802,29 +1950,73 @@ impl CodeGenerator {
       unimplemented!("nullable enum {model_ident}: {enumeration:#?}");
     }
 
+    let variant_descriptions = match schema_data.extensions.get(ENUM_DESCRIPTIONS_EXTENSION) {
+      None => serde_json::Map::new(),
+      Some(serde_json::Value::Object(descriptions)) => descriptions.clone(),
+      Some(_) => panic!("`{ENUM_DESCRIPTIONS_EXTENSION}` should have been validated"),
+    };
+
+    let variant_names = match schema_data.extensions.get(ENUM_VARNAMES_EXTENSION) {
+      None => None,
+      Some(serde_json::Value::Array(names)) => Some(
+        names
+          .iter()
+          .map(|name| {
+            name
+              .as_str()
+              .unwrap_or_else(|| panic!("`{ENUM_VARNAMES_EXTENSION}` should have been validated"))
+          })
+          .collect::<Vec<_>>(),
+      ),
+      Some(_) => panic!("`{ENUM_VARNAMES_EXTENSION}` should have been validated"),
+    };
+
+    let mut used_variant_idents = HashSet::new();
     let (variants, parse_cases, as_str_cases) = enumeration
       .iter()
-      .map(|variant| {
+      .enumerate()
+      .map(|(idx, variant)| {
         let variant = variant.as_ref().expect("enum should not be nullable");
-        let variant_pascal = variant.to_case(Case::Pascal);
-        let variant_ident = self.identifier(&match variant.as_str().chars().next() {
-          // Hopefully users won't have both empty string and literal `empty_string` (or any other
-          // version that collides as PascalCase) as variants.
-          None => Cow::Borrowed("EmptyString"),
-          // If the variant doesn't start with a valid starting character for a Rust identifier,
-          // prefix it with `__`. Hopefully this won't collide with other variant names.
-          Some(c) if c != '_' && !unicode_ident::is_xid_start(c) => {
-            Cow::Owned(format!("__{variant_pascal}"))
+        let variant_ident = match &variant_names {
+          Some(names) => {
+            let variant_ident = self
+              .variant_name_ident(names[idx])
+              .unwrap_or_else(|| panic!("`{ENUM_VARNAMES_EXTENSION}` should have been validated"));
+            used_variant_idents.insert(variant_ident.to_string());
+            variant_ident
           }
-          _ => Cow::Borrowed(variant_pascal.as_str()),
-        });
+          None => {
+            let variant_ident = self.default_variant_ident(variant);
+            if used_variant_idents.insert(variant_ident.to_string()) {
+              variant_ident
+            } else {
+              // Distinct values may map to the same identifier (e.g., `foo-bar` and `foo_bar`), so
+              // append an incrementing number until we find an unused identifier.
+              let variant_name = variant_ident.to_string();
+              let variant_name = variant_name.trim_start_matches("r#");
+              (2..)
+                .map(|i| self.identifier(&format!("{variant_name}{i}")))
+                .find(|ident| used_variant_idents.insert(ident.to_string()))
+                .expect("should find an unused variant identifier")
+            }
+          }
+        };
+        let doc_attr = match variant_descriptions.get(variant) {
+          None => quote! {},
+          Some(serde_json::Value::String(description)) => description_to_doc_attr(description),
+          Some(_) => panic!("`{ENUM_DESCRIPTIONS_EXTENSION}` should have been validated"),
+        };
         let variant_tok = if variant_ident != variant {
           quote! {
+            #doc_attr
             #[serde(rename = #variant)]
             #variant_ident,
           }
         } else {
-          quote! { #variant_ident, }
+          quote! {
+            #doc_attr
+            #variant_ident,
+          }
         };
 
         (
@@ -835,8 +2027,21 @@ impl CodeGenerator {
       })
       .unzip_n::<TokenStream, TokenStream, TokenStream>();
 
+    let derive_attr = self.derive_attr(
+      &[
+        "Clone",
+        "Copy",
+        "Debug",
+        "Deserialize",
+        "Serialize",
+        "PartialEq",
+        "Eq",
+        "Hash",
+      ],
+      schema_data,
+    );
     Some(quote! {
-      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #derive_attr
       #[serde(#serde_crate_attr)]
       pub enum #model_ident {
         #variants
@@ -866,6 +2071,31 @@ impl CodeGenerator {
     })
   }
 
+  /// Returns the identifier for an `x-enum-varnames` entry, or `None` if the name isn't a valid
+  /// Rust identifier.
+  fn variant_name_ident(&self, name: &str) -> Option<Ident> {
+    let name_pascal = name.to_case(Case::Pascal);
+    match name_pascal.chars().next() {
+      Some(c) if c == '_' || unicode_ident::is_xid_start(c) => Some(self.identifier(&name_pascal)),
+      _ => None,
+    }
+  }
+
+  /// Returns the identifier for a string `enum` variant that has no `x-enum-varnames` entry.
+  fn default_variant_ident(&self, variant: &str) -> Ident {
+    let variant_pascal = variant.to_case(Case::Pascal);
+    self.identifier(&match variant.chars().next() {
+      // Collisions with other variants (e.g., a literal `empty_string`) are resolved by the caller.
+      None => Cow::Borrowed("EmptyString"),
+      // If the variant doesn't start with a valid starting character for a Rust identifier,
+      // prefix it with `__`.
+      Some(c) if c != '_' && !unicode_ident::is_xid_start(c) => {
+        Cow::Owned(format!("__{variant_pascal}"))
+      }
+      _ => Cow::Borrowed(variant_pascal.as_str()),
+    })
+  }
+
   pub(crate) fn identifier(&self, name: &str) -> Ident {
     if let Ok(ident) = syn::parse_str::<Ident>(name) {
       ident
@@ -880,13 +2110,13 @@ impl CodeGenerator {
     }
   }
 
-  fn reference_schema_name<'a>(&self, reference: &'a str) -> &'a str {
+  fn reference_schema_name<'a>(&self, reference: &'a str) -> Cow<'a, str> {
     const EXPECTED_PREFIX: &str = "#/components/schemas/";
     if !reference.starts_with(EXPECTED_PREFIX) {
       panic!("unexpected reference `{reference}` does not start with `{EXPECTED_PREFIX}`");
     }
 
-    &reference[EXPECTED_PREFIX.len()..]
+    unescape_pointer_token(&reference[EXPECTED_PREFIX.len()..])
   }
 
   pub(crate) fn inline_ref_or_schema<T>(
@@ -901,7 +2131,7 @@ impl CodeGenerator {
     match ref_or_schema {
       ReferenceOr::Reference { reference } => {
         let target_schema_name = self.reference_schema_name(reference);
-        let Some(target) = components_schemas.get(target_schema_name) else {
+        let Some(target) = components_schemas.get(target_schema_name.as_ref()) else {
           panic!("invalid schema reference `{reference}`: target schema does not exist");
         };
         let ReferenceOr::Item(target_schema) = target else {
@@ -926,7 +2156,12 @@ impl CodeGenerator {
         };
 
         let schema_tok = if reference_points_to_model {
-          quote! { crate::models::#model_ident }
+          if let Some(module) = schema_rust_module(target_schema) {
+            let module_ident = self.identifier(module);
+            quote! { crate::models::#module_ident::#model_ident }
+          } else {
+            quote! { crate::models::#model_ident }
+          }
         } else {
           self.inline_type(target_schema, components_schemas, generated_models)
         };
@@ -946,6 +2181,12 @@ impl CodeGenerator {
     components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
     generated_models: GeneratedModels,
   ) -> TokenStream {
+    if let Some(rust_type) = schema_rust_type(schema) {
+      return rust_type
+        .parse::<TokenStream>()
+        .unwrap_or_else(|err| panic!("invalid Rust type `{rust_type}`: {err}"));
+    }
+
     let crate_import = self.crate_use_name();
     match &schema.schema_kind {
       SchemaKind::Type(schema_type) => match schema_type {
@@ -969,14 +2210,22 @@ impl CodeGenerator {
                 #crate_import::models::EmptyModel
               }
             }
-            Some(AdditionalProperties::Any(true)) => {
-              let any = self.inline_any_type();
-              quote! { std::collections::HashMap<String, #any> }
-            }
+            Some(AdditionalProperties::Any(true)) => self
+              .map_type
+              .map_type(&crate_import, self.inline_any_type()),
             Some(AdditionalProperties::Schema(ref_or_schema)) => {
+              self.check_nested_date_time(
+                ref_or_schema,
+                components_schemas,
+                "additionalProperties",
+              );
               let (additional_property_tok, _) =
                 self.inline_ref_or_schema(ref_or_schema, components_schemas, generated_models);
-              quote! { std::collections::HashMap<String, #additional_property_tok> }
+              let additional_property_tok =
+                self.nullable_type(ref_or_schema, components_schemas, additional_property_tok);
+              self
+                .map_type
+                .map_type(&crate_import, additional_property_tok)
             }
           }
         }
@@ -1014,6 +2263,160 @@ impl CodeGenerator {
     }
   }
 
+  /// Whether the schema (or the target of the schema reference) permits `null` values.
+  fn is_nullable<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> bool
+  where
+    T: Borrow<Schema>,
+  {
+    match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(self.reference_schema_name(reference).as_ref())
+        .and_then(ReferenceOr::as_item)
+        .map_or(false, |schema| schema.schema_data.nullable),
+      ReferenceOr::Item(schema) => schema.borrow().schema_data.nullable,
+    }
+  }
+
+  /// Whether the schema (or the target of the schema reference) is marked `writeOnly` or has the
+  /// `password` string format, in which case its values are redacted from `Debug` output and logs.
+  pub(crate) fn is_sensitive<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> bool
+  where
+    T: Borrow<Schema>,
+  {
+    let is_sensitive_schema = |schema: &Schema| {
+      schema.schema_data.write_only
+        || matches!(
+          &schema.schema_kind,
+          SchemaKind::Type(Type::String(StringType {
+            format: VariantOrUnknownOrEmpty::Item(StringFormat::Password),
+            ..
+          }))
+        )
+    };
+    match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(self.reference_schema_name(reference).as_ref())
+        .and_then(ReferenceOr::as_item)
+        .map_or(false, is_sensitive_schema),
+      ReferenceOr::Item(schema) => is_sensitive_schema(schema.borrow()),
+    }
+  }
+
+  /// Returns the expression specified by the [`RUST_DEFAULT_RESPONSE_EXTENSION`] of a property
+  /// schema (or the target of the schema reference), if any.
+  fn response_default<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<TokenStream>
+  where
+    T: Borrow<Schema>,
+  {
+    let schema = match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(self.reference_schema_name(reference).as_ref())
+        .and_then(ReferenceOr::as_item)?,
+      ReferenceOr::Item(schema) => schema.borrow(),
+    };
+    let default = schema
+      .schema_data
+      .extensions
+      .get(RUST_DEFAULT_RESPONSE_EXTENSION)?;
+    let default = default
+      .as_str()
+      .and_then(|default| syn::parse_str::<syn::Expr>(default).ok())
+      .unwrap_or_else(|| panic!("`{RUST_DEFAULT_RESPONSE_EXTENSION}` should have been validated"));
+    Some(quote! { #default })
+  }
+
+  /// Returns the format of the schema (or the target of the schema reference) if it's a string
+  /// schema with one of the formats defined by the OpenAPI specification.
+  /// Returns the schema's `default` value (or that of the target of the schema reference).
+  fn schema_default<'a, T>(
+    &self,
+    ref_or_schema: &'a ReferenceOr<T>,
+    components_schemas: &'a IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<&'a serde_json::Value>
+  where
+    T: Borrow<Schema>,
+  {
+    match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(self.reference_schema_name(reference).as_ref())
+        .and_then(ReferenceOr::as_item)
+        .and_then(|schema| schema.schema_data.default.as_ref()),
+      ReferenceOr::Item(schema) => schema.borrow().schema_data.default.as_ref(),
+    }
+  }
+
+  pub(crate) fn string_format<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<StringFormat>
+  where
+    T: Borrow<Schema>,
+  {
+    let schema_string_format = |schema: &Schema| match &schema.schema_kind {
+      SchemaKind::Type(Type::String(StringType {
+        format: VariantOrUnknownOrEmpty::Item(string_format),
+        ..
+      })) => Some(*string_format),
+      _ => None,
+    };
+    match ref_or_schema {
+      ReferenceOr::Reference { reference } => components_schemas
+        .get(self.reference_schema_name(reference).as_ref())
+        .and_then(ReferenceOr::as_item)
+        .and_then(schema_string_format),
+      ReferenceOr::Item(schema) => schema_string_format(schema.borrow()),
+    }
+  }
+
+  /// Panics if the schema is a `date-time` string nested within a container (e.g., an array) and
+  /// [`DateTimeBackend::Time`] is selected, since `time::OffsetDateTime` is only (de)serialized as
+  /// RFC 3339 when annotated with `#[serde(with = ...)]`. These schemas are reported as unsupported
+  /// by `find_unsupported_features` before any code is generated.
+  fn check_nested_date_time<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    container: &str,
+  ) where
+    T: Borrow<Schema>,
+  {
+    if self.datetime_backend == DateTimeBackend::Time
+      && self.string_format(ref_or_schema, components_schemas) == Some(StringFormat::DateTime)
+    {
+      unimplemented!("`date-time` {container} with `DateTimeBackend::Time`");
+    }
+  }
+
+  /// Wraps the inline type of a schema in an `Option` if the schema permits `null` values.
+  pub(crate) fn nullable_type<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    type_tok: TokenStream,
+  ) -> TokenStream
+  where
+    T: Borrow<Schema>,
+  {
+    if self.is_nullable(ref_or_schema, components_schemas) {
+      quote! { Option<#type_tok> }
+    } else {
+      type_tok
+    }
+  }
+
   fn inline_array(
     &self,
     array: &ArrayType,
@@ -1023,22 +2426,175 @@ impl CodeGenerator {
     let crate_import = self.crate_use_name();
     let ArrayType {
       items,
+      min_items,
+      max_items,
       unique_items,
-      ..
     } = array;
 
     let item_type = if let Some(items) = items {
-      self
-        .inline_ref_or_schema(items, components_schemas, generated_models)
-        .0
+      self.check_nested_date_time(items, components_schemas, "array items");
+      let (item_type, _) = self.inline_ref_or_schema(items, components_schemas, generated_models);
+      self.nullable_type(items, components_schemas, item_type)
     } else {
       self.inline_any_type()
     };
 
-    if *unique_items {
-      quote! { #crate_import::models::IndexSet<#item_type> }
+    let collection_type =
+      match self.unique_items_set_type(*unique_items, items.as_ref(), components_schemas) {
+        Some(set_type) => set_type.set_type(&crate_import, item_type),
+        None => quote! { Vec<#item_type> },
+      };
+
+    // Arrays without bounds use the plain collection type.
+    if min_items.unwrap_or(0) == 0 && max_items.is_none() {
+      return collection_type;
+    }
+    let min_items = proc_macro2::Literal::usize_unsuffixed(min_items.unwrap_or(0));
+    let max_items = max_items.map_or_else(
+      || quote! { { usize::MAX } },
+      |max_items| {
+        let max_items = proc_macro2::Literal::usize_unsuffixed(max_items);
+        quote! { #max_items }
+      },
+    );
+    quote! { #crate_import::models::Bounded<#collection_type, #min_items, #max_items> }
+  }
+
+  /// Returns the set type representing an array with the given `uniqueItems` and `items`, or `None`
+  /// if the array is represented as a `Vec`.
+  ///
+  /// If the item type doesn't implement the traits required by the configured
+  /// [`SetType`], this falls back to [`SetType::UniqueVec`], or to a `Vec` (without a uniqueness
+  /// check) if the item type doesn't even implement `PartialEq`.
+  fn unique_items_set_type<T>(
+    &self,
+    unique_items: bool,
+    items: Option<&ReferenceOr<T>>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Option<SetType>
+  where
+    T: Borrow<Schema>,
+  {
+    if !unique_items {
+      return None;
+    }
+
+    let Some(item_trait) = self.set_type.item_trait() else {
+      return Some(self.set_type);
+    };
+    // Arrays without `items` contain arbitrary JSON values, which implement neither trait.
+    let items_implement_trait = items.map_or(false, |items| {
+      self.schema_implements(items, components_schemas, item_trait)
+    });
+    let items_comparison = items.map_or(Comparison::Eq, |items| {
+      self.schema_comparison(items, components_schemas)
+    });
+    if items_implement_trait {
+      Some(self.set_type)
+    } else if items_comparison >= Comparison::PartialEq {
+      Some(SetType::UniqueVec)
     } else {
-      quote! { Vec<#item_type> }
+      None
+    }
+  }
+
+  /// Returns whether the Rust type representing a schema is known to implement `item_trait` (used
+  /// to select the set type for arrays with `uniqueItems: true`).
+  ///
+  /// Existing Rust types (i.e., those specified via a custom `format` or mapped via
+  /// [`CodeGenerator::map_schema_ref`]) are assumed to implement the trait.
+  fn schema_implements<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+    item_trait: SetItemTrait,
+  ) -> bool
+  where
+    T: Borrow<Schema>,
+  {
+    let schema = match ref_or_schema {
+      ReferenceOr::Reference { reference } => {
+        match components_schemas.get(self.reference_schema_name(reference).as_ref()) {
+          Some(ReferenceOr::Item(schema)) => schema,
+          _ => return true,
+        }
+      }
+      ReferenceOr::Item(schema) => schema.borrow(),
+    };
+    if schema_rust_type(schema).is_some() {
+      return true;
+    }
+
+    let trait_name = match item_trait {
+      SetItemTrait::Hash => "Hash",
+      SetItemTrait::Ord => "Ord",
+    };
+    // Generated models implement any trait derived via `CodeGenerator::with_model_derives` or the
+    // schema's `x-rust-derives` extension.
+    let derives_trait = matches!(ref_or_schema, ReferenceOr::Reference { .. })
+      && self
+        .model_derives
+        .iter()
+        .map(String::as_str)
+        .chain(
+          schema
+            .schema_data
+            .extensions
+            .get(RUST_DERIVES_EXTENSION)
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_json::Value::as_str),
+        )
+        .any(|derive| derive.rsplit("::").next() == Some(trait_name));
+    if derives_trait {
+      return true;
+    }
+
+    match &schema.schema_kind {
+      // String and integer enums derive `Hash` but not `Ord`.
+      SchemaKind::Type(Type::String(StringType { enumeration, .. })) if !enumeration.is_empty() => {
+        item_trait == SetItemTrait::Hash
+      }
+      SchemaKind::Type(Type::Integer(IntegerType { enumeration, .. }))
+        if !enumeration.is_empty() =>
+      {
+        item_trait == SetItemTrait::Hash
+      }
+      SchemaKind::Type(Type::String(_) | Type::Integer(_) | Type::Boolean(_)) => true,
+      SchemaKind::Type(Type::Number(NumberType {
+        format: VariantOrUnknownOrEmpty::Unknown(_),
+        ..
+      })) => true,
+      SchemaKind::Type(Type::Number(_)) => false,
+      SchemaKind::Type(Type::Array(ArrayType {
+        items,
+        unique_items,
+        ..
+      })) => {
+        // `IndexSet` implements neither trait, while `BTreeSet` and `UniqueVec` implement both.
+        items.as_ref().map_or(false, |items| {
+          self.schema_implements(items, components_schemas, item_trait)
+        }) && self.unique_items_set_type(*unique_items, items.as_ref(), components_schemas)
+          != Some(SetType::IndexSet)
+      }
+      // Only `BTreeMap` implements `Hash` and `Ord`.
+      SchemaKind::Type(Type::Object(ObjectType {
+        properties,
+        additional_properties: Some(AdditionalProperties::Schema(schema)),
+        ..
+      }))
+        if properties.is_empty() =>
+      {
+        self.map_type == MapType::BTreeMap
+          && self.schema_implements(schema, components_schemas, item_trait)
+      }
+      SchemaKind::Type(Type::Object(_))
+      | SchemaKind::OneOf { .. }
+      | SchemaKind::AllOf { .. }
+      | SchemaKind::AnyOf { .. }
+      | SchemaKind::Not { .. }
+      | SchemaKind::Any(_) => false,
     }
   }
 
@@ -1058,9 +2614,7 @@ impl CodeGenerator {
         IntegerFormat::Int32 => quote! { i32 },
         IntegerFormat::Int64 => quote! { i64 },
       },
-      VariantOrUnknownOrEmpty::Unknown(integer_format) => integer_format
-        .parse::<TokenStream>()
-        .unwrap_or_else(|err| panic!("invalid integer type {integer_format:#?}: {err}")),
+      VariantOrUnknownOrEmpty::Unknown(integer_format) => self.custom_format_type(integer_format),
       VariantOrUnknownOrEmpty::Empty => quote! { i64 },
     }
   }
@@ -1081,13 +2635,24 @@ impl CodeGenerator {
         NumberFormat::Float => quote! { f32 },
         NumberFormat::Double => quote! { f64 },
       },
-      VariantOrUnknownOrEmpty::Unknown(number_format) => number_format
-        .parse::<TokenStream>()
-        .unwrap_or_else(|err| panic!("invalid number type {number_format:#?}: {err}")),
+      VariantOrUnknownOrEmpty::Unknown(number_format) => self.custom_format_type(number_format),
       VariantOrUnknownOrEmpty::Empty => quote! { f64 },
     }
   }
 
+  /// Rust type representing a custom (non-standard) schema `format`: either the type mapped via
+  /// [`CodeGenerator::map_format_to_type`] or the format itself, interpreted as a Rust type.
+  fn custom_format_type(&self, format: &str) -> TokenStream {
+    let rust_type = self
+      .format_types
+      .get(format)
+      .map(String::as_str)
+      .unwrap_or(format);
+    rust_type
+      .parse::<TokenStream>()
+      .unwrap_or_else(|err| panic!("unsupported format {format:#?}: {err}"))
+  }
+
   fn inline_string(&self, string: &StringType) -> TokenStream {
     let StringType {
       format,
@@ -1101,22 +2666,145 @@ impl CodeGenerator {
 
     match format {
       VariantOrUnknownOrEmpty::Item(string_format) => match string_format {
-        StringFormat::Date => quote! { chrono::NaiveDate },
-        StringFormat::DateTime => quote! { chrono::DateTime<chrono::Utc> },
+        StringFormat::Date => self.datetime_backend.date_type(),
+        StringFormat::DateTime => self.datetime_backend.date_time_type(),
         // `byte` represents a base64-encoded file. We just pass it as a string and let the user
         // base64-decode it for now.
         StringFormat::Byte | StringFormat::Password => quote! { String },
         StringFormat::Binary => quote! { Vec<u8> },
       },
-      VariantOrUnknownOrEmpty::Unknown(string_format) => string_format
-        .parse::<TokenStream>()
-        .unwrap_or_else(|err| panic!("unsupported string type {string_format:#?}: {err}")),
+      VariantOrUnknownOrEmpty::Unknown(string_format) => self.custom_format_type(string_format),
       VariantOrUnknownOrEmpty::Empty => quote! { String },
     }
   }
 
+  /// Returns the comparison traits implemented by the Rust type representing a schema.
+  ///
+  /// Floating-point numbers (and any types containing them) only implement `PartialEq`, while
+  /// existing Rust types (i.e., those specified via a custom `format` or mapped via
+  /// [`CodeGenerator::map_schema_ref`]) may not implement either trait.
+  fn schema_comparison<T>(
+    &self,
+    ref_or_schema: &ReferenceOr<T>,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Comparison
+  where
+    T: Borrow<Schema>,
+  {
+    let schema = match ref_or_schema {
+      ReferenceOr::Reference { reference } => {
+        match components_schemas.get(self.reference_schema_name(reference).as_ref()) {
+          Some(ReferenceOr::Item(schema)) => schema,
+          _ => return Comparison::None,
+        }
+      }
+      ReferenceOr::Item(schema) => schema.borrow(),
+    };
+    if schema_rust_type(schema).is_some() {
+      return Comparison::None;
+    }
+
+    match &schema.schema_kind {
+      SchemaKind::Type(Type::Object(object)) => self.object_comparison(object, components_schemas),
+      SchemaKind::Type(Type::Array(ArrayType { items, .. })) => items
+        .as_ref()
+        .map(|items| self.schema_comparison(items, components_schemas))
+        .unwrap_or(Comparison::Eq),
+      SchemaKind::Type(Type::String(StringType {
+        format: VariantOrUnknownOrEmpty::Unknown(_),
+        ..
+      }))
+      | SchemaKind::Type(Type::Integer(IntegerType {
+        format: VariantOrUnknownOrEmpty::Unknown(_),
+        ..
+      }))
+      | SchemaKind::Type(Type::Number(NumberType {
+        format: VariantOrUnknownOrEmpty::Unknown(_),
+        ..
+      })) => Comparison::None,
+      SchemaKind::Type(Type::String(_) | Type::Integer(_) | Type::Boolean(_)) => Comparison::Eq,
+      SchemaKind::Type(Type::Number(_)) => Comparison::PartialEq,
+      SchemaKind::OneOf { one_of: schemas }
+      | SchemaKind::AllOf { all_of: schemas }
+      | SchemaKind::AnyOf { any_of: schemas } => {
+        self.schemas_comparison(schemas, components_schemas)
+      }
+      SchemaKind::Not { .. } => Comparison::None,
+      // Object enums wrap a `serde_json::Value` but only derive `PartialEq`.
+      SchemaKind::Any(any) if is_object_enum_schema(any) => Comparison::PartialEq,
+      SchemaKind::Any(_) => Comparison::Eq,
+    }
+  }
+
+  /// Returns the comparison traits implemented by a type composed of the Rust types representing
+  /// each of the `schemas`.
+  fn schemas_comparison<T>(
+    &self,
+    schemas: &[ReferenceOr<T>],
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Comparison
+  where
+    T: Borrow<Schema>,
+  {
+    schemas
+      .iter()
+      .map(|schema| self.schema_comparison(schema, components_schemas))
+      .min()
+      .unwrap_or(Comparison::Eq)
+  }
+
+  /// Returns the comparison traits implemented by the Rust type representing an object schema.
+  fn object_comparison(
+    &self,
+    object: &ObjectType,
+    components_schemas: &IndexMap<String, ReferenceOr<Schema>>,
+  ) -> Comparison {
+    let additional_properties_comparison = match &object.additional_properties {
+      Some(AdditionalProperties::Schema(schema)) => {
+        self.schema_comparison(schema, components_schemas)
+      }
+      Some(AdditionalProperties::Any(_)) | None => Comparison::Eq,
+    };
+    object
+      .properties
+      .values()
+      .map(|property| self.schema_comparison(property, components_schemas))
+      .chain([additional_properties_comparison])
+      .min()
+      .unwrap_or(Comparison::Eq)
+  }
+
+  /// Returns the `#[derive(...)]` attribute for a model.
+  ///
+  /// In addition to the `base_derives` required by the generated code, this includes any derives
+  /// specified via [`CodeGenerator::with_model_derives`] and any listed in the schema's
+  /// `x-rust-derives` extension. Duplicate derives are omitted.
+  fn derive_attr(&self, base_derives: &[&str], schema_data: &SchemaData) -> TokenStream {
+    // Invalid `x-rust-derives` extensions were already reported by `schema_errors`.
+    let schema_derives = schema_data
+      .extensions
+      .get(RUST_DERIVES_EXTENSION)
+      .and_then(serde_json::Value::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(serde_json::Value::as_str);
+
+    let derives = base_derives
+      .iter()
+      .copied()
+      .chain(self.model_derives.iter().map(String::as_str))
+      .chain(schema_derives)
+      .map(|derive| {
+        syn::parse_str::<syn::Path>(derive).expect("derives should have been validated")
+      })
+      .unique_by(|derive| quote! { #derive }.to_string())
+      .collect::<Vec<_>>();
+
+    quote! { #[derive(#(#derives),*)] }
+  }
+
   fn serde_crate_attr(&self) -> TokenStream {
-    let serde_import = format!("{}::__private::serde", self.crate_use_name());
+    let serde_import = format!("{}::__private::serde", self.crate_name);
     quote! { crate = #serde_import }
   }
 }
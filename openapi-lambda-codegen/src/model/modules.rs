@@ -0,0 +1,138 @@
+use crate::inline::InlineApi;
+use crate::model::RUST_MODULE_EXTENSION;
+use crate::reference::unescape_pointer_token;
+
+use convert_case::{Case, Casing};
+use itertools::Itertools;
+use openapiv3::ReferenceOr;
+use serde_yaml::Value;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Submodule containing models that aren't referenced exclusively by operations with a single tag.
+const COMMON_MODULE: &str = "common";
+
+const SCHEMA_REFERENCE_PREFIX: &str = "#/components/schemas/";
+
+const METHODS: [&str; 8] = [
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Annotates every schema in `components.schemas` with the `models` submodule that should contain
+/// its generated model (see [`ModelModules::ByTag`](crate::ModelModules::ByTag)).
+///
+/// Webhooks are only visited if `visit_callbacks` is true, since we otherwise don't generate any
+/// code for them.
+pub(in crate::model) fn assign_model_modules(openapi: &mut InlineApi, visit_callbacks: bool) {
+  let root = serde_yaml::to_value(&**openapi).expect("failed to serialize OpenAPI spec");
+  let webhooks = if visit_callbacks {
+    serde_yaml::to_value(&openapi.webhooks).expect("failed to serialize webhooks")
+  } else {
+    Value::Null
+  };
+
+  // Tags of the operations that reference each schema (directly or transitively), where `None`
+  // represents an untagged operation.
+  let mut schema_tags = HashMap::<String, BTreeSet<Option<String>>>::new();
+
+  let path_items = root
+    .get("paths")
+    .and_then(Value::as_mapping)
+    .into_iter()
+    .chain(webhooks.as_mapping())
+    .flat_map(|path_items| path_items.values())
+    .map(
+      |path_item| match path_item.get("$ref").and_then(Value::as_str) {
+        Some(reference) => resolve_local_reference(&root, reference).unwrap_or(&Value::Null),
+        None => path_item,
+      },
+    );
+  for path_item in path_items {
+    for op in METHODS.iter().filter_map(|method| path_item.get(method)) {
+      let tags = op
+        .get("tags")
+        .and_then(Value::as_sequence)
+        .map(|tags| {
+          tags
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|tag| Some(tag.to_owned()))
+            .collect::<Vec<_>>()
+        })
+        .filter(|tags| !tags.is_empty())
+        .unwrap_or_else(|| vec![None]);
+
+      let mut references = HashSet::new();
+      collect_references(&root, op, &mut references);
+      if let Some(parameters) = path_item.get("parameters") {
+        collect_references(&root, parameters, &mut references);
+      }
+
+      references
+        .iter()
+        .filter_map(|reference| reference.strip_prefix(SCHEMA_REFERENCE_PREFIX))
+        .for_each(|schema_name| {
+          schema_tags
+            .entry(unescape_pointer_token(schema_name).into_owned())
+            .or_default()
+            .extend(tags.iter().cloned())
+        });
+    }
+  }
+
+  let Some(components) = &mut openapi.components else {
+    return;
+  };
+  for (schema_name, schema) in &mut components.schemas {
+    let ReferenceOr::Item(schema) = schema else {
+      continue;
+    };
+
+    let module = match schema_tags
+      .get(schema_name)
+      .map(|tags| tags.iter().exactly_one())
+    {
+      Some(Ok(Some(tag))) => tag.to_case(Case::Snake),
+      _ => COMMON_MODULE.to_string(),
+    };
+    schema
+      .schema_data
+      .extensions
+      .insert(RUST_MODULE_EXTENSION.to_string(), module.into());
+  }
+}
+
+/// Recursively collect every local reference reachable from `value`, following each reference to
+/// its target.
+fn collect_references(root: &Value, value: &Value, references: &mut HashSet<String>) {
+  match value {
+    Value::Mapping(mapping) => {
+      for (key, child) in mapping {
+        match (key.as_str(), child) {
+          (Some("$ref"), Value::String(reference)) => {
+            if references.insert(reference.to_owned()) {
+              if let Some(target) = resolve_local_reference(root, reference) {
+                collect_references(root, target, references);
+              }
+            }
+          }
+          _ => collect_references(root, child, references),
+        }
+      }
+    }
+    Value::Sequence(sequence) => sequence
+      .iter()
+      .for_each(|child| collect_references(root, child, references)),
+    Value::Tagged(tagged) => collect_references(root, &tagged.value, references),
+    Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_) => {}
+  }
+}
+
+fn resolve_local_reference<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+  reference
+    .strip_prefix("#/")?
+    .split('/')
+    .try_fold(root, |value, token| {
+      value.get(unescape_pointer_token(token).as_ref())
+    })
+}
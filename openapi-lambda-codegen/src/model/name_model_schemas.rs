@@ -1,4 +1,5 @@
 use crate::inline::InlineApi;
+use crate::model::{is_object_enum_schema, schema_rust_type};
 
 use convert_case::{Case, Casing};
 use indexmap::IndexMap;
@@ -12,7 +13,14 @@ use openapiv3::{
 
 use std::borrow::BorrowMut;
 
-pub(in crate::model) fn visit_openapi(openapi: &mut InlineApi) {
+/// Moves unnamed schemas that require named models into `components.schemas`.
+///
+/// Callbacks (and webhooks) are only visited if `visit_callbacks` is true, since we otherwise don't
+/// generate any code for them or their models.
+pub(in crate::model) fn visit_openapi(openapi: &mut InlineApi, visit_callbacks: bool) {
+  let InlineApi { webhooks, .. } = openapi;
+  let mut webhooks = std::mem::take(webhooks);
+
   let OpenAPI {
     components: components_opt,
     paths,
@@ -20,7 +28,7 @@ pub(in crate::model) fn visit_openapi(openapi: &mut InlineApi) {
   } = &mut **openapi;
 
   let components = if let Some(components) = components_opt {
-    visit_components(components);
+    visit_components(components, visit_callbacks);
     components
   } else {
     components_opt.insert(Components::default())
@@ -32,11 +40,18 @@ pub(in crate::model) fn visit_openapi(openapi: &mut InlineApi) {
     let ReferenceOr::Item(path_item) = path_item else {
       continue;
     };
-    visit_path_item(path_item, &mut components.schemas)
+    visit_path_item(path_item, &mut components.schemas, visit_callbacks)
+  }
+
+  if visit_callbacks {
+    for (_name, path_item) in &mut webhooks {
+      visit_path_item(path_item, &mut components.schemas, visit_callbacks);
+    }
   }
+  openapi.webhooks = webhooks;
 }
 
-fn visit_components(components: &mut Components) {
+fn visit_components(components: &mut Components, visit_callbacks: bool) {
   for (response_name, response) in &mut components.responses {
     let ReferenceOr::Item(response) = response else {
       continue;
@@ -103,8 +118,17 @@ fn visit_components(components: &mut Components) {
     }
   }
 
-  // We don't bother visiting callbacks here because we don't generate any code for them or their
-  // models
+  if visit_callbacks {
+    for (_, callback) in &mut components.callbacks {
+      // As with path items, we reach any reference targets directly.
+      let ReferenceOr::Item(callback) = callback else {
+        continue;
+      };
+      for (_expression, path_item) in callback {
+        visit_path_item(path_item, &mut components.schemas, visit_callbacks);
+      }
+    }
+  }
 
   // We just leave `components.extensions` alone for now.
 }
@@ -139,7 +163,16 @@ fn visit_media_type(
 fn visit_operation(
   operation: &mut Operation,
   components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+  visit_callbacks: bool,
 ) {
+  if visit_callbacks {
+    for (_, callback) in &mut operation.callbacks {
+      for (_expression, path_item) in callback {
+        visit_path_item(path_item, components_schemas, visit_callbacks);
+      }
+    }
+  }
+
   // We require an operation ID for any operation handled by an API Lambda, so just ignore any
   // operations without one. We'll error out later if the user mapped it to an API Lambda.
   let Some(operation_id) = &operation.operation_id else {
@@ -221,6 +254,7 @@ fn visit_parameter_schema_or_content(
 fn visit_path_item(
   path_item: &mut PathItem,
   components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
+  visit_callbacks: bool,
 ) {
   path_item
     .get
@@ -232,7 +266,7 @@ fn visit_path_item(
     .chain(path_item.head.iter_mut())
     .chain(path_item.patch.iter_mut())
     .chain(path_item.trace.iter_mut())
-    .for_each(|operation| visit_operation(operation, components_schemas));
+    .for_each(|operation| visit_operation(operation, components_schemas, visit_callbacks));
 
   for parameter in &mut path_item.parameters {
     let ReferenceOr::Item(parameter) = parameter else {
@@ -333,6 +367,11 @@ fn visit_schema(
   schema_naming_context: &str,
   components_schemas: &mut IndexMap<String, ReferenceOr<Schema>>,
 ) -> bool {
+  // Schemas mapped to existing Rust types don't need any models.
+  if schema_rust_type(schema).is_some() {
+    return false;
+  }
+
   match &mut schema.schema_kind {
     SchemaKind::Type(schema_type) => match schema_type {
       Type::Object(ObjectType {
@@ -419,13 +458,26 @@ fn visit_schema(
       true
     }
     SchemaKind::AllOf { all_of } => {
-      for inner in all_of {
-        let ReferenceOr::Item(inner) = inner else {
-          continue;
-        };
-        // Don't inline allOf components because we'll generate a model that combines all of the
-        // constituent fields.
-        visit_schema(inner, schema_naming_context, components_schemas);
+      for inner_schema_or_ref in all_of {
+        match inner_schema_or_ref {
+          // `oneOf` components become separate enum models that are flattened into the combined
+          // model.
+          ReferenceOr::Item(Schema {
+            schema_kind: SchemaKind::OneOf { .. },
+            ..
+          }) => visit_unnamed_schema(
+            inner_schema_or_ref,
+            &format!("{schema_naming_context}OneOf"),
+            components_schemas,
+            std::convert::identity,
+          ),
+          // Don't inline other allOf components because we'll generate a model that combines all
+          // of the constituent fields.
+          ReferenceOr::Item(inner) => {
+            visit_schema(inner, schema_naming_context, components_schemas);
+          }
+          ReferenceOr::Reference { .. } => {}
+        }
       }
 
       // Always generate Rust structs or enums for compound schemas.
@@ -434,6 +486,8 @@ fn visit_schema(
     SchemaKind::Not { .. } => {
       unimplemented!("`not` schema {schema:#?}");
     }
+    // We generate a model that validates the permitted values.
+    SchemaKind::Any(any) if is_object_enum_schema(any) => true,
     SchemaKind::Any(any) => {
       if *any != AnySchema::default() {
         unimplemented!("`any` schema in context {schema_naming_context}: {any:#?}");
@@ -454,6 +508,23 @@ fn visit_unnamed_schema<F, T>(
   T: BorrowMut<Schema>,
 {
   if let ReferenceOr::Item(unnamed_schema) = ref_or_schema {
+    // Prefer the schema's `title` (if any) over the name synthesized from its context.
+    let schema_naming_context = unnamed_schema
+      .borrow()
+      .schema_data
+      .title
+      .as_deref()
+      .map(|title| {
+        title
+          .to_case(Case::Pascal)
+          .chars()
+          .filter(char::is_ascii_alphanumeric)
+          .collect::<String>()
+      })
+      .filter(|title| title.starts_with(|c: char| c.is_ascii_alphabetic()))
+      .unwrap_or_else(|| schema_naming_context.to_string());
+    let schema_naming_context = schema_naming_context.as_str();
+
     if visit_schema(
       unnamed_schema.borrow_mut(),
       schema_naming_context,
@@ -490,8 +561,6 @@ fn visit_unnamed_schema<F, T>(
 /// `media-type` (content type) or
 /// [`media-range`](https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.2).
 fn media_type_or_range_name_pascal_case(media_type_or_range: &str) -> &'static str {
-  // We don't currently support media ranges, but the as-yet-unreleased 0.4 version of `mime` should
-  // add support for parsing those.
   let mime_type = match media_type_or_range.parse::<Mime>() {
     Ok(mime) => mime,
     Err(err) => {
@@ -513,6 +582,13 @@ fn media_type_or_range_name_pascal_case(media_type_or_range: &str) -> &'static s
     "text/csv" => "Csv",
     "text/html" => "Html",
     "text/plain" => "PlainText",
+    // Media ranges.
+    "*/*" => "Any",
+    "application/*" => "Application",
+    "audio/*" => "Audio",
+    "image/*" => "Image",
+    "text/*" => "Text",
+    "video/*" => "Video",
     _ => {
       warn!("ignoring unrecognized MIME type `{media_type_or_range}`");
       ""
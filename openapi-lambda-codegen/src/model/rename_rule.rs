@@ -0,0 +1,97 @@
+use std::str::FromStr;
+
+/// A serde `rename_all` rule applied to the fields of a generated struct.
+///
+/// Mirrors the field renaming performed by serde so that the code generator can determine which
+/// fields still require an individual `rename` attribute.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum RenameRule {
+  Lower,
+  Upper,
+  Pascal,
+  Camel,
+  Snake,
+  ScreamingSnake,
+  Kebab,
+  ScreamingKebab,
+}
+
+impl RenameRule {
+  /// All rules supported by serde's `rename_all` attribute.
+  pub(crate) const ALL: [RenameRule; 8] = [
+    RenameRule::Lower,
+    RenameRule::Upper,
+    RenameRule::Pascal,
+    RenameRule::Camel,
+    RenameRule::Snake,
+    RenameRule::ScreamingSnake,
+    RenameRule::Kebab,
+    RenameRule::ScreamingKebab,
+  ];
+
+  /// Rules considered (in order) when inferring a rule from an object's property names.
+  pub(crate) const INFERRED: [RenameRule; 4] = [
+    RenameRule::Camel,
+    RenameRule::Pascal,
+    RenameRule::Kebab,
+    RenameRule::ScreamingSnake,
+  ];
+
+  pub(crate) fn as_str(self) -> &'static str {
+    match self {
+      RenameRule::Lower => "lowercase",
+      RenameRule::Upper => "UPPERCASE",
+      RenameRule::Pascal => "PascalCase",
+      RenameRule::Camel => "camelCase",
+      RenameRule::Snake => "snake_case",
+      RenameRule::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+      RenameRule::Kebab => "kebab-case",
+      RenameRule::ScreamingKebab => "SCREAMING-KEBAB-CASE",
+    }
+  }
+
+  /// Returns the serialized name serde derives from a snake_case field identifier (without any
+  /// `r#` prefix).
+  pub(crate) fn apply_to_field(self, field: &str) -> String {
+    match self {
+      RenameRule::Lower | RenameRule::Snake => field.to_owned(),
+      RenameRule::Upper | RenameRule::ScreamingSnake => field.to_ascii_uppercase(),
+      RenameRule::Pascal => {
+        let mut pascal = String::new();
+        let mut capitalize = true;
+        for ch in field.chars() {
+          if ch == '_' {
+            capitalize = true;
+          } else if capitalize {
+            pascal.push(ch.to_ascii_uppercase());
+            capitalize = false;
+          } else {
+            pascal.push(ch);
+          }
+        }
+        pascal
+      }
+      RenameRule::Camel => {
+        let pascal = RenameRule::Pascal.apply_to_field(field);
+        let mut chars = pascal.chars();
+        chars
+          .next()
+          .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+          .unwrap_or_default()
+      }
+      RenameRule::Kebab => field.replace('_', "-"),
+      RenameRule::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+    }
+  }
+}
+
+impl FromStr for RenameRule {
+  type Err = ();
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    RenameRule::ALL
+      .into_iter()
+      .find(|rule| rule.as_str() == s)
+      .ok_or(())
+  }
+}
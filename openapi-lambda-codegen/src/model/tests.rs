@@ -1,9 +1,12 @@
-use crate::CodeGenerator;
+use crate::model::const_schemas_to_enums;
+use crate::{
+  CodeGenerator, CodegenError, DateTimeBackend, DocCache, MapType, SetType, ValidationIssue,
+};
 
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
 use openapi_lambda::error::format_error;
-use openapiv3::{Components, OpenAPI, ReferenceOr, Schema};
+use openapiv3::{Callback, Components, OpenAPI, ReferenceOr, Schema};
 use pretty_assertions::assert_eq;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
@@ -95,7 +98,7 @@ schemas:
       .unwrap()
       .to_owned(),
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub struct FooBar {
           #[serde(skip_serializing_if = "Option::is_none")]
@@ -223,7 +226,7 @@ ObjectAdditionalProperties:
     "Foo",
     quote! {
       #[doc = "Description Foo"]
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Deserialize, Serialize)]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub struct Foo {
         #[doc = "Description `string_plain`"]
@@ -266,22 +269,57 @@ ObjectAdditionalProperties:
         pub array_obj_ref_properties: Option<Vec<crate::models::ObjectProperties>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub array_obj_ref_addl_properties: Option<
-          Vec<std::collections::HashMap<String, foo::Bar>>,
+          Vec<openapi_lambda::models::IndexMap<String, foo::Bar>>,
         >,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub array_unique: Option<openapi_lambda::models::IndexSet<String>>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        pub obj_addl_properties: Option<std::collections::HashMap<String, i64>>,
+        pub obj_addl_properties: Option<openapi_lambda::models::IndexMap<String, i64>>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub obj_empty: Option<openapi_lambda::models::EmptyModel>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub obj_ref_properties: Option<crate::models::ObjectProperties>,
-        pub obj_ref_addl_properties: std::collections::HashMap<String, foo::Bar>,
+        pub obj_ref_addl_properties: openapi_lambda::models::IndexMap<String, foo::Bar>,
         #[serde(rename = "SnakeCase", skip_serializing_if = "Option::is_none")]
         pub snake_case: Option<String>,
         #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
         pub r#type: Option<String>,
       }
+
+      impl std::fmt::Debug for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          f.debug_struct("Foo")
+            .field("string_plain", &self.string_plain)
+            .field("string_format", &self.string_format)
+            .field("string_date", &self.string_date)
+            .field("string_datetime", &self.string_datetime)
+            .field("string_byte", &self.string_byte)
+            .field("string_password", &"***")
+            .field("string_binary", &self.string_binary)
+            .field("string_ref", &self.string_ref)
+            .field("integer", &self.integer)
+            .field("integer_format", &self.integer_format)
+            .field("integer_32", &self.integer_32)
+            .field("integer_64", &self.integer_64)
+            .field("number", &self.number)
+            .field("number_format", &self.number_format)
+            .field("number_float", &self.number_float)
+            .field("number_double", &self.number_double)
+            .field("boolean", &self.boolean)
+            .field("array", &self.array)
+            .field("array_any", &self.array_any)
+            .field("array_obj_ref_properties", &self.array_obj_ref_properties)
+            .field("array_obj_ref_addl_properties", &self.array_obj_ref_addl_properties)
+            .field("array_unique", &self.array_unique)
+            .field("obj_addl_properties", &self.obj_addl_properties)
+            .field("obj_empty", &self.obj_empty)
+            .field("obj_ref_properties", &self.obj_ref_properties)
+            .field("obj_ref_addl_properties", &self.obj_ref_addl_properties)
+            .field("snake_case", &self.snake_case)
+            .field("type", &self.r#type)
+            .finish()
+        }
+      }
     },
   );
 }
@@ -304,13 +342,13 @@ Bar:
     "##,
     "Foo",
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Debug, Default, Deserialize, Serialize)]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub struct Foo {
         #[serde(skip_serializing_if = "Option::is_none")]
         pub foo: Option<String>,
         #[serde(flatten)]
-        pub additional_properties: std::collections::HashMap<String, foo::bar>,
+        pub additional_properties: openapi_lambda::models::IndexMap<String, foo::bar>,
       }
     },
   );
@@ -333,68 +371,1587 @@ Bar:
     "##,
     "Foo",
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub foo: Option<String>,
+        #[serde(flatten)]
+        pub additional_properties: openapi_lambda::models::IndexMap<String, crate::models::Bar>,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_comparison() {
+  // Floating-point numbers only implement `PartialEq`, including when nested in other models.
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    bar:
+      $ref: "#/components/schemas/Bar"
+    tags:
+      type: array
+      items:
+        type: string
+  required:
+    - bar
+    - tags
+
+Bar:
+  type: object
+  properties:
+    price:
+      type: number
+  required:
+    - price
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub bar: crate::models::Bar,
+        pub tags: Vec<String>,
+      }
+    },
+  );
+
+  // Existing Rust types aren't known to implement either trait.
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    id:
+      type: string
+      format: foo::Id
+  required:
+    - id
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub id: foo::Id,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_additional_properties_map_type() {
+  let components_schemas_str = r##"
+Foo:
+  type: object
+  properties:
+    foo:
+      type: object
+      additionalProperties: true
+  additionalProperties:
+    type: integer
+    "##;
+
+  expect_model_with_generator(
+    mock_code_generator().with_map_type(MapType::BTreeMap),
+    components_schemas_str,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub foo: Option<
+          std::collections::BTreeMap<String, openapi_lambda::models::serde_json::Value>
+        >,
+        #[serde(flatten)]
+        pub additional_properties: std::collections::BTreeMap<String, i64>,
+      }
+    },
+  );
+
+  expect_model_with_generator(
+    mock_code_generator().with_map_type(MapType::HashMap),
+    components_schemas_str,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub foo: Option<
+          std::collections::HashMap<String, openapi_lambda::models::serde_json::Value>
+        >,
+        #[serde(flatten)]
+        pub additional_properties: std::collections::HashMap<String, i64>,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_unique_items() {
+  let components_schemas_str = r##"
+Foo:
+  type: object
+  properties:
+    strings:
+      type: array
+      items:
+        type: string
+      uniqueItems: true
+    numbers:
+      type: array
+      items:
+        type: number
+      uniqueItems: true
+    bars:
+      type: array
+      items:
+        $ref: "#/components/schemas/Bar"
+      uniqueItems: true
+    bazzes:
+      type: array
+      items:
+        $ref: "#/components/schemas/Baz"
+      uniqueItems: true
+Bar:
+  type: object
+  properties:
+    name:
+      type: string
+Baz:
+  type: object
+  x-rust-derives: [Hash]
+  properties:
+    name:
+      type: string
+    "##;
+
+  expect_model(
+    components_schemas_str,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub strings: Option<openapi_lambda::models::IndexSet<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub numbers: Option<openapi_lambda::models::UniqueVec<f64>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bars: Option<openapi_lambda::models::UniqueVec<crate::models::Bar>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bazzes: Option<openapi_lambda::models::IndexSet<crate::models::Baz>>,
+      }
+    },
+  );
+
+  expect_model_with_generator(
+    mock_code_generator().with_set_type(SetType::BTreeSet),
+    components_schemas_str,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub strings: Option<std::collections::BTreeSet<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub numbers: Option<openapi_lambda::models::UniqueVec<f64>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bars: Option<openapi_lambda::models::UniqueVec<crate::models::Bar>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bazzes: Option<openapi_lambda::models::UniqueVec<crate::models::Baz>>,
+      }
+    },
+  );
+
+  expect_model_with_generator(
+    mock_code_generator().with_set_type(SetType::UniqueVec),
+    components_schemas_str,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub strings: Option<openapi_lambda::models::UniqueVec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub numbers: Option<openapi_lambda::models::UniqueVec<f64>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bars: Option<openapi_lambda::models::UniqueVec<crate::models::Bar>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bazzes: Option<openapi_lambda::models::UniqueVec<crate::models::Baz>>,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_array_bounds() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    unbounded:
+      type: array
+      items:
+        type: string
+      minItems: 0
+    min:
+      type: array
+      items:
+        type: string
+      minItems: 1
+    max:
+      type: array
+      items:
+        type: integer
+      maxItems: 10
+    both:
+      type: array
+      items:
+        type: string
+      uniqueItems: true
+      minItems: 1
+      maxItems: 3
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub unbounded: Option<Vec<String>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub min: Option<openapi_lambda::models::Bounded<Vec<String>, 1, { usize::MAX }>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub max: Option<openapi_lambda::models::Bounded<Vec<i64>, 0, 10>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub both: Option<
+          openapi_lambda::models::Bounded<openapi_lambda::models::IndexSet<String>, 1, 3>
+        >,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_nullable_properties() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    required_nullable:
+      type: string
+      nullable: true
+    optionalNullable:
+      type: integer
+      format: int32
+      nullable: true
+    nullable_ref:
+      $ref: "#/components/schemas/Bar"
+    nullable_items:
+      type: array
+      items:
+        type: string
+        nullable: true
+    nullable_values:
+      type: object
+      additionalProperties:
+        type: boolean
+        nullable: true
+  required:
+    - required_nullable
+    - nullable_ref
+    - nullable_items
+    - nullable_values
+
+Bar:
+  type: object
+  nullable: true
+  properties:
+    bar:
+      type: string
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub required_nullable: Option<String>,
+        #[serde(
+          rename = "optionalNullable",
+          default,
+          skip_serializing_if = "Option::is_none",
+          with = "openapi_lambda::__private::double_option"
+        )]
+        pub optional_nullable: Option<Option<i32>>,
+        pub nullable_ref: Option<crate::models::Bar>,
+        pub nullable_items: Vec<Option<String>>,
+        pub nullable_values: openapi_lambda::models::IndexMap<String, Option<bool> >,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_sensitive_properties() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    username:
+      type: string
+    password:
+      type: string
+      format: password
+    apiKey:
+      type: string
+      writeOnly: true
+    secret:
+      $ref: "#/components/schemas/Secret"
+  required:
+    - username
+    - password
+  additionalProperties:
+    type: string
+
+Secret:
+  type: string
+  format: foo::Secret
+  writeOnly: true
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Deserialize, Serialize)]
+      #[serde(crate = "openapi_lambda::__private::serde", rename_all = "camelCase")]
+      pub struct Foo {
+        pub username: String,
+        pub password: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub api_key: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub secret: Option<foo::Secret>,
+        #[serde(flatten)]
+        pub additional_properties: openapi_lambda::models::IndexMap<String, String>,
+      }
+
+      impl std::fmt::Debug for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          f.debug_struct("Foo")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .field("api_key", &"***")
+            .field("secret", &"***")
+            .field("additional_properties", &self.additional_properties)
+            .finish()
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_default() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    limit:
+      type: integer
+      default: 20
+    status:
+      $ref: "#/components/schemas/Status"
+    cursor:
+      type: string
+      default: abc
+  required:
+    - limit
+    - status
+  additionalProperties: true
+
+Status:
+  type: string
+  enum:
+    - active
+    - inactive
+  default: active
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub limit: i64,
+        pub status: crate::models::Status,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub cursor: Option<String>,
+        #[serde(flatten)]
+        pub additional_properties:
+          openapi_lambda::models::IndexMap<String, openapi_lambda::models::serde_json::Value>,
+      }
+
+      impl Default for Foo {
+        fn default() -> Self {
+          Self {
+            limit: openapi_lambda::models::serde_json::from_str("20")
+              .expect("invalid default value for `Foo.limit`"),
+            status: openapi_lambda::models::serde_json::from_str("\"active\"")
+              .expect("invalid default value for `Foo.status`"),
+            cursor: None,
+            additional_properties: Default::default(),
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_default_invalid() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: object
+  properties:
+    limit:
+      type: integer
+      format: int32
+      default: 3000000000
+    status:
+      $ref: "#/components/schemas/Status"
+    created:
+      type: string
+      format: date-time
+      default: "2024-02-30T00:00:00Z"
+    # Valid defaults aren't reported.
+    updated:
+      type: string
+      format: date-time
+      default: "2024-02-29T12:00:00.5+01:00"
+    expires:
+      type: string
+      format: date
+      default: "2024-12-31"
+    tags:
+      type: array
+      items:
+        type: string
+      default: [a, null]
+    cursor:
+      type: string
+      nullable: true
+      default: null
+  required:
+    - limit
+    - status
+  default:
+    limit: 10
+    status: deleted
+
+Status:
+  type: string
+  enum:
+    - active
+    - inactive
+  default: true
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo: `default` does not match the schema: property \
+       `status`: \"deleted\" is not an `enum` value",
+      "openapi.yaml#/components/schemas/Foo/properties/limit: `default` does not match the schema: \
+       3000000000 does not fit in an `i32`",
+      "openapi.yaml#/components/schemas/Foo/properties/created: `default` does not match the \
+       schema: \"2024-02-30T00:00:00Z\" is not an RFC 3339 `date-time`",
+      "openapi.yaml#/components/schemas/Foo/properties/tags: `default` does not match the schema: \
+       item 1: found `null`, but the schema is not `nullable`",
+      "openapi.yaml#/components/schemas/Status: `default` does not match the schema: expected a \
+       string, but found true",
+    ],
+  );
+}
+
+#[test]
+fn test_object_builder() {
+  expect_model_with_generator(
+    mock_code_generator().with_builders(),
+    r##"
+Foo:
+  type: object
+  properties:
+    name:
+      type: string
+    type:
+      type: integer
+    nickname:
+      type: string
+      nullable: true
+  required:
+    - name
+  additionalProperties:
+    type: string
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub name: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub r#type: Option<i64>,
+        #[serde(
+          default,
+          skip_serializing_if = "Option::is_none",
+          with = "openapi_lambda::__private::double_option"
+        )]
+        pub nickname: Option<Option<String>>,
+        #[serde(flatten)]
+        pub additional_properties: openapi_lambda::models::IndexMap<String, String>,
+      }
+
+      impl Foo {
+        /// Returns a builder for constructing this model.
+        pub fn builder() -> FooBuilder {
+          FooBuilder::default()
+        }
+      }
+
+      #[doc = "Builder for [`Foo`]."]
+      #[derive(Clone, Default)]
+      pub struct FooBuilder {
+        name: Option<String>,
+        r#type: Option<i64>,
+        nickname: Option<Option<String>>,
+        additional_properties: openapi_lambda::models::IndexMap<String, String>,
+      }
+
+      impl FooBuilder {
+        pub fn name(mut self, value: String) -> Self {
+          self.name = Some(value);
+          self
+        }
+        pub fn r#type(mut self, value: i64) -> Self {
+          self.r#type = Some(value);
+          self
+        }
+        pub fn nickname(mut self, value: Option<String>) -> Self {
+          self.nickname = Some(value);
+          self
+        }
+        pub fn additional_properties(
+          mut self,
+          value: openapi_lambda::models::IndexMap<String, String>,
+        ) -> Self {
+          self.additional_properties = value;
+          self
+        }
+
+        /// Builds the model, returning an error if any required field hasn't been set.
+        pub fn build(self) -> Result<Foo, openapi_lambda::models::MissingField> {
+          Ok(Foo {
+            name: self.name.ok_or(openapi_lambda::models::MissingField {
+              field_name: "name",
+            })?,
+            r#type: self.r#type,
+            nickname: self.nickname,
+            additional_properties: self.additional_properties,
+          })
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_response_defaults() {
+  expect_model_with_generator(
+    mock_code_generator().with_builders(),
+    r##"
+Foo:
+  type: object
+  properties:
+    id:
+      type: string
+      readOnly: true
+      x-rust-default-response: crate::new_id()
+    created_at:
+      type: integer
+      readOnly: true
+      x-rust-default-response: crate::now()
+    name:
+      type: string
+  required:
+    - id
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub created_at: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub name: Option<String>,
+      }
+
+      impl Default for Foo {
+        fn default() -> Self {
+          Self {
+            id: crate::new_id(),
+            created_at: Some(crate::now()),
+            name: None,
+          }
+        }
+      }
+
+      impl Foo {
+        /// Returns a builder for constructing this model.
+        pub fn builder() -> FooBuilder {
+          FooBuilder::default()
+        }
+      }
+
+      #[doc = "Builder for [`Foo`]."]
+      #[derive(Clone, Default)]
+      pub struct FooBuilder {
+        id: Option<String>,
+        created_at: Option<i64>,
+        name: Option<String>,
+      }
+
+      impl FooBuilder {
+        pub fn id(mut self, value: String) -> Self {
+          self.id = Some(value);
+          self
+        }
+        pub fn created_at(mut self, value: i64) -> Self {
+          self.created_at = Some(value);
+          self
+        }
+        pub fn name(mut self, value: String) -> Self {
+          self.name = Some(value);
+          self
+        }
+
+        /// Builds the model, returning an error if any required field hasn't been set.
+        pub fn build(self) -> Result<Foo, openapi_lambda::models::MissingField> {
+          Ok(Foo {
+            id: if let Some(value) = self.id {
+              value
+            } else {
+              crate::new_id()
+            },
+            created_at: if self.created_at.is_some() {
+              self.created_at
+            } else {
+              Some(crate::now())
+            },
+            name: self.name,
+          })
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_response_default_invalid() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: object
+  properties:
+    id:
+      type: string
+      x-rust-default-response: crate::new_id()
+    created:
+      type: string
+      readOnly: true
+      x-rust-default-response: 1
+    updated:
+      type: string
+      readOnly: true
+      x-rust-default-response: crate::now(
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo/properties/id: `x-rust-default-response` is only \
+       supported for `readOnly` schemas",
+      "openapi.yaml#/components/schemas/Foo/properties/created: `x-rust-default-response` must be a \
+       string, but found 1",
+      "openapi.yaml#/components/schemas/Foo/properties/updated: `x-rust-default-response` must be a \
+       Rust expression, but found `crate::now(`: cannot parse string into token stream",
+    ],
+  );
+}
+
+#[test]
+fn test_object_rename_all() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  properties:
+    userId:
+      type: string
+    type:
+      type: string
+    createdAt:
+      type: string
+  required:
+    - userId
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde", rename_all = "camelCase")]
+      pub struct Foo {
+        pub user_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub r#type: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub created_at: Option<String>,
+      }
+    },
+  );
+
+  // Mixed casing falls back to renaming individual fields.
+  expect_model(
+    r##"
+Bar:
+  type: object
+  properties:
+    userId:
+      type: string
+    display_name:
+      type: string
+    "##,
+    "Bar",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Bar {
+        #[serde(rename = "userId", skip_serializing_if = "Option::is_none")]
+        pub user_id: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub display_name: Option<String>,
+      }
+    },
+  );
+
+  // The extension forces the rule, and fields that don't follow it are renamed individually.
+  expect_model(
+    r##"
+Baz:
+  type: object
+  properties:
+    userId:
+      type: string
+    display_name:
+      type: string
+  x-rust-rename-all: camelCase
+    "##,
+    "Baz",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde", rename_all = "camelCase")]
+      pub struct Baz {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub user_id: Option<String>,
+        #[serde(rename = "display_name", skip_serializing_if = "Option::is_none")]
+        pub display_name: Option<String>,
+      }
+    },
+  );
+
+  expect_model(
+    r##"
+Qux:
+  type: object
+  properties:
+    USER_ID:
+      type: string
+    EMAIL:
+      type: string
+  required:
+    - USER_ID
+    - EMAIL
+    "##,
+    "Qux",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(
+        crate = "openapi_lambda::__private::serde",
+        rename_all = "SCREAMING_SNAKE_CASE"
+      )]
+      pub struct Qux {
+        pub user_id: String,
+        pub email: String,
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_rename_all_invalid() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: object
+  properties:
+    userId:
+      type: string
+  x-rust-rename-all: camel
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo: `x-rust-rename-all` must be one of \"lowercase\", \
+       \"UPPERCASE\", \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \
+       \"kebab-case\", \"SCREAMING-KEBAB-CASE\", but found \"camel\"",
+    ],
+  );
+}
+
+#[test]
+fn test_object_additional_properties() {
+  expect_no_model(
+    r##"
+Baz:
+  type: object
+  additionalProperties:
+    type: string
+    format: foo::Bar
+    "##,
+    "Baz",
+  );
+}
+
+#[test]
+fn test_string() {
+  expect_no_model(
+    r##"
+Foo:
+  type: string
+    "##,
+    "Foo",
+  );
+}
+
+#[test]
+fn test_string_enum() {
+  expect_model(
+    r##"
+Foo:
+  type: string
+  enum:
+    - option_a
+    - option_b
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub enum Foo {
+        #[serde(rename = "option_a")]
+        OptionA,
+        #[serde(rename = "option_b")]
+        OptionB,
+      }
+      impl Foo {
+        fn as_str(&self) -> &'static str {
+          match self {
+            Self::OptionA => "option_a",
+            Self::OptionB => "option_b",
+          }
+        }
+      }
+      impl std::fmt::Display for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", self.as_str())
+        }
+      }
+      impl std::str::FromStr for Foo {
+        type Err = anyhow::Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          match s {
+            "option_a" => Ok(Self::OptionA),
+            "option_b" => Ok(Self::OptionB),
+            _ => Err(anyhow!("invalid enum variant `{}`", s)),
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_string_enum_descriptions() {
+  expect_model(
+    r##"
+Foo:
+  type: string
+  enum:
+    - option_a
+    - OptionB
+  x-enum-descriptions:
+    option_a: |-
+      First option
+      (the default)
+    OptionB: Second option
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub enum Foo {
+        #[doc = "First option"]
+        #[doc = "(the default)"]
+        #[serde(rename = "option_a")]
+        OptionA,
+        #[doc = "Second option"]
+        OptionB,
+      }
+      impl Foo {
+        fn as_str(&self) -> &'static str {
+          match self {
+            Self::OptionA => "option_a",
+            Self::OptionB => "OptionB",
+          }
+        }
+      }
+      impl std::fmt::Display for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", self.as_str())
+        }
+      }
+      impl std::str::FromStr for Foo {
+        type Err = anyhow::Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          match s {
+            "option_a" => Ok(Self::OptionA),
+            "OptionB" => Ok(Self::OptionB),
+            _ => Err(anyhow!("invalid enum variant `{}`", s)),
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_string_enum_descriptions_invalid() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: string
+  enum:
+    - option_a
+    - option_b
+  x-enum-descriptions:
+    option_b: 2
+    option_c: Third option
+Bar:
+  type: string
+  enum: [bar]
+  x-enum-descriptions: [Bar]
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo: `x-enum-descriptions` must map enum values to \
+       strings, but found 2 for `option_b`",
+      "openapi.yaml#/components/schemas/Foo: `x-enum-descriptions` describes `option_c`, which is \
+       not an enum value",
+      "openapi.yaml#/components/schemas/Bar: `x-enum-descriptions` must be an object mapping enum \
+       values to descriptions, but found [\"Bar\"]",
+    ],
+  );
+}
+
+#[test]
+fn test_string_enum_varnames() {
+  expect_model(
+    r##"
+Foo:
+  type: string
+  enum:
+    - "1"
+    - A1
+    - x-y
+  x-enum-varnames:
+    - ONE
+    - Gold
+    - crossed_out
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub enum Foo {
+        #[serde(rename = "1")]
+        One,
+        #[serde(rename = "A1")]
+        Gold,
+        #[serde(rename = "x-y")]
+        CrossedOut,
+      }
+      impl Foo {
+        fn as_str(&self) -> &'static str {
+          match self {
+            Self::One => "1",
+            Self::Gold => "A1",
+            Self::CrossedOut => "x-y",
+          }
+        }
+      }
+      impl std::fmt::Display for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", self.as_str())
+        }
+      }
+      impl std::str::FromStr for Foo {
+        type Err = anyhow::Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          match s {
+            "1" => Ok(Self::One),
+            "A1" => Ok(Self::Gold),
+            "x-y" => Ok(Self::CrossedOut),
+            _ => Err(anyhow!("invalid enum variant `{}`", s)),
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_string_enum_varnames_length_mismatch() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: string
+  enum:
+    - option_a
+    - option_b
+  x-enum-varnames:
+    - OptionA
+    "##,
+    &["openapi.yaml#/components/schemas/Foo: `x-enum-varnames` has 1 names, but `enum` has 2 values"],
+  );
+}
+
+#[test]
+fn test_string_enum_colliding_variants() {
+  expect_model(
+    r##"
+Foo:
+  type: string
+  enum:
+    - foo-bar
+    - foo_bar
+    - FooBar2
+    - ""
+    - empty_string
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub enum Foo {
+        #[serde(rename = "foo-bar")]
+        FooBar,
+        #[serde(rename = "foo_bar")]
+        FooBar2,
+        #[serde(rename = "FooBar2")]
+        FooBar22,
+        #[serde(rename = "")]
+        EmptyString,
+        #[serde(rename = "empty_string")]
+        EmptyString2,
+      }
+      impl Foo {
+        fn as_str(&self) -> &'static str {
+          match self {
+            Self::FooBar => "foo-bar",
+            Self::FooBar2 => "foo_bar",
+            Self::FooBar22 => "FooBar2",
+            Self::EmptyString => "",
+            Self::EmptyString2 => "empty_string",
+          }
+        }
+      }
+      impl std::fmt::Display for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", self.as_str())
+        }
+      }
+      impl std::str::FromStr for Foo {
+        type Err = anyhow::Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          match s {
+            "foo-bar" => Ok(Self::FooBar),
+            "foo_bar" => Ok(Self::FooBar2),
+            "FooBar2" => Ok(Self::FooBar22),
+            "" => Ok(Self::EmptyString),
+            "empty_string" => Ok(Self::EmptyString2),
+            _ => Err(anyhow!("invalid enum variant `{}`", s)),
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_string_enum_varnames_invalid() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: string
+  enum:
+    - a
+    - b
+    - c
+    - d
+  x-enum-varnames:
+    - OptionA
+    - option_a
+    - 1st
+    - 4
+Bar:
+  type: string
+  enum: [bar]
+  x-enum-varnames: Bar
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo: `x-enum-varnames` contains `option_a`, which \
+       duplicates the name of another variant (`OptionA`)",
+      "openapi.yaml#/components/schemas/Foo: `x-enum-varnames` contains `1st`, which is not a \
+       valid Rust identifier",
+      "openapi.yaml#/components/schemas/Foo: `x-enum-varnames` must be an array of strings, but \
+       found 4",
+      "openapi.yaml#/components/schemas/Bar: `x-enum-varnames` must be an array of strings, but \
+       found \"Bar\"",
+    ],
+  );
+}
+
+#[test]
+fn test_const_schemas_to_enums() {
+  let mut doc = parse_yaml::<serde_yaml::Mapping>(
+    r##"
+Foo:
+  type: object
+  properties:
+    kind:
+      const: foo
+    version:
+      type: integer
+      format: int32
+      const: 2
+    # Property named `const` is a schema, not a `const` keyword.
+    const:
+      type: string
+  example:
+    const: unchanged
+    "##,
+  );
+  const_schemas_to_enums(&mut doc);
+  assert_eq!(
+    doc,
+    parse_yaml::<serde_yaml::Mapping>(
+      r##"
+Foo:
+  type: object
+  properties:
+    kind:
+      type: string
+      enum: [foo]
+    version:
+      type: integer
+      format: int32
+      enum: [2]
+    const:
+      type: string
+  example:
+    const: unchanged
+      "##,
+    )
+  );
+}
+
+#[test]
+fn test_const_with_enum() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: object
+  properties:
+    kind:
+      type: string
+      const: foo
+      enum: [foo, bar]
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo/properties/kind: schemas must not specify both \
+       `const` and `enum`",
+    ],
+  );
+}
+
+#[test]
+fn test_integer_const() {
+  let mut doc = parse_yaml::<serde_yaml::Mapping>(
+    r##"
+Foo:
+  type: integer
+  format: int32
+  const: 2
+    "##,
+  );
+  const_schemas_to_enums(&mut doc);
+
+  expect_model(
+    &serde_yaml::to_string(&doc).unwrap(),
+    "Foo",
+    quote! {
+      #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+      pub struct Foo;
+      impl Foo {
+        /// The only value permitted by this type.
+        pub const VALUE: i32 = 2;
+      }
+      impl Serialize for Foo {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+          S: openapi_lambda::__private::serde::Serializer,
+        {
+          Self::VALUE.serialize(serializer)
+        }
+      }
+      impl<'de> Deserialize<'de> for Foo {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+          D: openapi_lambda::__private::serde::Deserializer<'de>,
+        {
+          let value = i32::deserialize(deserializer)?;
+          if value == Self::VALUE {
+            Ok(Self)
+          } else {
+            Err(openapi_lambda::__private::serde::de::Error::custom(format!(
+              "expected `{}`, but found `{}`",
+              Self::VALUE,
+              value
+            )))
+          }
+        }
+      }
+      impl std::fmt::Display for Foo {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+          write!(f, "{}", Self::VALUE)
+        }
+      }
+      impl std::str::FromStr for Foo {
+        type Err = anyhow::Error;
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+          if s.parse::<i32>()? == Self::VALUE {
+            Ok(Self)
+          } else {
+            Err(anyhow!("expected `{}`, but found `{}`", Self::VALUE, s))
+          }
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_object_enum() {
+  expect_model(
+    r##"
+Foo:
+  type: object
+  enum:
+    - kind: circle
+      radius: 1
+    - kind: square
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+      #[serde(
+        crate = "openapi_lambda::__private::serde",
+        try_from = "openapi_lambda::models::serde_json::Value",
+        into = "openapi_lambda::models::serde_json::Value"
+      )]
+      pub struct Foo(openapi_lambda::models::serde_json::Value);
+      impl Foo {
+        #[doc = " JSON encodings of the values permitted by the schema's `enum`."]
+        pub const ALLOWED_VALUES_JSON: &'static [&'static str] =
+          &["{\"kind\":\"circle\",\"radius\":1}", "{\"kind\":\"square\"}"];
+        #[doc = " Returns the underlying JSON value."]
+        pub fn as_value(&self) -> &openapi_lambda::models::serde_json::Value {
+          &self.0
+        }
+        #[doc = " Returns the underlying JSON value."]
+        pub fn into_value(self) -> openapi_lambda::models::serde_json::Value {
+          self.0
+        }
+      }
+      impl std::convert::TryFrom<openapi_lambda::models::serde_json::Value> for Foo {
+        type Error = anyhow::Error;
+        fn try_from(value: openapi_lambda::models::serde_json::Value) -> Result<Self, Self::Error> {
+          static ALLOWED_VALUES: openapi_lambda::__private::once_cell::sync::Lazy<
+            Vec<openapi_lambda::models::serde_json::Value>,
+          > = openapi_lambda::__private::once_cell::sync::Lazy::new(|| {
+            Foo::ALLOWED_VALUES_JSON
+              .iter()
+              .map(|allowed_value_json| {
+                openapi_lambda::models::serde_json::from_str(allowed_value_json)
+                  .expect("allowed value should be valid JSON")
+              })
+              .collect()
+          });
+          if ALLOWED_VALUES.contains(&value) {
+            Ok(Self(value))
+          } else {
+            Err(anyhow!("value is not one of the permitted `enum` values: {}", value))
+          }
+        }
+      }
+      impl From<Foo> for openapi_lambda::models::serde_json::Value {
+        fn from(value: Foo) -> Self {
+          value.0
+        }
+      }
+      impl AsRef<openapi_lambda::models::serde_json::Value> for Foo {
+        fn as_ref(&self) -> &openapi_lambda::models::serde_json::Value {
+          &self.0
+        }
+      }
+    },
+  );
+}
+
+#[test]
+fn test_crate_name() {
+  expect_model_with_generator(
+    mock_code_generator().with_crate_name("my_facade::openapi_lambda"),
+    r##"
+Foo:
+  type: object
+  properties:
+    metadata: {}
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "my_facade::openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub metadata: Option<my_facade::openapi_lambda::models::serde_json::Value>,
+      }
+    },
+  );
+}
+
+#[test]
+#[should_panic(expected = "invalid crate name `openapi-lambda`")]
+fn test_invalid_crate_name() {
+  mock_code_generator().with_crate_name("openapi-lambda");
+}
+
+#[test]
+fn test_map_format_to_type() {
+  expect_model_with_generator(
+    mock_code_generator()
+      .map_format_to_type("bar-id", "crate::types::BarId")
+      .map_format_to_type("money", "rust_decimal::Decimal"),
+    r##"
+Foo:
+  type: object
+  properties:
+    bar_id:
+      type: integer
+      format: bar-id
+    amount:
+      type: number
+      format: money
+    unmapped:
+      type: string
+      format: foo::Bar
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub bar_id: Option<crate::types::BarId>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub amount: Option<rust_decimal::Decimal>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub unmapped: Option<foo::Bar>,
+      }
+    },
+  );
+}
+
+#[test]
+#[should_panic(expected = "cannot map standard format `date-time` to a Rust type")]
+fn test_map_standard_format_to_type() {
+  mock_code_generator().map_format_to_type("date-time", "crate::types::Timestamp");
+}
+
+#[test]
+fn test_datetime_backend_time() {
+  expect_model_with_generator(
+    mock_code_generator().with_datetime_backend(DateTimeBackend::Time),
+    r##"
+Foo:
+  type: object
+  properties:
+    date:
+      type: string
+      format: date
+    createdAt:
+      type: string
+      format: date-time
+    updated_at:
+      type: string
+      format: date-time
+    deleted_at:
+      type: string
+      format: date-time
+      nullable: true
+    archived_at:
+      type: string
+      format: date-time
+      nullable: true
+    dates:
+      type: array
+      items:
+        type: string
+        format: date
+  required:
+    - date
+    - createdAt
+    - deleted_at
+    "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub struct Foo {
+        pub date: time::Date,
+        #[serde(rename = "createdAt", with = "openapi_lambda::models::time::serde::rfc3339")]
+        pub created_at: time::OffsetDateTime,
+        #[serde(
+          default,
+          skip_serializing_if = "Option::is_none",
+          with = "openapi_lambda::models::time::serde::rfc3339::option"
+        )]
+        pub updated_at: Option<time::OffsetDateTime>,
+        #[serde(with = "openapi_lambda::models::time::serde::rfc3339::option")]
+        pub deleted_at: Option<time::OffsetDateTime>,
+        #[serde(
+          default,
+          skip_serializing_if = "Option::is_none",
+          with = "openapi_lambda::__private::time::rfc3339_double_option"
+        )]
+        pub archived_at: Option<Option<time::OffsetDateTime>>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        pub foo: Option<String>,
-        #[serde(flatten)]
-        pub additional_properties: std::collections::HashMap<String, crate::models::Bar>,
+        pub dates: Option<Vec<time::Date>>,
       }
     },
   );
 }
 
 #[test]
-fn test_object_additional_properties() {
-  expect_no_model(
+#[should_panic(expected = "`date-time` array items with `DateTimeBackend::Time`")]
+fn test_datetime_backend_time_array_items() {
+  expect_model_with_generator(
+    mock_code_generator().with_datetime_backend(DateTimeBackend::Time),
     r##"
-Baz:
+Foo:
   type: object
-  additionalProperties:
-    type: string
-    format: foo::Bar
+  properties:
+    timestamps:
+      type: array
+      items:
+        type: string
+        format: date-time
     "##,
-    "Baz",
+    "Foo",
+    quote! {},
   );
 }
 
 #[test]
-fn test_string() {
-  expect_no_model(
+fn test_model_derives() {
+  expect_model_with_generator(
+    mock_code_generator().with_model_derives(&["PartialEq", "Clone"]),
     r##"
 Foo:
-  type: string
+  type: object
+  x-rust-derives:
+    - Eq
+    - PartialEq
+    - schemars::JsonSchema
+  properties:
+    foo:
+      type: string
     "##,
     "Foo",
+    quote! {
+      #[derive(
+        Clone,
+        Debug,
+        Default,
+        Deserialize,
+        Serialize,
+        PartialEq,
+        Eq,
+        schemars::JsonSchema
+      )]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub foo: Option<String>,
+      }
+    },
   );
-}
 
-#[test]
-fn test_string_enum() {
-  expect_model(
+  expect_model_with_generator(
+    mock_code_generator().with_model_derives(&["PartialEq"]),
     r##"
 Foo:
   type: string
+  x-rust-derives: [PartialOrd, Ord]
   enum:
     - option_a
-    - option_b
     "##,
     "Foo",
     quote! {
-      #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+      #[derive(
+        Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord
+      )]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub enum Foo {
         #[serde(rename = "option_a")]
         OptionA,
-        #[serde(rename = "option_b")]
-        OptionB,
       }
       impl Foo {
         fn as_str(&self) -> &'static str {
           match self {
             Self::OptionA => "option_a",
-            Self::OptionB => "option_b",
           }
         }
       }
@@ -408,7 +1965,6 @@ Foo:
         fn from_str(s: &str) -> Result<Self, Self::Err> {
           match s {
             "option_a" => Ok(Self::OptionA),
-            "option_b" => Ok(Self::OptionB),
             _ => Err(anyhow!("invalid enum variant `{}`", s)),
           }
         }
@@ -417,6 +1973,32 @@ Foo:
   );
 }
 
+#[test]
+fn test_invalid_model_derives_extension() {
+  expect_invalid_schemas(
+    r##"
+Foo:
+  type: object
+  x-rust-derives: PartialEq
+  properties:
+    foo:
+      type: string
+Bar:
+  type: string
+  enum: [bar]
+  x-rust-derives: [Hash, "Clone Debug", 1]
+    "##,
+    &[
+      "openapi.yaml#/components/schemas/Foo: `x-rust-derives` must be an array of strings, but \
+       found \"PartialEq\"",
+      "openapi.yaml#/components/schemas/Bar: `x-rust-derives` contains `Clone Debug`, which is \
+       not a valid Rust path: unexpected token",
+      "openapi.yaml#/components/schemas/Bar: `x-rust-derives` must be an array of strings, but \
+       found 1",
+    ],
+  );
+}
+
 #[test]
 fn test_array() {
   expect_no_model(
@@ -518,7 +2100,7 @@ Baz:
       ),
       "Foo",
       quote! {
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
         #[serde(crate = "openapi_lambda::__private::serde", tag = "foo")]
         pub enum Foo {
           #[serde(rename = "bar")]
@@ -532,6 +2114,64 @@ Baz:
   }
 }
 
+#[test]
+fn test_oneof_discriminator_additional_properties() {
+  expect_model(
+    r##"
+Foo:
+  oneOf:
+    - $ref: "#/components/schemas/Bar"
+    - $ref: "#/components/schemas/Baz"
+  discriminator:
+    propertyName: foo
+    mapping:
+      bar: "#/components/schemas/Bar"
+      baz: "#/components/schemas/Baz"
+
+Bar:
+  type: object
+  properties:
+    foo:
+      type: string
+    bar:
+      type: string
+  required:
+    - foo
+    - bar
+  additionalProperties:
+    type: integer
+
+Baz:
+  type: object
+  properties:
+    foo:
+      type: string
+  required:
+    - foo
+  additionalProperties: true
+        "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde", tag = "foo")]
+      pub enum Foo {
+        #[serde(rename = "bar")]
+        // NB: serde consumes the `foo` tag before deserializing the variant, so the flattened
+        // `additional_properties` never contain it.
+        Bar {
+          bar: String,
+          #[serde(flatten)]
+          additional_properties: openapi_lambda::models::IndexMap<String, i64>,
+        },
+        #[serde(rename = "baz")]
+        Baz(
+          openapi_lambda::models::IndexMap<String, openapi_lambda::models::serde_json::Value>,
+        ),
+      }
+    },
+  );
+}
+
 #[test]
 fn test_oneof_discriminator_no_mapping() {
   for discriminator_in_variants in [true, false] {
@@ -579,7 +2219,7 @@ Baz:
       ),
       "Foo",
       quote! {
-        #[derive(Clone, Debug, Deserialize, Serialize)]
+        #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
         #[serde(crate = "openapi_lambda::__private::serde", tag = "foo")]
         pub enum Foo {
           Bar { bar: String },
@@ -617,7 +2257,7 @@ Baz:
         "##,
     "Foo",
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
       #[serde(crate = "openapi_lambda::__private::serde", untagged)]
       pub enum Foo {
         Bar { bar: String },
@@ -627,6 +2267,64 @@ Baz:
   );
 }
 
+#[test]
+fn test_oneof_primitives() {
+  expect_model(
+    r##"
+Foo:
+  oneOf:
+    - type: string
+      description: Name of the foo.
+    - type: integer
+      format: int32
+    - type: string
+      format: date
+    - $ref: "#/components/schemas/Bar"
+    - type: boolean
+      nullable: true
+
+Bar:
+  type: object
+  properties:
+    bar:
+      type: string
+  required:
+    - bar
+        "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde", untagged)]
+      pub enum Foo {
+        #[doc = "Name of the foo."]
+        String1(String),
+        Integer(i32),
+        String2(chrono::NaiveDate),
+        Bar { bar: String },
+        Boolean(Option<bool>),
+      }
+    },
+  );
+}
+
+#[test]
+#[should_panic(expected = "enum variants must be either references to named schemas or primitive")]
+fn test_oneof_inline_object() {
+  expect_model(
+    r##"
+Foo:
+  oneOf:
+    - type: string
+    - type: object
+      properties:
+        bar:
+          type: string
+        "##,
+    "Foo",
+    quote! {},
+  );
+}
+
 #[test]
 fn test_allof_discriminator() {
   expect_model(
@@ -655,13 +2353,13 @@ Baz:
         "##,
     "Foo",
     quote! {
-      #[derive(Clone, Debug, Deserialize, Serialize)]
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
       #[serde(crate = "openapi_lambda::__private::serde")]
       pub struct Foo {
         pub bar: String,
         pub baz: String,
         #[serde(flatten)]
-        pub additional_properties: std::collections::HashMap<
+        pub additional_properties: openapi_lambda::models::IndexMap<
           String,
           openapi_lambda::models::serde_json::Value,
         >,
@@ -670,6 +2368,78 @@ Baz:
   );
 }
 
+#[test]
+fn test_allof_one_of() {
+  expect_model(
+    r##"
+Foo:
+  allOf:
+    - $ref: "#/components/schemas/Base"
+    - $ref: "#/components/schemas/Kind"
+
+Base:
+  type: object
+  properties:
+    name:
+      type: string
+  required:
+    - name
+
+Kind:
+  oneOf:
+    - $ref: "#/components/schemas/Bar"
+    - $ref: "#/components/schemas/Baz"
+  discriminator:
+    propertyName: kind
+
+Bar:
+  type: object
+  properties:
+    kind:
+      type: string
+    bar:
+      type: string
+  required:
+    - kind
+    - bar
+
+Baz:
+  type: object
+  properties:
+    kind:
+      type: string
+    baz:
+      type: integer
+  required:
+    - kind
+        "##,
+    "Foo",
+    quote! {
+      #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+      #[serde(crate = "openapi_lambda::__private::serde")]
+      pub struct Foo {
+        pub name: String,
+        #[serde(flatten)]
+        pub kind: crate::models::Kind,
+      }
+    },
+  );
+}
+
+#[test]
+#[should_panic(expected = "unexpected component type in `allOf` schema `Foo`")]
+fn test_allof_unsupported_component() {
+  expect_model(
+    r##"
+Foo:
+  allOf:
+    - type: string
+        "##,
+    "Foo",
+    quote! {},
+  );
+}
+
 #[test]
 #[should_panic(expected = "dependency cycle detected between models")]
 fn test_circular_reference() {
@@ -786,8 +2556,13 @@ components:
   );
 
   let code_generator = mock_code_generator();
-  let (_, models) =
-    code_generator.generate_models(code_generator.inline_openapi(openapi, HashMap::new()));
+  let (_, models) = code_generator
+    .generate_models(
+      code_generator
+        .inline_openapi(openapi, Callback::new(), DocCache::new())
+        .unwrap(),
+    )
+    .unwrap();
 
   assert_eq!(
     models
@@ -808,6 +2583,90 @@ components:
   );
 }
 
+#[test]
+fn test_unnamed_schemas_title() {
+  let openapi = parse_yaml::<OpenAPI>(
+    r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 1.0
+paths:
+  /foo:
+    get:
+      operationId: listFoo
+      responses:
+        200:
+          description: Success response
+          content:
+            application/json:
+              schema:
+                title: foo list
+                type: object
+                properties:
+                  items:
+                    type: array
+                    items:
+                      title: Pet
+                      type: object
+                      properties:
+                        kind:
+                          type: string
+                          enum:
+                            - cat
+                            - dog
+                  owner:
+                    title: Pet
+                    type: object
+                    properties:
+                      name:
+                        type: string
+                  color:
+                    title: "123"
+                    type: string
+                    enum:
+                      - red
+                      - blue
+components:
+  schemas:
+    Fruit:
+      type: object
+      properties:
+        type:
+          type: string
+          enum:
+            - berry
+            - stonefruit
+  "##,
+  );
+
+  let code_generator = mock_code_generator();
+  let (_, models) = code_generator
+    .generate_models(
+      code_generator
+        .inline_openapi(openapi, Callback::new(), DocCache::new())
+        .unwrap(),
+    )
+    .unwrap();
+
+  assert_eq!(
+    models
+      .keys()
+      .map(|ident| ident.to_string())
+      .sorted()
+      .collect::<Vec<_>>(),
+    [
+      "FooList",
+      "FooListColor",
+      "Fruit",
+      "FruitType",
+      "Pet",
+      "Pet2",
+      "PetKind"
+    ]
+  );
+}
+
 fn parse_yaml<T>(yaml: &str) -> T
 where
   T: DeserializeOwned,
@@ -841,8 +2700,21 @@ fn expect_token_stream_eq(a: TokenStream, b: TokenStream) {
 }
 
 fn expect_model(components_schemas_str: &str, model_name: &str, expected_model: TokenStream) {
+  expect_model_with_generator(
+    mock_code_generator(),
+    components_schemas_str,
+    model_name,
+    expected_model,
+  )
+}
+
+fn expect_model_with_generator(
+  code_generator: CodeGenerator,
+  components_schemas_str: &str,
+  model_name: &str,
+  expected_model: TokenStream,
+) {
   let components_schemas = parse_yaml::<Schemas>(components_schemas_str);
-  let code_generator = mock_code_generator();
   let mut models = HashMap::new();
   let model_ident = Ident::new(model_name, Span::call_site());
 
@@ -878,3 +2750,29 @@ fn expect_no_model(components_schemas_str: &str, model_name: &str) {
   );
   assert!(models.is_empty());
 }
+
+/// Asserts that generating code for the specified schemas fails with the expected `InvalidSpec`
+/// issues.
+fn expect_invalid_schemas(components_schemas_str: &str, expected_issues: &[&str]) {
+  let openapi = format!(
+    "openapi: 3.0.2\ninfo:\n  title: Test\n  version: 0.1.0\npaths: {{}}\ncomponents:\n  \
+     schemas:\n{}",
+    components_schemas_str
+      .lines()
+      .map(|line| format!("    {line}"))
+      .join("\n")
+  );
+
+  let Err(CodegenError::InvalidSpec(issues)) =
+    CodeGenerator::from_str(&openapi, ".openapi-lambda").try_generate_to_string()
+  else {
+    panic!("expected invalid schemas");
+  };
+  assert_eq!(
+    issues
+      .iter()
+      .map(ValidationIssue::to_string)
+      .collect::<Vec<_>>(),
+    expected_issues
+  );
+}
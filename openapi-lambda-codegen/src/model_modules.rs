@@ -0,0 +1,17 @@
+/// Layout of the generated `models` module.
+///
+/// See [`CodeGenerator::with_model_modules`](crate::CodeGenerator::with_model_modules).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ModelModules {
+  /// Generate every model directly within the `models` module (the default).
+  #[default]
+  Flat,
+  /// Group models into one submodule per operation tag (e.g., `models::pet` for the `pet` tag).
+  ///
+  /// Each model is placed in the submodule of the only tag whose operations reference it (directly
+  /// or via other schemas). Models referenced by operations with different tags (or by untagged
+  /// operations), as well as any models not referenced by an operation, are placed in
+  /// `models::common`.
+  ByTag,
+}
@@ -1,9 +1,10 @@
-use crate::DocCache;
+use crate::{CodegenError, DocCache};
 
 use openapiv3::ReferenceOr;
 use serde::de::DeserializeOwned;
 
-use std::fs::File;
+use std::borrow::Cow;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
 pub struct ResolvedReference<'a, T>
@@ -13,77 +14,157 @@ where
   // The root-relative reference after the fragment and slash (`#/`) (e.g., components/schemas/Foo).
   pub root_rel_ref: &'a str,
   pub target: T,
-  pub target_name: &'a str,
+  // The unescaped name of the target (i.e., the last component of the reference).
+  pub target_name: Cow<'a, str>,
+}
+
+/// Escape a JSON Pointer reference token (see RFC 6901).
+pub(crate) fn escape_pointer_token(token: &str) -> Cow<'_, str> {
+  if token.contains(['~', '/']) {
+    Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+  } else {
+    Cow::Borrowed(token)
+  }
+}
+
+/// Unescape a JSON Pointer reference token (see RFC 6901).
+pub(crate) fn unescape_pointer_token(token: &str) -> Cow<'_, str> {
+  if token.contains('~') {
+    // Per RFC 6901, `~1` must be replaced before `~0` so that `~01` becomes `~1` rather than `/`.
+    Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+  } else {
+    Cow::Borrowed(token)
+  }
+}
+
+/// Split a reference into the (possibly empty) relative document path and the root-relative
+/// reference after the fragment and slash (`#/`).
+fn split_reference(reference: &str) -> Result<(&str, &str), String> {
+  let ref_parts = reference.split('#').collect::<Vec<_>>();
+  if ref_parts.len() != 2 || !ref_parts[1].starts_with('/') {
+    return Err(format!("invalid reference: {reference}"));
+  }
+
+  Ok((ref_parts[0], &ref_parts[1][1..]))
+}
+
+/// Find the mapping referenced by a root-relative reference within a document, along with the
+/// unescaped name of the target (i.e., the last component of the reference).
+fn find_in_doc<'d, 'a>(
+  doc: &'d serde_yaml::Mapping,
+  reference: &str,
+  rel_ref: &'a str,
+) -> Result<(&'d serde_yaml::Mapping, Cow<'a, str>), String> {
+  rel_ref.split('/').try_fold(
+    (doc, Cow::Borrowed("")),
+    |(doc_context, _), ref_component| {
+      let key = unescape_pointer_token(ref_component);
+      let target_doc_context = doc_context.get(key.as_ref()).ok_or_else(|| {
+        format!("invalid reference `{reference}`: path component `{ref_component}` not found")
+      })?;
+      if let serde_yaml::Value::Mapping(next_doc_context) = target_doc_context {
+        Ok((next_doc_context, key))
+      } else {
+        Err(format!(
+          "invalid reference `{reference}`: must be a mapping, but found {target_doc_context:#?}"
+        ))
+      }
+    },
+  )
+}
+
+/// Parse an OpenAPI document as JSON if its path has a `.json` extension, or as YAML otherwise.
+pub(crate) fn parse_doc<R>(doc_path: &Path, reader: R) -> Result<serde_yaml::Mapping, String>
+where
+  R: Read,
+{
+  let is_json = doc_path
+    .extension()
+    .map_or(false, |extension| extension.eq_ignore_ascii_case("json"));
+  if is_json {
+    serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_reader(reader))
+      .map_err(|err| format!("failed to parse as JSON: {err}"))
+  } else {
+    serde_path_to_error::deserialize(serde_yaml::Deserializer::from_reader(reader))
+      .map_err(|err| format!("failed to parse as YAML: {err}"))
+  }
+}
+
+/// Path of the document containing the target of a (possibly external) reference.
+pub(crate) fn reference_doc_path(
+  referrer_doc_path: &Path,
+  reference: &str,
+) -> Result<PathBuf, String> {
+  let (rel_path, _) = split_reference(reference)
+    .map_err(|err| format!("{err} (referrer: {})", referrer_doc_path.display()))?;
+
+  if rel_path.is_empty() {
+    return Ok(PathBuf::from(referrer_doc_path));
+  }
+
+  // References are URIs, so the document path may be percent-encoded (e.g.,
+  // `common%20types.yaml#/components/schemas/Foo`).
+  let rel_path = urlencoding::decode(rel_path).map_err(|err| {
+    format!("invalid reference `{reference}`: failed to percent-decode document path: {err}")
+  })?;
+  Ok(
+    referrer_doc_path
+      .parent()
+      .ok_or_else(|| {
+        format!(
+          "invalid reference `{reference}`: referrer {} has no parent directory",
+          referrer_doc_path.display()
+        )
+      })?
+      .join(rel_path.as_ref()),
+  )
+}
+
+/// Find the mapping targeted by a (possibly external) reference without deserializing it.
+///
+/// Returns the path of the document containing the target along with the target mapping. External
+/// documents are loaded into `cached_external_docs` as needed.
+pub fn find_reference_target<'d>(
+  referrer_doc_path: &Path,
+  reference: &str,
+  cached_external_docs: &'d mut DocCache,
+) -> Result<(PathBuf, &'d serde_yaml::Mapping), String> {
+  let (_, rel_ref) = split_reference(reference)
+    .map_err(|err| format!("{err} (referrer: {})", referrer_doc_path.display()))?;
+  let doc_path = reference_doc_path(referrer_doc_path, reference)?;
+
+  let doc = cached_external_docs.load(&doc_path)?;
+
+  let (reference_target, _) = find_in_doc(doc, reference, rel_ref)?;
+  Ok((doc_path, reference_target))
 }
 
 pub fn resolve_reference<'a, T>(
   referrer_doc_path: &Path,
   reference: &'a str,
   cached_external_docs: &mut DocCache,
-) -> (PathBuf, ResolvedReference<'a, T>)
+) -> Result<(PathBuf, ResolvedReference<'a, T>), CodegenError>
 where
   T: DeserializeOwned,
 {
-  let ref_parts = reference.split('#').collect::<Vec<_>>();
-  if ref_parts.len() != 2 || !ref_parts[1].starts_with('/') {
-    panic!(
-      "invalid reference: {reference} (referrer: {})",
-      referrer_doc_path.display()
-    );
-  }
-
-  let (rel_path, rel_ref) = (ref_parts[0], &ref_parts[1][1..]);
-  let doc_path = if ref_parts[0].is_empty() {
-    PathBuf::from(referrer_doc_path)
-  } else {
-    PathBuf::from(referrer_doc_path)
-      .parent()
-      .unwrap()
-      .join(rel_path)
-  };
-  let doc: &serde_yaml::Mapping =
-    cached_external_docs
-      .entry(doc_path.clone())
-      .or_insert_with(|| {
-        println!("cargo:rerun-if-changed={}", doc_path.display());
-        let doc_file = File::open(&doc_path)
-          .unwrap_or_else(|err| panic!("failed to open {}: {err}", doc_path.to_string_lossy()));
-        serde_path_to_error::deserialize(serde_yaml::Deserializer::from_reader(&doc_file))
-          .unwrap_or_else(|err| {
-            panic!(
-              "failed to parse external OpenAPI doc {}: {err}",
-              doc_path.display()
-            )
-          })
-      });
+  let (_, rel_ref) = split_reference(reference).map_err(|err| {
+    CodegenError::InvalidReference(format!("{err} (referrer: {})", referrer_doc_path.display()))
+  })?;
+  let reference_target_name =
+    unescape_pointer_token(rel_ref.rsplit('/').next().unwrap_or_default());
 
-  let (reference_target, reference_target_name) =
-    rel_ref
-      .split('/')
-      .fold((doc, ""), |(doc_context, _), ref_component| {
-        let target_doc_context = doc_context.get(ref_component).unwrap_or_else(|| {
-          panic!(
-            "invalid reference `{reference}`: path component `{ref_component}` not found in \
-                 {doc_context:#?}"
-          )
-        });
-        if let serde_yaml::Value::Mapping(next_doc_context) = target_doc_context {
-          (next_doc_context, ref_component)
-        } else {
-          panic!(
-            "invalid reference `{reference}`: must be a mapping, but found {target_doc_context:#?}"
-          );
-        }
-      });
+  let (doc_path, reference_target) =
+    find_reference_target(referrer_doc_path, reference, cached_external_docs)
+      .map_err(CodegenError::InvalidReference)?;
 
   let target_ref_or_item: ReferenceOr<T> =
     serde_path_to_error::deserialize(serde_yaml::Value::Mapping(reference_target.to_owned()))
-      .unwrap_or_else(|err| {
-        panic!(
+      .map_err(|err| {
+        CodegenError::InvalidReference(format!(
           "failed to deserialize value referenced by `{reference}` (relative to {}): {err}",
           referrer_doc_path.display()
-        )
-      });
+        ))
+      })?;
 
   match target_ref_or_item {
     ReferenceOr::Reference {
@@ -95,76 +176,401 @@ where
       // completes, there are no reference chains (in particular, schema reference chains) reachable
       // from any of the operations. That way, when we generate the models, we'll know that any
       // schema references point directly to named schemas.
-      unimplemented!(
-        "reference chains (references to references): `{reference}` -> `{inner_reference}`"
-      );
+      Err(CodegenError::InvalidReference(format!(
+        "reference chains (references to references) are not supported: `{reference}` -> \
+         `{inner_reference}`"
+      )))
     }
-    ReferenceOr::Item(target) => (
+    ReferenceOr::Item(target) => Ok((
       doc_path,
       ResolvedReference {
         root_rel_ref: rel_ref,
         target,
         target_name: reference_target_name,
       },
-    ),
+    )),
   }
 }
 
 pub fn resolve_local_reference<'a, T>(
   reference: &'a str,
   openapi_inline: &serde_yaml::Mapping,
-) -> ResolvedReference<'a, T>
+) -> Result<ResolvedReference<'a, T>, CodegenError>
 where
   T: DeserializeOwned,
 {
-  let ref_parts = reference.split('#').collect::<Vec<_>>();
-  if ref_parts.len() != 2 || !ref_parts[1].starts_with('/') {
-    panic!("invalid reference: {reference}");
-  }
-
-  let (rel_path, rel_ref) = (ref_parts[0], &ref_parts[1][1..]);
+  let (rel_path, rel_ref) = split_reference(reference).map_err(CodegenError::InvalidReference)?;
   if !rel_path.is_empty() {
-    panic!("unexpected non-local reference: {reference}")
+    return Err(CodegenError::InvalidReference(format!(
+      "unexpected non-local reference: {reference}"
+    )));
   }
 
   let (reference_target, reference_target_name) =
-    rel_ref
-      .split('/')
-      .fold((openapi_inline, ""), |(doc_context, _), ref_component| {
-        let target_doc_context = doc_context.get(ref_component).unwrap_or_else(|| {
-          panic!(
-            "invalid reference `{reference}`: path component `{ref_component}` not found in \
-                 {doc_context:#?}"
-          )
-        });
-        if let serde_yaml::Value::Mapping(next_doc_context) = target_doc_context {
-          (next_doc_context, ref_component)
-        } else {
-          panic!(
-            "invalid reference `{reference}`: must be a mapping, but found {target_doc_context:#?}"
-          );
-        }
-      });
+    find_in_doc(openapi_inline, reference, rel_ref).map_err(CodegenError::InvalidReference)?;
 
   let target_ref_or_item: ReferenceOr<T> =
     serde_path_to_error::deserialize(serde_yaml::Value::Mapping(reference_target.to_owned()))
-      .unwrap_or_else(|err| {
-        panic!("failed to deserialize local value referenced by `{reference}`: {err}");
-      });
+      .map_err(|err| {
+        CodegenError::InvalidReference(format!(
+          "failed to deserialize local value referenced by `{reference}`: {err}"
+        ))
+      })?;
 
   match target_ref_or_item {
     ReferenceOr::Reference {
       reference: inner_reference,
     } => {
       // See note above.
-      unimplemented!(
-        "reference chains (references to references): `{reference}` -> `{inner_reference}`"
-      );
+      Err(CodegenError::InvalidReference(format!(
+        "reference chains (references to references) are not supported: `{reference}` -> \
+         `{inner_reference}`"
+      )))
     }
-    ReferenceOr::Item(target) => ResolvedReference {
+    ReferenceOr::Item(target) => Ok(ResolvedReference {
       root_rel_ref: rel_ref,
       target,
       target_name: reference_target_name,
-    },
+    }),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::inline::InlineApi;
+  use crate::reference::{resolve_local_reference, resolve_reference};
+  use crate::{CodeGenerator, CodegenError, DocCache};
+
+  use openapiv3::{Callback, OpenAPI, Parameter, Schema, SchemaKind, Type};
+  use pretty_assertions::assert_eq;
+  use proptest::prelude::*;
+
+  use std::path::Path;
+
+  const OPENAPI_YAML: &str = r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths: {}
+components:
+  parameters:
+    FooId:
+      name: foo_id
+      in: path
+      required: true
+      schema:
+        type: string
+  schemas:
+    Foo:
+      type: object
+      properties:
+        foo_id:
+          type: string
+    FooAlias:
+      $ref: "#/components/schemas/Foo"
+    application/vnd.foo~v1:
+      type: string
+"##;
+
+  fn openapi_yaml() -> serde_yaml::Mapping {
+    serde_yaml::from_str(OPENAPI_YAML).unwrap()
+  }
+
+  fn doc_cache() -> DocCache {
+    [(Path::new("openapi.yaml").to_path_buf(), openapi_yaml())]
+      .into_iter()
+      .collect()
+  }
+
+  fn resolve_schema(reference: &str) -> Result<(), CodegenError> {
+    resolve_reference::<Schema>(Path::new("openapi.yaml"), reference, &mut doc_cache()).map(|_| ())
+  }
+
+  fn resolve_local_schema(reference: &str) -> Result<(), CodegenError> {
+    resolve_local_reference::<Schema>(reference, &openapi_yaml()).map(|_| ())
+  }
+
+  fn inline(reference: &str) -> Result<InlineApi, CodegenError> {
+    let openapi = serde_yaml::from_str::<OpenAPI>(&format!(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    get:
+      operationId: getFoo
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                $ref: "{reference}"
+"##
+    ))
+    .unwrap();
+
+    CodeGenerator::new("openapi.yaml", ".openapi-lambda").inline_openapi(
+      openapi,
+      Callback::new(),
+      doc_cache(),
+    )
+  }
+
+  #[test]
+  fn test_external_json_reference() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    // Tabs aren't valid YAML indentation, so this only parses as JSON.
+    std::fs::write(
+      dir.join("other.json"),
+      "{\n\t\"components\": {\n\t\t\"schemas\": {\n\t\t\t\"Bar\": {\"type\": \"string\"}\n\t\t}\n\t}\n}",
+    )
+    .unwrap();
+
+    let resolved = resolve_reference::<Schema>(
+      &dir.join("openapi.json"),
+      "other.json#/components/schemas/Bar",
+      &mut DocCache::new(),
+    );
+
+    let (doc_path, resolved) = resolved.unwrap();
+    assert_eq!(doc_path, dir.join("other.json"));
+    assert_eq!(resolved.target_name, "Bar");
+    assert!(matches!(
+      resolved.target.schema_kind,
+      SchemaKind::Type(Type::String(_))
+    ));
+  }
+
+  #[test]
+  fn test_percent_encoded_external_reference() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dir = temp_dir.path();
+    std::fs::write(
+      dir.join("common types.yaml"),
+      "components:\n  schemas:\n    Bar:\n      type: string\n",
+    )
+    .unwrap();
+
+    let resolved = resolve_reference::<Schema>(
+      &dir.join("openapi.yaml"),
+      "common%20types.yaml#/components/schemas/Bar",
+      &mut DocCache::new(),
+    );
+    let invalid = resolve_reference::<Schema>(
+      &dir.join("openapi.yaml"),
+      "common%FFtypes.yaml#/components/schemas/Bar",
+      &mut DocCache::new(),
+    );
+
+    let (doc_path, resolved) = resolved.unwrap();
+    assert_eq!(doc_path, dir.join("common types.yaml"));
+    assert_eq!(resolved.target_name, "Bar");
+
+    let Err(CodegenError::InvalidReference(err)) = invalid else {
+      panic!("expected error");
+    };
+    assert!(
+      err.starts_with(
+        "invalid reference `common%FFtypes.yaml#/components/schemas/Bar`: failed to \
+         percent-decode document path: "
+      ),
+      "{err}"
+    );
+  }
+
+  #[test]
+  fn test_valid_references() {
+    let resolved = resolve_reference::<Schema>(
+      Path::new("openapi.yaml"),
+      "#/components/schemas/Foo",
+      &mut doc_cache(),
+    )
+    .unwrap()
+    .1;
+    assert_eq!(resolved.root_rel_ref, "components/schemas/Foo");
+    assert_eq!(resolved.target_name, "Foo");
+
+    let resolved =
+      resolve_local_reference::<Parameter>("#/components/parameters/FooId", &openapi_yaml())
+        .unwrap();
+    assert_eq!(resolved.target.parameter_data_ref().name, "foo_id");
+  }
+
+  #[test]
+  fn test_escaped_references() {
+    let resolved = resolve_reference::<Schema>(
+      Path::new("openapi.yaml"),
+      "#/components/schemas/application~1vnd.foo~0v1",
+      &mut doc_cache(),
+    )
+    .unwrap()
+    .1;
+    assert_eq!(
+      resolved.root_rel_ref,
+      "components/schemas/application~1vnd.foo~0v1"
+    );
+    assert_eq!(resolved.target_name, "application/vnd.foo~v1");
+
+    let resolved = resolve_local_reference::<Schema>(
+      "#/components/schemas/application~1vnd.foo~0v1",
+      &openapi_yaml(),
+    )
+    .unwrap();
+    assert_eq!(resolved.target_name, "application/vnd.foo~v1");
+    assert!(matches!(
+      resolved.target.schema_kind,
+      SchemaKind::Type(Type::String(_))
+    ));
+
+    // `~01` unescapes to `~1` rather than `/`.
+    assert_eq!(
+      resolve_local_schema("#/components/schemas/application~01vnd.foo~0v1"),
+      Err(CodegenError::InvalidReference(
+        "invalid reference `#/components/schemas/application~01vnd.foo~0v1`: path component \
+         `application~01vnd.foo~0v1` not found"
+          .to_string()
+      ))
+    );
+    assert!(inline("#/components/schemas/application~1vnd.foo~0v1").is_ok());
+  }
+
+  #[test]
+  fn test_malformed_references() {
+    for (reference, expected_err) in [
+      ("", "invalid reference: "),
+      ("#", "invalid reference: #"),
+      (
+        "#components/schemas/Foo",
+        "invalid reference: #components/schemas/Foo",
+      ),
+      (
+        "#/components#/schemas/Foo",
+        "invalid reference: #/components#/schemas/Foo",
+      ),
+      ("#/", "invalid reference `#/`: path component `` not found"),
+      (
+        "#/components/schemas/",
+        "invalid reference `#/components/schemas/`: path component `` not found",
+      ),
+      (
+        "#/components/schemas/Bar",
+        "invalid reference `#/components/schemas/Bar`: path component `Bar` not found",
+      ),
+      (
+        "#/components/schemas/Foo/type",
+        "invalid reference `#/components/schemas/Foo/type`: must be a mapping, but found \
+         String(\"object\")",
+      ),
+      (
+        "#/components/schemas/FooAlias",
+        "reference chains (references to references) are not supported: \
+         `#/components/schemas/FooAlias` -> `#/components/schemas/Foo`",
+      ),
+    ] {
+      assert_eq!(
+        resolve_local_schema(reference),
+        Err(CodegenError::InvalidReference(expected_err.to_string())),
+        "{reference}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_malformed_external_references() {
+    assert_eq!(
+      resolve_schema("#components/schemas/Foo"),
+      Err(CodegenError::InvalidReference(
+        "invalid reference: #components/schemas/Foo (referrer: openapi.yaml)".to_string()
+      ))
+    );
+
+    let Err(CodegenError::InvalidReference(err)) =
+      resolve_schema("missing.yaml#/components/schemas/Foo")
+    else {
+      panic!("expected error");
+    };
+    assert!(err.starts_with("failed to open missing.yaml: "), "{err}");
+
+    assert_eq!(
+      resolve_local_schema("other.yaml#/components/schemas/Foo"),
+      Err(CodegenError::InvalidReference(
+        "unexpected non-local reference: other.yaml#/components/schemas/Foo".to_string()
+      ))
+    );
+
+    let Err(CodegenError::InvalidReference(err)) =
+      resolve_reference::<Schema>(Path::new("/"), "foo.yaml#/Foo", &mut doc_cache())
+    else {
+      panic!("expected error");
+    };
+    assert_eq!(
+      err,
+      "invalid reference `foo.yaml#/Foo`: referrer / has no parent directory"
+    );
+  }
+
+  #[test]
+  fn test_wrong_target_type() {
+    let Err(CodegenError::InvalidReference(err)) =
+      resolve_local_reference::<Parameter>("#/components/schemas/Foo", &openapi_yaml())
+    else {
+      panic!("expected error");
+    };
+    assert!(
+      err.starts_with(
+        "failed to deserialize local value referenced by `#/components/schemas/Foo`: "
+      ),
+      "{err}"
+    );
+  }
+
+  #[test]
+  fn test_inline_malformed_reference() {
+    assert!(inline("#/components/schemas/Foo").is_ok());
+    assert_eq!(
+      inline("#/components/schemas/Bar").unwrap_err(),
+      CodegenError::InvalidReference(
+        "invalid reference `#/components/schemas/Bar`: path component `Bar` not found".to_string()
+      )
+    );
+  }
+
+  proptest! {
+    #[test]
+    fn test_arbitrary_references_do_not_panic(reference in r"[a-z./#~{}]{0,32}") {
+      let _ = resolve_schema(&reference);
+      let _ = resolve_local_schema(&reference);
+      let _ = inline(&reference);
+    }
+
+    #[test]
+    fn test_arbitrary_local_references_do_not_panic(
+      components in prop::collection::vec(
+        prop_oneof![
+          Just("components".to_string()),
+          Just("schemas".to_string()),
+          Just("parameters".to_string()),
+          Just("Foo".to_string()),
+          Just("FooAlias".to_string()),
+          Just("FooId".to_string()),
+          Just("properties".to_string()),
+          Just("foo_id".to_string()),
+          Just("type".to_string()),
+          r"[a-zA-Z0-9_~#]{0,8}",
+        ],
+        0..6,
+      ),
+    ) {
+      let reference = format!("#/{}", components.join("/"));
+      let _ = resolve_schema(&reference);
+      let _ = resolve_local_schema(&reference);
+      let _ = resolve_local_reference::<Parameter>(&reference, &openapi_yaml());
+      let _ = inline(&reference);
+    }
   }
 }
@@ -0,0 +1,40 @@
+use serde_json::json;
+
+/// Amazon API Gateway
+/// [request validation](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-method-request-validation.html)
+/// mode.
+///
+/// See [`CodeGenerator::with_request_validation`](crate::CodeGenerator::with_request_validation).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RequestValidation {
+  /// Don't validate requests in API Gateway (the default).
+  #[default]
+  None,
+  /// Validate required request parameters (path, query string, and header parameters).
+  ParamsOnly,
+  /// Validate request bodies against the request body schemas.
+  BodyOnly,
+  /// Validate both request parameters and request bodies.
+  All,
+}
+
+impl RequestValidation {
+  /// Name and definition of the corresponding entry in the
+  /// `x-amazon-apigateway-request-validators` extension, if any.
+  pub(crate) fn validator(&self) -> Option<(&'static str, serde_json::Value)> {
+    let (name, validate_body, validate_params) = match self {
+      RequestValidation::None => return None,
+      RequestValidation::ParamsOnly => ("params-only", false, true),
+      RequestValidation::BodyOnly => ("body-only", true, false),
+      RequestValidation::All => ("all", true, true),
+    };
+    Some((
+      name,
+      json!({
+        "validateRequestBody": validate_body,
+        "validateRequestParameters": validate_params,
+      }),
+    ))
+  }
+}
@@ -0,0 +1,51 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Rust type used to represent arrays with `uniqueItems: true`.
+///
+/// If the item type doesn't implement the traits required by the selected set type (e.g., `Hash`
+/// for floating-point numbers or objects that don't derive it), the generated code instead uses
+/// `openapi_lambda::models::UniqueVec`, which rejects duplicate items during deserialization.
+///
+/// See [`CodeGenerator::with_set_type`](crate::CodeGenerator::with_set_type).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum SetType {
+  /// Use [`IndexSet`](https://docs.rs/indexmap/latest/indexmap/set/struct.IndexSet.html)
+  /// (re-exported as `openapi_lambda::models::IndexSet`), which preserves insertion order and
+  /// requires items to implement `Hash` and `Eq` (the default).
+  #[default]
+  IndexSet,
+  /// Use [`std::collections::BTreeSet`], which sorts items and requires them to implement `Ord`.
+  BTreeSet,
+  /// Use `openapi_lambda::models::UniqueVec`, which preserves order and only requires items to
+  /// implement `PartialEq`, but checks uniqueness in quadratic time.
+  UniqueVec,
+}
+
+/// Trait that the items of a [`SetType`] must implement.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum SetItemTrait {
+  Hash,
+  Ord,
+}
+
+impl SetType {
+  /// Trait required of the item type (in addition to `PartialEq`), if any.
+  pub(crate) fn item_trait(&self) -> Option<SetItemTrait> {
+    match self {
+      SetType::IndexSet => Some(SetItemTrait::Hash),
+      SetType::BTreeSet => Some(SetItemTrait::Ord),
+      SetType::UniqueVec => None,
+    }
+  }
+
+  /// Type of a set of `item_type` values.
+  pub(crate) fn set_type(&self, crate_import: &syn::Path, item_type: TokenStream) -> TokenStream {
+    match self {
+      SetType::IndexSet => quote! { #crate_import::models::IndexSet<#item_type> },
+      SetType::BTreeSet => quote! { std::collections::BTreeSet<#item_type> },
+      SetType::UniqueVec => quote! { #crate_import::models::UniqueVec<#item_type> },
+    }
+  }
+}
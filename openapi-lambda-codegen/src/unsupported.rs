@@ -0,0 +1,825 @@
+use crate::api::{is_array_format, is_array_param};
+use crate::model::{is_object_enum_schema, schema_rust_type, CONFLICTING_CONST_EXTENSION};
+use crate::validate::{http_method, pointer_location};
+use crate::{CodeGenerator, DateTimeBackend, ValidationIssue};
+
+use indexmap::IndexMap;
+use openapiv3::{
+  AdditionalProperties, AnySchema, ArrayType, BooleanType, Components, Header, IntegerType,
+  MediaType, NumberType, OpenAPI, Operation, Parameter, ParameterSchemaOrContent, QueryStyle,
+  ReferenceOr, RequestBody, Response, Responses, Schema, SchemaKind, StringFormat, StringType,
+  Type,
+};
+
+use std::borrow::Borrow;
+use std::path::Path;
+
+impl CodeGenerator {
+  /// Find every construct in the (inlined) OpenAPI definition that code generation doesn't
+  /// support.
+  ///
+  /// Rather than stopping at the first unsupported construct, this reports all of them together
+  /// along with their locations so that users can fix them in a single pass. Unsupported schemas
+  /// are reported wherever they appear, while unsupported parameters, request bodies, and responses
+  /// are only reported for operations that code is generated for (i.e., those handled by an
+  /// [`ApiLambda`](crate::ApiLambda) or included in a generated client).
+  pub(crate) fn find_unsupported_features(&self, openapi: &OpenAPI) -> Vec<ValidationIssue> {
+    self.visit_unsupported_features(openapi).issues
+  }
+
+  /// Find every schema in the (inlined) OpenAPI definition that the OpenAPI parser accepts but that
+  /// code generation can't interpret (e.g., due to a malformed extension such as `x-rust-derives`).
+  ///
+  /// Like [`find_unsupported_features`](CodeGenerator::find_unsupported_features), this reports
+  /// all such schemas together along with their locations.
+  pub(crate) fn find_invalid_schemas(&self, openapi: &OpenAPI) -> Vec<ValidationIssue> {
+    self.visit_unsupported_features(openapi).invalid_schemas
+  }
+
+  fn visit_unsupported_features<'a>(
+    &'a self,
+    openapi: &'a OpenAPI,
+  ) -> UnsupportedFeatureVisitor<'a> {
+    let mut visitor = UnsupportedFeatureVisitor {
+      code_generator: self,
+      components: openapi.components.as_ref(),
+      openapi_path: &self.openapi_path,
+      pointer: Vec::new(),
+      issues: Vec::new(),
+      invalid_schemas: Vec::new(),
+    };
+    visitor.visit_openapi(openapi);
+    visitor
+  }
+
+  /// Whether code is generated for the operation (as opposed to only its models).
+  fn is_operation_generated(&self, method: &str, request_path: &str, op: &Operation) -> bool {
+    self.client_mod_name.is_some()
+      || self
+        .api_lambda_for_operation(&http_method(method), request_path, op)
+        .is_some()
+  }
+}
+
+/// Resolve a reference to an object in `components` (e.g., `#/components/parameters/Foo`), or
+/// return the inline object. References that can't be resolved were already reported as invalid.
+fn resolve_component<'b, T>(
+  ref_or_item: &'b ReferenceOr<T>,
+  prefix: &str,
+  components: Option<&'b IndexMap<String, ReferenceOr<T>>>,
+) -> Option<&'b T> {
+  match ref_or_item {
+    ReferenceOr::Item(item) => Some(item),
+    ReferenceOr::Reference { reference } => {
+      components?.get(reference.strip_prefix(prefix)?)?.as_item()
+    }
+  }
+}
+
+struct UnsupportedFeatureVisitor<'a> {
+  code_generator: &'a CodeGenerator,
+  components: Option<&'a Components>,
+  openapi_path: &'a Path,
+  /// JSON Pointer reference tokens of the location currently being visited.
+  pointer: Vec<String>,
+  issues: Vec<ValidationIssue>,
+  invalid_schemas: Vec<ValidationIssue>,
+}
+
+impl<'a> UnsupportedFeatureVisitor<'a> {
+  /// Visit a nested location identified by the specified JSON Pointer reference tokens.
+  fn nested<F>(&mut self, tokens: &[&str], visit: F)
+  where
+    F: FnOnce(&mut Self),
+  {
+    let depth = self.pointer.len();
+    self
+      .pointer
+      .extend(tokens.iter().map(|token| token.to_string()));
+    visit(self);
+    self.pointer.truncate(depth);
+  }
+
+  fn report<M>(&mut self, message: M)
+  where
+    M: Into<String>,
+  {
+    self.issues.push(ValidationIssue::new(
+      pointer_location(self.openapi_path, &self.pointer),
+      message,
+    ));
+  }
+
+  fn report_invalid_schema<M>(&mut self, message: M)
+  where
+    M: Into<String>,
+  {
+    self.invalid_schemas.push(ValidationIssue::new(
+      pointer_location(self.openapi_path, &self.pointer),
+      message,
+    ));
+  }
+
+  fn visit_openapi(&mut self, openapi: &OpenAPI) {
+    if let Some(components) = &openapi.components {
+      // Objects in `components` are only checked for unsupported schemas here. Any other
+      // unsupported features are reported where they're referenced by generated operations.
+      self.nested(&["components"], |this| {
+        for (name, schema) in &components.schemas {
+          this.nested(&["schemas", name], |this| this.visit_ref_or_schema(schema));
+        }
+        for (name, response) in &components.responses {
+          if let ReferenceOr::Item(response) = response {
+            this.nested(&["responses", name], |this| {
+              this.visit_response(response, false)
+            });
+          }
+        }
+        for (name, parameter) in &components.parameters {
+          if let ReferenceOr::Item(parameter) = parameter {
+            this.nested(&["parameters", name], |this| {
+              this.visit_parameter_schema_or_content(&parameter.parameter_data_ref().format)
+            });
+          }
+        }
+        for (name, request_body) in &components.request_bodies {
+          if let ReferenceOr::Item(request_body) = request_body {
+            this.nested(&["requestBodies", name], |this| {
+              this.visit_request_body(request_body, false)
+            });
+          }
+        }
+        for (name, header) in &components.headers {
+          if let ReferenceOr::Item(header) = header {
+            this.nested(&["headers", name], |this| this.visit_header(header));
+          }
+        }
+      });
+    }
+
+    for (request_path, path_item) in &openapi.paths.paths {
+      // References to path items in other files have already been inlined.
+      let ReferenceOr::Item(path_item) = path_item else {
+        continue;
+      };
+      self.nested(&["paths", request_path], |this| {
+        // We don't generate any code (including models) for operations without an operation ID.
+        let operations = path_item
+          .iter()
+          .filter(|(_, op)| op.operation_id.is_some())
+          .collect::<Vec<_>>();
+        let any_generated = operations.iter().any(|(method, op)| {
+          this
+            .code_generator
+            .is_operation_generated(method, request_path, op)
+        });
+
+        this.visit_parameters(&path_item.parameters, any_generated);
+        for (method, op) in operations {
+          this.nested(&[method], |this| {
+            this.visit_operation(method, request_path, op)
+          });
+        }
+      });
+    }
+  }
+
+  fn visit_operation(&mut self, method: &str, request_path: &str, op: &Operation) {
+    let generated = self
+      .code_generator
+      .is_operation_generated(method, request_path, op);
+
+    self.visit_parameters(&op.parameters, generated);
+    if let Some(request_body) = &op.request_body {
+      self.nested(&["requestBody"], |this| {
+        let request_bodies = this.components.map(|components| &components.request_bodies);
+        match request_body {
+          ReferenceOr::Item(request_body) => this.visit_request_body(request_body, generated),
+          reference if generated => {
+            if let Some(request_body) =
+              resolve_component(reference, "#/components/requestBodies/", request_bodies)
+            {
+              this.check_request_body(request_body);
+            }
+          }
+          ReferenceOr::Reference { .. } => {}
+        }
+      });
+    }
+    self.nested(&["responses"], |this| {
+      this.visit_responses(&op.responses, generated)
+    });
+  }
+
+  fn visit_parameters(&mut self, parameters: &[ReferenceOr<Parameter>], generated: bool) {
+    let components_parameters = self.components.map(|components| &components.parameters);
+    for (idx, parameter) in parameters.iter().enumerate() {
+      self.nested(&["parameters", &idx.to_string()], |this| {
+        if let ReferenceOr::Item(parameter) = parameter {
+          this.visit_parameter_schema_or_content(&parameter.parameter_data_ref().format);
+        }
+        if generated {
+          if let Some(parameter) =
+            resolve_component(parameter, "#/components/parameters/", components_parameters)
+          {
+            this.check_parameter(parameter);
+          }
+        }
+      });
+    }
+  }
+
+  fn check_parameter(&mut self, parameter: &Parameter) {
+    let parameter_data = parameter.parameter_data_ref();
+    if matches!(parameter_data.format, ParameterSchemaOrContent::Content(_)) {
+      self.report("parameters defined using `content` are not supported");
+    }
+    match parameter {
+      Parameter::Cookie { .. } => self.report("cookie parameters are not supported"),
+      Parameter::Header { .. } if is_array_param(parameter_data) => {
+        self.report("array header parameters are not supported")
+      }
+      Parameter::Query {
+        style: QueryStyle::DeepObject,
+        ..
+      } if !self.is_object_format(&parameter_data.format) => {
+        self.report("`deepObject` query parameters must have an object schema")
+      }
+      Parameter::Query {
+        style: QueryStyle::SpaceDelimited | QueryStyle::PipeDelimited,
+        ..
+      } if !is_array_param(parameter_data) => self.report(
+        "`spaceDelimited` and `pipeDelimited` query parameters must have an inline array schema",
+      ),
+      Parameter::Header { .. } | Parameter::Path { .. } | Parameter::Query { .. } => {}
+    }
+  }
+
+  /// Whether a parameter's schema is (or references) an object schema.
+  fn is_object_format(&self, format: &ParameterSchemaOrContent) -> bool {
+    let ParameterSchemaOrContent::Schema(ref_or_schema) = format else {
+      return false;
+    };
+    let components_schemas = self.components.map(|components| &components.schemas);
+    matches!(
+      resolve_component(ref_or_schema, "#/components/schemas/", components_schemas),
+      Some(Schema {
+        schema_kind: SchemaKind::Type(Type::Object(_)),
+        ..
+      })
+    )
+  }
+
+  fn visit_header(&mut self, header: &Header) {
+    self.visit_parameter_schema_or_content(&header.format);
+  }
+
+  fn visit_parameter_schema_or_content(&mut self, format: &ParameterSchemaOrContent) {
+    match format {
+      ParameterSchemaOrContent::Schema(schema) => self.nested(&["schema"], |this| match schema {
+        // Parameters aren't (de)serialized using serde, so array parameters support `date-time`
+        // items regardless of the `DateTimeBackend`.
+        ReferenceOr::Item(Schema {
+          schema_kind:
+            SchemaKind::Type(Type::Array(ArrayType {
+              items: Some(items), ..
+            })),
+          ..
+        }) => this.nested(&["items"], |this| this.visit_ref_or_schema(items)),
+        _ => this.visit_ref_or_schema(schema),
+      }),
+      ParameterSchemaOrContent::Content(content) => {
+        for (mime_type, media_type) in content {
+          self.nested(&["content", mime_type], |this| {
+            this.visit_media_type(media_type)
+          });
+        }
+      }
+    }
+  }
+
+  fn visit_request_body(&mut self, request_body: &RequestBody, generated: bool) {
+    for (mime_type, media_type) in &request_body.content {
+      self.nested(&["content", mime_type], |this| {
+        this.visit_media_type(media_type)
+      });
+    }
+    if generated {
+      self.check_request_body(request_body);
+    }
+  }
+
+  fn check_request_body(&mut self, request_body: &RequestBody) {
+    if request_body.content.len() > 1 {
+      self.report("request bodies with more than one media type are not supported");
+    }
+  }
+
+  fn visit_responses(&mut self, responses: &Responses, generated: bool) {
+    let components_responses = self.components.map(|components| &components.responses);
+    let default = responses
+      .default
+      .as_ref()
+      .map(|response| ("default".to_string(), response));
+    let by_status = responses
+      .responses
+      .iter()
+      .map(|(status_code, response)| (status_code.to_string(), response));
+    for (token, response) in default.into_iter().chain(by_status) {
+      self.nested(&[&token], |this| match response {
+        ReferenceOr::Item(response) => this.visit_response(response, generated),
+        reference if generated => {
+          if let Some(response) =
+            resolve_component(reference, "#/components/responses/", components_responses)
+          {
+            this.check_response(response);
+          }
+        }
+        ReferenceOr::Reference { .. } => {}
+      });
+    }
+  }
+
+  fn visit_response(&mut self, response: &Response, generated: bool) {
+    for (name, header) in &response.headers {
+      if let ReferenceOr::Item(header) = header {
+        self.nested(&["headers", name], |this| this.visit_header(header));
+      }
+    }
+    for (mime_type, media_type) in &response.content {
+      self.nested(&["content", mime_type], |this| {
+        this.visit_media_type(media_type)
+      });
+    }
+    if generated {
+      self.check_response(response);
+    }
+  }
+
+  fn check_response(&mut self, response: &Response) {
+    if response.content.len() > 1 {
+      self.report("responses with more than one media type are not supported");
+    }
+
+    let components_headers = self.components.map(|components| &components.headers);
+    for (name, header) in &response.headers {
+      if let Some(header) = resolve_component(header, "#/components/headers/", components_headers) {
+        self.nested(&["headers", name], |this| {
+          if matches!(header.format, ParameterSchemaOrContent::Content(_)) {
+            this.report("response headers defined using `content` are not supported");
+          } else if is_array_format(&header.format) {
+            this.report("array response headers are not supported");
+          }
+        });
+      }
+    }
+
+    let Some(multipart) = response.content.get("multipart/mixed") else {
+      return;
+    };
+    let components_schemas = self.components.map(|components| &components.schemas);
+    match &multipart.schema {
+      None => self.report("`multipart/mixed` responses must specify a schema"),
+      Some(schema) => {
+        if let Some(schema) = resolve_component(schema, "#/components/schemas/", components_schemas)
+        {
+          if !matches!(schema.schema_kind, SchemaKind::Type(Type::Object(_))) {
+            self.report("`multipart/mixed` response schemas must be objects");
+          }
+        }
+      }
+    }
+  }
+
+  fn visit_media_type(&mut self, media_type: &MediaType) {
+    if let Some(schema) = &media_type.schema {
+      self.nested(&["schema"], |this| this.visit_ref_or_schema(schema));
+    }
+  }
+
+  fn visit_ref_or_schema<T>(&mut self, ref_or_schema: &ReferenceOr<T>)
+  where
+    T: Borrow<Schema>,
+  {
+    // Reference targets are visited separately (e.g., via `components.schemas`).
+    if let ReferenceOr::Item(schema) = ref_or_schema {
+      self.visit_schema(schema.borrow());
+    }
+  }
+
+  /// Report `date-time` schemas nested within a container (e.g., an array) when
+  /// [`DateTimeBackend::Time`] is selected, since `time::OffsetDateTime` is only (de)serialized as
+  /// RFC 3339 when a struct field is annotated with `#[serde(with = ...)]`.
+  fn check_nested_date_time<T>(&mut self, ref_or_schema: &ReferenceOr<T>, container: &str)
+  where
+    T: Borrow<Schema>,
+  {
+    if self.code_generator.datetime_backend != DateTimeBackend::Time {
+      return;
+    }
+    let empty_schemas = IndexMap::new();
+    let components_schemas = self
+      .components
+      .map_or(&empty_schemas, |components| &components.schemas);
+    if self
+      .code_generator
+      .string_format(ref_or_schema, components_schemas)
+      == Some(StringFormat::DateTime)
+    {
+      self.report(format!(
+        "`date-time` {container} are not supported with `DateTimeBackend::Time`"
+      ));
+    }
+  }
+
+  fn visit_schema(&mut self, schema: &Schema) {
+    // No code is generated for schemas mapped to existing Rust types.
+    if schema_rust_type(schema).is_some() {
+      return;
+    }
+
+    let empty_schemas = IndexMap::new();
+    let components_schemas = self
+      .components
+      .map_or(&empty_schemas, |components| &components.schemas);
+    for error in self
+      .code_generator
+      .schema_errors(schema, components_schemas)
+    {
+      self.report_invalid_schema(error);
+    }
+    if schema
+      .schema_data
+      .extensions
+      .contains_key(CONFLICTING_CONST_EXTENSION)
+    {
+      self.report_invalid_schema("schemas must not specify both `const` and `enum`");
+    }
+
+    match &schema.schema_kind {
+      SchemaKind::Type(Type::Object(object)) => {
+        for (property_name, property) in &object.properties {
+          self.nested(&["properties", property_name], |this| {
+            this.visit_ref_or_schema(property)
+          });
+        }
+        if let Some(AdditionalProperties::Schema(additional_properties)) =
+          &object.additional_properties
+        {
+          self.nested(&["additionalProperties"], |this| {
+            this.check_nested_date_time(additional_properties, "`additionalProperties`");
+            this.visit_ref_or_schema(additional_properties)
+          });
+        }
+      }
+      SchemaKind::Type(Type::Array(array)) => {
+        if let Some(items) = &array.items {
+          self.nested(&["items"], |this| {
+            this.check_nested_date_time(items, "array items");
+            this.visit_ref_or_schema(items)
+          });
+        }
+      }
+      SchemaKind::Type(Type::String(StringType { enumeration, .. })) => {
+        if enumeration.contains(&None) {
+          self.report("`null` string `enum` values are not supported");
+        }
+      }
+      SchemaKind::Type(Type::Integer(IntegerType { enumeration, .. })) => {
+        if enumeration.contains(&None) {
+          self.report("`null` integer `enum` values are not supported");
+        } else if enumeration.len() > 1 {
+          self.report("integer `enum` schemas with more than one value are not supported");
+        }
+      }
+      SchemaKind::Type(Type::Number(NumberType { enumeration, .. })) => {
+        if !enumeration.is_empty() {
+          self.report("number `enum` schemas are not supported");
+        }
+      }
+      SchemaKind::Type(Type::Boolean(BooleanType { enumeration })) => {
+        if !enumeration.is_empty() {
+          self.report("boolean `enum` schemas are not supported");
+        }
+      }
+      SchemaKind::OneOf { one_of: inner } | SchemaKind::AllOf { all_of: inner } => {
+        let keyword = if matches!(schema.schema_kind, SchemaKind::OneOf { .. }) {
+          "oneOf"
+        } else {
+          "allOf"
+        };
+        for (idx, inner_schema) in inner.iter().enumerate() {
+          self.nested(&[keyword, &idx.to_string()], |this| {
+            this.visit_ref_or_schema(inner_schema)
+          });
+        }
+      }
+      SchemaKind::AnyOf { .. } => self.report("`anyOf` schemas are not supported"),
+      SchemaKind::Not { .. } => self.report("`not` schemas are not supported"),
+      SchemaKind::Any(any) if is_object_enum_schema(any) => {}
+      SchemaKind::Any(any) => {
+        if *any != AnySchema::default() {
+          self.report(
+            "schemas without a supported `type` are not supported unless they are empty or an \
+             object `enum`",
+          );
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{ApiLambda, CodeGenerator, DateTimeBackend, LambdaArn, ValidationIssue};
+
+  use openapiv3::OpenAPI;
+  use pretty_assertions::assert_eq;
+
+  fn code_generator() -> CodeGenerator {
+    CodeGenerator::new("openapi.yaml", ".openapi-lambda").add_api_lambda(
+      ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      )
+      .with_op_filter(|op| op.operation_id.as_deref() != Some("getIgnored")),
+    )
+  }
+
+  #[test]
+  fn test_find_unsupported_schemas() {
+    let openapi = serde_yaml::from_str::<OpenAPI>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo/{foo_id}:
+    get:
+      operationId: getFoo
+      parameters:
+        - name: foo_id
+          in: path
+          required: true
+          schema:
+            type: number
+            enum: [1.5, 2.5]
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  not:
+                    type: string
+components:
+  schemas:
+    Foo:
+      type: object
+      properties:
+        bar:
+          anyOf:
+            - type: string
+            - type: integer
+        baz:
+          oneOf:
+            - type: boolean
+              enum: [true]
+            - $ref: "#/components/schemas/Supported"
+    Supported:
+      type: integer
+      enum: [1]
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(
+      code_generator().find_unsupported_features(&openapi),
+      vec![
+        ValidationIssue::new(
+          "openapi.yaml#/components/schemas/Foo/properties/bar",
+          "`anyOf` schemas are not supported",
+        ),
+        ValidationIssue::new(
+          "openapi.yaml#/components/schemas/Foo/properties/baz/oneOf/0",
+          "boolean `enum` schemas are not supported",
+        ),
+        ValidationIssue::new(
+          "openapi.yaml#/paths/~1foo~1{foo_id}/get/parameters/0/schema",
+          "number `enum` schemas are not supported",
+        ),
+        ValidationIssue::new(
+          "openapi.yaml#/paths/~1foo~1{foo_id}/get/responses/200/content/application~1json/schema/\
+           items",
+          "`not` schemas are not supported",
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_find_unsupported_operation_features() {
+    let openapi = serde_yaml::from_str::<OpenAPI>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    parameters:
+      - name: session
+        in: cookie
+        schema:
+          type: string
+    post:
+      operationId: createFoo
+      parameters:
+        - name: X-Ids
+          in: header
+          schema:
+            type: array
+            items:
+              type: string
+        - name: filter
+          in: query
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  name:
+                    type: string
+        - $ref: "#/components/parameters/Session"
+        - name: sort
+          in: query
+          style: deepObject
+          schema:
+            type: string
+        - name: tags
+          in: query
+          style: pipeDelimited
+          schema:
+            type: string
+      requestBody:
+        $ref: "#/components/requestBodies/Foo"
+      responses:
+        "2XX":
+          description: Success
+          headers:
+            X-Ids:
+              schema:
+                type: array
+                items:
+                  type: string
+            X-Meta:
+              content:
+                application/json:
+                  schema:
+                    type: object
+        "200":
+          description: Success
+          content:
+            application/json: {}
+            text/plain: {}
+        "206":
+          $ref: "#/components/responses/Parts"
+  /ignored:
+    get:
+      operationId: getIgnored
+      parameters:
+        - name: session
+          in: cookie
+          schema:
+            type: string
+      responses:
+        "4XX":
+          description: Error
+components:
+  parameters:
+    Session:
+      name: session
+      in: cookie
+      schema:
+        type: string
+  requestBodies:
+    Foo:
+      content:
+        application/json: {}
+        text/plain: {}
+  responses:
+    Parts:
+      description: Parts
+      content:
+        multipart/mixed:
+          schema:
+            type: string
+"##,
+    )
+    .unwrap();
+
+    assert_eq!(
+      code_generator()
+        .find_unsupported_features(&openapi)
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1foo/parameters/0: cookie parameters are not supported",
+        "openapi.yaml#/paths/~1foo/post/parameters/0: array header parameters are not supported",
+        "openapi.yaml#/paths/~1foo/post/parameters/1: parameters defined using `content` are not \
+         supported",
+        "openapi.yaml#/paths/~1foo/post/parameters/2: cookie parameters are not supported",
+        "openapi.yaml#/paths/~1foo/post/parameters/3: `deepObject` query parameters must have an \
+         object schema",
+        "openapi.yaml#/paths/~1foo/post/parameters/4: `spaceDelimited` and `pipeDelimited` query \
+         parameters must have an inline array schema",
+        "openapi.yaml#/paths/~1foo/post/requestBody: request bodies with more than one media type \
+         are not supported",
+        "openapi.yaml#/paths/~1foo/post/responses/2XX/headers/X-Ids: array response headers are \
+         not supported",
+        "openapi.yaml#/paths/~1foo/post/responses/2XX/headers/X-Meta: response headers defined \
+         using `content` are not supported",
+        "openapi.yaml#/paths/~1foo/post/responses/200: responses with more than one media type are \
+         not supported",
+        "openapi.yaml#/paths/~1foo/post/responses/206: `multipart/mixed` response schemas must be \
+         objects",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_find_unsupported_nested_date_times() {
+    let openapi = serde_yaml::from_str::<OpenAPI>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo/{since}:
+    get:
+      operationId: getFoo
+      parameters:
+        - name: since
+          in: path
+          required: true
+          schema:
+            type: string
+            format: date-time
+        - name: at
+          in: query
+          schema:
+            type: array
+            items:
+              type: string
+              format: date-time
+      responses:
+        "200":
+          description: Success
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  $ref: "#/components/schemas/Timestamp"
+components:
+  schemas:
+    Timestamp:
+      type: string
+      format: date-time
+    Foo:
+      type: object
+      properties:
+        createdAt:
+          $ref: "#/components/schemas/Timestamp"
+        updates:
+          type: object
+          additionalProperties:
+            type: string
+            format: date-time
+"##,
+    )
+    .unwrap();
+
+    // Parameters and `date-time` properties are supported.
+    assert_eq!(
+      code_generator()
+        .with_datetime_backend(DateTimeBackend::Time)
+        .find_unsupported_features(&openapi)
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/components/schemas/Foo/properties/updates/additionalProperties: \
+         `date-time` `additionalProperties` are not supported with `DateTimeBackend::Time`",
+        "openapi.yaml#/paths/~1foo~1{since}/get/responses/200/content/application~1json/schema/\
+         items: `date-time` array items are not supported with `DateTimeBackend::Time`",
+      ]
+    );
+    assert_eq!(code_generator().find_unsupported_features(&openapi), vec![]);
+  }
+}
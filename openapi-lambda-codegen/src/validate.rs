@@ -0,0 +1,585 @@
+use crate::api::operation::PathOperation;
+use crate::reference::{escape_pointer_token, find_reference_target, parse_doc};
+use crate::{CodeGenerator, CodegenError, DocCache};
+
+use http::Method;
+use openapiv3::{OpenAPI, Operation, Parameter, ReferenceOr};
+use serde::de::DeserializeOwned;
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// A structural problem found in an OpenAPI definition.
+///
+/// See [`CodeGenerator::validate_spec`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationIssue {
+  location: String,
+  message: String,
+}
+
+impl ValidationIssue {
+  pub(crate) fn new<L, M>(location: L, message: M) -> Self
+  where
+    L: Into<String>,
+    M: Into<String>,
+  {
+    Self {
+      location: location.into(),
+      message: message.into(),
+    }
+  }
+
+  /// Location of the problem within the OpenAPI definition (e.g.,
+  /// `openapi.yaml#/paths/~1foo/get`).
+  pub fn location(&self) -> &str {
+    &self.location
+  }
+
+  /// Description of the problem.
+  pub fn message(&self) -> &str {
+    &self.message
+  }
+}
+
+impl Display for ValidationIssue {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}: {}", self.location, self.message)
+  }
+}
+
+pub(crate) fn pointer_location(doc_path: &Path, pointer: &[String]) -> String {
+  format!(
+    "{}#/{}",
+    doc_path.display(),
+    pointer
+      .iter()
+      .map(|token| escape_pointer_token(token))
+      .collect::<Vec<_>>()
+      .join("/")
+  )
+}
+
+impl CodeGenerator {
+  /// Validate the structure of the OpenAPI definition passed to [`CodeGenerator::new`] (or
+  /// [`CodeGenerator::from_str`]/[`CodeGenerator::from_reader`]).
+  ///
+  /// Rather than stopping at the first problem, this method reports all of the following issues
+  /// together:
+  ///  * References (`$ref`) whose targets can't be found, including references to other files.
+  ///  * Operations handled by an [`ApiLambda`](crate::ApiLambda) that lack an `operationId`.
+  ///  * Duplicate `operationId`s.
+  ///  * Parameters defined more than once (with the same `name` and `in` location) for the same
+  ///    path item or operation.
+  ///  * Paths that differ only in the names of their path parameters (e.g., `/pets/{id}` and
+  ///    `/pets/{petId}`), which Amazon API Gateway rejects.
+  ///
+  /// Code generation also catches these errors, but typically stops at the first one with a less
+  /// precise message. To run this validation automatically before generating code, use
+  /// [`with_spec_validation`](CodeGenerator::with_spec_validation).
+  pub fn validate_spec(&self) -> Result<(), Vec<ValidationIssue>> {
+    let openapi_yaml = match &self.openapi_contents {
+      Some(openapi_contents) => parse_doc(&self.openapi_path, openapi_contents.as_slice()),
+      None => std::fs::File::open(&self.openapi_path)
+        .map_err(|err| format!("failed to open: {err}"))
+        .and_then(|openapi_file| parse_doc(&self.openapi_path, &openapi_file)),
+    }
+    .map_err(|err| {
+      vec![ValidationIssue::new(
+        self.openapi_path.display().to_string(),
+        err,
+      )]
+    })?;
+
+    let issues = self.validate_openapi_yaml(&self.openapi_path, openapi_yaml, &mut DocCache::new());
+    if issues.is_empty() {
+      Ok(())
+    } else {
+      Err(issues)
+    }
+  }
+
+  pub(crate) fn validate_openapi_yaml(
+    &self,
+    openapi_path: &Path,
+    openapi_yaml: serde_yaml::Mapping,
+    cached_external_docs: &mut DocCache,
+  ) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    cached_external_docs.insert(openapi_path.to_path_buf(), openapi_yaml.clone());
+
+    // Check every reference in the root document as well as every document it references
+    // (transitively).
+    let mut docs_to_visit = vec![openapi_path.to_path_buf()];
+    let mut visited_docs = HashSet::new();
+    while let Some(doc_path) = docs_to_visit.pop() {
+      if !visited_docs.insert(doc_path.clone()) {
+        continue;
+      }
+      let Some(doc) = cached_external_docs.get(&doc_path).cloned() else {
+        continue;
+      };
+      let mut references = Vec::new();
+      collect_references(
+        &serde_yaml::Value::Mapping(doc),
+        &mut Vec::new(),
+        &mut references,
+      );
+      for (pointer, reference) in references {
+        match find_reference_target(&doc_path, &reference, cached_external_docs) {
+          Ok((target_doc_path, _)) => docs_to_visit.push(target_doc_path),
+          Err(err) => issues.push(ValidationIssue::new(
+            pointer_location(&doc_path, &pointer),
+            first_line(&err),
+          )),
+        }
+      }
+    }
+
+    let openapi = match serde_path_to_error::deserialize::<_, OpenAPI>(serde_yaml::Value::Mapping(
+      openapi_yaml,
+    )) {
+      Ok(openapi) => openapi,
+      Err(err) => {
+        issues.push(ValidationIssue::new(
+          openapi_path.display().to_string(),
+          format!("failed to parse OpenAPI definition: {err}"),
+        ));
+        return issues;
+      }
+    };
+
+    issues.extend(conflicting_path_templates(
+      openapi_path,
+      openapi.paths.paths.keys().map(String::as_str),
+    ));
+
+    let mut operation_id_locations = HashMap::<String, String>::new();
+    for (request_path, path_item_or_ref) in &openapi.paths.paths {
+      let path_item_location = pointer_location(
+        openapi_path,
+        &["paths".to_string(), request_path.to_owned()],
+      );
+      // Problems with references were already reported above, so we just skip any references we
+      // can't resolve.
+      let Some((path_item_doc_path, path_item)) =
+        resolve(openapi_path, path_item_or_ref, cached_external_docs)
+      else {
+        continue;
+      };
+
+      check_duplicate_parameters(
+        &path_item_location,
+        &path_item_doc_path,
+        &path_item.parameters,
+        cached_external_docs,
+        &mut issues,
+      );
+
+      for (method, op) in path_item.iter() {
+        let op_location = format!("{path_item_location}/{method}");
+        check_duplicate_parameters(
+          &op_location,
+          &path_item_doc_path,
+          &op.parameters,
+          cached_external_docs,
+          &mut issues,
+        );
+
+        if let Some(operation_id) = &op.operation_id {
+          if let Some(first_location) =
+            operation_id_locations.insert(operation_id.to_owned(), op_location.clone())
+          {
+            issues.push(ValidationIssue::new(
+              &op_location,
+              format!("duplicate operationId `{operation_id}` (also used by {first_location})"),
+            ));
+          }
+        } else if let Some(mod_name) =
+          self.api_lambda_for_operation(&http_method(method), request_path, op)
+        {
+          issues.push(ValidationIssue::new(
+            &op_location,
+            format!(
+              "{} {request_path} is handled by API Lambda `{mod_name}` but has no operationId",
+              method.to_uppercase()
+            ),
+          ));
+        }
+      }
+    }
+
+    issues
+  }
+
+  /// Checks that every operation handled by an API Lambda (or, if enabled, the generated client)
+  /// has an `operationId`, and that no two operations share one.
+  ///
+  /// Unlike [`validate_spec`](CodeGenerator::validate_spec), this check always runs since the
+  /// generated code identifies operations by their `operationId`.
+  pub(crate) fn check_operation_ids(
+    &self,
+    operations: &[PathOperation],
+  ) -> Result<(), CodegenError> {
+    let mut issues = Vec::new();
+    let mut operation_id_locations = HashMap::<&str, String>::new();
+    for operation in operations {
+      let op_location = pointer_location(
+        &self.openapi_path,
+        &[
+          "paths".to_string(),
+          operation.request_path.clone(),
+          operation.method.as_str().to_lowercase(),
+        ],
+      );
+      if let Some(operation_id) = &operation.op.operation_id {
+        if let Some(first_location) =
+          operation_id_locations.insert(operation_id, op_location.clone())
+        {
+          issues.push(ValidationIssue::new(
+            &op_location,
+            format!("duplicate operationId `{operation_id}` (also used by {first_location})"),
+          ));
+        }
+      } else if let Some(mod_name) =
+        self.api_lambda_for_operation(&operation.method, &operation.request_path, &operation.op)
+      {
+        issues.push(ValidationIssue::new(
+          &op_location,
+          format!(
+            "{} {} is handled by API Lambda `{mod_name}` but has no operationId",
+            operation.method, operation.request_path
+          ),
+        ));
+      } else if self.client_mod_name.is_some() {
+        issues.push(ValidationIssue::new(
+          &op_location,
+          format!(
+            "{} {} has no operationId, which the generated client requires",
+            operation.method, operation.request_path
+          ),
+        ));
+      }
+    }
+
+    if issues.is_empty() {
+      Ok(())
+    } else {
+      Err(CodegenError::InvalidSpec(issues))
+    }
+  }
+
+  /// Returns the module name of the first API Lambda whose filters include the operation.
+  pub(crate) fn api_lambda_for_operation(
+    &self,
+    method: &Method,
+    request_path: &str,
+    op: &Operation,
+  ) -> Option<&str> {
+    self
+      .api_lambdas
+      .values()
+      .find(|api_lambda| api_lambda.includes_operation(method, request_path, op))
+      .map(|api_lambda| api_lambda.mod_name.as_str())
+  }
+}
+
+/// Convert a lowercase HTTP method returned by [`PathItem::iter`](openapiv3::PathItem::iter).
+pub(crate) fn http_method(method: &str) -> Method {
+  Method::from_bytes(method.to_uppercase().as_bytes()).expect("invalid HTTP method")
+}
+
+/// Report any path templates that are equivalent after normalizing their path parameter names.
+///
+/// OpenAPI considers templated paths that differ only in parameter names (e.g., `/pets/{id}` and
+/// `/pets/{petId}`) to be identical, and Amazon API Gateway rejects definitions containing them.
+pub(crate) fn conflicting_path_templates<'a>(
+  openapi_path: &Path,
+  request_paths: impl Iterator<Item = &'a str>,
+) -> Vec<ValidationIssue> {
+  let mut normalized_paths = HashMap::<String, &str>::new();
+  request_paths
+    .filter_map(|request_path| {
+      let first_path = *normalized_paths
+        .entry(normalize_path_template(request_path))
+        .or_insert(request_path);
+      (first_path != request_path).then(|| {
+        ValidationIssue::new(
+          pointer_location(
+            openapi_path,
+            &["paths".to_string(), request_path.to_owned()],
+          ),
+          format!(
+            "path `{request_path}` conflicts with `{first_path}` since they differ only in path \
+             parameter names"
+          ),
+        )
+      })
+    })
+    .collect()
+}
+
+/// Replace each path parameter name (e.g., `{id}` or greedy `{proxy+}`) with an empty placeholder.
+fn normalize_path_template(request_path: &str) -> String {
+  let mut normalized = String::with_capacity(request_path.len());
+  let mut rest = request_path;
+  while let Some((prefix, param_and_rest)) = rest.split_once('{') {
+    let Some((param, suffix)) = param_and_rest.split_once('}') else {
+      break;
+    };
+    normalized.push_str(prefix);
+    normalized.push_str(if param.ends_with('+') { "{+}" } else { "{}" });
+    rest = suffix;
+  }
+  normalized.push_str(rest);
+  normalized
+}
+
+fn first_line(err: &str) -> &str {
+  err.lines().next().unwrap_or_default()
+}
+
+/// Recursively collect every `$ref` in a document along with its JSON Pointer location.
+fn collect_references(
+  value: &serde_yaml::Value,
+  pointer: &mut Vec<String>,
+  references: &mut Vec<(Vec<String>, String)>,
+) {
+  match value {
+    serde_yaml::Value::Mapping(mapping) => {
+      for (key, child) in mapping {
+        let key = match key {
+          serde_yaml::Value::String(key) => key.to_owned(),
+          other => serde_yaml::to_string(other)
+            .unwrap_or_default()
+            .trim_end()
+            .to_string(),
+        };
+        match child {
+          serde_yaml::Value::String(reference) if key == "$ref" => {
+            references.push((pointer.clone(), reference.to_owned()))
+          }
+          _ => {
+            pointer.push(key);
+            collect_references(child, pointer, references);
+            pointer.pop();
+          }
+        }
+      }
+    }
+    serde_yaml::Value::Sequence(sequence) => {
+      for (idx, child) in sequence.iter().enumerate() {
+        pointer.push(idx.to_string());
+        collect_references(child, pointer, references);
+        pointer.pop();
+      }
+    }
+    serde_yaml::Value::Tagged(tagged) => collect_references(&tagged.value, pointer, references),
+    serde_yaml::Value::Null
+    | serde_yaml::Value::Bool(_)
+    | serde_yaml::Value::Number(_)
+    | serde_yaml::Value::String(_) => {}
+  }
+}
+
+/// Resolve a (possibly external) reference without panicking, returning the path of the document
+/// containing the target along with the target.
+fn resolve<T>(
+  referrer_doc_path: &Path,
+  ref_or_item: &ReferenceOr<T>,
+  cached_external_docs: &mut DocCache,
+) -> Option<(PathBuf, T)>
+where
+  T: Clone + DeserializeOwned,
+{
+  match ref_or_item {
+    ReferenceOr::Item(item) => Some((referrer_doc_path.to_path_buf(), item.clone())),
+    ReferenceOr::Reference { reference } => {
+      let (target_doc_path, target) =
+        find_reference_target(referrer_doc_path, reference, cached_external_docs).ok()?;
+      let target =
+        serde_yaml::from_value::<T>(serde_yaml::Value::Mapping(target.to_owned())).ok()?;
+      Some((target_doc_path, target))
+    }
+  }
+}
+
+fn check_duplicate_parameters(
+  location: &str,
+  doc_path: &Path,
+  parameters: &[ReferenceOr<Parameter>],
+  cached_external_docs: &mut DocCache,
+  issues: &mut Vec<ValidationIssue>,
+) {
+  let mut seen_parameters = HashSet::new();
+  for (idx, parameter) in parameters.iter().enumerate() {
+    let Some((_, parameter)) = resolve(doc_path, parameter, cached_external_docs) else {
+      continue;
+    };
+    let location_name = match parameter {
+      Parameter::Query { .. } => "query",
+      Parameter::Header { .. } => "header",
+      Parameter::Path { .. } => "path",
+      Parameter::Cookie { .. } => "cookie",
+    };
+    let name = &parameter.parameter_data_ref().name;
+    if !seen_parameters.insert((name.to_owned(), location_name)) {
+      issues.push(ValidationIssue::new(
+        format!("{location}/parameters/{idx}"),
+        format!("duplicate {location_name} parameter `{name}`"),
+      ));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{ApiLambda, CodeGenerator, DocCache, LambdaArn, ValidationIssue};
+
+  use pretty_assertions::assert_eq;
+
+  use std::path::Path;
+
+  #[test]
+  fn test_multiple_issues() {
+    let openapi_yaml = serde_yaml::from_str::<serde_yaml::Mapping>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /foo:
+    parameters:
+      - name: foo
+        in: query
+        schema:
+          type: string
+      - name: foo
+        in: query
+        schema:
+          type: integer
+    get:
+      operationId: getFoo
+      responses:
+        "200":
+          $ref: "#/components/responses/Missing"
+    post:
+      responses: {}
+  /foo/{foo_id}:
+    get:
+      operationId: getFoo
+      parameters:
+        - $ref: "#/components/parameters/FooId"
+        - $ref: "#/components/parameters/FooId"
+      responses: {}
+components:
+  parameters:
+    FooId:
+      name: foo_id
+      in: path
+      required: true
+      schema:
+        type: string
+"##,
+    )
+    .unwrap();
+
+    let code_generator =
+      CodeGenerator::new("openapi.yaml", ".openapi-lambda").add_api_lambda(ApiLambda::new(
+        "backend",
+        LambdaArn::cloud_formation("BackendApiFunction.Alias"),
+      ));
+    let issues = code_generator.validate_openapi_yaml(
+      Path::new("openapi.yaml"),
+      openapi_yaml,
+      &mut DocCache::new(),
+    );
+
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1foo/get/responses/200: invalid reference \
+         `#/components/responses/Missing`: path component `responses` not found",
+        "openapi.yaml#/paths/~1foo/parameters/1: duplicate query parameter `foo`",
+        "openapi.yaml#/paths/~1foo/post: POST /foo is handled by API Lambda `backend` but has no \
+         operationId",
+        "openapi.yaml#/paths/~1foo~1{foo_id}/get/parameters/1: duplicate path parameter `foo_id`",
+        "openapi.yaml#/paths/~1foo~1{foo_id}/get: duplicate operationId `getFoo` (also used by \
+         openapi.yaml#/paths/~1foo/get)",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_conflicting_path_templates() {
+    let openapi_yaml = serde_yaml::from_str::<serde_yaml::Mapping>(
+      r##"
+openapi: 3.0.2
+info:
+  title: Test
+  version: 0.1.0
+paths:
+  /pets/{id}:
+    get:
+      operationId: getPet
+      responses: {}
+  /pets/{petId}:
+    delete:
+      operationId: deletePet
+      responses: {}
+  /pets/{id}/photo.{format}:
+    get:
+      operationId: getPetPhoto
+      responses: {}
+  /pets/{petId}/photo.{ext}:
+    put:
+      operationId: putPetPhoto
+      responses: {}
+  /pets/{id}/toys:
+    get:
+      operationId: listPetToys
+      responses: {}
+  /files/{proxy+}:
+    get:
+      operationId: getFile
+      responses: {}
+  /files/{path}:
+    put:
+      operationId: putFile
+      responses: {}
+  /files/{path+}:
+    delete:
+      operationId: deleteFile
+      responses: {}
+"##,
+    )
+    .unwrap();
+
+    let issues = CodeGenerator::new("openapi.yaml", ".openapi-lambda").validate_openapi_yaml(
+      Path::new("openapi.yaml"),
+      openapi_yaml,
+      &mut DocCache::new(),
+    );
+
+    assert_eq!(
+      issues
+        .iter()
+        .map(ValidationIssue::to_string)
+        .collect::<Vec<_>>(),
+      vec![
+        "openapi.yaml#/paths/~1pets~1{petId}: path `/pets/{petId}` conflicts with `/pets/{id}` \
+         since they differ only in path parameter names",
+        "openapi.yaml#/paths/~1pets~1{petId}~1photo.{ext}: path `/pets/{petId}/photo.{ext}` \
+         conflicts with `/pets/{id}/photo.{format}` since they differ only in path parameter \
+         names",
+        "openapi.yaml#/paths/~1files~1{path+}: path `/files/{path+}` conflicts with \
+         `/files/{proxy+}` since they differ only in path parameter names",
+      ]
+    );
+  }
+}
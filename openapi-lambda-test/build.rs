@@ -1,20 +1,73 @@
 use env_logger::Env;
-use openapi_lambda_codegen::{ApiLambda, CodeGenerator, LambdaArn};
+use openapi_lambda_codegen::{
+  ApiGatewayValidation, ApiLambda, CodeGenerator, CorsConfig, EventSource, LambdaArn, Method,
+};
+
+use std::time::Duration;
 
 fn main() {
   env_logger::init_from_env(Env::default().filter_or("RUST_LOG", "info"));
 
-  // If using an OpenAPI spec that contains references to other files, be sure to edit the
-  // `rerun_glob` (second argument) below so that updates trigger the codegen build script.
+  // Changes to the OpenAPI spec and to any files it references (directly or transitively) via
+  // `$ref` automatically trigger the codegen build script.
   CodeGenerator::new("spec/openapi.yaml", ".openapi-lambda")
-    // Divide the API into 3 Lambda functions based on the tag of each endpoint.
+    .with_api_gateway_validation(ApiGatewayValidation::Warn)
+    .with_spec_validation(true)
+    // Keep hand-written integrations (e.g., `GET /bar/legacy`) in `openapi-apigw.yaml`.
+    .with_preserve_integrations(true)
+    .map_format_to_type("bar-id", "crate::types::BarId")
+    // Pass header parameters to each handler as a typed struct.
+    .with_request_header_structs()
+    // Write a route manifest for non-CloudFormation infrastructure-as-code tools.
+    .with_manifest("routes.json")
+    // Divide the API into 5 Lambda functions based on the tag of each endpoint.
     .add_api_lambda(
       ApiLambda::new("foo", LambdaArn::cloud_formation("FooApiFunction.Alias"))
-        .with_op_filter(|op| op.tags.iter().any(|tag| tag == "foo")),
+        .with_op_filter(|op| op.tags.iter().any(|tag| tag == "foo"))
+        // Health checks aren't part of the OpenAPI definition.
+        .with_extra_route(Method::GET, "/health", "crate::health_check")
+        .with_max_body_bytes(1024)
+        .with_response_compression(true)
+        .with_cors(
+          CorsConfig::new("https://example.com")
+            .with_allow_headers(["Authorization", "Content-Type"])
+            .with_max_age(Duration::from_secs(600)),
+        ),
     )
     .add_api_lambda(
       ApiLambda::new("bar", LambdaArn::cloud_formation("BarApiFunction.Alias"))
         .with_op_filter(|op| op.tags.iter().any(|tag| tag == "bar")),
     )
+    .add_api_lambda(
+      ApiLambda::new("baz", LambdaArn::cloud_formation("BazApiFunction.Alias"))
+        .with_op_filter(|op| op.tags.iter().any(|tag| tag == "baz"))
+        .with_event_source(EventSource::ApiGatewayV2Http)
+        // Requests to a non-`$default` HTTP API stage include the stage name in their paths.
+        .with_base_path("/prod")
+        .with_cors(CorsConfig::new("https://example.com").with_allow_credentials(true))
+        .with_extra_route(
+          Method::GET,
+          "/ready",
+          "|| openapi_lambda::HttpResponse::new(openapi_lambda::Body::Empty)",
+        ),
+    )
+    .add_api_lambda(
+      ApiLambda::new("qux", LambdaArn::cloud_formation("QuxApiFunction.Alias"))
+        .with_op_filter(|op| op.tags.iter().any(|tag| tag == "qux"))
+        .with_event_source(EventSource::ApplicationLoadBalancer)
+        .with_extra_route(Method::GET, "/health", "crate::health_check"),
+    )
+    .add_api_lambda(
+      ApiLambda::new("quux", LambdaArn::cloud_formation("QuuxApiFunction.Alias"))
+        // Route by HTTP method and path instead of by tag.
+        .with_route_filter(|method, path, _| method == Method::GET && path.starts_with("/quux/"))
+        .with_response_streaming(true),
+    )
+    // Generate a client for every operation.
+    .generate_client("client")
+    // Generate a `callbacks` module for the callback operations.
+    .generate_callbacks()
+    // Return example responses from the generated handlers where possible.
+    .generate_mock_handlers()
     .generate();
 }
@@ -3,9 +3,11 @@
 include!(concat!(env!("OUT_DIR"), "/out.rs"));
 
 mod types {
+  use std::fmt::{Display, Formatter};
   use std::num::ParseIntError;
   use std::str::FromStr;
 
+  #[allow(dead_code)]
   #[derive(Clone, Copy, Debug)]
   pub struct BarId(i64);
 
@@ -16,6 +18,26 @@ mod types {
       i64::from_str(s).map(Self)
     }
   }
+
+  // Required by the generated client to serialize parameters.
+  impl Display for BarId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      self.0.fmt(f)
+    }
+  }
+}
+
+// Responds to the `/health` extra routes registered in `build.rs`.
+fn health_check() -> openapi_lambda::HttpResponse {
+  openapi_lambda::Response::builder()
+    .status(openapi_lambda::StatusCode::OK)
+    .body(openapi_lambda::Body::Text("OK".to_string()))
+    .expect("failed to build response")
+}
+
+// Generates the `id` of `AuditRecord` models (see `x-rust-default-response` in the spec).
+fn new_audit_id() -> String {
+  "audit-1".to_string()
 }
 
 // Make sure the auto-generated handler templates compile.
@@ -27,18 +49,72 @@ pub mod foo_handler;
 #[allow(dead_code)]
 pub mod bar_handler;
 
+#[path = "../.openapi-lambda/baz_handler.rs"]
+#[allow(dead_code)]
+pub mod baz_handler;
+
+#[path = "../.openapi-lambda/qux_handler.rs"]
+#[allow(dead_code)]
+pub mod qux_handler;
+
+#[path = "../.openapi-lambda/quux_handler.rs"]
+#[allow(dead_code)]
+pub mod quux_handler;
+
+#[path = "../.openapi-lambda/callbacks_handler.rs"]
+#[allow(dead_code)]
+pub mod callbacks_handler;
+
 // TO UPDATE THE OUTPUT SNAPSHOTS BELOW, RUN:
 //   cargo insta test --review
 // This requires having previously run `cargo install cargo-insta`.
 // See: https://insta.rs/docs/quickstart/
 #[cfg(test)]
 mod tests {
-  use insta::{assert_display_snapshot, assert_yaml_snapshot};
+  use crate::baz::{Api as BazApi, GetBazOkHeaders, GetBazResponse};
+  use crate::callbacks::Api as CallbacksApi;
+  use crate::callbacks_handler::CallbacksApiHandler;
+  use crate::client::{self, Client};
+  use crate::foo::{Api, CreateFooResponse, GetFooBatchResponse, PutFooAvatarResponse};
+  use crate::foo_handler::FooApiHandler;
+  use crate::models::{
+    AuditRecord, Claims, Foo, FooBatch, Labels, NotFound, Notification, Ping, Point, Polygon,
+    Profile, Shape, Sort,
+  };
+  use crate::quux::{Api as QuuxApi, GetQuuxEventsResponse};
+  use crate::qux::{
+    Api as QuxApi, GetQuxFileResponse, GetQuxItemsResponse, GetQuxResponse, SearchQuxResponse,
+  };
+
+  use aws_lambda_events::alb::AlbTargetGroupRequest;
+  use aws_lambda_events::apigw::{
+    ApiGatewayProxyRequest, ApiGatewayProxyRequestContext, ApiGatewayV2httpRequest,
+    ApiGatewayV2httpRequestContextAuthorizerDescription,
+    ApiGatewayV2httpRequestContextHttpDescription,
+  };
+  use aws_lambda_events::http::header::CONTENT_TYPE;
+  use aws_lambda_events::http::{HeaderValue, Method};
+  use aws_lambda_events::query_map::QueryMap;
+  use futures::stream::{self, TryStreamExt};
+  use insta::{assert_snapshot, assert_yaml_snapshot};
+  use openapi_lambda::async_trait::async_trait;
   use openapi_lambda::error::format_error;
+  use openapi_lambda::models::{serde_json, IndexMap};
+  use openapi_lambda::test_util::TestRequest;
+  use openapi_lambda::{
+    run_local, AlbTargetGroupRequestContext, ApiGatewayV2httpRequestContext, Body, ClientError,
+    HeaderMap, HttpResponse, LambdaContext, LambdaEvent, Middleware, OperationInfo, Response,
+    ResponseStream, SecurityRequirement, StatusCode, UnauthenticatedMiddleware,
+  };
   use openapiv3::OpenAPI;
   use proc_macro2::TokenStream;
+  use tokio::io::{AsyncReadExt, AsyncWriteExt};
+  use tokio::net::TcpListener;
 
+  use std::collections::{HashMap, HashSet};
+  use std::convert::TryFrom;
   use std::fs::File;
+  use std::io::Read;
   use std::path::Path;
 
   #[test]
@@ -59,6 +135,14 @@ mod tests {
     assert_yaml_snapshot!("openapi-apigw.yaml", openapi_apigw_contents);
   }
 
+  #[test]
+  fn test_manifest() {
+    let manifest_path = Path::new(".openapi-lambda/routes.json");
+    let manifest_contents = std::fs::read_to_string(manifest_path)
+      .unwrap_or_else(|err| panic!("failed to read {}: {err}", manifest_path.display()));
+    assert_snapshot!("routes.json", manifest_contents);
+  }
+
   #[test]
   fn test_out_rs() {
     let out_rs_path = Path::new(concat!(env!("OUT_DIR"), "/out.rs"));
@@ -72,7 +156,81 @@ mod tests {
           out_rs_path.display()
         )
       });
-    assert_display_snapshot!("out.rs", out_rs_contents);
+    assert_snapshot!("out.rs", out_rs_contents);
+  }
+
+  #[test]
+  fn test_operations() {
+    assert_eq!(
+      crate::foo::OPERATIONS
+        .iter()
+        .map(|operation| operation.operation_id)
+        .collect::<Vec<_>>(),
+      ["createFoo", "getFoo", "getFooBatch", "putFooAvatar"]
+    );
+    assert_eq!(
+      crate::foo::find_operation("getFooBatch"),
+      Some(&OperationInfo {
+        operation_id: "getFooBatch",
+        method: "GET",
+        path: "/foo/{foo_id}/batch",
+      })
+    );
+    assert_eq!(crate::foo::find_operation("getBaz"), None);
+    // Operations with a preserved API Gateway integration aren't handled by the Lambda function.
+    assert_eq!(crate::bar::find_operation("getLegacyBar"), None);
+    assert_eq!(
+      crate::callbacks::find_operation("onFooCreated").map(|operation| operation.path),
+      Some("{$request.body#/callbackUrl}")
+    );
+  }
+
+  #[test]
+  fn test_authorizer_claims() {
+    let mut request_context = ApiGatewayProxyRequestContext::default();
+    request_context.authorizer.insert(
+      "claims".to_string(),
+      serde_json::json!({"sub": "user", "email": "user@example.com"}),
+    );
+    assert_eq!(
+      crate::foo::bearer_auth_claims(&request_context).unwrap(),
+      Claims {
+        sub: "user".to_string(),
+        email: Some("user@example.com".to_string()),
+      }
+    );
+
+    request_context.authorizer.clear();
+    assert_eq!(
+      crate::foo::bearer_auth_claims(&request_context)
+        .unwrap_err()
+        .name(),
+      "InvalidAuthorizerContext"
+    );
+
+    let mut request_context = ApiGatewayV2httpRequestContext::default();
+    let mut authorizer = ApiGatewayV2httpRequestContextAuthorizerDescription::default();
+    authorizer
+      .lambda
+      .insert("sub".to_string(), serde_json::json!("user"));
+    request_context.authorizer = Some(authorizer);
+    assert_eq!(
+      crate::baz::bearer_auth_claims(&request_context).unwrap(),
+      Claims {
+        sub: "user".to_string(),
+        email: None,
+      }
+    );
+  }
+
+  #[test]
+  fn test_api_metadata() {
+    assert_eq!(crate::API_TITLE, "Integration test");
+    assert_eq!(crate::API_VERSION, "0.1.0");
+    assert_eq!(
+      crate::API_DESCRIPTION,
+      Some("Exercises the generated code end to end.")
+    );
   }
 
   #[test]
@@ -88,7 +246,7 @@ mod tests {
           foo_handler_path.display()
         )
       });
-    assert_display_snapshot!("foo_handler.rs", foo_handler_contents);
+    assert_snapshot!("foo_handler.rs", foo_handler_contents);
   }
 
   #[test]
@@ -104,6 +262,1790 @@ mod tests {
           bar_handler_path.display()
         )
       });
-    assert_display_snapshot!("bar_handler.rs", bar_handler_contents);
+    assert_snapshot!("bar_handler.rs", bar_handler_contents);
+  }
+
+  #[test]
+  fn test_baz_handler() {
+    let baz_handler_path = Path::new(".openapi-lambda/baz_handler.rs");
+    let baz_handler_contents = std::fs::read_to_string(baz_handler_path)
+      .unwrap_or_else(|err| panic!("failed to read {}: {err}", baz_handler_path.display()));
+    baz_handler_contents
+      .parse::<TokenStream>()
+      .unwrap_or_else(|err| {
+        panic!(
+          "failed to parse {} into token stream: {err}",
+          baz_handler_path.display()
+        )
+      });
+    assert_snapshot!("baz_handler.rs", baz_handler_contents);
+  }
+
+  #[test]
+  fn test_qux_handler() {
+    let qux_handler_path = Path::new(".openapi-lambda/qux_handler.rs");
+    let qux_handler_contents = std::fs::read_to_string(qux_handler_path)
+      .unwrap_or_else(|err| panic!("failed to read {}: {err}", qux_handler_path.display()));
+    qux_handler_contents
+      .parse::<TokenStream>()
+      .unwrap_or_else(|err| {
+        panic!(
+          "failed to parse {} into token stream: {err}",
+          qux_handler_path.display()
+        )
+      });
+    assert_snapshot!("qux_handler.rs", qux_handler_contents);
+  }
+
+  #[test]
+  fn test_quux_handler() {
+    let quux_handler_path = Path::new(".openapi-lambda/quux_handler.rs");
+    let quux_handler_contents = std::fs::read_to_string(quux_handler_path)
+      .unwrap_or_else(|err| panic!("failed to read {}: {err}", quux_handler_path.display()));
+    quux_handler_contents
+      .parse::<TokenStream>()
+      .unwrap_or_else(|err| {
+        panic!(
+          "failed to parse {} into token stream: {err}",
+          quux_handler_path.display()
+        )
+      });
+    assert_snapshot!("quux_handler.rs", quux_handler_contents);
+  }
+
+  #[test]
+  fn test_callbacks_handler() {
+    let callbacks_handler_path = Path::new(".openapi-lambda/callbacks_handler.rs");
+    let callbacks_handler_contents = std::fs::read_to_string(callbacks_handler_path)
+      .unwrap_or_else(|err| panic!("failed to read {}: {err}", callbacks_handler_path.display()));
+    callbacks_handler_contents
+      .parse::<TokenStream>()
+      .unwrap_or_else(|err| {
+        panic!(
+          "failed to parse {} into token stream: {err}",
+          callbacks_handler_path.display()
+        )
+      });
+    assert_snapshot!("callbacks_handler.rs", callbacks_handler_contents);
+  }
+
+  #[tokio::test]
+  async fn test_cors_preflight() {
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::OPTIONS,
+      resource: Some("/foo/{foo_id}".to_string()),
+      path: Some("/foo/123".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 204);
+    let headers = &response.multi_value_headers;
+    assert_eq!(headers.get("allow").unwrap(), "GET, OPTIONS");
+    assert_eq!(
+      headers.get("access-control-allow-methods").unwrap(),
+      "GET, OPTIONS"
+    );
+    assert_eq!(
+      headers.get("access-control-allow-origin").unwrap(),
+      "https://example.com"
+    );
+    assert_eq!(
+      headers.get("access-control-allow-headers").unwrap(),
+      "Authorization, Content-Type"
+    );
+    assert_eq!(headers.get("access-control-max-age").unwrap(), "600");
+    assert!(headers.get("access-control-allow-credentials").is_none());
+
+    // Browsers require CORS response headers on every response, not just preflight responses.
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/unknown".to_string()),
+      path: Some("/unknown".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_ne!(response.status_code, 204);
+    assert_eq!(
+      response
+        .multi_value_headers
+        .get("access-control-allow-origin")
+        .unwrap(),
+      "https://example.com"
+    );
+
+    // Requests to `/{proxy+}` resources (including those from `run_local`) are matched using the
+    // request path.
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::OPTIONS,
+      resource: Some("/{proxy+}".to_string()),
+      path: Some("/foo/123".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 204);
+    assert_eq!(
+      response.multi_value_headers.get("allow").unwrap(),
+      "GET, OPTIONS"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_mock_handler() {
+    let mut request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/foo/{foo_id}".to_string()),
+      path: Some("/foo/456".to_string()),
+      path_parameters: vec![("foo_id".to_string(), "456".to_string())]
+        .into_iter()
+        .collect(),
+      ..Default::default()
+    };
+    request.request_context.operation_name = Some("getFoo".to_string());
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    // The mock handler returns the example response from the OpenAPI definition.
+    assert_eq!(response.status_code, 200);
+    let Some(Body::Text(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    assert_eq!(
+      serde_json::from_str::<Foo>(&body).unwrap(),
+      Foo {
+        foo_id: "123".to_string(),
+        r#type: "bar".to_string(),
+        description: None,
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn test_dispatch_without_operation_name() {
+    // API Gateway populates the path parameters for non-proxy resources.
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/foo/{foo_id}".to_string()),
+      path: Some("/foo/456".to_string()),
+      path_parameters: vec![("foo_id".to_string(), "456".to_string())]
+        .into_iter()
+        .collect(),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 200);
+
+    // Requests to proxy resources are routed using the request path.
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/{proxy+}".to_string()),
+      path: Some("/foo/456".to_string()),
+      path_parameters: vec![("proxy".to_string(), "foo/456".to_string())]
+        .into_iter()
+        .collect(),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 200);
+
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/{proxy+}".to_string()),
+      path: Some("/unknown".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 404);
+  }
+
+  /// Rejects every request, responding with the security requirements it received.
+  struct SecurityRequirementsMiddleware;
+
+  #[async_trait]
+  impl Middleware for SecurityRequirementsMiddleware {
+    type AuthOk = ();
+
+    async fn authenticate(
+      &self,
+      _operation_id: &str,
+      _headers: &HeaderMap,
+      _request_context: &ApiGatewayProxyRequestContext,
+      _lambda_context: &LambdaContext,
+    ) -> Result<Self::AuthOk, HttpResponse> {
+      unreachable!("authenticate_with_security should be called instead")
+    }
+
+    async fn authenticate_with_security(
+      &self,
+      _operation_id: &str,
+      security: &[SecurityRequirement],
+      _headers: &HeaderMap,
+      _request_context: &ApiGatewayProxyRequestContext,
+      _lambda_context: &LambdaContext,
+    ) -> Result<Self::AuthOk, HttpResponse> {
+      let alternatives = security
+        .iter()
+        .map(|requirement| {
+          requirement
+            .schemes
+            .iter()
+            .map(|scheme| format!("{}{:?}", scheme.name, scheme.scopes))
+            .collect::<Vec<_>>()
+            .join(" + ")
+        })
+        .collect::<Vec<_>>()
+        .join(" | ");
+      Err(
+        Response::builder()
+          .status(StatusCode::UNAUTHORIZED)
+          .body(alternatives.into())
+          .unwrap(),
+      )
+    }
+  }
+
+  #[tokio::test]
+  async fn test_security_requirements() {
+    let mut request = ApiGatewayProxyRequest {
+      http_method: Method::POST,
+      resource: Some("/foo".to_string()),
+      path: Some("/foo".to_string()),
+      body: Some(r#"{"name": "foo"}"#.to_string()),
+      ..Default::default()
+    };
+    request
+      .headers
+      .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    request.request_context.operation_name = Some("createFoo".to_string());
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &SecurityRequirementsMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 401);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        r#"bearerAuth[] | oauth2Auth["foo:write"] + bearerAuth[]"#.to_string()
+      ))
+    );
+  }
+
+  #[tokio::test]
+  async fn test_callback_dispatch() {
+    let mut request = ApiGatewayProxyRequest {
+      http_method: Method::POST,
+      resource: Some("/callbacks/foo-created".to_string()),
+      path: Some("/callbacks/foo-created".to_string()),
+      headers: vec![(CONTENT_TYPE, HeaderValue::from_static("application/json"))]
+        .into_iter()
+        .collect(),
+      body: Some(r#"{"foo":{"foo_id":"123","type":"bar"}}"#.to_string()),
+      ..Default::default()
+    };
+    request.request_context.operation_name = Some("onFooCreated".to_string());
+    let response = CallbacksApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    // The mock handler returns the empty `200 OK` response.
+    assert_eq!(response.status_code, 200);
+  }
+
+  #[tokio::test]
+  async fn test_apigw_v2_cors_preflight() {
+    // Lambda function URLs and `$default` routes are matched using the request path after
+    // stripping the base path.
+    for raw_path in ["/prod/baz/123", "/baz/123"] {
+      let mut request = ApiGatewayV2httpRequest {
+        route_key: Some("$default".to_string()),
+        raw_path: Some(raw_path.to_string()),
+        ..Default::default()
+      };
+      request.request_context.http.method = Method::OPTIONS;
+      let response = TestBazHandler
+        .dispatch_request(
+          LambdaEvent::new(request, LambdaContext::default()),
+          &UnauthenticatedMiddleware,
+        )
+        .await;
+
+      assert_eq!(response.status_code, 204, "{raw_path}");
+      assert_eq!(
+        response.headers.get("allow").unwrap(),
+        "GET, OPTIONS",
+        "{raw_path}"
+      );
+      assert_eq!(
+        response
+          .headers
+          .get("access-control-allow-credentials")
+          .unwrap(),
+        "true",
+        "{raw_path}"
+      );
+    }
+  }
+
+  struct TestBazHandler;
+
+  #[async_trait]
+  impl BazApi for TestBazHandler {
+    type AuthOk = ();
+    type HandlerError = ();
+
+    async fn respond_to_handler_error(&self, _err: Self::HandlerError) -> HttpResponse {
+      unreachable!()
+    }
+
+    async fn get_baz(
+      &self,
+      baz_id: String,
+      tag: Option<Vec<String>>,
+      headers: HeaderMap,
+      request_context: ApiGatewayV2httpRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+    ) -> Result<(GetBazResponse, HeaderMap), Self::HandlerError> {
+      assert_eq!(Some(&operation), crate::baz::find_operation("getBaz"));
+      let cookie = headers
+        .get("cookie")
+        .map(|cookie| cookie.to_str().unwrap().to_string());
+      Ok((
+        GetBazResponse::Ok(
+          format!("{baz_id} {tag:?} {cookie:?}"),
+          GetBazOkHeaders {
+            x_rate_limit_remaining: 9,
+            x_request_id: None,
+          },
+        ),
+        HeaderMap::new(),
+      ))
+    }
+  }
+
+  #[tokio::test]
+  async fn test_apigw_v2_dispatch() {
+    let request = ApiGatewayV2httpRequest {
+      route_key: Some("GET /baz/{baz_id}".to_string()),
+      raw_path: Some("/baz/123".to_string()),
+      cookies: Some(vec!["a=1".to_string(), "b=2".to_string()]),
+      path_parameters: HashMap::from([("baz_id".to_string(), "123".to_string())]),
+      query_string_parameters: QueryMap::from(HashMap::from([(
+        "tag".to_string(),
+        vec!["x".to_string(), "y".to_string()],
+      )])),
+      ..Default::default()
+    };
+    let response = TestBazHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 200);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        r#"123 Some(["x", "y"]) Some("a=1; b=2")"#.to_string()
+      ))
+    );
+    assert_eq!(response.headers.get("x-rate-limit-remaining").unwrap(), "9");
+    assert!(response.headers.get("x-request-id").is_none());
+  }
+
+  #[tokio::test]
+  async fn test_apigw_v2_base_path() {
+    for raw_path in ["/prod/baz/123", "/baz/123"] {
+      let request = ApiGatewayV2httpRequest {
+        route_key: Some("$default".to_string()),
+        raw_path: Some(raw_path.to_string()),
+        request_context: ApiGatewayV2httpRequestContext {
+          http: ApiGatewayV2httpRequestContextHttpDescription {
+            method: Method::GET,
+            ..Default::default()
+          },
+          ..Default::default()
+        },
+        ..Default::default()
+      };
+      let response = TestBazHandler
+        .dispatch_request(
+          LambdaEvent::new(request, LambdaContext::default()),
+          &UnauthenticatedMiddleware,
+        )
+        .await;
+
+      assert_eq!(response.status_code, 200, "{raw_path}");
+      assert_eq!(
+        response.body,
+        Some(Body::Text("123 None None".to_string())),
+        "{raw_path}"
+      );
+    }
+  }
+
+  #[tokio::test]
+  async fn test_apigw_v2_unknown_route_key() {
+    let request = ApiGatewayV2httpRequest {
+      route_key: Some("POST /baz/{baz_id}".to_string()),
+      ..Default::default()
+    };
+    let response = TestBazHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 404);
+  }
+
+  #[tokio::test]
+  async fn test_function_url_dispatch() {
+    let mut request = ApiGatewayV2httpRequest {
+      route_key: Some("$default".to_string()),
+      raw_path: Some("/baz/a%2Fb".to_string()),
+      ..Default::default()
+    };
+    request.request_context.http.method = Method::GET;
+    let response = TestBazHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("a/b None None".to_string())));
+  }
+
+  struct TestQuxHandler;
+
+  #[async_trait]
+  impl QuxApi for TestQuxHandler {
+    type AuthOk = ();
+    type HandlerError = ();
+
+    async fn respond_to_handler_error(&self, _err: Self::HandlerError) -> HttpResponse {
+      unreachable!()
+    }
+
+    async fn get_qux(
+      &self,
+      qux_id: String,
+      filter: Option<String>,
+      headers: HeaderMap,
+      request_context: AlbTargetGroupRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+      auth_ok: Option<Self::AuthOk>,
+    ) -> Result<(GetQuxResponse, HeaderMap), Self::HandlerError> {
+      Ok((
+        GetQuxResponse::Ok(format!("{qux_id} {filter:?} {auth_ok:?}")),
+        HeaderMap::new(),
+      ))
+    }
+
+    async fn get_qux_file(
+      &self,
+      proxy: String,
+      headers: HeaderMap,
+      request_context: AlbTargetGroupRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+    ) -> Result<(GetQuxFileResponse, HeaderMap), Self::HandlerError> {
+      let response = match proxy.as_str() {
+        "partial" => GetQuxFileResponse::Success(StatusCode::PARTIAL_CONTENT, proxy),
+        // Outside of the declared `2XX` range.
+        "missing" => GetQuxFileResponse::Success(StatusCode::NOT_FOUND, proxy),
+        "cached" => {
+          GetQuxFileResponse::Ok(proxy).with_status(StatusCode::NON_AUTHORITATIVE_INFORMATION)
+        }
+        _ => GetQuxFileResponse::Ok(proxy),
+      };
+      Ok(response.into())
+    }
+
+    async fn get_qux_items(
+      &self,
+      ids: Vec<String>,
+      version: i32,
+      headers: HeaderMap,
+      request_context: AlbTargetGroupRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+    ) -> Result<(GetQuxItemsResponse, HeaderMap), Self::HandlerError> {
+      Ok((
+        GetQuxItemsResponse::Ok(format!("{ids:?} {version}")),
+        HeaderMap::new(),
+      ))
+    }
+
+    async fn search_qux(
+      &self,
+      ids: Vec<i32>,
+      tags: Option<Vec<String>>,
+      exclude: Option<Vec<i32>>,
+      sort: Option<Sort>,
+      headers: HeaderMap,
+      request_context: AlbTargetGroupRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+    ) -> Result<(SearchQuxResponse, HeaderMap), Self::HandlerError> {
+      Ok((
+        SearchQuxResponse::Ok(format!("{ids:?} {tags:?} {exclude:?} {sort:?}")),
+        HeaderMap::new(),
+      ))
+    }
+  }
+
+  #[tokio::test]
+  async fn test_alb_dispatch() {
+    let request = AlbTargetGroupRequest {
+      http_method: Method::GET,
+      path: Some("/qux/a%2Fb".to_string()),
+      query_string_parameters: QueryMap::from(HashMap::from([(
+        "filter".to_string(),
+        "x%20y".to_string(),
+      )])),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.status_description.as_deref(), Some("200 OK"));
+    assert_eq!(
+      response.body,
+      Some(Body::Text(r#"a/b Some("x y") Some(())"#.to_string()))
+    );
+  }
+
+  #[tokio::test]
+  async fn test_extra_routes() {
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      resource: Some("/health".to_string()),
+      path: Some("/health".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("OK".to_string())));
+
+    // Extra routes only match their own HTTP method.
+    let request = ApiGatewayProxyRequest {
+      http_method: Method::POST,
+      resource: Some("/health".to_string()),
+      path: Some("/health".to_string()),
+      ..Default::default()
+    };
+    let response = FooApiHandler::new(())
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 404);
+
+    let request = ApiGatewayV2httpRequest {
+      route_key: Some("GET /ready".to_string()),
+      raw_path: Some("/prod/ready".to_string()),
+      request_context: ApiGatewayV2httpRequestContext {
+        http: ApiGatewayV2httpRequestContextHttpDescription {
+          method: Method::GET,
+          ..Default::default()
+        },
+        ..Default::default()
+      },
+      ..Default::default()
+    };
+    let response = TestBazHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Empty));
+
+    let request = AlbTargetGroupRequest {
+      http_method: Method::GET,
+      path: Some("/health".to_string()),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("OK".to_string())));
+  }
+
+  #[tokio::test]
+  async fn test_greedy_path_parameter() {
+    let request = AlbTargetGroupRequest {
+      http_method: Method::GET,
+      path: Some("/qux/files/a/b%2Fc.txt".to_string()),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    // Greedy path parameters are passed to the handler without URL-decoding.
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("a/b%2Fc.txt".to_string())));
+  }
+
+  #[tokio::test]
+  async fn test_param_styles() {
+    let dispatch = |path: &str, query: &[(&str, &str)]| {
+      let request = AlbTargetGroupRequest {
+        http_method: Method::GET,
+        path: Some(path.to_string()),
+        query_string_parameters: QueryMap::from(
+          query
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>(),
+        ),
+        ..Default::default()
+      };
+      TestQuxHandler.dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+    };
+
+    let response = dispatch(
+      "/qux/search/.1,2",
+      &[
+        ("tags", "a|b"),
+        ("exclude", "3,4"),
+        ("sort[field]", "name"),
+        ("sort[descending]", "true"),
+      ],
+    )
+    .await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        r#"[1, 2] Some(["a", "b"]) Some([3, 4]) Some(Sort { field: "name", descending: Some(true) })"#
+          .to_string()
+      ))
+    );
+
+    let response = dispatch("/qux/search/.", &[]).await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(
+      response.body,
+      Some(Body::Text("[] None None None".to_string()))
+    );
+
+    // Invalid array items are identified by their index and value.
+    let response = dispatch("/qux/search/.1", &[("exclude", "1,abc,3")]).await;
+    assert_eq!(response.status_code, 400);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        "Invalid item `abc` at index 1 of `exclude` query parameter".to_string()
+      ))
+    );
+
+    // Missing `label` prefix.
+    let response = dispatch("/qux/search/1,2", &[]).await;
+    assert_eq!(response.status_code, 400);
+
+    // Invalid `deepObject` property.
+    let response = dispatch("/qux/search/.1", &[("sort[descending]", "yes")]).await;
+    assert_eq!(response.status_code, 400);
+  }
+
+  #[tokio::test]
+  async fn test_matrix_and_label_path_params() {
+    let dispatch = |path: &str| {
+      let request = AlbTargetGroupRequest {
+        http_method: Method::GET,
+        path: Some(path.to_string()),
+        ..Default::default()
+      };
+      TestQuxHandler.dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+    };
+
+    // Each element is URL-decoded after splitting the exploded matrix value.
+    let response = dispatch("/qux/items/;ids=a%3Bb;ids=c/.2").await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(r#"["a;b", "c"] 2"#.to_string()))
+    );
+
+    // Empty matrix arrays omit the `=`.
+    let response = dispatch("/qux/items/;ids/.2").await;
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("[] 2".to_string())));
+
+    // Missing `label` prefix.
+    let response = dispatch("/qux/items/;ids=a/2").await;
+    assert_eq!(response.status_code, 400);
+
+    // Missing `matrix` prefix.
+    let response = dispatch("/qux/items/a/.2").await;
+    assert_eq!(response.status_code, 400);
+  }
+
+  #[tokio::test]
+  async fn test_status_code_range_response() {
+    let dispatch = |proxy: &str| {
+      let request = AlbTargetGroupRequest {
+        http_method: Method::GET,
+        path: Some(format!("/qux/files/{proxy}")),
+        ..Default::default()
+      };
+      TestQuxHandler.dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+    };
+
+    let response = dispatch("partial").await;
+    assert_eq!(response.status_code, 206);
+    assert_eq!(response.body, Some(Body::Text("partial".to_string())));
+
+    let response = dispatch("missing").await;
+    assert_eq!(response.status_code, 500);
+    assert_eq!(response.body, Some(Body::Empty));
+  }
+
+  #[tokio::test]
+  async fn test_response_status_override() {
+    let request = AlbTargetGroupRequest {
+      http_method: Method::GET,
+      path: Some("/qux/files/cached".to_string()),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 203);
+    assert_eq!(
+      response.status_description.as_deref(),
+      Some("203 Non Authoritative Information")
+    );
+    assert_eq!(
+      response.headers.get(CONTENT_TYPE),
+      Some(&HeaderValue::from_static("text/plain"))
+    );
+    assert_eq!(response.body, Some(Body::Text("cached".to_string())));
+  }
+
+  /// Rejects every request.
+  struct RejectingMiddleware;
+
+  #[async_trait]
+  impl Middleware<AlbTargetGroupRequestContext> for RejectingMiddleware {
+    type AuthOk = ();
+
+    async fn authenticate(
+      &self,
+      _operation_id: &str,
+      _headers: &HeaderMap,
+      _request_context: &AlbTargetGroupRequestContext,
+      _lambda_context: &LambdaContext,
+    ) -> Result<Self::AuthOk, HttpResponse> {
+      Err(
+        Response::builder()
+          .status(StatusCode::UNAUTHORIZED)
+          .body(Body::Empty)
+          .unwrap(),
+      )
+    }
+  }
+
+  #[tokio::test]
+  async fn test_optional_authentication() {
+    let request = AlbTargetGroupRequest {
+      http_method: Method::GET,
+      path: Some("/qux/123".to_string()),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &RejectingMiddleware,
+      )
+      .await;
+
+    // Failing to authenticate doesn't reject requests to endpoints with optional authentication.
+    assert_eq!(response.status_code, 200);
+    assert_eq!(response.body, Some(Body::Text("123 None None".to_string())));
+  }
+
+  #[tokio::test]
+  async fn test_alb_unknown_route() {
+    let request = AlbTargetGroupRequest {
+      http_method: Method::POST,
+      path: Some("/qux/123".to_string()),
+      ..Default::default()
+    };
+    let response = TestQuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await;
+
+    assert_eq!(response.status_code, 404);
+    assert_eq!(
+      response.status_description.as_deref(),
+      Some("404 Not Found")
+    );
+  }
+
+  struct TestQuuxHandler;
+
+  #[async_trait]
+  impl QuuxApi for TestQuuxHandler {
+    type AuthOk = ();
+    type HandlerError = ();
+
+    async fn respond_to_handler_error(&self, _err: Self::HandlerError) -> HttpResponse {
+      unreachable!()
+    }
+
+    async fn get_quux_events(
+      &self,
+      quux_id: String,
+      headers: HeaderMap,
+      request_context: ApiGatewayProxyRequestContext,
+      lambda_context: LambdaContext,
+      operation: OperationInfo,
+    ) -> Result<(GetQuuxEventsResponse, HeaderMap), Self::HandlerError> {
+      if quux_id == "missing" {
+        return Ok(
+          GetQuuxEventsResponse::NotFound(NotFound {
+            message: format!("quux `{quux_id}` not found"),
+          })
+          .into(),
+        );
+      }
+
+      let events =
+        stream::iter(["data: 1\n\n", "data: 2\n\n"].map(Ok::<_, std::convert::Infallible>));
+      let mut response_headers = HeaderMap::new();
+      response_headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/event-stream"));
+      Ok((
+        GetQuuxEventsResponse::Streaming(StatusCode::OK, ResponseStream::new(events)),
+        response_headers,
+      ))
+    }
+  }
+
+  async fn dispatch_quux_request(quux_id: &str) -> openapi_lambda::StreamingResponse {
+    let mut request = ApiGatewayProxyRequest {
+      http_method: Method::GET,
+      path_parameters: HashMap::from([("quux_id".to_string(), quux_id.to_string())]),
+      ..Default::default()
+    };
+    request.request_context.operation_name = Some("getQuuxEvents".to_string());
+    TestQuuxHandler
+      .dispatch_request(
+        LambdaEvent::new(request, LambdaContext::default()),
+        &UnauthenticatedMiddleware,
+      )
+      .await
+  }
+
+  #[tokio::test]
+  async fn test_response_streaming() {
+    let response = dispatch_quux_request("123").await;
+
+    assert_eq!(response.metadata_prelude.status_code, StatusCode::OK);
+    assert_eq!(
+      response.metadata_prelude.headers[CONTENT_TYPE],
+      "text/event-stream"
+    );
+    let chunks = response.stream.try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(chunks, ["data: 1\n\n", "data: 2\n\n"]);
+  }
+
+  #[tokio::test]
+  async fn test_response_streaming_buffered() {
+    let response = dispatch_quux_request("missing").await;
+
+    assert_eq!(response.metadata_prelude.status_code, StatusCode::NOT_FOUND);
+    let chunks = response.stream.try_collect::<Vec<_>>().await.unwrap();
+    assert_eq!(chunks, [r#"{"message":"quux `missing` not found"}"#]);
+  }
+
+  #[test]
+  fn test_response_default() {
+    assert_eq!(
+      AuditRecord::default(),
+      AuditRecord {
+        id: "audit-1".to_string(),
+        message: None,
+      }
+    );
+  }
+
+  #[test]
+  fn test_multipart_response() {
+    let response = GetFooBatchResponse::Ok(FooBatch {
+      foo: Foo {
+        foo_id: "123".to_string(),
+        r#type: "bar".to_string(),
+        description: None,
+      },
+      summary: "one foo".to_string(),
+      thumbnail: None,
+    })
+    .into_http_response(HeaderMap::new())
+    .unwrap();
+
+    let content_type = response.headers()[CONTENT_TYPE].to_str().unwrap();
+    let boundary = content_type
+      .strip_prefix("multipart/mixed; boundary=")
+      .unwrap_or_else(|| panic!("unexpected Content-Type {}", content_type));
+    assert_eq!(
+      response.body(),
+      &Body::Text(format!(
+        "--{boundary}\r\nContent-Type: application/json\r\n\r\n\
+         {{\"foo_id\":\"123\",\"type\":\"bar\"}}\r\n\
+         --{boundary}\r\nContent-Type: text/plain\r\n\r\none foo\r\n\
+         --{boundary}--\r\n"
+      ))
+    );
+  }
+
+  #[test]
+  fn test_handler_content_type() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+      CONTENT_TYPE,
+      HeaderValue::from_static("text/plain; charset=utf-8"),
+    );
+    let response = GetQuxFileResponse::Ok("contents".to_string())
+      .into_http_response(headers)
+      .unwrap();
+
+    // The handler's `Content-Type` replaces the one from the OpenAPI definition.
+    assert_eq!(
+      response
+        .headers()
+        .get_all(CONTENT_TYPE)
+        .iter()
+        .collect::<Vec<_>>(),
+      vec![&HeaderValue::from_static("text/plain; charset=utf-8")]
+    );
+
+    let response = GetQuxFileResponse::Ok("contents".to_string())
+      .into_http_response(HeaderMap::new())
+      .unwrap();
+    assert_eq!(
+      response
+        .headers()
+        .get_all(CONTENT_TYPE)
+        .iter()
+        .collect::<Vec<_>>(),
+      vec![&HeaderValue::from_static("text/plain")]
+    );
+  }
+
+  #[test]
+  fn test_handler_typed_header() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-rate-limit-remaining", HeaderValue::from_static("0"));
+    let response = GetBazResponse::Ok(
+      "baz".to_string(),
+      GetBazOkHeaders {
+        x_rate_limit_remaining: 9,
+        x_request_id: Some("abc".to_string()),
+      },
+    )
+    .into_http_response(headers)
+    .unwrap();
+
+    // The handler's header replaces the typed header of the same name.
+    assert_eq!(
+      response
+        .headers()
+        .get_all("x-rate-limit-remaining")
+        .iter()
+        .collect::<Vec<_>>(),
+      vec![&HeaderValue::from_static("0")]
+    );
+    assert_eq!(
+      response
+        .headers()
+        .get_all("x-request-id")
+        .iter()
+        .collect::<Vec<_>>(),
+      vec![&HeaderValue::from_static("abc")]
+    );
+  }
+
+  #[test]
+  fn test_response_from_http() {
+    let response = GetBazResponse::Ok(
+      "baz".to_string(),
+      GetBazOkHeaders {
+        x_rate_limit_remaining: 42,
+        x_request_id: Some("abc".to_string()),
+      },
+    )
+    .into_http_response(HeaderMap::new())
+    .unwrap();
+    let GetBazResponse::Ok(body, headers) = GetBazResponse::try_from(response).unwrap() else {
+      panic!("unexpected variant");
+    };
+    assert_eq!(body, "baz");
+    assert_eq!(headers.x_rate_limit_remaining, 42);
+    assert_eq!(headers.x_request_id.as_deref(), Some("abc"));
+
+    let foo = Foo {
+      foo_id: "123".to_string(),
+      r#type: "bar".to_string(),
+      description: Some("a foo".to_string()),
+    };
+    let response = CreateFooResponse::Ok(foo.clone())
+      .into_http_response(HeaderMap::new())
+      .unwrap();
+    let CreateFooResponse::Ok(decoded) = CreateFooResponse::try_from(response).unwrap() else {
+      panic!("unexpected variant");
+    };
+    assert_eq!(decoded, foo);
+
+    let response = CreateFooResponse::from_http(
+      StatusCode::OK,
+      Some("application/json".to_string()),
+      &HeaderMap::new(),
+      b"{\"foo_id\":1}".to_vec(),
+    );
+    assert!(
+      matches!(response, Err(ClientError::InvalidResponseJson(_))),
+      "unexpected response {:?}",
+      response
+    );
+
+    // Status codes that the OpenAPI definition doesn't declare can't be decoded.
+    let response = CreateFooResponse::Ok(foo)
+      .with_status(StatusCode::IM_A_TEAPOT)
+      .into_http_response(HeaderMap::new())
+      .unwrap();
+    match CreateFooResponse::try_from(response) {
+      Err(ClientError::UnexpectedResponse {
+        status_code,
+        content_type,
+        ..
+      }) => {
+        assert_eq!(status_code, StatusCode::IM_A_TEAPOT);
+        assert_eq!(content_type.as_deref(), Some("application/json"));
+      }
+      other => panic!("unexpected response {:?}", other),
+    }
+  }
+
+  #[tokio::test]
+  async fn test_media_range_body() {
+    let api = FooApiHandler::new(());
+    let put_avatar = |content_type: &'static str| {
+      TestRequest::new(Method::PUT, "/foo/avatar")
+        .with_operation_name("putFooAvatar")
+        .with_header("content-type", content_type)
+        .with_binary_body([0x89, b'P', b'N', b'G'])
+        .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+    };
+
+    // Any image type reaches the (unimplemented) handler.
+    assert_eq!(put_avatar("image/png").await.status_code, 500);
+    assert_eq!(put_avatar("image/webp").await.status_code, 500);
+    assert_eq!(put_avatar("text/plain").await.status_code, 400);
+
+    // Media ranges aren't valid response content types, so the handler should set its own.
+    let response = PutFooAvatarResponse::Ok(vec![0x89, b'P', b'N', b'G'])
+      .into_http_response(HeaderMap::new())
+      .unwrap();
+    assert_eq!(
+      response.headers()[CONTENT_TYPE],
+      HeaderValue::from_static("application/octet-stream")
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+    let response = PutFooAvatarResponse::Ok(vec![0x89, b'P', b'N', b'G'])
+      .into_http_response(headers)
+      .unwrap();
+    assert_eq!(
+      response.headers()[CONTENT_TYPE],
+      HeaderValue::from_static("image/png")
+    );
+  }
+
+  #[test]
+  fn test_tagged_enum_additional_properties() {
+    let json = r#"{"kind":"email","address":"foo@example.com","locale":"en-US"}"#;
+    let email = serde_json::from_str::<Notification>(json).unwrap();
+    let Notification::EmailNotification {
+      address,
+      additional_properties,
+    } = &email
+    else {
+      panic!("unexpected variant {:?}", email);
+    };
+    assert_eq!(address, "foo@example.com");
+    // The `kind` tag shouldn't be captured as an additional property.
+    assert_eq!(
+      additional_properties,
+      &IndexMap::from([("locale".to_string(), "en-US".to_string())])
+    );
+    assert_eq!(serde_json::to_string(&email).unwrap(), json);
+
+    // Additional properties must match the variant's `additionalProperties` schema.
+    serde_json::from_str::<Notification>(
+      r#"{"kind":"email","address":"foo@example.com","retries":3}"#,
+    )
+    .unwrap_err();
+
+    let json = r#"{"kind":"webhook","url":"https://example.com","retries":3}"#;
+    let webhook = serde_json::from_str::<Notification>(json).unwrap();
+    let Notification::WebhookNotification(additional_properties) = &webhook else {
+      panic!("unexpected variant {:?}", webhook);
+    };
+    assert_eq!(
+      additional_properties.keys().collect::<Vec<_>>(),
+      ["url", "retries"]
+    );
+    assert_eq!(serde_json::to_string(&webhook).unwrap(), json);
+  }
+
+  #[test]
+  fn test_object_enum() {
+    let shape = serde_json::from_str::<Shape>(r#"{"kind":"square","side":2}"#).unwrap();
+    assert_eq!(
+      shape.as_value(),
+      &serde_json::json!({"kind": "square", "side": 2})
+    );
+    assert_eq!(
+      serde_json::to_string(&shape).unwrap(),
+      r#"{"kind":"square","side":2}"#
+    );
+
+    serde_json::from_str::<Shape>(r#"{"kind":"square","side":3}"#).unwrap_err();
+
+    let circle = Shape::try_from(serde_json::json!({"kind": "circle", "radius": 1})).unwrap();
+    assert_eq!(circle.as_ref()["radius"], 1);
+    assert_eq!(
+      serde_json::Value::from(circle),
+      serde_json::json!({"kind": "circle", "radius": 1})
+    );
+    Shape::try_from(serde_json::json!({"kind": "circle", "radius": 2})).unwrap_err();
+  }
+
+  #[test]
+  fn test_const_model() {
+    let ping = serde_json::from_str::<Ping>(r#"{"kind":"ping","version":1}"#).unwrap();
+    assert_eq!(
+      serde_json::to_string(&ping).unwrap(),
+      r#"{"kind":"ping","version":1}"#
+    );
+
+    serde_json::from_str::<Ping>(r#"{"kind":"pong","version":1}"#).unwrap_err();
+    serde_json::from_str::<Ping>(r#"{"kind":"ping","version":2}"#).unwrap_err();
+  }
+
+  #[test]
+  fn test_nullable_model() {
+    for (json, expected) in [
+      (
+        r#"{"bio":null}"#,
+        Profile {
+          bio: None,
+          nickname: None,
+        },
+      ),
+      (
+        r#"{"bio":"Hi","nickname":null}"#,
+        Profile {
+          bio: Some("Hi".to_string()),
+          nickname: Some(None),
+        },
+      ),
+      (
+        r#"{"bio":null,"nickname":"Al"}"#,
+        Profile {
+          bio: None,
+          nickname: Some(Some("Al".to_string())),
+        },
+      ),
+    ] {
+      let profile = serde_json::from_str::<Profile>(json).unwrap();
+      assert_eq!(profile, expected);
+      assert_eq!(serde_json::to_string(&profile).unwrap(), json);
+    }
+  }
+
+  #[test]
+  fn test_additional_properties_order() {
+    let json = r#"{"name":"release","zeta":"1","alpha":"2","mid":"3"}"#;
+    let labels = serde_json::from_str::<Labels>(json).unwrap();
+    assert_eq!(
+      labels.additional_properties.keys().collect::<Vec<_>>(),
+      vec!["zeta", "alpha", "mid"]
+    );
+    assert_eq!(serde_json::to_string(&labels).unwrap(), json);
+  }
+
+  #[test]
+  fn test_unique_items() {
+    let polygon = serde_json::from_str::<Polygon>(
+      r#"{"vertices":[{"x":0.0,"y":0.0},{"x":1.0,"y":0.5},{"x":0.0,"y":1.0}]}"#,
+    )
+    .unwrap();
+    assert_eq!(polygon.vertices.len(), 3);
+    assert_eq!(polygon.vertices[1], Point { x: 1.0, y: 0.5 });
+
+    assert_eq!(
+      serde_json::from_str::<Polygon>(
+        r#"{"vertices":[{"x":0.0,"y":0.0},{"x":1.0,"y":0.5},{"x":0.0,"y":0.0}]}"#
+      )
+      .unwrap_err()
+      .to_string(),
+      "duplicate array item at index 2 at line 1 column 68"
+    );
+  }
+
+  #[test]
+  fn test_array_bounds() {
+    assert_eq!(
+      serde_json::from_str::<Polygon>(r#"{"vertices":[{"x":0.0,"y":0.0},{"x":1.0,"y":0.5}]}"#)
+        .unwrap_err()
+        .to_string(),
+      "expected at least 3 items, but found 2 at line 1 column 50"
+    );
+  }
+
+  #[test]
+  fn test_model_derives() {
+    let foo = Foo {
+      foo_id: "123".to_string(),
+      r#type: "bar".to_string(),
+      description: None,
+    };
+    assert_eq!(foo, foo.clone());
+    assert_eq!(
+      vec![foo.clone(), foo]
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .len(),
+      1
+    );
+  }
+
+  /// Serve a single HTTP request on a local port, responding with `response`.
+  ///
+  /// Returns the base URL of the server and a handle resolving to the raw request head.
+  async fn serve_once(response: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+      let (mut stream, _) = listener.accept().await.unwrap();
+      let mut request = Vec::new();
+      let mut buf = [0; 1024];
+      while !request.ends_with(b"\r\n\r\n") {
+        let len = stream.read(&mut buf).await.unwrap();
+        assert_ne!(len, 0, "connection closed before end of request head");
+        request.extend_from_slice(&buf[..len]);
+      }
+      stream.write_all(response.as_bytes()).await.unwrap();
+      stream.shutdown().await.unwrap();
+      String::from_utf8(request).unwrap()
+    });
+    (base_url, handle)
+  }
+
+  #[tokio::test]
+  async fn test_run_local() {
+    // Reserve an unused port for the local server.
+    let addr = TcpListener::bind("127.0.0.1:0")
+      .await
+      .unwrap()
+      .local_addr()
+      .unwrap();
+    let api = FooApiHandler::new(());
+    let server = run_local(addr, |event| {
+      api.dispatch_request(event, &UnauthenticatedMiddleware)
+    });
+    let client = Client::new(format!("http://{}/", addr), reqwest::Client::new());
+
+    // The local server matches the request path since API Gateway doesn't provide an operation
+    // name.
+    let response = tokio::select! {
+      biased;
+      result = server => panic!("local server exited: {:?}", result),
+      response = client.get_foo("456".to_string(), None, reqwest::header::HeaderMap::new()) => {
+        response.unwrap()
+      }
+    };
+    let client::GetFooResponse::Ok(foo) = response;
+    assert_eq!(
+      foo,
+      Foo {
+        foo_id: "123".to_string(),
+        r#type: "bar".to_string(),
+        description: None,
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn test_test_request() {
+    let api = FooApiHandler::new(());
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_resource("/foo")
+      .with_header("authorization", "Bearer foobar")
+      .with_json_body(&serde_json::json!({"name": 1}))
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 400);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        "Invalid request body (path: `name`): invalid type: integer `1`, expected a string at \
+         line 1 column 9"
+          .to_string()
+      ))
+    );
+
+    let response = TestRequest::new(Method::GET, "/foo/456")
+      .with_path_param("foo_id", "456")
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+    assert_eq!(response.status_code, 200);
+  }
+
+  #[tokio::test]
+  async fn test_request_header_struct() {
+    // Header parameters are grouped into a typed struct passed to the handler.
+    let request_headers = crate::foo::GetFooRequestHeaders {
+      x_api_version: Some(2),
+    };
+    assert_eq!(request_headers.x_api_version, Some(2));
+
+    let api = FooApiHandler::new(());
+    let response = TestRequest::new(Method::GET, "/foo/456")
+      .with_path_param("foo_id", "456")
+      .with_header("x-api-version", "2")
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+    assert_eq!(response.status_code, 200);
+
+    // The handler wrapper still rejects headers that fail to parse.
+    let response = TestRequest::new(Method::GET, "/foo/456")
+      .with_path_param("foo_id", "456")
+      .with_header("x-api-version", "two")
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+    assert_eq!(response.status_code, 400);
+    assert_eq!(
+      response.body,
+      Some(Body::Text(
+        "Invalid `x-api-version` request header".to_string()
+      ))
+    );
+  }
+
+  #[tokio::test]
+  async fn test_error_content_negotiation() {
+    let api = FooApiHandler::new(());
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_header("accept", "application/json")
+      .with_json_body(&serde_json::json!({"name": 1}))
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 400);
+    let headers = &response.multi_value_headers;
+    assert_eq!(headers.get("content-type").unwrap(), "application/json");
+    assert_eq!(headers.get("vary").unwrap(), "accept");
+    let Some(Body::Text(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+      serde_json::json!({
+        "error": "InvalidBodyJson",
+        "message": "Invalid request body (path: `name`): invalid type: integer `1`, expected a \
+                    string at line 1 column 9",
+        "path": "name",
+      })
+    );
+
+    // Errors are plaintext unless the client prefers JSON.
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_header("accept", "text/plain, application/json;q=0.9")
+      .with_json_body(&serde_json::json!({"name": 1}))
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 400);
+    let headers = &response.multi_value_headers;
+    assert_eq!(headers.get("content-type").unwrap(), "text/plain");
+    assert_eq!(headers.get("vary").unwrap(), "accept");
+
+    // Clients may opt into RFC 7807 problem details.
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_header("accept", "application/problem+json")
+      .with_json_body(&serde_json::json!({"name": 1}))
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 400);
+    let headers = &response.multi_value_headers;
+    assert_eq!(
+      headers.get("content-type").unwrap(),
+      "application/problem+json"
+    );
+    let Some(Body::Text(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    assert_eq!(
+      serde_json::from_str::<openapi_lambda::models::Problem>(&body).unwrap(),
+      openapi_lambda::models::Problem::new(openapi_lambda::StatusCode::BAD_REQUEST).with_detail(
+        "Invalid request body (path: `name`): invalid type: integer `1`, expected a string at \
+         line 1 column 9"
+      )
+    );
+  }
+
+  #[tokio::test]
+  async fn test_response_compression() {
+    let api = FooApiHandler::new(());
+    let response = TestRequest::new(Method::GET, "/foo/456")
+      .with_path_param("foo_id", "456")
+      .with_header("accept-encoding", "gzip, deflate, br")
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 200);
+    assert!(response.is_base64_encoded);
+    let headers = &response.multi_value_headers;
+    assert_eq!(headers.get("content-encoding").unwrap(), "gzip");
+    assert_eq!(headers.get("content-type").unwrap(), "application/json");
+    assert_eq!(headers.get("vary").unwrap(), "accept-encoding");
+    let Some(Body::Binary(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    let mut decompressed = String::new();
+    flate2::read::GzDecoder::new(body.as_slice())
+      .read_to_string(&mut decompressed)
+      .unwrap();
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&decompressed).unwrap(),
+      serde_json::json!({"foo_id": "123", "type": "bar"})
+    );
+
+    // Responses are uncompressed unless the client accepts a supported content coding.
+    let response = TestRequest::new(Method::GET, "/foo/456")
+      .with_path_param("foo_id", "456")
+      .with_header("accept-encoding", "br")
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 200);
+    assert!(!response.is_base64_encoded);
+    let headers = &response.multi_value_headers;
+    assert!(headers.get("content-encoding").is_none());
+    assert_eq!(headers.get("vary").unwrap(), "accept-encoding");
+    assert!(matches!(response.body, Some(Body::Text(_))));
+  }
+
+  #[tokio::test]
+  async fn test_max_body_bytes() {
+    let api = FooApiHandler::new(());
+    let name = "a".repeat(1024);
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_header("accept", "application/json")
+      .with_json_body(&serde_json::json!({ "name": name }))
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+
+    assert_eq!(response.status_code, 413);
+    let Some(Body::Text(body)) = response.body else {
+      panic!("unexpected body {:?}", response.body);
+    };
+    assert_eq!(
+      serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+      serde_json::json!({
+        "error": "BodyTooLarge",
+        "message": "Request body exceeds the maximum size of 1024 bytes",
+      })
+    );
+
+    // The limit applies to the decoded size of base64-encoded bodies.
+    let body = serde_json::to_vec(&serde_json::json!({ "name": "a".repeat(1000) })).unwrap();
+    let response = TestRequest::new(Method::POST, "/foo")
+      .with_operation_name("createFoo")
+      .with_header("content-type", "application/json")
+      .with_binary_body(body)
+      .dispatch(|event| api.dispatch_request(event, &UnauthenticatedMiddleware))
+      .await;
+    assert_ne!(response.status_code, 413);
+  }
+
+  #[tokio::test]
+  async fn test_client_ok_response() {
+    let body = r#"{"foo_id":"a b","type":"bar"}"#;
+    let (base_url, request) = serve_once(Box::leak(
+      format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        body.len(),
+        body
+      )
+      .into_boxed_str(),
+    ))
+    .await;
+
+    let response = Client::new(format!("{}/", base_url), reqwest::Client::new())
+      .get_foo(
+        "a b".to_string(),
+        Some(2),
+        reqwest::header::HeaderMap::new(),
+      )
+      .await
+      .unwrap();
+    let client::GetFooResponse::Ok(foo) = response;
+    assert_eq!(
+      foo,
+      Foo {
+        foo_id: "a b".to_string(),
+        r#type: "bar".to_string(),
+        description: None,
+      }
+    );
+
+    let request = request.await.unwrap();
+    assert!(
+      request.starts_with("GET /foo/a%20b HTTP/1.1\r\n"),
+      "unexpected request: {}",
+      request
+    );
+    assert!(
+      request.contains("x-api-version: 2\r\n"),
+      "unexpected request: {}",
+      request
+    );
+  }
+
+  #[tokio::test]
+  async fn test_client_query_params() {
+    let (base_url, request) = serve_once(
+      "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\
+       X-Rate-Limit-Remaining: 9\r\nConnection: close\r\n\r\nok",
+    )
+    .await;
+
+    let response = Client::new(base_url, reqwest::Client::new())
+      .get_baz(
+        "1".to_string(),
+        Some(vec!["x".to_string(), "y".to_string()]),
+        reqwest::header::HeaderMap::new(),
+      )
+      .await
+      .unwrap();
+    let client::GetBazResponse::Ok(body, headers) = response;
+    assert_eq!(body, "ok");
+    assert_eq!(headers.x_rate_limit_remaining, 9);
+    assert_eq!(headers.x_request_id, None);
+
+    let request = request.await.unwrap();
+    assert!(
+      request.starts_with("GET /baz/1?tag=x&tag=y HTTP/1.1\r\n"),
+      "unexpected request: {}",
+      request
+    );
+  }
+
+  #[tokio::test]
+  async fn test_client_param_styles() {
+    let (base_url, request) = serve_once(
+      "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\
+       Connection: close\r\n\r\nok",
+    )
+    .await;
+
+    let response = Client::new(base_url, reqwest::Client::new())
+      .search_qux(
+        vec![1, 2],
+        Some(vec!["a".to_string(), "b c".to_string()]),
+        Some(vec![3, 4]),
+        Some(Sort {
+          field: "name".to_string(),
+          descending: Some(true),
+        }),
+        reqwest::header::HeaderMap::new(),
+      )
+      .await
+      .unwrap();
+    let client::SearchQuxResponse::Ok(body) = response;
+    assert_eq!(body, "ok");
+
+    let request = request.await.unwrap();
+    assert!(
+      request.starts_with(
+        "GET /qux/search/.1,2?tags=a%7Cb+c&exclude=3%2C4&sort%5Bdescending%5D=true&\
+         sort%5Bfield%5D=name HTTP/1.1\r\n"
+      ),
+      "unexpected request: {}",
+      request
+    );
+  }
+
+  #[tokio::test]
+  async fn test_client_matrix_and_label_path_params() {
+    let (base_url, request) = serve_once(
+      "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\
+       Connection: close\r\n\r\nok",
+    )
+    .await;
+
+    let response = Client::new(base_url, reqwest::Client::new())
+      .get_qux_items(
+        vec!["a;b".to_string(), "c".to_string()],
+        2,
+        reqwest::header::HeaderMap::new(),
+      )
+      .await
+      .unwrap();
+    let client::GetQuxItemsResponse::Ok(body) = response;
+    assert_eq!(body, "ok");
+
+    let request = request.await.unwrap();
+    assert!(
+      request.starts_with("GET /qux/items/;ids=a%3Bb;ids=c/.2 HTTP/1.1\r\n"),
+      "unexpected request: {}",
+      request
+    );
+  }
+
+  #[tokio::test]
+  async fn test_client_error_responses() {
+    let body = r#"{"message":"quux `missing` not found"}"#;
+    let (base_url, _) = serve_once(Box::leak(
+      format!(
+        "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        body.len(),
+        body
+      )
+      .into_boxed_str(),
+    ))
+    .await;
+    let err = Client::new(base_url, reqwest::Client::new())
+      .get_quux_events("missing".to_string(), reqwest::header::HeaderMap::new())
+      .await
+      .unwrap_err();
+    match err {
+      ClientError::ErrorResponse {
+        status_code,
+        response: client::GetQuuxEventsErrorResponse::NotFound(not_found),
+      } => {
+        assert_eq!(status_code, StatusCode::NOT_FOUND);
+        assert_eq!(not_found.message, "quux `missing` not found");
+      }
+      other => panic!("unexpected error: {:?}", other),
+    }
+
+    let (base_url, _) = serve_once(
+      "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\nConnection: close\r\n\r\n\
+       oops",
+    )
+    .await;
+    let err = Client::new(base_url, reqwest::Client::new())
+      .get_quux_events("123".to_string(), reqwest::header::HeaderMap::new())
+      .await
+      .unwrap_err();
+    match err {
+      ClientError::UnexpectedResponse {
+        status_code,
+        content_type,
+        body,
+      } => {
+        assert_eq!(status_code, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(content_type, None);
+        assert_eq!(body, b"oops");
+      }
+      other => panic!("unexpected error: {:?}", other),
+    }
   }
 }
@@ -0,0 +1,153 @@
+use crate::{ApiGatewayProxyRequestContext, ApiGatewayV2httpRequestContext, EventError};
+
+use backtrace::Backtrace;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Key under which Amazon Cognito user pool authorizers store the caller's claims in the
+/// REST API request context.
+const COGNITO_CLAIMS_KEY: &str = "claims";
+
+/// Request context that carries the output of an Amazon API Gateway authorizer.
+///
+/// Generated API modules use this trait to implement the `<SCHEME>_claims()` helper functions for
+/// security schemes that declare an `x-authorizer-claims` extension.
+pub trait AuthorizerContext {
+  /// Return the claims (for Amazon Cognito user pool and JWT authorizers) or context (for Lambda
+  /// authorizers) that the authorizer attached to the request.
+  ///
+  /// Returns an empty JSON object if the request wasn't authorized by an authorizer.
+  fn authorizer_context(&self) -> Value;
+
+  /// Deserialize the output of the authorizer (see
+  /// [`authorizer_context`](AuthorizerContext::authorizer_context)) into `T`.
+  fn authorizer_claims<T>(&self) -> Result<T, EventError>
+  where
+    T: DeserializeOwned,
+  {
+    serde_path_to_error::deserialize(self.authorizer_context())
+      .map_err(|err| EventError::InvalidAuthorizerContext(Box::new(err), Backtrace::new()))
+  }
+}
+
+impl AuthorizerContext for ApiGatewayProxyRequestContext {
+  fn authorizer_context(&self) -> Value {
+    match self.authorizer.get(COGNITO_CLAIMS_KEY) {
+      Some(claims @ Value::Object(_)) => claims.clone(),
+      _ => Value::Object(
+        self
+          .authorizer
+          .iter()
+          .map(|(key, value)| (key.clone(), value.clone()))
+          .collect(),
+      ),
+    }
+  }
+}
+
+impl AuthorizerContext for ApiGatewayV2httpRequestContext {
+  fn authorizer_context(&self) -> Value {
+    let Some(authorizer) = &self.authorizer else {
+      return Value::Object(Map::new());
+    };
+
+    if let Some(jwt) = &authorizer.jwt {
+      Value::Object(
+        jwt
+          .claims
+          .iter()
+          .map(|(key, value)| (key.clone(), Value::String(value.clone())))
+          .collect(),
+      )
+    } else {
+      Value::Object(
+        authorizer
+          .lambda
+          .iter()
+          .map(|(key, value)| (key.clone(), value.clone()))
+          .collect(),
+      )
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::AuthorizerContext;
+  use crate::{ApiGatewayProxyRequestContext, ApiGatewayV2httpRequestContext, EventError};
+
+  use aws_lambda_events::apigw::{
+    ApiGatewayV2httpRequestContextAuthorizerDescription,
+    ApiGatewayV2httpRequestContextAuthorizerJwtDescription,
+  };
+  use serde::Deserialize;
+  use serde_json::json;
+
+  #[derive(Debug, Deserialize, PartialEq)]
+  struct Claims {
+    sub: String,
+  }
+
+  #[test]
+  fn test_rest_authorizer_context() {
+    let mut request_context = ApiGatewayProxyRequestContext::default();
+    assert_eq!(request_context.authorizer_context(), json!({}));
+
+    request_context
+      .authorizer
+      .insert("sub".to_string(), json!("lambda-user"));
+    assert_eq!(
+      request_context.authorizer_claims::<Claims>().unwrap(),
+      Claims {
+        sub: "lambda-user".to_string()
+      }
+    );
+
+    request_context
+      .authorizer
+      .insert("claims".to_string(), json!({"sub": "cognito-user"}));
+    assert_eq!(
+      request_context.authorizer_claims::<Claims>().unwrap(),
+      Claims {
+        sub: "cognito-user".to_string()
+      }
+    );
+
+    request_context
+      .authorizer
+      .insert("claims".to_string(), json!({"sub": 1}));
+    let err = request_context.authorizer_claims::<Claims>().unwrap_err();
+    assert!(
+      matches!(err, EventError::InvalidAuthorizerContext(ref err, _) if err.path().to_string() == "sub")
+    );
+  }
+
+  #[test]
+  fn test_v2_authorizer_context() {
+    let mut request_context = ApiGatewayV2httpRequestContext::default();
+    assert_eq!(request_context.authorizer_context(), json!({}));
+
+    let mut authorizer = ApiGatewayV2httpRequestContextAuthorizerDescription::default();
+    authorizer
+      .lambda
+      .insert("sub".to_string(), json!("lambda-user"));
+    request_context.authorizer = Some(authorizer.clone());
+    assert_eq!(
+      request_context.authorizer_claims::<Claims>().unwrap(),
+      Claims {
+        sub: "lambda-user".to_string()
+      }
+    );
+
+    let mut jwt = ApiGatewayV2httpRequestContextAuthorizerJwtDescription::default();
+    jwt.claims.insert("sub".to_string(), "jwt-user".to_string());
+    authorizer.jwt = Some(jwt);
+    request_context.authorizer = Some(authorizer);
+    assert_eq!(
+      request_context.authorizer_claims::<Claims>().unwrap(),
+      Claims {
+        sub: "jwt-user".to_string()
+      }
+    );
+  }
+}
@@ -0,0 +1,72 @@
+use crate::StatusCode;
+
+use thiserror::Error;
+
+use std::convert::Infallible;
+use std::string::FromUtf8Error;
+
+/// Error that occurred while sending a request using a client generated via
+/// `CodeGenerator::generate_client`.
+///
+/// Responses with successful (or other non-error) status codes declared in the OpenAPI definition
+/// are returned as `Ok` values of the operation's response type. Declared error responses (i.e.,
+/// those with `4XX` and `5XX` status codes) are returned as a [`ClientError::ErrorResponse`]
+/// holding a variant of the operation's error response type `E`. Operations that don't declare any
+/// error responses use the default `E` of [`Infallible`]. Only responses that can't be represented
+/// by either type produce a [`ClientError::UnexpectedResponse`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ClientError<E = Infallible> {
+  /// Response status code is declared as an error response for the operation in the OpenAPI
+  /// definition.
+  #[error("error response with status code {status_code}")]
+  ErrorResponse {
+    /// HTTP status code of the response.
+    status_code: StatusCode,
+    /// Error response decoded according to the OpenAPI definition.
+    response: E,
+  },
+  /// Response header declared in the OpenAPI definition is missing (if required) or failed to
+  /// parse.
+  #[error("missing or invalid response header `{header_name}`")]
+  InvalidResponseHeader {
+    /// Name of the response header.
+    header_name: &'static str,
+    /// Underlying error that occurred while parsing the header, if any.
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+  },
+  /// Failed to JSON deserialize response body.
+  #[error("failed to JSON deserialize response body")]
+  InvalidResponseJson(#[source] Box<serde_path_to_error::Error<serde_json::Error>>),
+  /// Invalid UTF-8 encoding for response body.
+  #[error("invalid UTF-8 encoding for response body")]
+  InvalidResponseUtf8(#[source] FromUtf8Error),
+  /// Failed to build or send the request, or to receive the response.
+  ///
+  /// This includes request header parameters that aren't valid HTTP header values.
+  #[error("failed to send request")]
+  Request(#[source] reqwest::Error),
+  /// Failed to JSON serialize request body.
+  #[error("failed to JSON serialize request body")]
+  SerializeRequestBody(#[source] serde_json::Error),
+  /// Failed to serialize a `deepObject` request query parameter.
+  #[error("failed to serialize request parameter `{param_name}`")]
+  SerializeRequestParam {
+    /// Name of the parameter.
+    param_name: &'static str,
+    /// Underlying serialization error.
+    #[source]
+    source: serde_json::Error,
+  },
+  /// Response status code is not declared for the operation in the OpenAPI definition.
+  #[error("unexpected response with status code {status_code}")]
+  UnexpectedResponse {
+    /// HTTP status code of the response.
+    status_code: StatusCode,
+    /// Value of the `Content-Type` response header, if any.
+    content_type: Option<String>,
+    /// Raw response body.
+    body: Vec<u8>,
+  },
+}
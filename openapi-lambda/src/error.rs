@@ -1,12 +1,16 @@
-use crate::{HeaderName, HttpResponse, StatusCode};
+use crate::__private::log::error;
+use crate::models::{Problem, PROBLEM_JSON_CONTENT_TYPE};
+use crate::{HeaderMap, HeaderName, HttpResponse, StatusCode};
 
 use aws_lambda_events::encodings::Body;
 // Until std::error::Backtrace is fully stabilized, we can't embed a type named `Backtrace` within
 // a thiserror::Error (see https://github.com/dtolnay/thiserror/issues/204).
 use backtrace::Backtrace as _Backtrace;
 use headers::{ContentType, Header};
+use http::header::{ACCEPT, VARY};
+use http::HeaderValue;
 use itertools::Itertools;
-use log::error;
+use mime::Mime;
 use thiserror::Error;
 
 use std::borrow::Cow;
@@ -16,9 +20,20 @@ use std::string::FromUtf8Error;
 #[non_exhaustive]
 #[derive(Debug, Error)]
 pub enum EventError {
+  /// Request body exceeds the maximum size (in bytes) configured for the API Lambda.
+  #[error("request body exceeds the maximum size of {0} bytes")]
+  BodyTooLarge(usize, _Backtrace),
   /// Failed to prepare HTTP response.
   #[error("failed to prepare HTTP response")]
   HttpResponse(#[source] Box<http::Error>, _Backtrace),
+  /// Failed to deserialize the claims or context produced by an Amazon API Gateway authorizer.
+  // API Gateway populates the authorizer context, so this is an internal error (e.g., a mismatch
+  // between the authorizer and the `x-authorizer-claims` schema).
+  #[error("failed to deserialize authorizer context")]
+  InvalidAuthorizerContext(
+    #[source] Box<serde_path_to_error::Error<serde_json::Error>>,
+    _Backtrace,
+  ),
   /// Invalid base64 encoding for request body.
   // The base64 encoding comes from AWS, so this is actually an internal error.
   #[error("invalid base64 encoding for request body")]
@@ -41,6 +56,17 @@ pub enum EventError {
     #[source] Box<dyn std::error::Error + Send + Sync + 'static>,
     _Backtrace,
   ),
+  /// Failed to parse request header parameter.
+  #[error("failed to parse request header parameter `{param_name}`")]
+  InvalidRequestHeaderParam {
+    /// Name of the header that failed to parse.
+    param_name: Cow<'static, str>,
+    /// Underlying error that occurred while parsing the header.
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+    /// Stack trace indicating where the error occurred.
+    backtrace: _Backtrace,
+  },
   /// Failed to parse request path parameter.
   #[error("failed to parse request path parameter `{param_name}`")]
   InvalidRequestPathParam {
@@ -63,6 +89,22 @@ pub enum EventError {
     /// Stack trace indicating where the error occurred.
     backtrace: _Backtrace,
   },
+  /// Failed to parse an item of an array request query param.
+  #[error("failed to parse item {index} of request query param `{param_name}`")]
+  InvalidRequestQueryParamItem {
+    /// Name of the parameter whose item failed to parse.
+    param_name: Cow<'static, str>,
+    /// Zero-based index of the item that failed to parse.
+    index: usize,
+    /// Value of the item that failed to parse, or `None` if the parameter is sensitive (i.e.,
+    /// `writeOnly` or `format: password`).
+    value: Option<String>,
+    /// Underlying error that occurred while parsing the item.
+    #[source]
+    source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    /// Stack trace indicating where the error occurred.
+    backtrace: _Backtrace,
+  },
   /// Missing required request body.
   #[error("missing required request body")]
   MissingRequestBody(_Backtrace),
@@ -75,6 +117,10 @@ pub enum EventError {
   /// Request handler panicked.
   #[error("request handler panicked: {0}")]
   Panic(String, _Backtrace),
+  /// Response status code is outside of the status code range (e.g., `2XX`) declared by the
+  /// OpenAPI definition.
+  #[error("response status code {0} is outside of the declared `{1}` range")]
+  ResponseStatusOutOfRange(StatusCode, &'static str, _Backtrace),
   /// Failed to serialize response body to JSON.
   #[error("failed to serialize {type_name} response to JSON")]
   ToJsonResponse {
@@ -98,17 +144,22 @@ impl EventError {
   /// Return the backtrace associated with the error, if known.
   pub fn backtrace(&self) -> Option<&_Backtrace> {
     match self {
-      EventError::HttpResponse(_, backtrace)
+      EventError::BodyTooLarge(_, backtrace)
+      | EventError::HttpResponse(_, backtrace)
+      | EventError::InvalidAuthorizerContext(_, backtrace)
       | EventError::InvalidBodyBase64(_, backtrace)
       | EventError::InvalidBodyJson(_, backtrace)
       | EventError::InvalidBodyUtf8(_, backtrace)
       | EventError::InvalidHeaderUtf8(_, _, backtrace)
+      | EventError::InvalidRequestHeaderParam { backtrace, .. }
       | EventError::InvalidRequestPathParam { backtrace, .. }
       | EventError::InvalidRequestQueryParam { backtrace, .. }
+      | EventError::InvalidRequestQueryParamItem { backtrace, .. }
       | EventError::MissingRequestBody(backtrace)
       | EventError::MissingRequestHeader(_, backtrace)
       | EventError::MissingRequestParam(_, backtrace)
       | EventError::Panic(_, backtrace)
+      | EventError::ResponseStatusOutOfRange(_, _, backtrace)
       | EventError::ToJsonResponse { backtrace, .. }
       | EventError::UnexpectedContentType(_, backtrace)
       | EventError::UnexpectedOperationId(_, backtrace) => Some(backtrace),
@@ -118,17 +169,22 @@ impl EventError {
   /// Return the name of the error variant (e.g., `InvalidBodyBase64`).
   pub fn name(&self) -> &str {
     match self {
+      EventError::BodyTooLarge(_, _) => "BodyTooLarge",
       EventError::HttpResponse(_, _) => "HttpResponse",
+      EventError::InvalidAuthorizerContext(_, _) => "InvalidAuthorizerContext",
       EventError::InvalidBodyBase64(_, _) => "InvalidBodyBase64",
       EventError::InvalidBodyJson(_, _) => "InvalidBodyJson",
       EventError::InvalidBodyUtf8(_, _) => "InvalidBodyUtf8",
       EventError::InvalidHeaderUtf8(_, _, _) => "InvalidHeaderUtf8",
+      EventError::InvalidRequestHeaderParam { .. } => "InvalidRequestHeaderParam",
       EventError::InvalidRequestPathParam { .. } => "InvalidRequestPathParam",
       EventError::InvalidRequestQueryParam { .. } => "InvalidRequestQueryParam",
+      EventError::InvalidRequestQueryParamItem { .. } => "InvalidRequestQueryParamItem",
       EventError::MissingRequestBody(_) => "MissingRequestBody",
       EventError::MissingRequestHeader(_, _) => "MissingRequestHeader",
       EventError::MissingRequestParam(_, _) => "MissingRequestParam",
       EventError::Panic(_, _) => "Panic",
+      EventError::ResponseStatusOutOfRange(_, _, _) => "ResponseStatusOutOfRange",
       EventError::ToJsonResponse { .. } => "ToJsonResponse",
       EventError::UnexpectedContentType(_, _) => "UnexpectedContentType",
       EventError::UnexpectedOperationId(_, _) => "UnexpectedOperationId",
@@ -136,6 +192,82 @@ impl EventError {
   }
 }
 
+/// Content type of the response body returned to the client when an [`EventError`] occurs.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ErrorContentType {
+  /// JSON response body (see [`EventError::to_json_response`]).
+  Json,
+  /// [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem details response body (see
+  /// [`EventError::to_problem_json_response`]).
+  ProblemJson,
+  /// Plaintext response body (see the `From<&EventError>` implementation for [`HttpResponse`]).
+  #[default]
+  Text,
+}
+
+impl ErrorContentType {
+  /// Negotiate the content type of error responses based on the request's `Accept` header.
+  ///
+  /// Returns [`ErrorContentType::Json`] if the client prefers `application/json` over `text/plain`,
+  /// taking into account wildcards (e.g., `text/*`) and quality values (e.g., `q=0.5`). Otherwise,
+  /// including when the `Accept` header is missing or both are equally acceptable, returns
+  /// [`ErrorContentType::Text`]. Problem details are opt-in: [`ErrorContentType::ProblemJson`] is
+  /// only returned if the client prefers `application/problem+json` over both of the others.
+  pub fn negotiate(headers: &HeaderMap) -> Self {
+    let media_ranges = headers
+      .get_all(ACCEPT)
+      .iter()
+      .filter_map(|value| value.to_str().ok())
+      .flat_map(|value| value.split(','))
+      .filter_map(|media_range| media_range.trim().parse::<Mime>().ok())
+      .collect::<Vec<_>>();
+
+    let json_quality = accept_quality(&media_ranges, &mime::APPLICATION_JSON);
+    let text_quality = accept_quality(&media_ranges, &mime::TEXT_PLAIN);
+    let problem_json_quality = accept_quality(
+      &media_ranges,
+      &PROBLEM_JSON_CONTENT_TYPE
+        .parse()
+        .expect("problem+json should be a valid MIME type"),
+    );
+
+    if problem_json_quality > json_quality && problem_json_quality > text_quality {
+      ErrorContentType::ProblemJson
+    } else if json_quality > text_quality {
+      ErrorContentType::Json
+    } else {
+      ErrorContentType::Text
+    }
+  }
+}
+
+/// Return the quality value assigned to `media_type` by the most specific matching media range (see
+/// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.1)), or 0 if none match.
+fn accept_quality(media_ranges: &[Mime], media_type: &Mime) -> f32 {
+  media_ranges
+    .iter()
+    .filter_map(|media_range| {
+      let specificity = if media_range.type_() == mime::STAR {
+        0
+      } else if media_range.type_() != media_type.type_() {
+        return None;
+      } else if media_range.subtype() == mime::STAR {
+        1
+      } else if media_range.subtype() == media_type.subtype() {
+        2
+      } else {
+        return None;
+      };
+      let quality = media_range
+        .get_param("q")
+        .map_or(Some(1.0), |quality| quality.as_str().parse::<f32>().ok())
+        .unwrap_or(0.0);
+      Some((specificity, quality))
+    })
+    .max_by_key(|(specificity, _)| *specificity)
+    .map_or(0.0, |(_, quality)| quality)
+}
+
 // For convenience.
 impl From<EventError> for HttpResponse {
   fn from(err: EventError) -> HttpResponse {
@@ -146,70 +278,18 @@ impl From<EventError> for HttpResponse {
 impl From<&EventError> for HttpResponse {
   /// Build a client-facing [`HttpResponse`] appropriate for the error that occurred.
   ///
-  /// This function will set the appropriate HTTP status code (400 or 500) depending on whether the
-  /// error is internal (500) or caused by the client (400). For client errors, the
-  /// response body contains a human-readable description of the error and the `Content-Type`
-  /// response header is set to `text/plain`. For internal errors, no response body is returned to
-  /// the client.
+  /// This function will set the appropriate HTTP status code (400, 413, or 500) depending on whether
+  /// the error is internal (500) or caused by the client (400, or 413 for oversized request bodies).
+  /// For client errors, the response body contains a human-readable description of the error and
+  /// the `Content-Type` response header is set to `text/plain`. For internal errors, no response
+  /// body is returned to the client.
+  ///
+  /// See [`EventError::to_json_response`] for an alternative that returns JSON response bodies.
   fn from(err: &EventError) -> HttpResponse {
-    let (status_code, body) = match err {
-      // 400
-      EventError::InvalidBodyJson(err, _) => (
-        StatusCode::BAD_REQUEST,
-        // We expose parse errors to the client to provide better 400 Bad Request diagnostics.
-        Some(if err.path().iter().next().is_none() {
-          format!("Invalid request body: {}", err.inner())
-        } else {
-          format!(
-            "Invalid request body (path: `{}`): {}",
-            err.path(),
-            err.inner()
-          )
-        }),
-      ),
-      EventError::InvalidBodyUtf8(_, _) => (
-        StatusCode::BAD_REQUEST,
-        Some("Request body must be UTF-8 encoded".to_string()),
-      ),
-      EventError::InvalidHeaderUtf8(header_name, _, _) => (
-        StatusCode::BAD_REQUEST,
-        Some(format!(
-          "Invalid value for header `{header_name}`: must be UTF-8 encoded"
-        )),
-      ),
-      EventError::InvalidRequestPathParam { param_name, .. } => (
-        StatusCode::BAD_REQUEST,
-        Some(format!("Invalid `{param_name}` request path parameter")),
-      ),
-      EventError::InvalidRequestQueryParam { param_name, .. } => (
-        StatusCode::BAD_REQUEST,
-        Some(format!("Invalid `{param_name}` query parameter")),
-      ),
-      EventError::MissingRequestBody(_) => (
-        StatusCode::BAD_REQUEST,
-        Some("Missing request body".to_string()),
-      ),
-      EventError::MissingRequestHeader(header_name, _) => (
-        StatusCode::BAD_REQUEST,
-        Some(format!("Missing request header `{header_name}`")),
-      ),
-      EventError::MissingRequestParam(param_name, _) => (
-        StatusCode::BAD_REQUEST,
-        Some(format!("Missing required parameter `{param_name}`")),
-      ),
-      EventError::UnexpectedContentType(content_type, _) => (
-        StatusCode::BAD_REQUEST,
-        Some(format!("Unexpected content type `{content_type}`")),
-      ),
-      // 500
-      EventError::HttpResponse(_, _)
-      | EventError::InvalidBodyBase64(_, _)
-      | EventError::Panic(_, _)
-      | EventError::ToJsonResponse { .. }
-      | EventError::UnexpectedOperationId(_, _) => (StatusCode::INTERNAL_SERVER_ERROR, None),
-    };
+    let client_message = err.client_message();
+    let status_code = err.status_code();
 
-    let mut response = if let Some(body_str) = body {
+    let mut response = if let Some(body_str) = client_message {
       error!("Responding with error status {status_code}: {body_str}");
 
       let mut response = HttpResponse::new(Body::Text(body_str));
@@ -234,6 +314,213 @@ impl From<&EventError> for HttpResponse {
   }
 }
 
+impl EventError {
+  /// Build a client-facing [`HttpResponse`] with a machine-readable JSON body describing the error.
+  ///
+  /// The HTTP status code (400, 413, or 500) is the same as for the default plaintext response returned
+  /// by the `From<&EventError>` implementation for [`HttpResponse`], and the `Content-Type` response
+  /// header is set to `application/json`. The response body is a JSON object of the form:
+  ///
+  /// ```json
+  /// {
+  ///   "error": "InvalidBodyJson",
+  ///   "message": "Invalid request body (path: `name`): invalid type: integer `1`, expected a string at line 1 column 9",
+  ///   "path": "name"
+  /// }
+  /// ```
+  ///
+  /// The `error` field contains the name of the error variant (see [`EventError::name`]) for client
+  /// errors, or `InternalServerError` for internal errors, whose details are not exposed to the
+  /// client. The `path` field is only present for [`EventError::InvalidBodyJson`] errors that
+  /// occurred within the request body (rather than at the top level).
+  ///
+  /// By default, the generated `Api::respond_to_event_error` method only responds with JSON error
+  /// bodies if the client prefers them (see [`ErrorContentType::negotiate`]). To always respond
+  /// with JSON error bodies, override that method:
+  ///
+  /// ```rust,ignore
+  /// async fn respond_to_event_error(
+  ///   &self,
+  ///   err: EventError,
+  ///   _content_type: ErrorContentType,
+  /// ) -> HttpResponse {
+  ///   log::error!(
+  ///     "{}",
+  ///     format_error(&err, Some(&format!("EventError::{}", err.name())), err.backtrace()),
+  ///   );
+  ///
+  ///   err.to_json_response()
+  /// }
+  /// ```
+  pub fn to_json_response(&self) -> HttpResponse {
+    let status_code = self.status_code();
+
+    let mut body = serde_json::Map::new();
+    if let Some(message) = self.client_message() {
+      error!("Responding with error status {status_code}: {message}");
+
+      body.insert("error".to_string(), self.name().into());
+      body.insert("message".to_string(), message.into());
+      if let EventError::InvalidBodyJson(err, _) = self {
+        if err.path().iter().next().is_some() {
+          body.insert("path".to_string(), err.path().to_string().into());
+        }
+      }
+    } else {
+      error!("Responding with error status {status_code}");
+
+      body.insert("error".to_string(), "InternalServerError".into());
+      body.insert("message".to_string(), "Internal server error".into());
+    }
+
+    let mut response = HttpResponse::new(Body::Text(serde_json::Value::Object(body).to_string()));
+    response.headers_mut().insert(
+      ContentType::name().to_owned(),
+      ContentType::json()
+        .to_string()
+        .try_into()
+        .expect("MIME type should be a valid header"),
+    );
+    *response.status_mut() = status_code;
+
+    response
+  }
+
+  /// Build a client-facing [`HttpResponse`] with an
+  /// [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807) problem details body describing the error.
+  ///
+  /// The HTTP status code (400, 413, or 500) is the same as for the default plaintext response returned
+  /// by the `From<&EventError>` implementation for [`HttpResponse`], and the `Content-Type` response
+  /// header is set to `application/problem+json`. The response body is a [`Problem`] of the form:
+  ///
+  /// ```json
+  /// {
+  ///   "type": "about:blank",
+  ///   "title": "Bad Request",
+  ///   "status": 400,
+  ///   "detail": "Missing request body"
+  /// }
+  /// ```
+  ///
+  /// The `title` field is the reason phrase of the HTTP status code. The `detail` field contains a
+  /// human-readable description of client errors and is omitted for internal errors, whose details
+  /// are not exposed to the client.
+  pub fn to_problem_json_response(&self) -> HttpResponse {
+    let status_code = self.status_code();
+
+    let problem = if let Some(detail) = self.client_message() {
+      error!("Responding with error status {status_code}: {detail}");
+      Problem::new(status_code).with_detail(detail)
+    } else {
+      error!("Responding with error status {status_code}");
+      Problem::new(status_code)
+    };
+
+    problem.into()
+  }
+
+  /// Build a client-facing [`HttpResponse`] with a body of the specified content type.
+  ///
+  /// Returns [`EventError::to_json_response`] for [`ErrorContentType::Json`],
+  /// [`EventError::to_problem_json_response`] for [`ErrorContentType::ProblemJson`], or the
+  /// plaintext response returned by the `From<&EventError>` implementation for [`HttpResponse`] for
+  /// [`ErrorContentType::Text`]. Since the content type is typically negotiated based on the
+  /// request's `Accept` header, the response includes a `Vary: Accept` header.
+  pub fn to_response(&self, content_type: ErrorContentType) -> HttpResponse {
+    let mut response = match content_type {
+      ErrorContentType::Json => self.to_json_response(),
+      ErrorContentType::ProblemJson => self.to_problem_json_response(),
+      ErrorContentType::Text => self.into(),
+    };
+    response
+      .headers_mut()
+      .insert(VARY, HeaderValue::from_static(ACCEPT.as_str()));
+    response
+  }
+
+  /// HTTP status code to return to the client: 400 for client errors (or 413 for oversized request
+  /// bodies) and 500 for internal errors.
+  fn status_code(&self) -> StatusCode {
+    if let EventError::BodyTooLarge(_, _) = self {
+      StatusCode::PAYLOAD_TOO_LARGE
+    } else if self.client_message().is_some() {
+      StatusCode::BAD_REQUEST
+    } else {
+      StatusCode::INTERNAL_SERVER_ERROR
+    }
+  }
+
+  /// Human-readable description of a client error, or `None` for internal errors.
+  fn client_message(&self) -> Option<String> {
+    match self {
+      // 413
+      EventError::BodyTooLarge(max_body_bytes, _) => Some(format!(
+        "Request body exceeds the maximum size of {max_body_bytes} bytes"
+      )),
+      // 400
+      EventError::InvalidBodyJson(err, _) => Some(
+        // We expose parse errors to the client to provide better 400 Bad Request diagnostics.
+        if err.path().iter().next().is_none() {
+          format!("Invalid request body: {}", err.inner())
+        } else {
+          format!(
+            "Invalid request body (path: `{}`): {}",
+            err.path(),
+            err.inner()
+          )
+        },
+      ),
+      EventError::InvalidBodyUtf8(_, _) => Some("Request body must be UTF-8 encoded".to_string()),
+      EventError::InvalidHeaderUtf8(header_name, _, _) => Some(format!(
+        "Invalid value for header `{header_name}`: must be UTF-8 encoded"
+      )),
+      EventError::InvalidRequestHeaderParam { param_name, .. } => {
+        Some(format!("Invalid `{param_name}` request header"))
+      }
+      EventError::InvalidRequestPathParam { param_name, .. } => {
+        Some(format!("Invalid `{param_name}` request path parameter"))
+      }
+      EventError::InvalidRequestQueryParam { param_name, .. } => {
+        Some(format!("Invalid `{param_name}` query parameter"))
+      }
+      EventError::InvalidRequestQueryParamItem {
+        param_name,
+        index,
+        value: Some(value),
+        ..
+      } => Some(format!(
+        "Invalid item `{value}` at index {index} of `{param_name}` query parameter"
+      )),
+      EventError::InvalidRequestQueryParamItem {
+        param_name,
+        index,
+        value: None,
+        ..
+      } => Some(format!(
+        "Invalid item at index {index} of `{param_name}` query parameter"
+      )),
+      EventError::MissingRequestBody(_) => Some("Missing request body".to_string()),
+      EventError::MissingRequestHeader(header_name, _) => {
+        Some(format!("Missing request header `{header_name}`"))
+      }
+      EventError::MissingRequestParam(param_name, _) => {
+        Some(format!("Missing required parameter `{param_name}`"))
+      }
+      EventError::UnexpectedContentType(content_type, _) => {
+        Some(format!("Unexpected content type `{content_type}`"))
+      }
+      // 500
+      EventError::HttpResponse(_, _)
+      | EventError::InvalidAuthorizerContext(_, _)
+      | EventError::InvalidBodyBase64(_, _)
+      | EventError::Panic(_, _)
+      | EventError::ResponseStatusOutOfRange(_, _, _)
+      | EventError::ToJsonResponse { .. }
+      | EventError::UnexpectedOperationId(_, _) => None,
+    }
+  }
+}
+
 /// Helper function for formatting an error as a string containing a human-readable chain of causes.
 ///
 /// This function will walk over the chain of causes returned by
@@ -247,7 +534,7 @@ impl From<&EventError> for HttpResponse {
 /// * `backtrace` - Optional [`Backtrace`](backtrace::Backtrace) indicating where the top-level
 ///   error occurred.
 pub fn format_error(
-  err: &(dyn std::error::Error),
+  err: &dyn std::error::Error,
   name: Option<&str>,
   backtrace: Option<&_Backtrace>,
 ) -> String {
@@ -290,3 +577,292 @@ fn format_backtrace(backtrace: &_Backtrace, indent: usize) -> String {
     .lines()
     .join(&format!("{indent_str}\n"))
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::models::Problem;
+  use crate::{Body, ErrorContentType, EventError, HeaderMap, HttpResponse, StatusCode};
+
+  use backtrace::Backtrace;
+  use http::header::{ACCEPT, CONTENT_TYPE, VARY};
+  use serde::Deserialize;
+
+  #[allow(dead_code)]
+  #[derive(Debug, Deserialize)]
+  struct Request {
+    name: String,
+  }
+
+  fn invalid_body_json_error(body: &str) -> EventError {
+    EventError::InvalidBodyJson(
+      Box::new(
+        serde_path_to_error::deserialize::<_, Request>(&mut serde_json::Deserializer::from_str(
+          body,
+        ))
+        .unwrap_err(),
+      ),
+      Backtrace::new(),
+    )
+  }
+
+  fn json_body(response: &HttpResponse) -> serde_json::Value {
+    assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+    let Body::Text(body) = response.body() else {
+      panic!("unexpected body {:?}", response.body());
+    };
+    serde_json::from_str(body).unwrap()
+  }
+
+  #[test]
+  fn test_to_json_response() {
+    let err = invalid_body_json_error(r#"{"name": 1}"#);
+    let response = err.to_json_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "InvalidBodyJson",
+        "message": "Invalid request body (path: `name`): invalid type: integer `1`, expected a \
+                    string at line 1 column 10",
+        "path": "name",
+      })
+    );
+
+    // The plaintext response remains the default.
+    let response = HttpResponse::from(&err);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/plain");
+
+    let response = invalid_body_json_error("1").to_json_response();
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "InvalidBodyJson",
+        "message": "Invalid request body: invalid type: integer `1`, expected struct Request at \
+                    line 1 column 1",
+      })
+    );
+
+    let response =
+      EventError::MissingRequestHeader("x-api-key".into(), Backtrace::new()).to_json_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "MissingRequestHeader",
+        "message": "Missing request header `x-api-key`",
+      })
+    );
+
+    let response = EventError::Panic("oops".to_string(), Backtrace::new()).to_json_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "InternalServerError",
+        "message": "Internal server error",
+      })
+    );
+  }
+
+  #[test]
+  fn test_negotiate_error_content_type() {
+    let negotiate = |accept: &[&str]| {
+      let mut headers = HeaderMap::new();
+      for value in accept {
+        headers.append(ACCEPT, value.parse().unwrap());
+      }
+      ErrorContentType::negotiate(&headers)
+    };
+
+    assert_eq!(negotiate(&[]), ErrorContentType::Text);
+    assert_eq!(negotiate(&["*/*"]), ErrorContentType::Text);
+    assert_eq!(negotiate(&["text/plain"]), ErrorContentType::Text);
+    assert_eq!(negotiate(&["text/html"]), ErrorContentType::Text);
+    assert_eq!(negotiate(&["application/json"]), ErrorContentType::Json);
+    assert_eq!(negotiate(&["application/*"]), ErrorContentType::Json);
+    assert_eq!(
+      negotiate(&["application/json, text/plain"]),
+      ErrorContentType::Text
+    );
+    assert_eq!(
+      negotiate(&["application/json", "*/*;q=0.8"]),
+      ErrorContentType::Json
+    );
+    assert_eq!(
+      negotiate(&["text/*;q=0.5, application/json;q=0.9"]),
+      ErrorContentType::Json
+    );
+    assert_eq!(
+      negotiate(&["application/json;q=0.5, text/plain"]),
+      ErrorContentType::Text
+    );
+    // The most specific media range determines the quality value.
+    assert_eq!(
+      negotiate(&["*/*, application/json;q=0"]),
+      ErrorContentType::Text
+    );
+    assert_eq!(negotiate(&["not a media type"]), ErrorContentType::Text);
+    assert_eq!(
+      negotiate(&["application/problem+json"]),
+      ErrorContentType::ProblemJson
+    );
+    assert_eq!(
+      negotiate(&["application/problem+json, application/json;q=0.9"]),
+      ErrorContentType::ProblemJson
+    );
+    // Problem details are only returned if the client explicitly prefers them.
+    assert_eq!(
+      negotiate(&["application/problem+json, application/json"]),
+      ErrorContentType::Json
+    );
+  }
+
+  #[test]
+  fn test_to_response() {
+    let err = EventError::MissingRequestBody(Backtrace::new());
+
+    let response = err.to_response(ErrorContentType::Json);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()[VARY], "accept");
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "MissingRequestBody",
+        "message": "Missing request body",
+      })
+    );
+
+    let response = err.to_response(ErrorContentType::Text);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()[VARY], "accept");
+    assert_eq!(response.headers()[CONTENT_TYPE], "text/plain");
+    assert_eq!(
+      response.body(),
+      &Body::Text("Missing request body".to_string())
+    );
+  }
+
+  #[test]
+  fn test_to_problem_json_response() {
+    let problem_body = |response: &HttpResponse| {
+      assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+      let Body::Text(body) = response.body() else {
+        panic!("unexpected body {:?}", response.body());
+      };
+      serde_json::from_str::<serde_json::Value>(body).unwrap()
+    };
+
+    let err = EventError::MissingRequestBody(Backtrace::new());
+    let response = err.to_response(ErrorContentType::ProblemJson);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()[VARY], "accept");
+    assert_eq!(
+      problem_body(&response),
+      serde_json::json!({
+        "type": "about:blank",
+        "title": "Bad Request",
+        "status": 400,
+        "detail": "Missing request body",
+      })
+    );
+
+    let response = EventError::BodyTooLarge(1024, Backtrace::new()).to_problem_json_response();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+      problem_body(&response),
+      serde_json::json!({
+        "type": "about:blank",
+        "title": "Payload Too Large",
+        "status": 413,
+        "detail": "Request body exceeds the maximum size of 1024 bytes",
+      })
+    );
+
+    let response =
+      EventError::Panic("oops".to_string(), Backtrace::new()).to_problem_json_response();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+      problem_body(&response),
+      serde_json::json!({
+        "type": "about:blank",
+        "title": "Internal Server Error",
+        "status": 500,
+      })
+    );
+
+    // Handlers can return the same shape for their own errors.
+    let response = HttpResponse::from(
+      Problem::new(StatusCode::FORBIDDEN)
+        .with_type("https://example.com/problems/access-denied")
+        .with_detail("Access denied"),
+    );
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(
+      problem_body(&response),
+      serde_json::json!({
+        "type": "https://example.com/problems/access-denied",
+        "title": "Forbidden",
+        "status": 403,
+        "detail": "Access denied",
+      })
+    );
+  }
+
+  #[test]
+  fn test_invalid_request_query_param_item() {
+    let err = EventError::InvalidRequestQueryParamItem {
+      param_name: "ids".into(),
+      index: 1,
+      value: Some("abc".to_string()),
+      source: "abc".parse::<i64>().unwrap_err().into(),
+      backtrace: Backtrace::new(),
+    };
+    let response = err.to_json_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "InvalidRequestQueryParamItem",
+        "message": "Invalid item `abc` at index 1 of `ids` query parameter",
+      })
+    );
+
+    // Values of sensitive parameters aren't echoed back to the client.
+    let err = EventError::InvalidRequestQueryParamItem {
+      param_name: "pins".into(),
+      index: 0,
+      value: None,
+      source: "abc".parse::<i64>().unwrap_err().into(),
+      backtrace: Backtrace::new(),
+    };
+    let response = HttpResponse::from(&err);
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      response.body(),
+      &Body::Text("Invalid item at index 0 of `pins` query parameter".to_string())
+    );
+  }
+
+  #[test]
+  fn test_body_too_large() {
+    let err = EventError::BodyTooLarge(1024, Backtrace::new());
+
+    let response = err.to_response(ErrorContentType::Json);
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+      json_body(&response),
+      serde_json::json!({
+        "error": "BodyTooLarge",
+        "message": "Request body exceeds the maximum size of 1024 bytes",
+      })
+    );
+
+    let response = HttpResponse::from(&err);
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    assert_eq!(
+      response.body(),
+      &Body::Text("Request body exceeds the maximum size of 1024 bytes".to_string())
+    );
+  }
+}
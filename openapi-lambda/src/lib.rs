@@ -1,45 +1,235 @@
 #![doc = include_str!("../README.md")]
 #![warn(missing_docs)]
 
-use aws_lambda_events::apigw::ApiGatewayProxyResponse;
+use aws_lambda_events::alb::AlbTargetGroupResponse;
+use aws_lambda_events::apigw::{ApiGatewayProxyResponse, ApiGatewayV2httpResponse};
+use http::header::SET_COOKIE;
+use http::HeaderValue;
 
 // These are documented public exports since either the generated `Api` traits or the `Middleware`
 // depends on them.
 pub use async_trait;
-pub use aws_lambda_events::apigw::ApiGatewayProxyRequestContext;
+pub use aws_lambda_events::alb::AlbTargetGroupRequestContext;
+pub use aws_lambda_events::apigw::{ApiGatewayProxyRequestContext, ApiGatewayV2httpRequestContext};
 pub use aws_lambda_events::encodings::Body;
 pub use aws_lambda_events::http::{HeaderMap, HeaderName};
 pub use http::{Response, StatusCode};
 pub use lambda_runtime::{Context as LambdaContext, LambdaEvent};
 
+mod authorizer;
+
+pub use authorizer::AuthorizerContext;
+
+#[cfg(feature = "client")]
+mod client;
+
+#[cfg(feature = "client")]
+pub use client::ClientError;
+
 /// Error handling.
 pub mod error;
 
-pub use error::EventError;
+pub use error::{ErrorContentType, EventError};
+
+#[cfg(feature = "local")]
+mod local;
+
+#[cfg(feature = "local")]
+pub use local::run_local;
 
 mod middleware;
 
-pub use middleware::{Middleware, UnauthenticatedMiddleware};
+pub use middleware::{
+  Middleware, SecurityRequirement, SecuritySchemeRequirement, UnauthenticatedMiddleware,
+};
 
 /// Request/response model-related types and re-exports.
 pub mod models;
 
+mod operation;
+
+pub use operation::OperationInfo;
+
 mod runtime;
 
-pub use runtime::run_lambda;
+pub use runtime::{run_lambda, run_lambda_alb, run_lambda_streaming, run_lambda_v2};
+
+mod streaming;
+
+pub use streaming::{
+  http_response_to_stream, ResponseBodyStream, ResponseStream, StreamError, StreamingResponse,
+};
+
+/// Utilities for testing API handlers in-process (requires the `test-util` crate feature).
+#[cfg(feature = "test-util")]
+pub mod test_util;
 
 /// HTTP response.
 pub type HttpResponse = Response<Body>;
 
 /// Serialize an [`HttpResponse`] as an [`ApiGatewayProxyResponse`].
+///
+/// Both the `headers` and `multiValueHeaders` fields of the response are populated so that every
+/// header reaches the client regardless of how the integration is configured. The `headers` field
+/// contains the last value of each header. Binary response bodies are base64-encoded, which
+/// requires the API to define
+/// [binary media types](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-payload-encodings.html)
+/// for API Gateway to decode them before responding to the client.
 pub fn http_response_to_apigw(response: HttpResponse) -> ApiGatewayProxyResponse {
   let (parts, body) = response.into_parts();
+  let headers = parts
+    .headers
+    .keys()
+    .filter_map(|header_name| {
+      parts
+        .headers
+        .get_all(header_name)
+        .iter()
+        .next_back()
+        .map(|header_value| (header_name.to_owned(), header_value.to_owned()))
+    })
+    .collect();
+
   ApiGatewayProxyResponse {
     status_code: parts.status.as_u16() as i64,
-    headers: Default::default(),
+    headers,
     multi_value_headers: parts.headers,
+    // `Body::Binary` is serialized as a base64-encoded string.
+    is_base64_encoded: matches!(body, Body::Binary(_)),
+    body: Some(body),
+  }
+}
+
+/// Serialize an [`HttpResponse`] as an [`ApiGatewayV2httpResponse`] (HTTP API payload format
+/// version 2.0).
+///
+/// Payload format version 2.0 doesn't support multi-value headers, so multiple values for the same
+/// header are joined into a single comma-separated value. `Set-Cookie` headers are instead returned
+/// via the response's `cookies` field.
+pub fn http_response_to_apigw_v2(response: HttpResponse) -> ApiGatewayV2httpResponse {
+  let (mut parts, body) = response.into_parts();
+  let cookies = take_set_cookie_headers(&mut parts.headers);
+
+  ApiGatewayV2httpResponse {
+    status_code: parts.status.as_u16() as i64,
+    headers: join_multi_value_headers(&parts.headers),
+    multi_value_headers: Default::default(),
+    is_base64_encoded: matches!(body, Body::Binary(_)),
     body: Some(body),
-    is_base64_encoded: false,
+    cookies,
+  }
+}
+
+/// Remove any `Set-Cookie` headers and return their values.
+fn take_set_cookie_headers(headers: &mut HeaderMap) -> Vec<String> {
+  let cookies = headers
+    .get_all(SET_COOKIE)
+    .iter()
+    .map(|cookie| String::from_utf8_lossy(cookie.as_bytes()).into_owned())
+    .collect();
+  headers.remove(SET_COOKIE);
+  cookies
+}
+
+/// Join multiple values for the same header into a single comma-separated value.
+fn join_multi_value_headers(headers: &HeaderMap) -> HeaderMap {
+  headers
+    .keys()
+    .map(|header_name| {
+      let joined_values = headers
+        .get_all(header_name)
+        .iter()
+        .map(HeaderValue::as_bytes)
+        .collect::<Vec<_>>()
+        .join(&b", "[..]);
+      (
+        header_name.to_owned(),
+        HeaderValue::from_bytes(&joined_values)
+          .expect("joined header values should be a valid header value"),
+      )
+    })
+    .collect()
+}
+
+/// Serialize an [`HttpResponse`] as an [`AlbTargetGroupResponse`] (Application Load Balancer).
+///
+/// Both the `headers` and `multiValueHeaders` fields of the response are populated so that the
+/// response is compatible with target groups that have multi-value headers either enabled or
+/// disabled. If multi-value headers are disabled, only the first value of each header is returned
+/// to the client.
+pub fn http_response_to_alb(response: HttpResponse) -> AlbTargetGroupResponse {
+  let (parts, body) = response.into_parts();
+  AlbTargetGroupResponse {
+    status_code: parts.status.as_u16() as i64,
+    // Load balancers require a status description such as `200 OK`.
+    status_description: Some(format!(
+      "{} {}",
+      parts.status.as_u16(),
+      parts.status.canonical_reason().unwrap_or("Unknown")
+    )),
+    headers: parts.headers.clone(),
+    multi_value_headers: parts.headers,
+    is_base64_encoded: matches!(body, Body::Binary(_)),
+    body: Some(body),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{http_response_to_apigw, Body, Response};
+
+  use http::header::{CONTENT_TYPE, SET_COOKIE};
+
+  #[test]
+  fn test_http_response_to_apigw_headers() {
+    let response = Response::builder()
+      .header(CONTENT_TYPE, "application/json")
+      .header(SET_COOKIE, "a=1")
+      .header(SET_COOKIE, "b=2")
+      .body(Body::Empty)
+      .unwrap();
+
+    let apigw_response = http_response_to_apigw(response);
+    assert_eq!(apigw_response.headers[CONTENT_TYPE], "application/json");
+    assert_eq!(apigw_response.headers[SET_COOKIE], "b=2");
+    assert_eq!(
+      apigw_response.multi_value_headers[CONTENT_TYPE],
+      "application/json"
+    );
+    assert_eq!(
+      apigw_response
+        .multi_value_headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .collect::<Vec<_>>(),
+      ["a=1", "b=2"]
+    );
+  }
+
+  #[test]
+  fn test_http_response_to_apigw_binary() {
+    let png_bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+    let response = Response::builder()
+      .header(CONTENT_TYPE, "image/png")
+      .body(Body::Binary(png_bytes))
+      .unwrap();
+
+    let apigw_response = http_response_to_apigw(response);
+    assert!(apigw_response.is_base64_encoded);
+    let serialized = serde_json::to_value(&apigw_response).unwrap();
+    assert_eq!(serialized["body"], "iVBORw0KGgo=");
+    assert_eq!(serialized["isBase64Encoded"], true);
+  }
+
+  #[test]
+  fn test_http_response_to_apigw_text() {
+    let response = Response::builder()
+      .body(Body::Text("hello".to_string()))
+      .unwrap();
+
+    let apigw_response = http_response_to_apigw(response);
+    assert!(!apigw_response.is_base64_encoded);
+    assert_eq!(apigw_response.body, Some(Body::Text("hello".to_string())));
   }
 }
 
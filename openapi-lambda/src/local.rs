@@ -0,0 +1,272 @@
+use crate::__private::log;
+use crate::{Body, LambdaContext, LambdaEvent, StatusCode};
+
+use aws_lambda_events::apigw::{
+  ApiGatewayProxyRequest, ApiGatewayProxyRequestContext, ApiGatewayProxyResponse,
+};
+use aws_lambda_events::query_map::QueryMap;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine as _;
+use bytes::Bytes;
+use futures::future::{self, Either};
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+use http::request::Parts;
+use http::Response;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::Request;
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use std::future::Future;
+use std::net::SocketAddr;
+
+/// Start a local HTTP server that handles requests for the specified API using the specified
+/// middleware, without deploying to AWS or running the AWS SAM CLI.
+///
+/// Each HTTP request is converted into an [`ApiGatewayProxyRequest`] and passed to the same
+/// dispatcher used by [`run_lambda`](crate::run_lambda), and the resulting
+/// [`ApiGatewayProxyResponse`] is converted back into an HTTP response. As with an API Gateway
+/// `/{proxy+}` resource, the request doesn't specify an operation name, so the dispatcher
+/// identifies the operation by matching the HTTP method and path of the request against the path
+/// template of each operation. Response streaming is not supported.
+///
+/// This function only returns if the server fails to bind to the specified address.
+///
+/// Requires the `local` crate feature.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Replace `my_api` with the name of your crate and `backend` with the name of the module
+/// // passed to `ApiLambda::new()`.
+/// use my_api::backend::Api;
+/// use my_api::backend_handler::BackendApiHandler;
+/// use openapi_lambda::run_local;
+///
+/// #[tokio::main]
+/// pub async fn main() {
+///   let api = BackendApiHandler::new(...);
+///   let middleware = ...; // Instantiate your middleware here.
+///
+///   run_local(
+///     ([127, 0, 0, 1], 3000).into(),
+///     |event| api.dispatch_request(event, &middleware),
+///   )
+///   .await
+///   .expect("failed to start local server")
+/// }
+/// ```
+pub async fn run_local<F, Fut>(addr: SocketAddr, dispatch_event: F) -> std::io::Result<()>
+where
+  F: Fn(LambdaEvent<ApiGatewayProxyRequest>) -> Fut,
+  Fut: Future<Output = ApiGatewayProxyResponse>,
+{
+  let listener = TcpListener::bind(addr).await?;
+  log::info!("listening on http://{}", listener.local_addr()?);
+
+  // Connections are served concurrently within the current task (rather than by spawning a task
+  // for each one) so that `dispatch_event` may borrow the API handler and middleware, as with
+  // `run_lambda`.
+  let dispatch_event = &dispatch_event;
+  let mut connections = FuturesUnordered::new();
+  loop {
+    let accepted = if connections.is_empty() {
+      listener.accept().await
+    } else {
+      match future::select(Box::pin(listener.accept()), connections.next()).await {
+        Either::Left((accepted, _)) => accepted,
+        Either::Right(_) => continue,
+      }
+    };
+
+    let (stream, remote_addr) = match accepted {
+      Ok(accepted) => accepted,
+      Err(err) => {
+        log::warn!("failed to accept connection: {err}");
+        continue;
+      }
+    };
+
+    connections.push(
+      http1::Builder::new()
+        .serve_connection(
+          TokioIo::new(stream),
+          service_fn(move |request| handle_request(dispatch_event, request)),
+        )
+        .map(move |result| {
+          if let Err(err) = result {
+            log::warn!("failed to serve connection from {remote_addr}: {err}");
+          }
+        }),
+    );
+  }
+}
+
+async fn handle_request<F, Fut>(
+  dispatch_event: &F,
+  request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, hyper::Error>
+where
+  F: Fn(LambdaEvent<ApiGatewayProxyRequest>) -> Fut,
+  Fut: Future<Output = ApiGatewayProxyResponse>,
+{
+  let (parts, body) = request.into_parts();
+  let body = body.collect().await?.to_bytes();
+
+  let event = LambdaEvent::new(
+    http_request_to_apigw(parts, &body),
+    LambdaContext::default(),
+  );
+  Ok(apigw_response_to_http(dispatch_event(event).await))
+}
+
+/// Resource of the API Gateway greedy proxy integration emulated by the local server.
+const PROXY_RESOURCE: &str = "/{proxy+}";
+
+/// Convert an HTTP request into an [`ApiGatewayProxyRequest`] similar to the one that API Gateway
+/// sends to a Lambda proxy integration for a `/{proxy+}` resource.
+fn http_request_to_apigw(parts: Parts, body: &[u8]) -> ApiGatewayProxyRequest {
+  let path = parts.uri.path().to_string();
+  let path_parameters = [(
+    "proxy".to_string(),
+    path.trim_start_matches('/').to_string(),
+  )]
+  .into_iter()
+  .collect();
+  let query = parts
+    .uri
+    .query()
+    .unwrap_or_default()
+    .parse::<QueryMap>()
+    .unwrap_or_else(|err| match err {});
+
+  // Like API Gateway, base64-encode any request bodies that aren't valid UTF-8.
+  let (body, is_base64_encoded) = if body.is_empty() {
+    (None, false)
+  } else {
+    match std::str::from_utf8(body) {
+      Ok(body) => (Some(body.to_string()), false),
+      Err(_) => (Some(BASE64_STANDARD.encode(body)), true),
+    }
+  };
+
+  ApiGatewayProxyRequest {
+    resource: Some(PROXY_RESOURCE.to_string()),
+    path: Some(path.clone()),
+    http_method: parts.method.clone(),
+    headers: parts.headers.clone(),
+    multi_value_headers: parts.headers,
+    query_string_parameters: query.clone(),
+    multi_value_query_string_parameters: query,
+    path_parameters,
+    request_context: ApiGatewayProxyRequestContext {
+      http_method: parts.method,
+      path: Some(path),
+      resource_path: Some(PROXY_RESOURCE.to_string()),
+      ..Default::default()
+    },
+    body,
+    is_base64_encoded,
+    ..Default::default()
+  }
+}
+
+/// Convert an [`ApiGatewayProxyResponse`] into an HTTP response.
+fn apigw_response_to_http(response: ApiGatewayProxyResponse) -> Response<Full<Bytes>> {
+  let body = match response.body {
+    Some(Body::Text(text)) => Bytes::from(text),
+    Some(Body::Binary(binary)) => Bytes::from(binary),
+    Some(Body::Empty) | None => Bytes::new(),
+  };
+
+  let mut http_response = Response::new(Full::new(body));
+  *http_response.status_mut() = u16::try_from(response.status_code)
+    .ok()
+    .and_then(|status_code| StatusCode::from_u16(status_code).ok())
+    .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+  // `http_response_to_apigw` populates both fields, but `multiValueHeaders` includes every value.
+  *http_response.headers_mut() = if response.multi_value_headers.is_empty() {
+    response.headers
+  } else {
+    response.multi_value_headers
+  };
+  http_response
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{apigw_response_to_http, http_request_to_apigw};
+  use crate::{http_response_to_apigw, Body, Response, StatusCode};
+
+  use http::header::{CONTENT_TYPE, SET_COOKIE};
+  use http::{Method, Request};
+  use http_body_util::BodyExt;
+
+  #[test]
+  fn test_http_request_to_apigw() {
+    let (parts, ()) = Request::builder()
+      .method(Method::POST)
+      .uri("http://localhost:3000/pet/123?tag=a&tag=b%20c")
+      .header(CONTENT_TYPE, "application/json")
+      .body(())
+      .unwrap()
+      .into_parts();
+
+    let request = http_request_to_apigw(parts, br#"{"name":"foo"}"#);
+    assert_eq!(request.http_method, Method::POST);
+    assert_eq!(request.path.as_deref(), Some("/pet/123"));
+    assert_eq!(request.resource.as_deref(), Some("/{proxy+}"));
+    assert_eq!(request.path_parameters["proxy"], "pet/123");
+    assert_eq!(request.request_context.operation_name, None);
+    assert_eq!(request.headers[CONTENT_TYPE], "application/json");
+    assert_eq!(request.query_string_parameters.first("tag"), Some("a"));
+    assert_eq!(
+      request.multi_value_query_string_parameters.all("tag"),
+      Some(vec!["a", "b c"])
+    );
+    assert_eq!(request.body.as_deref(), Some(r#"{"name":"foo"}"#));
+    assert!(!request.is_base64_encoded);
+
+    let (parts, ()) = Request::builder()
+      .uri("/upload")
+      .body(())
+      .unwrap()
+      .into_parts();
+    let request = http_request_to_apigw(parts, b"\x89PNG\r\n\x1a\n");
+    assert_eq!(request.body.as_deref(), Some("iVBORw0KGgo="));
+    assert!(request.is_base64_encoded);
+  }
+
+  #[test]
+  fn test_apigw_response_to_http() {
+    let response = http_response_to_apigw(
+      Response::builder()
+        .status(StatusCode::CREATED)
+        .header(SET_COOKIE, "a=1")
+        .header(SET_COOKIE, "b=2")
+        .body(Body::Text("hello".to_string()))
+        .unwrap(),
+    );
+
+    let http_response = apigw_response_to_http(response);
+    assert_eq!(http_response.status(), StatusCode::CREATED);
+    assert_eq!(
+      http_response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .collect::<Vec<_>>(),
+      ["a=1", "b=2"]
+    );
+    assert_eq!(
+      futures::executor::block_on(http_response.into_body().collect())
+        .unwrap()
+        .to_bytes(),
+      "hello"
+    );
+  }
+}
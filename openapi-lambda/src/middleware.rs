@@ -4,13 +4,44 @@ use async_trait::async_trait;
 
 use std::future::Future;
 
+/// Alternative set of security schemes that an operation accepts for authentication.
+///
+/// This corresponds to a
+/// [security requirement object](https://spec.openapis.org/oas/v3.0.3#security-requirement-object)
+/// in the OpenAPI definition. A request satisfies the requirement only if it satisfies *all* of the
+/// listed schemes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SecurityRequirement {
+  /// Security schemes that must all be satisfied.
+  pub schemes: &'static [SecuritySchemeRequirement],
+}
+
+/// Security scheme listed in a [`SecurityRequirement`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SecuritySchemeRequirement {
+  /// Name of the security scheme (as defined in `components.securitySchemes`).
+  pub name: &'static str,
+  /// Scopes required by the security scheme (e.g., for OAuth2 schemes). Empty if the scheme does
+  /// not use scopes.
+  pub scopes: &'static [&'static str],
+}
+
 /// Middleware interface for handling request authentication and optionally wrapping each request
 /// (e.g., to perform logging/telemetry).
 ///
 /// This trait is intended to be used with the [`#[async_trait]`](async_trait::async_trait)
 /// attribute.
+///
+/// The `RequestContext` type parameter is the request context type of the Lambda event handled by
+/// the generated API. It defaults to [`ApiGatewayProxyRequestContext`] (Amazon API Gateway REST
+/// APIs). Middleware for HTTP APIs using payload format version 2.0 should instead implement
+/// `Middleware<ApiGatewayV2httpRequestContext>` (see
+/// [`ApiGatewayV2httpRequestContext`](crate::ApiGatewayV2httpRequestContext)).
 #[async_trait]
-pub trait Middleware {
+pub trait Middleware<RequestContext = ApiGatewayProxyRequestContext>
+where
+  RequestContext: Send + Sync + 'static,
+{
   /// Type returned by a successful call to [`authenticate`](Middleware::authenticate).
   ///
   /// This might represent a user, authentication session, or other abstraction relevant to
@@ -26,18 +57,57 @@ pub trait Middleware {
   ///   definition).
   /// * `headers` - HTTP request headers (e.g., `Authorization`, `Cookie`, etc.).
   /// * `request_context` - Amazon API Gateway request context containing information to identify
-  ///   the AWS account and resources invoking the Lambda function. It also includes Cognito
-  ///   identity information for the caller (see the
+  ///   the AWS account and resources invoking the Lambda function. For REST APIs, it also includes
+  ///   Cognito identity information for the caller (see the
   ///   [`identity`](ApiGatewayProxyRequestContext::identity) field).
   /// * `lambda_context` - Lambda function execution context.
   async fn authenticate(
     &self,
     operation_id: &str,
     headers: &HeaderMap,
-    request_context: &ApiGatewayProxyRequestContext,
+    request_context: &RequestContext,
     lambda_context: &LambdaContext,
   ) -> Result<Self::AuthOk, HttpResponse>;
 
+  /// Authenticate the current request using any of the operation's security requirements.
+  ///
+  /// The generated code invokes this method for each request to an endpoint that requires (or
+  /// optionally accepts) authentication. The
+  /// `security` argument lists the alternative security requirements declared by the operation
+  /// (or, if the operation doesn't declare any, the top-level `security` of the OpenAPI
+  /// definition). A request should be considered authenticated if it satisfies any one of these
+  /// alternatives, allowing middleware that supports several authentication methods (e.g., bearer
+  /// tokens and API keys) to try each in turn. The list is empty if the OpenAPI definition doesn't
+  /// declare any security requirements for the operation.
+  ///
+  /// The default implementation ignores `security` and calls
+  /// [`authenticate`](Middleware::authenticate).
+  ///
+  /// # Arguments
+  ///
+  /// * `operation_id` - Operation ID associated with the current request (as defined in the OpenAPI
+  ///   definition).
+  /// * `security` - Alternative security requirements for the operation.
+  /// * `headers` - HTTP request headers (e.g., `Authorization`, `Cookie`, etc.).
+  /// * `request_context` - Amazon API Gateway request context containing information to identify
+  ///   the AWS account and resources invoking the Lambda function. For REST APIs, it also includes
+  ///   Cognito identity information for the caller (see the
+  ///   [`identity`](ApiGatewayProxyRequestContext::identity) field).
+  /// * `lambda_context` - Lambda function execution context.
+  async fn authenticate_with_security(
+    &self,
+    operation_id: &str,
+    security: &[SecurityRequirement],
+    headers: &HeaderMap,
+    request_context: &RequestContext,
+    lambda_context: &LambdaContext,
+  ) -> Result<Self::AuthOk, HttpResponse> {
+    let _ = security;
+    self
+      .authenticate(operation_id, headers, request_context, lambda_context)
+      .await
+  }
+
   /// Wrap an authenticated request.
   ///
   /// This method serves as an optional hook for running arbitrary code before and/or after each
@@ -56,8 +126,8 @@ pub trait Middleware {
   ///   definition).
   /// * `headers` - HTTP request headers (e.g., `Authorization`, `Cookie`, etc.).
   /// * `request_context` - Amazon API Gateway request context containing information to identify
-  ///   the AWS account and resources invoking the Lambda function. It also includes Cognito
-  ///   identity information for the caller (see the
+  ///   the AWS account and resources invoking the Lambda function. For REST APIs, it also includes
+  ///   Cognito identity information for the caller (see the
   ///   [`identity`](ApiGatewayProxyRequestContext::identity) field).
   /// * `lambda_context` - Lambda function execution context.
   /// * `auth_ok` - Output of successful call to [`authenticate`](Middleware::authenticate) method.
@@ -66,12 +136,12 @@ pub trait Middleware {
     api_handler: F,
     operation_id: &str,
     headers: HeaderMap,
-    request_context: ApiGatewayProxyRequestContext,
+    request_context: RequestContext,
     lambda_context: LambdaContext,
     auth_ok: Self::AuthOk,
   ) -> HttpResponse
   where
-    F: FnOnce(HeaderMap, ApiGatewayProxyRequestContext, LambdaContext, Self::AuthOk) -> Fut + Send,
+    F: FnOnce(HeaderMap, RequestContext, LambdaContext, Self::AuthOk) -> Fut + Send,
     Fut: Future<Output = HttpResponse> + Send,
   {
     let _ = operation_id;
@@ -96,8 +166,8 @@ pub trait Middleware {
   ///   definition).
   /// * `headers` - HTTP request headers (e.g., `Authorization`, `Cookie`, etc.).
   /// * `request_context` - Amazon API Gateway request context containing information to identify
-  ///   the AWS account and resources invoking the Lambda function. It also includes Cognito
-  ///   identity information for the caller (see the
+  ///   the AWS account and resources invoking the Lambda function. For REST APIs, it also includes
+  ///   Cognito identity information for the caller (see the
   ///   [`identity`](ApiGatewayProxyRequestContext::identity) field).
   /// * `lambda_context` - Lambda function execution context.
   async fn wrap_handler_unauthed<F, Fut>(
@@ -105,11 +175,11 @@ pub trait Middleware {
     api_handler: F,
     operation_id: &str,
     headers: HeaderMap,
-    request_context: ApiGatewayProxyRequestContext,
+    request_context: RequestContext,
     lambda_context: LambdaContext,
   ) -> HttpResponse
   where
-    F: FnOnce(HeaderMap, ApiGatewayProxyRequestContext, LambdaContext) -> Fut + Send,
+    F: FnOnce(HeaderMap, RequestContext, LambdaContext) -> Fut + Send,
     Fut: Future<Output = HttpResponse> + Send,
   {
     let _ = operation_id;
@@ -129,14 +199,17 @@ pub trait Middleware {
 pub struct UnauthenticatedMiddleware;
 
 #[async_trait]
-impl Middleware for UnauthenticatedMiddleware {
+impl<RequestContext> Middleware<RequestContext> for UnauthenticatedMiddleware
+where
+  RequestContext: Send + Sync + 'static,
+{
   type AuthOk = ();
 
   async fn authenticate(
     &self,
     _operation_id: &str,
     _headers: &HeaderMap,
-    _request_context: &ApiGatewayProxyRequestContext,
+    _request_context: &RequestContext,
     _lambda_context: &LambdaContext,
   ) -> Result<Self::AuthOk, HttpResponse> {
     Ok(())
@@ -1,11 +1,482 @@
-use serde::{Deserialize, Serialize};
+use crate::{Body, HttpResponse, StatusCode};
 
+use http::header::CONTENT_TYPE;
+use http::HeaderValue;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use std::collections::BTreeSet;
+use std::ops::Deref;
+
+#[cfg(feature = "chrono")]
 pub use chrono;
-pub use indexmap::IndexSet;
+pub use indexmap::{IndexMap, IndexSet};
 pub use serde_json;
+#[cfg(feature = "time")]
+pub use time;
+
+/// Media type of [`Problem`] response bodies.
+pub(crate) const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
 
 /// An empty object (e.g., `{}` in a JSON request/response body).
 ///
 /// Note that this type ignores any unexpected fields during deserialization.
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct EmptyModel {}
+
+/// Problem details describing an error (see [RFC 7807](https://www.rfc-editor.org/rfc/rfc7807)).
+///
+/// [`EventError::to_problem_json_response`](crate::EventError::to_problem_json_response) returns
+/// this shape for errors that occur outside of API handlers. Handlers may return the same shape for
+/// their own errors by converting a `Problem` into an [`HttpResponse`] (e.g., from
+/// `Api::respond_to_handler_error()`).
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Problem {
+  /// URI reference identifying the problem type (defaults to `about:blank`).
+  #[serde(default = "Problem::default_type")]
+  pub r#type: String,
+  /// Short, human-readable summary of the problem type.
+  pub title: String,
+  /// HTTP status code.
+  pub status: u16,
+  /// Human-readable explanation specific to this occurrence of the problem.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub detail: Option<String>,
+}
+
+impl Problem {
+  /// Construct a `Problem` of type `about:blank` whose title is the canonical reason phrase of
+  /// `status` (e.g., `Bad Request`).
+  pub fn new(status: StatusCode) -> Self {
+    Self {
+      r#type: Self::default_type(),
+      title: status.canonical_reason().unwrap_or("Unknown").to_string(),
+      status: status.as_u16(),
+      detail: None,
+    }
+  }
+
+  /// Set the human-readable explanation specific to this occurrence of the problem.
+  pub fn with_detail<D: Into<String>>(self, detail: D) -> Self {
+    Self {
+      detail: Some(detail.into()),
+      ..self
+    }
+  }
+
+  /// Set the URI reference identifying the problem type.
+  pub fn with_type<T: Into<String>>(self, r#type: T) -> Self {
+    Self {
+      r#type: r#type.into(),
+      ..self
+    }
+  }
+
+  fn default_type() -> String {
+    "about:blank".to_string()
+  }
+}
+
+impl From<&Problem> for HttpResponse {
+  /// Build an [`HttpResponse`] with the problem's status code and an `application/problem+json`
+  /// body.
+  fn from(problem: &Problem) -> HttpResponse {
+    let mut response = HttpResponse::new(Body::Text(
+      serde_json::to_string(problem).expect("Problem should serialize to JSON"),
+    ));
+    response.headers_mut().insert(
+      CONTENT_TYPE,
+      HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+    );
+    *response.status_mut() =
+      StatusCode::from_u16(problem.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    response
+  }
+}
+
+// For convenience.
+impl From<Problem> for HttpResponse {
+  fn from(problem: Problem) -> HttpResponse {
+    (&problem).into()
+  }
+}
+
+/// Error returned by a generated model builder's `build()` method when a required field hasn't
+/// been set.
+///
+/// Model builders are only generated when calling `CodeGenerator::with_builders()`.
+#[derive(Clone, Copy, Debug, Error, Eq, PartialEq)]
+#[error("missing required field `{field_name}`")]
+pub struct MissingField {
+  /// Name of the missing field.
+  pub field_name: &'static str,
+}
+
+/// Error returned when constructing a [`UniqueVec`] from items that aren't unique.
+#[derive(Clone, Copy, Debug, Error, Eq, PartialEq)]
+#[error("duplicate array item at index {index}")]
+pub struct DuplicateItem {
+  /// Index of the first item that equals an earlier item.
+  pub index: usize,
+}
+
+/// Array whose items are unique (i.e., `uniqueItems: true`) but can't be stored in a set.
+///
+/// The generated code uses this type in place of `IndexSet` or `BTreeSet` when the item type doesn't
+/// implement the traits required by the set (e.g., `Hash` for floating-point numbers). Unlike a
+/// set, uniqueness is checked by comparing each pair of items, which takes quadratic time.
+///
+/// Deserialization fails if any two items are equal.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct UniqueVec<T>(Vec<T>);
+
+impl<T> UniqueVec<T> {
+  /// Construct an empty `UniqueVec`.
+  pub fn new() -> Self {
+    Self(Vec::new())
+  }
+
+  /// Return the underlying `Vec`.
+  pub fn into_inner(self) -> Vec<T> {
+    self.0
+  }
+}
+
+impl<T> UniqueVec<T>
+where
+  T: PartialEq,
+{
+  /// Append `item` if it doesn't equal any existing item.
+  ///
+  /// Returns `true` if the item was appended (as with
+  /// [`HashSet::insert`](std::collections::HashSet::insert)).
+  pub fn push(&mut self, item: T) -> bool {
+    if self.0.contains(&item) {
+      false
+    } else {
+      self.0.push(item);
+      true
+    }
+  }
+}
+
+impl<T> Deref for UniqueVec<T> {
+  type Target = [T];
+
+  fn deref(&self) -> &[T] {
+    &self.0
+  }
+}
+
+impl<T> AsRef<[T]> for UniqueVec<T> {
+  fn as_ref(&self) -> &[T] {
+    &self.0
+  }
+}
+
+impl<T> From<UniqueVec<T>> for Vec<T> {
+  fn from(unique_vec: UniqueVec<T>) -> Self {
+    unique_vec.0
+  }
+}
+
+impl<T> TryFrom<Vec<T>> for UniqueVec<T>
+where
+  T: PartialEq,
+{
+  type Error = DuplicateItem;
+
+  fn try_from(items: Vec<T>) -> Result<Self, DuplicateItem> {
+    if let Some(index) = (1..items.len()).find(|index| items[..*index].contains(&items[*index])) {
+      Err(DuplicateItem { index })
+    } else {
+      Ok(Self(items))
+    }
+  }
+}
+
+impl<T> IntoIterator for UniqueVec<T> {
+  type Item = T;
+  type IntoIter = std::vec::IntoIter<T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a UniqueVec<T> {
+  type Item = &'a T;
+  type IntoIter = std::slice::Iter<'a, T>;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.iter()
+  }
+}
+
+impl<'de, T> Deserialize<'de> for UniqueVec<T>
+where
+  T: Deserialize<'de> + PartialEq,
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Vec::<T>::deserialize(deserializer)?
+      .try_into()
+      .map_err(D::Error::custom)
+  }
+}
+
+/// Collection whose number of items can be constrained by [`Bounded`].
+pub trait Collection {
+  /// Number of items in the collection.
+  fn len(&self) -> usize;
+
+  /// Returns `true` if the collection contains no items.
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+impl<T> Collection for Vec<T> {
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T, S> Collection for IndexSet<T, S> {
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> Collection for BTreeSet<T> {
+  fn len(&self) -> usize {
+    self.len()
+  }
+}
+
+impl<T> Collection for UniqueVec<T> {
+  fn len(&self) -> usize {
+    self.0.len()
+  }
+}
+
+/// Error returned when constructing a [`Bounded`] collection with too few or too many items.
+#[derive(Clone, Copy, Debug, Error, Eq, PartialEq)]
+pub enum ItemCountError {
+  /// The collection has fewer than the minimum number of items.
+  #[error("expected at least {min_items} items, but found {len}")]
+  TooFew {
+    /// Minimum number of items.
+    min_items: usize,
+    /// Actual number of items.
+    len: usize,
+  },
+  /// The collection has more than the maximum number of items.
+  #[error("expected at most {max_items} items, but found {len}")]
+  TooMany {
+    /// Maximum number of items.
+    max_items: usize,
+    /// Actual number of items.
+    len: usize,
+  },
+}
+
+/// Collection (e.g., a `Vec`) containing between `MIN_ITEMS` and `MAX_ITEMS` items (inclusive).
+///
+/// The generated code uses this type for array schemas with `minItems` and/or `maxItems`
+/// (defaulting to `0` and `usize::MAX`, respectively). Deserialization fails if the number of
+/// items is out of bounds.
+///
+/// Collections can be converted to and from `Bounded` via `TryFrom` and `From`, respectively.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(transparent)]
+pub struct Bounded<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize>(C);
+
+impl<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> Bounded<C, MIN_ITEMS, MAX_ITEMS>
+where
+  C: Collection,
+{
+  /// Construct a `Bounded` collection, returning an error if the number of items is out of bounds.
+  pub fn new(items: C) -> Result<Self, ItemCountError> {
+    let len = items.len();
+    if len < MIN_ITEMS {
+      Err(ItemCountError::TooFew {
+        min_items: MIN_ITEMS,
+        len,
+      })
+    } else if len > MAX_ITEMS {
+      Err(ItemCountError::TooMany {
+        max_items: MAX_ITEMS,
+        len,
+      })
+    } else {
+      Ok(Self(items))
+    }
+  }
+}
+
+impl<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> Bounded<C, MIN_ITEMS, MAX_ITEMS> {
+  /// Return the underlying collection.
+  pub fn into_inner(self) -> C {
+    self.0
+  }
+}
+
+impl<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> Deref for Bounded<C, MIN_ITEMS, MAX_ITEMS> {
+  type Target = C;
+
+  fn deref(&self) -> &C {
+    &self.0
+  }
+}
+
+impl<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> AsRef<C>
+  for Bounded<C, MIN_ITEMS, MAX_ITEMS>
+{
+  fn as_ref(&self) -> &C {
+    &self.0
+  }
+}
+
+// Neither `impl<C> TryFrom<C> for Bounded<C, ..>` (which conflicts with the blanket `TryFrom`
+// implementation) nor `impl<C> From<Bounded<C, ..>> for C` (which isn't permitted by the orphan
+// rules) compiles, so each collection type gets its own conversions.
+macro_rules! bounded_conversions {
+  ($collection:ty, $($param:ident),+) => {
+    impl<$($param,)+ const MIN_ITEMS: usize, const MAX_ITEMS: usize> TryFrom<$collection>
+      for Bounded<$collection, MIN_ITEMS, MAX_ITEMS>
+    {
+      type Error = ItemCountError;
+
+      fn try_from(items: $collection) -> Result<Self, ItemCountError> {
+        Self::new(items)
+      }
+    }
+
+    impl<$($param,)+ const MIN_ITEMS: usize, const MAX_ITEMS: usize>
+      From<Bounded<$collection, MIN_ITEMS, MAX_ITEMS>> for $collection
+    {
+      fn from(bounded: Bounded<$collection, MIN_ITEMS, MAX_ITEMS>) -> Self {
+        bounded.0
+      }
+    }
+  };
+}
+
+bounded_conversions!(Vec<T>, T);
+bounded_conversions!(IndexSet<T, S>, T, S);
+bounded_conversions!(BTreeSet<T>, T);
+bounded_conversions!(UniqueVec<T>, T);
+
+impl<C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> IntoIterator
+  for Bounded<C, MIN_ITEMS, MAX_ITEMS>
+where
+  C: IntoIterator,
+{
+  type Item = C::Item;
+  type IntoIter = C::IntoIter;
+
+  fn into_iter(self) -> Self::IntoIter {
+    self.0.into_iter()
+  }
+}
+
+impl<'de, C, const MIN_ITEMS: usize, const MAX_ITEMS: usize> Deserialize<'de>
+  for Bounded<C, MIN_ITEMS, MAX_ITEMS>
+where
+  C: Collection + Deserialize<'de>,
+{
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Self::new(C::deserialize(deserializer)?).map_err(D::Error::custom)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::models::{Bounded, DuplicateItem, ItemCountError, UniqueVec};
+
+  use indexmap::IndexSet;
+
+  use std::collections::BTreeSet;
+
+  #[test]
+  fn test_unique_vec() {
+    assert_eq!(
+      serde_json::from_str::<UniqueVec<f64>>("[1.5, 2.5]")
+        .unwrap()
+        .into_inner(),
+      vec![1.5, 2.5]
+    );
+    assert_eq!(
+      serde_json::from_str::<UniqueVec<f64>>("[1.5, 2.5, 1.5]")
+        .unwrap_err()
+        .to_string(),
+      "duplicate array item at index 2"
+    );
+    assert_eq!(
+      UniqueVec::try_from(vec![1.5, 1.5]),
+      Err(DuplicateItem { index: 1 })
+    );
+
+    let mut unique_vec = UniqueVec::new();
+    assert!(unique_vec.push(1.5));
+    assert!(!unique_vec.push(1.5));
+    assert_eq!(serde_json::to_string(&unique_vec).unwrap(), "[1.5]");
+  }
+
+  #[test]
+  fn test_bounded() {
+    type Tags = Bounded<Vec<String>, 1, 2>;
+
+    assert_eq!(
+      serde_json::from_str::<Tags>(r#"["a","b"]"#)
+        .unwrap()
+        .into_inner(),
+      vec!["a".to_string(), "b".to_string()]
+    );
+    assert_eq!(
+      serde_json::from_str::<Tags>("[]").unwrap_err().to_string(),
+      "expected at least 1 items, but found 0"
+    );
+    assert_eq!(
+      Tags::new(vec!["a".to_string(); 3]),
+      Err(ItemCountError::TooMany {
+        max_items: 2,
+        len: 3
+      })
+    );
+    assert_eq!(
+      serde_json::to_string(&Tags::new(vec!["a".to_string()]).unwrap()).unwrap(),
+      r#"["a"]"#
+    );
+  }
+
+  #[test]
+  fn test_bounded_conversions() {
+    let tags = Bounded::<Vec<String>, 1, 2>::try_from(vec!["a".to_string()]).unwrap();
+    assert_eq!(Vec::from(tags), vec!["a".to_string()]);
+    assert_eq!(
+      Bounded::<Vec<String>, 1, 2>::try_from(Vec::new()),
+      Err(ItemCountError::TooFew {
+        min_items: 1,
+        len: 0
+      })
+    );
+
+    let ids: Bounded<IndexSet<i64>, 0, 1> = IndexSet::from([1]).try_into().unwrap();
+    assert_eq!(IndexSet::from(ids), IndexSet::from([1]));
+    let ids: Bounded<BTreeSet<i64>, 0, 1> = BTreeSet::from([1]).try_into().unwrap();
+    assert_eq!(BTreeSet::from(ids), BTreeSet::from([1]));
+    let ids: Bounded<UniqueVec<i64>, 0, 1> =
+      UniqueVec::try_from(vec![1]).unwrap().try_into().unwrap();
+    let ids: UniqueVec<i64> = ids.into();
+    assert_eq!(ids.into_inner(), vec![1]);
+  }
+}
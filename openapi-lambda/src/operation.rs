@@ -0,0 +1,21 @@
+/// Operation handled by a generated API module.
+///
+/// Each generated API module exposes the operations it handles via its `OPERATIONS` constant and
+/// `find_operation()` function, which is useful for building custom routers, health checks, or
+/// telemetry attributes (e.g., the OpenTelemetry `http.route`) without reimplementing the routing
+/// logic of the generated code.
+///
+/// Each generated `Api` trait handler method also receives the `OperationInfo` of the operation
+/// it handles via its `operation` argument.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct OperationInfo {
+  /// Operation ID (as defined in the OpenAPI definition).
+  pub operation_id: &'static str,
+  /// Uppercase HTTP method of the operation (e.g., `GET`).
+  pub method: &'static str,
+  /// Request path template of the operation (e.g., `/pets/{petId}`).
+  ///
+  /// For callback operations, this is the callback's runtime expression (e.g.,
+  /// `{$request.body#/callbackUrl}`) or the webhook name.
+  pub path: &'static str,
+}
@@ -0,0 +1,84 @@
+use aws_lambda_events::alb::AlbTargetGroupRequest;
+use aws_lambda_events::query_map::QueryMap;
+
+use std::collections::HashMap;
+
+/// Normalize an Application Load Balancer request so that it can be handled the same way
+/// regardless of whether the target group has multi-value headers enabled.
+///
+/// After normalization, `headers` contains all request headers, and both `query_string_parameters`
+/// and `multi_value_query_string_parameters` contain all URL-decoded query parameters. Unlike
+/// Amazon API Gateway, load balancers don't URL-decode query parameters.
+pub fn normalize_request(request: &mut AlbTargetGroupRequest) {
+  if !request.multi_value_headers.is_empty() {
+    request.headers = std::mem::take(&mut request.multi_value_headers);
+  }
+
+  let raw_query = if request.multi_value_query_string_parameters.is_empty() {
+    &request.query_string_parameters
+  } else {
+    &request.multi_value_query_string_parameters
+  };
+  let mut query = HashMap::<String, Vec<String>>::new();
+  for (name, value) in raw_query.iter() {
+    query
+      .entry(decode_query_component(name))
+      .or_default()
+      .push(decode_query_component(value));
+  }
+
+  let query = QueryMap::from(query);
+  request.query_string_parameters = query.clone();
+  request.multi_value_query_string_parameters = query;
+}
+
+fn decode_query_component(component: &str) -> String {
+  // Query strings use `+` to encode spaces (i.e., `application/x-www-form-urlencoded`).
+  let component = component.replace('+', " ");
+  match urlencoding::decode(&component) {
+    Ok(decoded) => decoded.into_owned(),
+    // Leave components that don't decode to valid UTF-8 unchanged.
+    Err(_) => component,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::normalize_request;
+
+  use aws_lambda_events::alb::AlbTargetGroupRequest;
+  use aws_lambda_events::query_map::QueryMap;
+  use http::HeaderValue;
+
+  use std::collections::HashMap;
+
+  #[test]
+  fn test_normalize_multi_value_request() {
+    let mut request = AlbTargetGroupRequest::default();
+    request
+      .multi_value_headers
+      .append("x-foo", HeaderValue::from_static("a"));
+    request
+      .multi_value_headers
+      .append("x-foo", HeaderValue::from_static("b"));
+    request.multi_value_query_string_parameters = QueryMap::from(HashMap::from([(
+      "tag%5B%5D".to_string(),
+      vec!["a%20b".to_string(), "c+d".to_string()],
+    )]));
+
+    normalize_request(&mut request);
+
+    assert_eq!(
+      request.headers.get_all("x-foo").iter().collect::<Vec<_>>(),
+      vec!["a", "b"]
+    );
+    assert_eq!(
+      request.query_string_parameters.all("tag[]"),
+      Some(vec!["a b", "c d"])
+    );
+    assert_eq!(
+      request.multi_value_query_string_parameters.first("tag[]"),
+      Some("a b")
+    );
+  }
+}
@@ -0,0 +1,26 @@
+use super::log;
+
+use aws_lambda_events::apigw::ApiGatewayV2httpRequest;
+use http::header::COOKIE;
+use http::HeaderValue;
+
+/// Move the request cookies into a `Cookie` header.
+///
+/// HTTP API payload format version 2.0 removes the `Cookie` header from the request and instead
+/// provides each cookie via the `cookies` field. Restoring the header allows middleware and API
+/// handlers to access cookies the same way regardless of the event source.
+pub fn restore_v2_cookie_header(request: &mut ApiGatewayV2httpRequest) {
+  let Some(cookies) = request.cookies.take() else {
+    return;
+  };
+  if cookies.is_empty() || request.headers.contains_key(COOKIE) {
+    return;
+  }
+
+  match HeaderValue::from_str(&cookies.join("; ")) {
+    Ok(cookie_header) => {
+      request.headers.insert(COOKIE, cookie_header);
+    }
+    Err(err) => log::warn!("ignoring invalid request cookies: {err}"),
+  }
+}
@@ -0,0 +1,211 @@
+use crate::{HeaderMap, HttpResponse};
+
+use aws_lambda_events::encodings::Body;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, VARY};
+use http::HeaderValue;
+
+use std::io::Write;
+
+/// Content coding used to compress a response body.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContentCoding {
+  /// `deflate` content coding (i.e., the zlib format).
+  Deflate,
+  /// `gzip` content coding.
+  Gzip,
+}
+
+impl ContentCoding {
+  fn as_str(self) -> &'static str {
+    match self {
+      ContentCoding::Deflate => "deflate",
+      ContentCoding::Gzip => "gzip",
+    }
+  }
+
+  fn encode(self, bytes: &[u8]) -> Vec<u8> {
+    match self {
+      ContentCoding::Deflate => {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).and_then(|()| encoder.finish())
+      }
+      ContentCoding::Gzip => {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).and_then(|()| encoder.finish())
+      }
+    }
+    .expect("compressing to a Vec should not fail")
+  }
+}
+
+/// Negotiate the content coding of the response based on the request's `Accept-Encoding` header.
+///
+/// Prefers `gzip` over `deflate` when both are equally acceptable, and returns `None` if neither is
+/// acceptable (including when the `Accept-Encoding` header is missing).
+pub fn negotiate_content_coding(headers: &HeaderMap) -> Option<ContentCoding> {
+  let (mut gzip_quality, mut deflate_quality, mut wildcard_quality) = (None, None, None);
+  for coding in headers
+    .get_all(ACCEPT_ENCODING)
+    .iter()
+    .filter_map(|value| value.to_str().ok())
+    .flat_map(|value| value.split(','))
+  {
+    let mut params = coding.split(';');
+    let name = params.next().unwrap_or_default().trim();
+    let quality = params
+      .find_map(|param| param.trim().strip_prefix("q="))
+      .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())
+      .unwrap_or(0.0);
+
+    if name.eq_ignore_ascii_case("gzip") || name.eq_ignore_ascii_case("x-gzip") {
+      gzip_quality = Some(quality);
+    } else if name.eq_ignore_ascii_case("deflate") {
+      deflate_quality = Some(quality);
+    } else if name == "*" {
+      wildcard_quality = Some(quality);
+    }
+  }
+
+  // Codings not listed explicitly are covered by the `*` wildcard, if present.
+  let gzip_quality = gzip_quality.or(wildcard_quality).unwrap_or(0.0);
+  let deflate_quality = deflate_quality.or(wildcard_quality).unwrap_or(0.0);
+  if gzip_quality > 0.0 && gzip_quality >= deflate_quality {
+    Some(ContentCoding::Gzip)
+  } else if deflate_quality > 0.0 {
+    Some(ContentCoding::Deflate)
+  } else {
+    None
+  }
+}
+
+/// Compress the response body using the negotiated content coding, if any.
+///
+/// Empty bodies and responses that already specify a `Content-Encoding` are returned unmodified.
+/// Compressed bodies are binary, so they are returned as [`Body::Binary`] (i.e., base64-encoded
+/// within the Lambda response).
+pub fn compress_response(
+  mut response: HttpResponse,
+  content_coding: Option<ContentCoding>,
+) -> HttpResponse {
+  if response.headers().contains_key(CONTENT_ENCODING) || *response.body() == Body::Empty {
+    return response;
+  }
+
+  // The response depends on the `Accept-Encoding` request header even if it isn't compressed.
+  response
+    .headers_mut()
+    .append(VARY, HeaderValue::from_static(ACCEPT_ENCODING.as_str()));
+
+  let Some(content_coding) = content_coding else {
+    return response;
+  };
+
+  let compressed = match response.body() {
+    Body::Empty => unreachable!("empty bodies are returned above"),
+    Body::Text(text) => content_coding.encode(text.as_bytes()),
+    Body::Binary(bytes) => content_coding.encode(bytes),
+  };
+  *response.body_mut() = Body::Binary(compressed);
+  let headers = response.headers_mut();
+  headers.remove(CONTENT_LENGTH);
+  headers.insert(
+    CONTENT_ENCODING,
+    HeaderValue::from_static(content_coding.as_str()),
+  );
+
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::__private::compression::{compress_response, negotiate_content_coding, ContentCoding};
+  use crate::{HeaderMap, HttpResponse};
+
+  use aws_lambda_events::encodings::Body;
+  use flate2::read::{GzDecoder, ZlibDecoder};
+  use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, VARY};
+
+  use std::io::Read;
+
+  #[test]
+  fn test_negotiate_content_coding() {
+    let negotiate = |accept_encoding: &[&str]| {
+      let mut headers = HeaderMap::new();
+      for value in accept_encoding {
+        headers.append(ACCEPT_ENCODING, value.parse().unwrap());
+      }
+      negotiate_content_coding(&headers)
+    };
+
+    assert_eq!(negotiate(&[]), None);
+    assert_eq!(negotiate(&["identity"]), None);
+    assert_eq!(negotiate(&["br"]), None);
+    assert_eq!(negotiate(&["gzip"]), Some(ContentCoding::Gzip));
+    assert_eq!(negotiate(&["GZIP"]), Some(ContentCoding::Gzip));
+    assert_eq!(negotiate(&["deflate"]), Some(ContentCoding::Deflate));
+    assert_eq!(negotiate(&["gzip, deflate, br"]), Some(ContentCoding::Gzip));
+    assert_eq!(
+      negotiate(&["gzip;q=0.5", "deflate"]),
+      Some(ContentCoding::Deflate)
+    );
+    assert_eq!(negotiate(&["gzip;q=0, deflate;q=0"]), None);
+    assert_eq!(negotiate(&["*"]), Some(ContentCoding::Gzip));
+    assert_eq!(negotiate(&["*, gzip;q=0"]), Some(ContentCoding::Deflate));
+  }
+
+  #[test]
+  fn test_compress_response() {
+    let body = "a".repeat(1024);
+
+    let response = compress_response(
+      HttpResponse::new(Body::Text(body.clone())),
+      Some(ContentCoding::Gzip),
+    );
+    assert_eq!(response.headers()[CONTENT_ENCODING], "gzip");
+    assert_eq!(response.headers()[VARY], "accept-encoding");
+    let Body::Binary(compressed) = response.body() else {
+      panic!("unexpected body {:?}", response.body());
+    };
+    assert!(compressed.len() < body.len());
+    let mut decompressed = String::new();
+    GzDecoder::new(compressed.as_slice())
+      .read_to_string(&mut decompressed)
+      .unwrap();
+    assert_eq!(decompressed, body);
+
+    let response = compress_response(
+      HttpResponse::new(Body::Binary(body.clone().into_bytes())),
+      Some(ContentCoding::Deflate),
+    );
+    assert_eq!(response.headers()[CONTENT_ENCODING], "deflate");
+    let Body::Binary(compressed) = response.body() else {
+      panic!("unexpected body {:?}", response.body());
+    };
+    let mut decompressed = String::new();
+    ZlibDecoder::new(compressed.as_slice())
+      .read_to_string(&mut decompressed)
+      .unwrap();
+    assert_eq!(decompressed, body);
+
+    // The client doesn't accept any supported coding.
+    let response = compress_response(HttpResponse::new(Body::Text(body.clone())), None);
+    assert!(!response.headers().contains_key(CONTENT_ENCODING));
+    assert_eq!(response.headers()[VARY], "accept-encoding");
+    assert_eq!(response.body(), &Body::Text(body.clone()));
+
+    // Empty and already-encoded bodies are left as-is.
+    let response = compress_response(HttpResponse::new(Body::Empty), Some(ContentCoding::Gzip));
+    assert!(response.headers().is_empty());
+    assert_eq!(response.body(), &Body::Empty);
+
+    let mut response = HttpResponse::new(Body::Binary(body.clone().into_bytes()));
+    response
+      .headers_mut()
+      .insert(CONTENT_ENCODING, "br".parse().unwrap());
+    let response = compress_response(response, Some(ContentCoding::Gzip));
+    assert_eq!(response.headers()[CONTENT_ENCODING], "br");
+    assert_eq!(response.body(), &Body::Binary(body.into_bytes()));
+  }
+}
@@ -0,0 +1,98 @@
+use crate::{HttpResponse, StatusCode};
+
+use aws_lambda_events::encodings::Body;
+use http::header::{
+  ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS,
+  ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_MAX_AGE, ALLOW,
+};
+use http::HeaderValue;
+
+/// Build a response to a CORS preflight (`OPTIONS`) request.
+pub fn preflight_response(
+  allowed_methods: &'static str,
+  allow_origin: &'static str,
+  allow_headers: Option<&'static str>,
+  allow_credentials: bool,
+  max_age_secs: Option<u64>,
+) -> HttpResponse {
+  let mut response = HttpResponse::new(Body::Empty);
+  *response.status_mut() = StatusCode::NO_CONTENT;
+
+  let headers = response.headers_mut();
+  headers.insert(ALLOW, HeaderValue::from_static(allowed_methods));
+  headers.insert(
+    ACCESS_CONTROL_ALLOW_METHODS,
+    HeaderValue::from_static(allowed_methods),
+  );
+  if let Some(allow_headers) = allow_headers {
+    headers.insert(
+      ACCESS_CONTROL_ALLOW_HEADERS,
+      HeaderValue::from_static(allow_headers),
+    );
+  }
+  if let Some(max_age_secs) = max_age_secs {
+    headers.insert(ACCESS_CONTROL_MAX_AGE, HeaderValue::from(max_age_secs));
+  }
+
+  add_response_headers(response, allow_origin, allow_credentials)
+}
+
+/// Add the CORS response headers that browsers require on every response to a cross-origin
+/// request (not just preflight responses).
+///
+/// Headers already set by the handler are left unmodified.
+pub fn add_response_headers(
+  mut response: HttpResponse,
+  allow_origin: &'static str,
+  allow_credentials: bool,
+) -> HttpResponse {
+  let headers = response.headers_mut();
+  headers
+    .entry(ACCESS_CONTROL_ALLOW_ORIGIN)
+    .or_insert_with(|| HeaderValue::from_static(allow_origin));
+  if allow_credentials {
+    headers
+      .entry(ACCESS_CONTROL_ALLOW_CREDENTIALS)
+      .or_insert_with(|| HeaderValue::from_static("true"));
+  }
+
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::__private::cors::add_response_headers;
+  use crate::HttpResponse;
+
+  use aws_lambda_events::encodings::Body;
+  use http::header::{ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN};
+
+  #[test]
+  fn test_add_response_headers() {
+    let response =
+      add_response_headers(HttpResponse::new(Body::Empty), "https://example.com", true);
+    assert_eq!(
+      response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+      "https://example.com"
+    );
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+    let response = add_response_headers(HttpResponse::new(Body::Empty), "*", false);
+    assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], "*");
+    assert!(!response
+      .headers()
+      .contains_key(ACCESS_CONTROL_ALLOW_CREDENTIALS));
+
+    // Handlers may override the configured origin (e.g., to echo an allowed request origin).
+    let mut response = HttpResponse::new(Body::Empty);
+    response.headers_mut().insert(
+      ACCESS_CONTROL_ALLOW_ORIGIN,
+      "https://other.example.com".parse().unwrap(),
+    );
+    let response = add_response_headers(response, "https://example.com", false);
+    assert_eq!(
+      response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN],
+      "https://other.example.com"
+    );
+  }
+}
@@ -0,0 +1,29 @@
+//! Serde helpers for optional properties that may also be explicitly `null`.
+//!
+//! Used via `#[serde(default, skip_serializing_if = "Option::is_none", with = "...")]` on
+//! `Option<Option<T>>` fields so that a missing property (`None`) is distinguishable from a `null`
+//! value (`Some(None)`).
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialize a present property, which may be `null`.
+pub fn serialize<T, S>(value: &Option<Option<T>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+  T: Serialize,
+  S: Serializer,
+{
+  match value {
+    Some(value) => value.serialize(serializer),
+    // Missing properties should be skipped via `skip_serializing_if`.
+    None => serializer.serialize_none(),
+  }
+}
+
+/// Deserialize a present property, which may be `null`.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+  T: Deserialize<'de>,
+  D: Deserializer<'de>,
+{
+  Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -1,4 +1,6 @@
-use serde::Serialize;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
 
 pub fn to_json<T>(value: &T) -> Result<String, serde_path_to_error::Error<serde_json::Error>>
 where
@@ -9,3 +11,114 @@ where
   serde_path_to_error::serialize(value, &mut serializer)?;
   Ok(String::from_utf8(json_bytes).expect("JSON must be UTF-8"))
 }
+
+/// Serialize `value` to JSON, optionally pretty-printed and/or with the keys of every object sorted
+/// lexicographically.
+///
+/// Sorting keys requires first serializing `value` into an intermediate [`Value`], so it's
+/// noticeably slower than [`to_json`].
+pub fn to_json_with_format<T>(
+  value: &T,
+  pretty: bool,
+  sort_keys: bool,
+) -> Result<String, serde_path_to_error::Error<serde_json::Error>>
+where
+  T: Serialize,
+{
+  if sort_keys {
+    let json_value = serde_path_to_error::serialize(value, serde_json::value::Serializer)?;
+    if pretty {
+      to_json_pretty(&SortedKeys(&json_value))
+    } else {
+      to_json(&SortedKeys(&json_value))
+    }
+  } else if pretty {
+    to_json_pretty(value)
+  } else {
+    to_json(value)
+  }
+}
+
+fn to_json_pretty<T>(value: &T) -> Result<String, serde_path_to_error::Error<serde_json::Error>>
+where
+  T: Serialize,
+{
+  let mut json_bytes = Vec::new();
+  let mut serializer = serde_json::Serializer::pretty(&mut json_bytes);
+  serde_path_to_error::serialize(value, &mut serializer)?;
+  Ok(String::from_utf8(json_bytes).expect("JSON must be UTF-8"))
+}
+
+/// Serializes a JSON [`Value`] with the keys of every object sorted, regardless of whether the
+/// `preserve_order` feature of `serde_json` is enabled.
+struct SortedKeys<'a>(&'a Value);
+
+impl Serialize for SortedKeys<'_> {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match self.0 {
+      Value::Array(values) => serializer.collect_seq(values.iter().map(SortedKeys)),
+      Value::Object(object) => {
+        let mut entries = object.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for (key, value) in entries {
+          map.serialize_entry(key, &SortedKeys(value))?;
+        }
+        map.end()
+      }
+      value => value.serialize(serializer),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::to_json_with_format;
+
+  use serde::Serialize;
+
+  #[derive(Serialize)]
+  struct Foo {
+    name: &'static str,
+    id: u32,
+    tags: Vec<Bar>,
+  }
+
+  #[derive(Serialize)]
+  struct Bar {
+    value: &'static str,
+    key: &'static str,
+  }
+
+  fn foo() -> Foo {
+    Foo {
+      name: "foo",
+      id: 1,
+      tags: vec![Bar {
+        value: "b",
+        key: "a",
+      }],
+    }
+  }
+
+  #[test]
+  fn test_to_json_with_format() {
+    assert_eq!(
+      to_json_with_format(&foo(), false, false).unwrap(),
+      r#"{"name":"foo","id":1,"tags":[{"value":"b","key":"a"}]}"#
+    );
+    assert_eq!(
+      to_json_with_format(&foo(), false, true).unwrap(),
+      r#"{"id":1,"name":"foo","tags":[{"key":"a","value":"b"}]}"#
+    );
+    assert_eq!(
+      to_json_with_format(&foo(), true, true).unwrap(),
+      "{\n  \"id\": 1,\n  \"name\": \"foo\",\n  \"tags\": [\n    {\n      \"key\": \"a\",\n      \
+       \"value\": \"b\"\n    }\n  ]\n}"
+    );
+  }
+}
@@ -0,0 +1,74 @@
+//! Logging shim used by the generated code.
+//!
+//! By default, log messages are emitted via the [`log`](https://crates.io/crates/log) crate. When
+//! the `tracing` crate feature is enabled, they are instead emitted as
+//! [`tracing`](https://crates.io/crates/tracing) events, and each request is wrapped in a span that
+//! records the Lambda request ID and the operation ID. This allows the generated code to be the same
+//! regardless of which crate is used.
+
+#[cfg(not(feature = "tracing"))]
+pub use ::log::{debug, error, info, trace, warn};
+#[cfg(feature = "tracing")]
+pub use tracing::{debug, error, info, trace, warn};
+
+use std::future::Future;
+
+/// Span covering the handling of a single request (a no-op unless the `tracing` feature is
+/// enabled).
+pub struct RequestSpan {
+  #[cfg(feature = "tracing")]
+  span: tracing::Span,
+}
+
+impl RequestSpan {
+  /// Create a new span for the request with the specified Lambda request ID.
+  ///
+  /// The `operation_id` field is recorded once the request has been routed to an operation (see
+  /// [`record_operation_id`]).
+  #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+  pub fn new(request_id: &str) -> Self {
+    Self {
+      #[cfg(feature = "tracing")]
+      span: tracing::info_span!("request", request_id, operation_id = tracing::field::Empty),
+    }
+  }
+
+  /// Run the specified future within this span.
+  pub async fn instrument<F>(&self, future: F) -> F::Output
+  where
+    F: Future,
+  {
+    #[cfg(feature = "tracing")]
+    {
+      tracing::Instrument::instrument(future, self.span.clone()).await
+    }
+    #[cfg(not(feature = "tracing"))]
+    {
+      future.await
+    }
+  }
+}
+
+/// Record the operation ID of the current request in the current [`RequestSpan`] (a no-op unless
+/// the `tracing` feature is enabled).
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub fn record_operation_id(operation_id: &str) {
+  #[cfg(feature = "tracing")]
+  tracing::Span::current().record("operation_id", operation_id);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{info, record_operation_id, RequestSpan};
+
+  #[test]
+  fn test_request_span() {
+    let span = RequestSpan::new("request-id");
+    let output = futures::executor::block_on(span.instrument(async {
+      record_operation_id("getFoo");
+      info!("Handling request");
+      123
+    }));
+    assert_eq!(output, 123);
+  }
+}
@@ -6,14 +6,29 @@ pub use backtrace;
 pub use base64;
 pub use futures;
 pub use headers;
-pub use log;
 pub use mime;
+pub use once_cell;
+#[cfg(feature = "client")]
+pub use reqwest;
 pub use serde;
 pub use serde_json;
 pub use serde_path_to_error;
 pub use urlencoding;
 
+pub mod alb;
+pub mod apigw;
+#[cfg(feature = "compression")]
+pub mod compression;
+pub mod cors;
+pub mod double_option;
 pub mod encoding;
+pub mod log;
+pub mod multipart;
+pub mod params;
+pub mod routing;
+pub mod streaming;
+#[cfg(feature = "time")]
+pub mod time;
 
 /// Extract the panic string or error after catching a panic.
 pub fn panic_string(panic: Box<dyn Any + Send>) -> Result<String, Box<dyn Any + Send>> {
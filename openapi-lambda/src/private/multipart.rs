@@ -0,0 +1,106 @@
+use crate::Body;
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// A single part of a `multipart/mixed` body.
+pub struct Part {
+  content_type: &'static str,
+  body: Vec<u8>,
+}
+
+impl Part {
+  pub fn new<B>(content_type: &'static str, body: B) -> Self
+  where
+    B: Into<Vec<u8>>,
+  {
+    Self {
+      content_type,
+      body: body.into(),
+    }
+  }
+}
+
+/// Assemble a `multipart/mixed` body (see RFC 2046) from the specified parts.
+///
+/// Returns the `Content-Type` of the body (including the `boundary` parameter) along with the
+/// body. The boundary is chosen so that it doesn't appear within any of the parts.
+pub fn to_multipart_mixed(parts: Vec<Part>) -> (String, Body) {
+  let boundary = generate_boundary(&parts);
+
+  let mut body = Vec::new();
+  for part in &parts {
+    body.extend_from_slice(b"--");
+    body.extend_from_slice(boundary.as_bytes());
+    body.extend_from_slice(b"\r\nContent-Type: ");
+    body.extend_from_slice(part.content_type.as_bytes());
+    body.extend_from_slice(b"\r\n\r\n");
+    body.extend_from_slice(&part.body);
+    body.extend_from_slice(b"\r\n");
+  }
+  body.extend_from_slice(b"--");
+  body.extend_from_slice(boundary.as_bytes());
+  body.extend_from_slice(b"--\r\n");
+
+  let body = match String::from_utf8(body) {
+    Ok(text) => Body::Text(text),
+    Err(err) => Body::Binary(err.into_bytes()),
+  };
+
+  (format!("multipart/mixed; boundary={boundary}"), body)
+}
+
+fn generate_boundary(parts: &[Part]) -> String {
+  // `RandomState` is randomly seeded, which avoids depending on a random number generator.
+  let random_state = RandomState::new();
+  (0_u64..)
+    .map(|attempt| {
+      let mut hasher = random_state.build_hasher();
+      hasher.write_u64(attempt);
+      format!("openapi-lambda-{:016x}", hasher.finish())
+    })
+    .find(|boundary| {
+      !parts.iter().any(|part| {
+        part
+          .body
+          .windows(boundary.len())
+          .any(|window| window == boundary.as_bytes())
+      })
+    })
+    .expect("should find a boundary that doesn't appear in any part")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{to_multipart_mixed, Part};
+  use crate::Body;
+
+  #[test]
+  fn test_to_multipart_mixed() {
+    let (content_type, body) = to_multipart_mixed(vec![
+      Part::new("application/json", r#"{"foo_id":"123"}"#),
+      Part::new("text/plain", "hello"),
+    ]);
+
+    let boundary = content_type
+      .strip_prefix("multipart/mixed; boundary=")
+      .unwrap();
+    assert_eq!(
+      body,
+      Body::Text(format!(
+        "--{boundary}\r\nContent-Type: application/json\r\n\r\n{{\"foo_id\":\"123\"}}\r\n\
+         --{boundary}\r\nContent-Type: text/plain\r\n\r\nhello\r\n--{boundary}--\r\n"
+      ))
+    );
+  }
+
+  #[test]
+  fn test_to_multipart_mixed_binary() {
+    let (_, body) = to_multipart_mixed(vec![Part::new(
+      "application/octet-stream",
+      vec![0xff_u8, 0xfe],
+    )]);
+
+    assert!(matches!(body, Body::Binary(_)));
+  }
+}
@@ -0,0 +1,415 @@
+//! Helpers for request parameters serialized using a non-default `style` or `explode` (see
+//! <https://spec.openapis.org/oas/v3.0.3#style-values>).
+
+use crate::EventError;
+
+use aws_lambda_events::query_map::QueryMap;
+use backtrace::Backtrace;
+use serde::de::value::{Error, MapDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer, Serialize};
+use serde_json::Value;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Split a non-exploded array parameter value (e.g., `a,b,c`) into its elements.
+///
+/// An empty value represents an empty array.
+pub fn split_param_value<'a>(
+  value: &'a str,
+  separator: &'a str,
+) -> impl Iterator<Item = &'a str> + 'a {
+  (!value.is_empty())
+    .then(|| value.split(separator))
+    .into_iter()
+    .flatten()
+}
+
+/// Parse each item of an array query parameter using `parse`.
+///
+/// Returns [`EventError::InvalidRequestQueryParamItem`] identifying the first item that fails to
+/// parse. The item's value is omitted from the error if `redact` is `true` (i.e., for sensitive
+/// parameters).
+pub fn parse_query_param_items<'a, I, T, F>(
+  param_name: &'static str,
+  param_values: I,
+  redact: bool,
+  mut parse: F,
+) -> Result<Vec<T>, EventError>
+where
+  I: IntoIterator<Item = &'a str>,
+  F: FnMut(&'a str) -> Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>,
+{
+  param_values
+    .into_iter()
+    .enumerate()
+    .map(|(index, value)| {
+      parse(value).map_err(|source| EventError::InvalidRequestQueryParamItem {
+        param_name: Cow::Borrowed(param_name),
+        index,
+        value: (!redact).then(|| value.to_string()),
+        source,
+        backtrace: Backtrace::new(),
+      })
+    })
+    .collect()
+}
+
+/// Strip the `style` prefix (e.g., `.` for `label` or `;id=` for `matrix`) from a raw path
+/// parameter value and URL-decode the remainder.
+pub fn decode_path_param<'a>(
+  value: &'a str,
+  prefix: &str,
+) -> Result<Cow<'a, str>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+  Ok(urlencoding::decode(strip_path_param_prefix(
+    value, prefix,
+  )?)?)
+}
+
+/// Strip the `style` prefix (see [`decode_path_param`]) from a raw array path parameter value,
+/// split the remainder into its elements, and URL-decode each element.
+///
+/// Elements are split before decoding so that URL-encoded separators remain part of each element.
+pub fn split_path_param<'a>(
+  value: &'a str,
+  prefix: &str,
+  separator: &'a str,
+) -> Result<Vec<Cow<'a, str>>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+  split_param_value(strip_path_param_prefix(value, prefix)?, separator)
+    .map(|element| urlencoding::decode(element).map_err(Into::into))
+    .collect()
+}
+
+/// Strip the `style` prefix from a raw path parameter value.
+///
+/// The `matrix` style omits the `=` for empty values (e.g., `;id` rather than `;id=`).
+fn strip_path_param_prefix<'a>(value: &'a str, prefix: &str) -> Result<&'a str, String> {
+  match value.strip_prefix(prefix) {
+    Some(value) => Ok(value),
+    None if prefix.strip_suffix('=') == Some(value) => Ok(""),
+    None => Err(format!("expected `{prefix}` prefix")),
+  }
+}
+
+/// Deserialize a `deepObject` query parameter (e.g., `filter[color]=red&filter[size]=10`) into
+/// `T`, returning `None` if the query string doesn't contain any of its properties.
+///
+/// Property values are parsed from strings according to the type of the corresponding field of
+/// `T`. If a property is repeated, the first value is used.
+pub fn parse_deep_object<T>(param_name: &str, query: &QueryMap) -> Option<Result<T, Error>>
+where
+  T: DeserializeOwned,
+{
+  let mut properties = BTreeMap::new();
+  for (key, value) in query.iter() {
+    if let Some(property) = key
+      .strip_prefix(param_name)
+      .and_then(|key| key.strip_prefix('['))
+      .and_then(|key| key.strip_suffix(']'))
+    {
+      properties.entry(property).or_insert(value);
+    }
+  }
+
+  if properties.is_empty() {
+    return None;
+  }
+
+  Some(T::deserialize(MapDeserializer::new(
+    properties
+      .into_iter()
+      .map(|(property, value)| (property, ParamValueDeserializer(value))),
+  )))
+}
+
+/// Serialize `value` as the name/value pairs of a `deepObject` query parameter (e.g.,
+/// `filter[color]=red`).
+///
+/// Properties with `null` values are omitted, and string values are sent verbatim. Other
+/// (non-string) values are serialized as JSON.
+pub fn deep_object_query_pairs<T>(
+  param_name: &str,
+  value: &T,
+) -> Result<Vec<(String, String)>, serde_json::Error>
+where
+  T: Serialize,
+{
+  let properties = match serde_json::to_value(value)? {
+    Value::Object(properties) => properties,
+    _ => {
+      return Err(serde::ser::Error::custom(
+        "deepObject parameter must serialize to an object",
+      ))
+    }
+  };
+  Ok(
+    properties
+      .into_iter()
+      .filter_map(|(property, value)| {
+        let value = match value {
+          Value::Null => return None,
+          Value::String(value) => value,
+          value => value.to_string(),
+        };
+        Some((format!("{param_name}[{property}]"), value))
+      })
+      .collect(),
+  )
+}
+
+/// Deserializes a single raw parameter value, parsing it according to the requested type.
+struct ParamValueDeserializer<'a>(&'a str);
+
+impl ParamValueDeserializer<'_> {
+  fn parse<T>(&self) -> Result<T, Error>
+  where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+  {
+    self
+      .0
+      .parse()
+      .map_err(|err| serde::de::Error::custom(format!("invalid value `{}`: {err}", self.0)))
+  }
+}
+
+impl<'a> IntoDeserializer<'a, Error> for ParamValueDeserializer<'a> {
+  type Deserializer = Self;
+
+  fn into_deserializer(self) -> Self::Deserializer {
+    self
+  }
+}
+
+macro_rules! deserialize_parsed {
+  ($($method:ident => $visit:ident,)*) => {
+    $(
+      fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+      where
+        V: Visitor<'de>,
+      {
+        visitor.$visit(self.parse()?)
+      }
+    )*
+  };
+}
+
+impl<'de> Deserializer<'de> for ParamValueDeserializer<'de> {
+  type Error = Error;
+
+  fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+  where
+    V: Visitor<'de>,
+  {
+    visitor.visit_borrowed_str(self.0)
+  }
+
+  deserialize_parsed! {
+    deserialize_bool => visit_bool,
+    deserialize_i8 => visit_i8,
+    deserialize_i16 => visit_i16,
+    deserialize_i32 => visit_i32,
+    deserialize_i64 => visit_i64,
+    deserialize_i128 => visit_i128,
+    deserialize_u8 => visit_u8,
+    deserialize_u16 => visit_u16,
+    deserialize_u32 => visit_u32,
+    deserialize_u64 => visit_u64,
+    deserialize_u128 => visit_u128,
+    deserialize_f32 => visit_f32,
+    deserialize_f64 => visit_f64,
+  }
+
+  fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+  where
+    V: Visitor<'de>,
+  {
+    visitor.visit_some(self)
+  }
+
+  fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error>
+  where
+    V: Visitor<'de>,
+  {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_enum<V>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V,
+  ) -> Result<V::Value, Error>
+  where
+    V: Visitor<'de>,
+  {
+    visitor.visit_enum(self.0.into_deserializer())
+  }
+
+  forward_to_deserialize_any! {
+    char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    ignored_any
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    decode_path_param, deep_object_query_pairs, parse_deep_object, parse_query_param_items,
+    split_param_value, split_path_param,
+  };
+  use crate::EventError;
+
+  use aws_lambda_events::query_map::QueryMap;
+  use serde::{Deserialize, Serialize};
+
+  use std::collections::HashMap;
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  #[serde(rename_all = "camelCase")]
+  enum Color {
+    Red,
+    Blue,
+  }
+
+  #[derive(Debug, Deserialize, PartialEq, Serialize)]
+  struct Filter {
+    color: Color,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u32>,
+    name: Option<String>,
+  }
+
+  #[test]
+  fn test_split_param_value() {
+    assert_eq!(
+      split_param_value("a,b,,c", ",").collect::<Vec<_>>(),
+      vec!["a", "b", "", "c"]
+    );
+    assert_eq!(
+      split_param_value("a|b", "|").collect::<Vec<_>>(),
+      vec!["a", "b"]
+    );
+    assert_eq!(split_param_value("", ",").count(), 0);
+  }
+
+  #[test]
+  fn test_parse_query_param_items() {
+    let parse = |p: &str| p.parse::<i64>().map_err(Into::into);
+    assert_eq!(
+      parse_query_param_items("ids", ["1", "2"], false, parse).unwrap(),
+      vec![1, 2]
+    );
+
+    let err = parse_query_param_items("ids", ["1", "abc", "x"], false, parse).unwrap_err();
+    let EventError::InvalidRequestQueryParamItem {
+      param_name,
+      index,
+      value,
+      ..
+    } = err
+    else {
+      panic!("unexpected error {err:?}");
+    };
+    assert_eq!(
+      (param_name.as_ref(), index, value.as_deref()),
+      ("ids", 1, Some("abc"))
+    );
+
+    let err = parse_query_param_items("ids", ["abc"], true, parse).unwrap_err();
+    assert!(matches!(
+      err,
+      EventError::InvalidRequestQueryParamItem { value: None, .. }
+    ));
+  }
+
+  #[test]
+  fn test_decode_path_param() {
+    assert_eq!(decode_path_param("a%2Cb", "").unwrap(), "a,b");
+    assert_eq!(decode_path_param(".a", ".").unwrap(), "a");
+    assert_eq!(decode_path_param(";id=5", ";id=").unwrap(), "5");
+    assert_eq!(decode_path_param(";id", ";id=").unwrap(), "");
+    assert_eq!(
+      decode_path_param("5", ";id=").unwrap_err().to_string(),
+      "expected `;id=` prefix"
+    );
+  }
+
+  #[test]
+  fn test_split_path_param() {
+    assert_eq!(
+      split_path_param("a%2Cb,c", "", ",").unwrap(),
+      vec!["a,b", "c"]
+    );
+    assert_eq!(split_path_param(".a.b", ".", ".").unwrap(), vec!["a", "b"]);
+    assert_eq!(
+      split_path_param(";id=1;id=2", ";id=", ";id=").unwrap(),
+      vec!["1", "2"]
+    );
+    assert_eq!(
+      split_path_param(";id=1,2", ";id=", ",").unwrap(),
+      vec!["1", "2"]
+    );
+    assert_eq!(
+      split_path_param(";id", ";id=", ",").unwrap(),
+      Vec::<&str>::new()
+    );
+    assert!(split_path_param("1,2", ".", ",").is_err());
+  }
+
+  #[test]
+  fn test_parse_deep_object() {
+    let query = QueryMap::from(HashMap::from([
+      ("filter[color]".to_string(), vec!["blue".to_string()]),
+      ("filter[size]".to_string(), vec!["10".to_string()]),
+      ("other[color]".to_string(), vec!["red".to_string()]),
+      ("limit".to_string(), vec!["5".to_string()]),
+    ]));
+
+    assert_eq!(
+      parse_deep_object::<Filter>("filter", &query)
+        .unwrap()
+        .unwrap(),
+      Filter {
+        color: Color::Blue,
+        size: Some(10),
+        name: None,
+      }
+    );
+    assert!(parse_deep_object::<Filter>("missing", &query).is_none());
+
+    let query = QueryMap::from(HashMap::from([
+      ("filter[color]".to_string(), "red".to_string()),
+      ("filter[size]".to_string(), "big".to_string()),
+    ]));
+    assert_eq!(
+      parse_deep_object::<Filter>("filter", &query)
+        .unwrap()
+        .unwrap_err()
+        .to_string(),
+      "invalid value `big`: invalid digit found in string"
+    );
+  }
+
+  #[test]
+  fn test_deep_object_query_pairs() {
+    assert_eq!(
+      deep_object_query_pairs(
+        "filter",
+        &Filter {
+          color: Color::Red,
+          size: Some(10),
+          name: None,
+        }
+      )
+      .unwrap(),
+      vec![
+        ("filter[color]".to_string(), "red".to_string()),
+        ("filter[size]".to_string(), "10".to_string()),
+      ]
+    );
+    assert!(deep_object_query_pairs("filter", &"red").is_err());
+  }
+}
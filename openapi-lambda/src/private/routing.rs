@@ -0,0 +1,247 @@
+use crate::HttpResponse;
+
+use std::collections::HashMap;
+
+/// Match a request path against an OpenAPI path template (e.g., `/foo/{foo_id}`).
+///
+/// If the path matches, returns the path parameters, which remain URL-encoded. A greedy path
+/// parameter (e.g., `{proxy+}`) matches the remainder of the path, including any `/` characters.
+pub fn match_path_template(template: &str, path: &str) -> Option<HashMap<String, String>> {
+  let mut template_segments = template.split('/');
+  let mut path_segments = path.split('/');
+  let mut path_parameters = HashMap::new();
+  loop {
+    match (template_segments.next(), path_segments.next()) {
+      (None, None) => return Some(path_parameters),
+      (Some(template_segment), Some(path_segment)) => {
+        if let Some(param_name) = template_segment
+          .strip_prefix('{')
+          .and_then(|param| param.strip_suffix('}'))
+        {
+          if path_segment.is_empty() {
+            return None;
+          }
+          if let Some(param_name) = param_name.strip_suffix('+') {
+            let remainder = std::iter::once(path_segment)
+              .chain(path_segments)
+              .collect::<Vec<_>>()
+              .join("/");
+            path_parameters.insert(param_name.to_string(), remainder);
+            return template_segments
+              .next()
+              .is_none()
+              .then_some(path_parameters);
+          }
+          path_parameters.insert(param_name.to_string(), path_segment.to_string());
+        } else if template_segment != path_segment {
+          return None;
+        }
+      }
+      _ => return None,
+    }
+  }
+}
+
+/// Strip a base path (e.g., `/prod`) from the beginning of a request path.
+///
+/// Paths that don't begin with the base path are returned unchanged, since some deployments (e.g.,
+/// custom domain API mappings) already strip the base path.
+pub fn strip_base_path<'a>(path: &'a str, base_path: &str) -> &'a str {
+  match path.strip_prefix(base_path) {
+    Some("") => "/",
+    Some(stripped_path) if stripped_path.starts_with('/') => stripped_path,
+    _ => path,
+  }
+}
+
+/// Sort key for choosing among multiple matching path templates.
+///
+/// Literal path segments take precedence over templated ones (e.g., `/foo/bar` over
+/// `/foo/{foo_id}`), which in turn take precedence over greedy ones (e.g., `/foo/{proxy+}`).
+fn template_precedence(template: &str) -> Vec<u8> {
+  template
+    .split('/')
+    .map(|segment| {
+      if segment.starts_with('{') && segment.ends_with("+}") {
+        2
+      } else if segment.starts_with('{') {
+        1
+      } else {
+        0
+      }
+    })
+    .collect()
+}
+
+/// Find the path template that best matches a request path.
+pub fn match_path(templates: &[&'static str], path: &str) -> Option<&'static str> {
+  templates
+    .iter()
+    .filter(|template| match_path_template(template, path).is_some())
+    .min_by_key(|template| template_precedence(template))
+    .copied()
+}
+
+/// Find the route that best matches a request's HTTP method and path.
+///
+/// Each route is a tuple of `(method, path_template, operation_id)`. If a route matches, returns
+/// the corresponding operation ID and the URL-encoded path parameters.
+pub fn match_route(
+  routes: &[(&str, &'static str, &'static str)],
+  method: &str,
+  path: &str,
+) -> Option<(&'static str, HashMap<String, String>)> {
+  routes
+    .iter()
+    .filter(|(route_method, _, _)| *route_method == method)
+    .filter_map(|(_, template, operation_id)| {
+      match_path_template(template, path).map(|path_parameters| {
+        (
+          template_precedence(template),
+          *operation_id,
+          path_parameters,
+        )
+      })
+    })
+    .min_by(|(precedence_a, _, _), (precedence_b, _, _)| precedence_a.cmp(precedence_b))
+    .map(|(_, operation_id, path_parameters)| (operation_id, path_parameters))
+}
+
+/// Find the route matching an API Gateway REST API request that doesn't specify an operation name.
+///
+/// API Gateway only sets the operation name in the request context if the integration defines one,
+/// so this matches the request's HTTP method and `resource` (e.g., `/foo/{foo_id}`) against the
+/// path template of each route instead. Since API Gateway has already populated the path
+/// parameters in this case, none are returned. If the resource doesn't match any path template
+/// (e.g., for a `/{proxy+}` resource), the request path is matched as in [`match_route`], and the
+/// URL-encoded path parameters are returned.
+pub fn match_resource(
+  routes: &[(&str, &'static str, &'static str)],
+  method: &str,
+  resource: Option<&str>,
+  path: &str,
+) -> Option<(&'static str, Option<HashMap<String, String>>)> {
+  routes
+    .iter()
+    .find(|(route_method, template, _)| *route_method == method && Some(*template) == resource)
+    .map(|(_, _, operation_id)| (*operation_id, None))
+    .or_else(|| {
+      match_route(routes, method, path)
+        .map(|(operation_id, path_parameters)| (operation_id, Some(path_parameters)))
+    })
+}
+
+/// Respond to a request for an extra route registered via `ApiLambda::with_extra_route`.
+///
+/// The generated dispatcher calls the handler through this function so that the handler may be
+/// either a function or a closure expression.
+pub fn respond_to_extra_route<F>(handler: F) -> HttpResponse
+where
+  F: FnOnce() -> HttpResponse,
+{
+  handler()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{match_path, match_resource, match_route, strip_base_path};
+
+  use std::collections::HashMap;
+
+  const ROUTES: &[(&str, &str, &str)] = &[
+    ("GET", "/foo/{foo_id}", "getFoo"),
+    ("GET", "/foo/bar", "getFooBar"),
+    ("POST", "/foo", "createFoo"),
+    ("GET", "/files/{proxy+}", "getFile"),
+    ("GET", "/files/index.html", "getIndex"),
+  ];
+
+  #[test]
+  fn test_match_route() {
+    assert_eq!(
+      match_route(ROUTES, "GET", "/foo/a%20b"),
+      Some((
+        "getFoo",
+        HashMap::from([("foo_id".to_string(), "a%20b".to_string())])
+      ))
+    );
+    assert_eq!(
+      match_route(ROUTES, "GET", "/foo/bar"),
+      Some(("getFooBar", HashMap::new()))
+    );
+    assert_eq!(
+      match_route(ROUTES, "POST", "/foo"),
+      Some(("createFoo", HashMap::new()))
+    );
+    assert_eq!(match_route(ROUTES, "POST", "/foo/bar"), None);
+    assert_eq!(match_route(ROUTES, "GET", "/foo/"), None);
+    assert_eq!(match_route(ROUTES, "GET", "/foo/bar/baz"), None);
+  }
+
+  #[test]
+  fn test_match_route_greedy() {
+    assert_eq!(
+      match_route(ROUTES, "GET", "/files/a/b%2Fc/d.txt"),
+      Some((
+        "getFile",
+        HashMap::from([("proxy".to_string(), "a/b%2Fc/d.txt".to_string())])
+      ))
+    );
+    assert_eq!(
+      match_route(ROUTES, "GET", "/files/a.txt"),
+      Some((
+        "getFile",
+        HashMap::from([("proxy".to_string(), "a.txt".to_string())])
+      ))
+    );
+    assert_eq!(
+      match_route(ROUTES, "GET", "/files/index.html"),
+      Some(("getIndex", HashMap::new()))
+    );
+    assert_eq!(match_route(ROUTES, "GET", "/files/"), None);
+    assert_eq!(match_route(ROUTES, "GET", "/files"), None);
+  }
+
+  #[test]
+  fn test_match_resource() {
+    assert_eq!(
+      match_resource(ROUTES, "GET", Some("/foo/{foo_id}"), "/foo/bar"),
+      Some(("getFoo", None))
+    );
+    assert_eq!(
+      match_resource(ROUTES, "GET", Some("/{proxy+}"), "/foo/bar"),
+      Some(("getFooBar", Some(HashMap::new())))
+    );
+    assert_eq!(
+      match_resource(ROUTES, "GET", None, "/foo/123"),
+      Some((
+        "getFoo",
+        Some(HashMap::from([("foo_id".to_string(), "123".to_string())]))
+      ))
+    );
+    assert_eq!(
+      match_resource(ROUTES, "POST", Some("/foo/{foo_id}"), "/foo/123"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_match_path() {
+    let templates = &["/foo/{foo_id}", "/foo/bar"];
+    assert_eq!(match_path(templates, "/foo/bar"), Some("/foo/bar"));
+    assert_eq!(match_path(templates, "/foo/baz"), Some("/foo/{foo_id}"));
+    assert_eq!(match_path(templates, "/bar"), None);
+  }
+
+  #[test]
+  fn test_strip_base_path() {
+    assert_eq!(strip_base_path("/prod/foo/bar", "/prod"), "/foo/bar");
+    assert_eq!(strip_base_path("/prod", "/prod"), "/");
+    assert_eq!(strip_base_path("/prod/", "/prod"), "/");
+    assert_eq!(
+      strip_base_path("/production/foo", "/prod"),
+      "/production/foo"
+    );
+    assert_eq!(strip_base_path("/foo/bar", "/prod"), "/foo/bar");
+  }
+}
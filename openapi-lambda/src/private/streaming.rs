@@ -0,0 +1,24 @@
+use crate::{Body, EventError, HeaderMap, HttpResponse, Response, ResponseStream, StatusCode};
+
+use backtrace::Backtrace;
+
+/// Build an [`HttpResponse`] whose body is streamed from `response_stream`.
+///
+/// The stream is attached as a response extension so that middleware can still inspect and modify
+/// the status code and headers before the response is converted by
+/// [`http_response_to_stream`](crate::http_response_to_stream).
+pub fn streaming_http_response(
+  status_code: StatusCode,
+  headers: HeaderMap,
+  response_stream: ResponseStream,
+) -> Result<HttpResponse, EventError> {
+  headers
+    .iter()
+    .fold(
+      Response::builder().status(status_code),
+      |response, (header_name, header_value)| response.header(header_name, header_value),
+    )
+    .extension(response_stream)
+    .body(Body::Empty)
+    .map_err(|err| EventError::HttpResponse(Box::new(err), Backtrace::new()))
+}
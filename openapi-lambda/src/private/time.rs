@@ -0,0 +1,123 @@
+//! Helpers for request parameters and models generated with `DateTimeBackend::Time`.
+//!
+//! Unlike their `chrono` equivalents, `time::Date` and `time::OffsetDateTime` don't implement
+//! `FromStr`, and `OffsetDateTime`'s `Display` implementation doesn't produce RFC 3339 timestamps.
+
+use time::error::Parse;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime};
+
+/// Parse an OpenAPI `date` string (e.g., `2017-07-21`).
+pub fn parse_date(value: &str) -> Result<Date, Parse> {
+  Date::parse(value, format_description!("[year]-[month]-[day]"))
+}
+
+/// Parse an OpenAPI `date-time` string (e.g., `2017-07-21T17:32:28Z`).
+pub fn parse_date_time(value: &str) -> Result<OffsetDateTime, Parse> {
+  OffsetDateTime::parse(value, &Rfc3339)
+}
+
+/// Format an OpenAPI `date-time` string.
+///
+/// Timestamps that can't be represented in RFC 3339 (e.g., those with years after 9999) are
+/// formatted using their `Display` implementation instead.
+pub fn format_date_time(value: &OffsetDateTime) -> String {
+  value.format(&Rfc3339).unwrap_or_else(|_| value.to_string())
+}
+
+/// Serde helpers for optional `date-time` properties that may also be explicitly `null`.
+///
+/// Equivalent to [`double_option`](super::double_option), but (de)serializes timestamps as RFC 3339.
+pub mod rfc3339_double_option {
+  use serde::{Deserializer, Serializer};
+  use time::OffsetDateTime;
+
+  /// Serialize a present property, which may be `null`.
+  pub fn serialize<S>(
+    value: &Option<Option<OffsetDateTime>>,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match value {
+      Some(value) => time::serde::rfc3339::option::serialize(value, serializer),
+      // Missing properties should be skipped via `skip_serializing_if`.
+      None => serializer.serialize_none(),
+    }
+  }
+
+  /// Deserialize a present property, which may be `null`.
+  pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Option<OffsetDateTime>>, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    time::serde::rfc3339::option::deserialize(deserializer).map(Some)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{format_date_time, parse_date, parse_date_time};
+
+  use time::{Date, Month, Time, UtcOffset};
+
+  #[test]
+  fn test_parse_date() {
+    assert_eq!(
+      parse_date("2017-07-21").unwrap(),
+      Date::from_calendar_date(2017, Month::July, 21).unwrap()
+    );
+    parse_date("2017-07-21T17:32:28Z").unwrap_err();
+    parse_date("foo").unwrap_err();
+  }
+
+  #[test]
+  fn test_date_time_round_trip() {
+    let date_time = parse_date_time("2017-07-21T17:32:28+02:00").unwrap();
+    assert_eq!(
+      date_time,
+      Date::from_calendar_date(2017, Month::July, 21)
+        .unwrap()
+        .with_time(Time::from_hms(17, 32, 28).unwrap())
+        .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap())
+    );
+    assert_eq!(format_date_time(&date_time), "2017-07-21T17:32:28+02:00");
+
+    parse_date_time("2017-07-21").unwrap_err();
+  }
+
+  #[test]
+  fn test_rfc3339_double_option() {
+    #[derive(Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+    struct Foo {
+      #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "super::rfc3339_double_option"
+      )]
+      deleted_at: Option<Option<time::OffsetDateTime>>,
+    }
+
+    let deleted_at = parse_date_time("2017-07-21T17:32:28Z").unwrap();
+    for (json, foo) in [
+      ("{}", Foo { deleted_at: None }),
+      (
+        r#"{"deleted_at":null}"#,
+        Foo {
+          deleted_at: Some(None),
+        },
+      ),
+      (
+        r#"{"deleted_at":"2017-07-21T17:32:28Z"}"#,
+        Foo {
+          deleted_at: Some(Some(deleted_at)),
+        },
+      ),
+    ] {
+      assert_eq!(serde_json::from_str::<Foo>(json).unwrap(), foo);
+      assert_eq!(serde_json::to_string(&foo).unwrap(), json);
+    }
+  }
+}
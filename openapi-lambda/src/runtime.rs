@@ -1,6 +1,13 @@
-use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use crate::StreamingResponse;
+
+use aws_lambda_events::alb::{AlbTargetGroupRequest, AlbTargetGroupResponse};
+use aws_lambda_events::apigw::{
+  ApiGatewayProxyRequest, ApiGatewayProxyResponse, ApiGatewayV2httpRequest,
+  ApiGatewayV2httpResponse,
+};
 use futures::FutureExt;
 use lambda_runtime::{service_fn, LambdaEvent};
+use serde::de::DeserializeOwned;
 
 use std::future::Future;
 
@@ -35,3 +42,112 @@ where
   .await
   .expect("Lambda run loop should never exit")
 }
+
+/// Start the Lambda runtime to handle requests from an Amazon API Gateway HTTP API (payload format
+/// version 2.0) for the specified API using the specified middleware.
+///
+/// The API module must be generated with
+/// [`EventSource::ApiGatewayV2Http`](https://docs.rs/openapi-lambda-codegen/latest/openapi_lambda_codegen/enum.EventSource.html).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Replace `my_api` with the name of your crate and `backend` with the name of the module
+/// // passed to `ApiLambda::new()`.
+/// use my_api::backend::Api;
+/// use my_api::backend_handler::BackendApiHandler;
+/// use openapi_lambda::run_lambda_v2;
+///
+/// #[tokio::main]
+/// pub async fn main() {
+///   let api = BackendApiHandler::new(...);
+///   let middleware = ...; // Instantiate your middleware here.
+///
+///   run_lambda_v2(|event| api.dispatch_request(event, &middleware)).await
+/// }
+/// ```
+pub async fn run_lambda_v2<F, Fut>(mut dispatch_event: F)
+where
+  F: FnMut(LambdaEvent<ApiGatewayV2httpRequest>) -> Fut,
+  Fut: Future<Output = ApiGatewayV2httpResponse>,
+{
+  lambda_runtime::run(service_fn(|event: LambdaEvent<ApiGatewayV2httpRequest>| {
+    dispatch_event(event).map(Result::<_, std::convert::Infallible>::Ok)
+  }))
+  .await
+  .expect("Lambda run loop should never exit")
+}
+
+/// Start the Lambda runtime to handle requests from an Application Load Balancer for the specified
+/// API using the specified middleware.
+///
+/// The API module must be generated with
+/// [`EventSource::ApplicationLoadBalancer`](https://docs.rs/openapi-lambda-codegen/latest/openapi_lambda_codegen/enum.EventSource.html).
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Replace `my_api` with the name of your crate and `backend` with the name of the module
+/// // passed to `ApiLambda::new()`.
+/// use my_api::backend::Api;
+/// use my_api::backend_handler::BackendApiHandler;
+/// use openapi_lambda::run_lambda_alb;
+///
+/// #[tokio::main]
+/// pub async fn main() {
+///   let api = BackendApiHandler::new(...);
+///   let middleware = ...; // Instantiate your middleware here.
+///
+///   run_lambda_alb(|event| api.dispatch_request(event, &middleware)).await
+/// }
+/// ```
+pub async fn run_lambda_alb<F, Fut>(mut dispatch_event: F)
+where
+  F: FnMut(LambdaEvent<AlbTargetGroupRequest>) -> Fut,
+  Fut: Future<Output = AlbTargetGroupResponse>,
+{
+  lambda_runtime::run(service_fn(|event: LambdaEvent<AlbTargetGroupRequest>| {
+    dispatch_event(event).map(Result::<_, std::convert::Infallible>::Ok)
+  }))
+  .await
+  .expect("Lambda run loop should never exit")
+}
+
+/// Start the Lambda runtime to handle requests for the specified API using the specified
+/// middleware, streaming each response to the client using the
+/// [Lambda response streaming](https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html)
+/// API.
+///
+/// The API module must be generated with
+/// [`ApiLambda::with_response_streaming`](https://docs.rs/openapi-lambda-codegen/latest/openapi_lambda_codegen/struct.ApiLambda.html#method.with_response_streaming)
+/// enabled. The request type `E` is determined by the API Lambda's event source.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Replace `my_api` with the name of your crate and `backend` with the name of the module
+/// // passed to `ApiLambda::new()`.
+/// use my_api::backend::Api;
+/// use my_api::backend_handler::BackendApiHandler;
+/// use openapi_lambda::run_lambda_streaming;
+///
+/// #[tokio::main]
+/// pub async fn main() {
+///   let api = BackendApiHandler::new(...);
+///   let middleware = ...; // Instantiate your middleware here.
+///
+///   run_lambda_streaming(|event| api.dispatch_request(event, &middleware)).await
+/// }
+/// ```
+pub async fn run_lambda_streaming<E, F, Fut>(mut dispatch_event: F)
+where
+  E: DeserializeOwned,
+  F: FnMut(LambdaEvent<E>) -> Fut,
+  Fut: Future<Output = StreamingResponse>,
+{
+  lambda_runtime::run(service_fn(|event: LambdaEvent<E>| {
+    dispatch_event(event).map(Result::<_, std::convert::Infallible>::Ok)
+  }))
+  .await
+  .expect("Lambda run loop should never exit")
+}
@@ -0,0 +1,164 @@
+use crate::{join_multi_value_headers, take_set_cookie_headers, Body, HttpResponse};
+
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use lambda_runtime::{MetadataPrelude, StreamResponse};
+
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+
+/// Error that aborts a streaming response after the response has started.
+pub type StreamError = lambda_runtime::Error;
+
+/// Stream of response body chunks sent to the Lambda response streaming API.
+pub type ResponseBodyStream = BoxStream<'static, Result<Bytes, StreamError>>;
+
+/// Lambda response sent using the
+/// [Lambda response streaming](https://docs.aws.amazon.com/lambda/latest/dg/configuration-response-streaming.html)
+/// API.
+pub type StreamingResponse = StreamResponse<ResponseBodyStream>;
+
+/// Streaming HTTP response body.
+///
+/// API Lambdas generated with response streaming enabled include a `Streaming` variant in each
+/// operation's response type, which accepts a status code and a `ResponseStream`. The status code
+/// and response headers are sent to the client before the first chunk of the body.
+///
+/// Cloning a `ResponseStream` returns a handle to the same underlying stream, which can only be
+/// consumed once.
+#[derive(Clone)]
+pub struct ResponseStream(Arc<Mutex<Option<ResponseBodyStream>>>);
+
+impl ResponseStream {
+  /// Construct a new `ResponseStream` from a stream of body chunks.
+  ///
+  /// If the stream returns an error, the response is aborted. Since the status code and headers
+  /// have already been sent by that point, the client observes a truncated response body.
+  pub fn new<S, B, E>(stream: S) -> Self
+  where
+    S: Stream<Item = Result<B, E>> + Send + 'static,
+    B: Into<Bytes>,
+    E: Into<StreamError>,
+  {
+    Self(Arc::new(Mutex::new(Some(
+      stream
+        .map(|chunk| chunk.map(Into::into).map_err(Into::into))
+        .boxed(),
+    ))))
+  }
+
+  pub(crate) fn take(&self) -> Option<ResponseBodyStream> {
+    self
+      .0
+      .lock()
+      .expect("ResponseStream mutex should not be poisoned")
+      .take()
+  }
+}
+
+impl Debug for ResponseStream {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("ResponseStream").finish_non_exhaustive()
+  }
+}
+
+/// Serialize an [`HttpResponse`] as a [`StreamingResponse`].
+///
+/// If the response was constructed from a [`ResponseStream`], the body is streamed to the client
+/// as it becomes available. Otherwise, the buffered response body is sent as a single chunk.
+/// Multiple values for the same header are joined into a single comma-separated value, and
+/// `Set-Cookie` headers are instead returned via the response's `cookies` field.
+pub fn http_response_to_stream(response: HttpResponse) -> StreamingResponse {
+  let (mut parts, body) = response.into_parts();
+  let cookies = take_set_cookie_headers(&mut parts.headers);
+
+  let body_stream = parts
+    .extensions
+    .remove::<ResponseStream>()
+    .and_then(|response_stream| response_stream.take())
+    .unwrap_or_else(|| match body {
+      Body::Empty => stream::empty().boxed(),
+      Body::Text(text) => stream::iter([Ok(Bytes::from(text))]).boxed(),
+      Body::Binary(bytes) => stream::iter([Ok(Bytes::from(bytes))]).boxed(),
+    });
+
+  StreamResponse {
+    metadata_prelude: MetadataPrelude {
+      status_code: parts.status,
+      headers: join_multi_value_headers(&parts.headers),
+      cookies,
+    },
+    stream: body_stream,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{http_response_to_stream, Body, ResponseStream};
+
+  use futures::executor::block_on;
+  use futures::stream::{self, StreamExt, TryStreamExt};
+  use http::header::{CONTENT_TYPE, SET_COOKIE};
+  use http::{Response, StatusCode};
+
+  #[test]
+  fn test_buffered_body() {
+    let response = Response::builder()
+      .status(StatusCode::NOT_FOUND)
+      .header(CONTENT_TYPE, "text/plain")
+      .header(SET_COOKIE, "a=1")
+      .header(SET_COOKIE, "b=2")
+      .body(Body::Text("not found".to_string()))
+      .unwrap();
+
+    let streaming_response = http_response_to_stream(response);
+    assert_eq!(
+      streaming_response.metadata_prelude.status_code,
+      StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+      streaming_response.metadata_prelude.headers[CONTENT_TYPE],
+      "text/plain"
+    );
+    assert!(!streaming_response
+      .metadata_prelude
+      .headers
+      .contains_key(SET_COOKIE));
+    assert_eq!(streaming_response.metadata_prelude.cookies, ["a=1", "b=2"]);
+
+    let chunks = block_on(streaming_response.stream.try_collect::<Vec<_>>()).unwrap();
+    assert_eq!(chunks, ["not found"]);
+  }
+
+  #[test]
+  fn test_streamed_body() {
+    let response_stream = ResponseStream::new(stream::iter([
+      Ok::<_, std::io::Error>("data: 1\n\n"),
+      Ok("data: 2\n\n"),
+    ]));
+    let response = Response::builder()
+      .status(StatusCode::OK)
+      .extension(response_stream.clone())
+      .body(Body::Empty)
+      .unwrap();
+
+    let streaming_response = http_response_to_stream(response);
+    assert_eq!(
+      streaming_response.metadata_prelude.status_code,
+      StatusCode::OK
+    );
+    let chunks = block_on(streaming_response.stream.try_collect::<Vec<_>>()).unwrap();
+    assert_eq!(chunks, ["data: 1\n\n", "data: 2\n\n"]);
+
+    // The stream can only be consumed once, even by a clone.
+    assert!(response_stream.take().is_none());
+    let response = Response::builder()
+      .extension(response_stream)
+      .body(Body::Empty)
+      .unwrap();
+    assert_eq!(
+      block_on(http_response_to_stream(response).stream.count()),
+      0
+    );
+  }
+}
@@ -0,0 +1,238 @@
+use crate::{HeaderName, LambdaContext, LambdaEvent};
+
+use aws_lambda_events::apigw::{ApiGatewayProxyRequest, ApiGatewayProxyResponse};
+use aws_lambda_events::query_map::QueryMap;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine as _;
+use http::header::CONTENT_TYPE;
+use http::HeaderValue;
+use serde::Serialize;
+
+use std::collections::HashMap;
+use std::future::Future;
+
+pub use http::Method;
+
+/// Builder for synthetic API Gateway REST API events used to test API handlers without a network
+/// or AWS.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Replace `my_api` with the name of your crate and `backend` with the name of the module
+/// // passed to `ApiLambda::new()`.
+/// use my_api::backend::Api;
+/// use my_api::backend_handler::BackendApiHandler;
+/// use openapi_lambda::test_util::{Method, TestRequest};
+///
+/// #[tokio::test]
+/// async fn test_create_pet() {
+///   let api = BackendApiHandler::new(...);
+///   let middleware = ...; // Instantiate your middleware here.
+///
+///   let response = TestRequest::new(Method::POST, "/pet")
+///     .with_operation_name("addPet")
+///     .with_header("authorization", "Bearer foobar")
+///     .with_json_body(&serde_json::json!({"name": "foo", "photoUrls": []}))
+///     .dispatch(|event| api.dispatch_request(event, &middleware))
+///     .await;
+///
+///   assert_eq!(response.status_code, 200);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TestRequest {
+  request: ApiGatewayProxyRequest,
+  query_params: HashMap<String, Vec<String>>,
+  lambda_context: LambdaContext,
+}
+
+impl TestRequest {
+  /// Construct a new request with the specified HTTP method and path (e.g., `/pet/123`).
+  ///
+  /// The request has no operation name by default, in which case the generated dispatcher matches
+  /// the method and path against the path template of each operation. See
+  /// [`with_operation_name`](TestRequest::with_operation_name).
+  pub fn new(method: Method, path: &str) -> Self {
+    let mut request = ApiGatewayProxyRequest {
+      http_method: method.clone(),
+      path: Some(path.to_string()),
+      ..Default::default()
+    };
+    request.request_context.http_method = method;
+    request.request_context.path = Some(path.to_string());
+
+    Self {
+      request,
+      query_params: HashMap::new(),
+      lambda_context: LambdaContext::default(),
+    }
+  }
+
+  /// Operation ID that API Gateway includes in the request context for integrations defined in
+  /// the generated `openapi-apigw.yaml`.
+  pub fn with_operation_name(mut self, operation_name: &str) -> Self {
+    self.request.request_context.operation_name = Some(operation_name.to_string());
+    self
+  }
+
+  /// Path template of the API Gateway resource that matched the request (e.g., `/pet/{petId}`).
+  pub fn with_resource(mut self, resource: &str) -> Self {
+    self.request.resource = Some(resource.to_string());
+    self.request.request_context.resource_path = Some(resource.to_string());
+    self
+  }
+
+  /// Add a request header.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the header name or value is invalid.
+  pub fn with_header(mut self, name: &str, value: &str) -> Self {
+    let name = HeaderName::try_from(name)
+      .unwrap_or_else(|err| panic!("invalid header name `{name}`: {err}"));
+    let value = HeaderValue::try_from(value)
+      .unwrap_or_else(|err| panic!("invalid value for header `{name}`: {err}"));
+    self.request.headers.insert(name.clone(), value.clone());
+    self.request.multi_value_headers.append(name, value);
+    self
+  }
+
+  /// Add a URL-encoded path parameter as populated by API Gateway.
+  pub fn with_path_param(mut self, name: &str, value: &str) -> Self {
+    self
+      .request
+      .path_parameters
+      .insert(name.to_string(), value.to_string());
+    self
+  }
+
+  /// Add a (decoded) query parameter. Repeated parameters are combined into a list.
+  pub fn with_query_param(mut self, name: &str, value: &str) -> Self {
+    self
+      .query_params
+      .entry(name.to_string())
+      .or_default()
+      .push(value.to_string());
+    self
+  }
+
+  /// Set a UTF-8 request body.
+  pub fn with_body<B>(mut self, body: B) -> Self
+  where
+    B: Into<String>,
+  {
+    self.request.body = Some(body.into());
+    self.request.is_base64_encoded = false;
+    self
+  }
+
+  /// Set a binary request body, which is base64-encoded as for API Gateway
+  /// [binary media types](https://docs.aws.amazon.com/apigateway/latest/developerguide/api-gateway-payload-encodings.html).
+  pub fn with_binary_body<B>(mut self, body: B) -> Self
+  where
+    B: AsRef<[u8]>,
+  {
+    self.request.body = Some(BASE64_STANDARD.encode(body));
+    self.request.is_base64_encoded = true;
+    self
+  }
+
+  /// Set a JSON request body along with the `Content-Type: application/json` request header.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the body fails to serialize.
+  pub fn with_json_body<B>(self, body: &B) -> Self
+  where
+    B: Serialize + ?Sized,
+  {
+    let body = serde_json::to_string(body)
+      .unwrap_or_else(|err| panic!("failed to serialize JSON request body: {err}"));
+    self
+      .with_header(CONTENT_TYPE.as_str(), "application/json")
+      .with_body(body)
+  }
+
+  /// Lambda context passed to the handler (defaults to [`LambdaContext::default`]).
+  pub fn with_lambda_context(mut self, lambda_context: LambdaContext) -> Self {
+    self.lambda_context = lambda_context;
+    self
+  }
+
+  /// Build the Lambda event.
+  pub fn into_event(self) -> LambdaEvent<ApiGatewayProxyRequest> {
+    let mut request = self.request;
+    request.query_string_parameters = QueryMap::from(
+      self
+        .query_params
+        .iter()
+        .filter_map(|(name, values)| Some((name.clone(), values.last()?.clone())))
+        .collect::<HashMap<_, _>>(),
+    );
+    request.multi_value_query_string_parameters = QueryMap::from(self.query_params);
+    LambdaEvent::new(request, self.lambda_context)
+  }
+
+  /// Build the Lambda event and pass it to the specified dispatcher (typically
+  /// `Api::dispatch_request`), returning the response.
+  pub async fn dispatch<F, Fut>(self, dispatch_event: F) -> ApiGatewayProxyResponse
+  where
+    F: FnOnce(LambdaEvent<ApiGatewayProxyRequest>) -> Fut,
+    Fut: Future<Output = ApiGatewayProxyResponse>,
+  {
+    dispatch_event(self.into_event()).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Method, TestRequest};
+
+  use http::header::{AUTHORIZATION, CONTENT_TYPE};
+
+  #[test]
+  fn test_into_event() {
+    let event = TestRequest::new(Method::POST, "/pet/123")
+      .with_operation_name("updatePet")
+      .with_resource("/pet/{petId}")
+      .with_path_param("petId", "123")
+      .with_query_param("tag", "a")
+      .with_query_param("tag", "b")
+      .with_header("authorization", "Bearer foobar")
+      .with_json_body(&serde_json::json!({"name": "foo"}))
+      .into_event();
+
+    let request = event.payload;
+    assert_eq!(request.http_method, Method::POST);
+    assert_eq!(request.path.as_deref(), Some("/pet/123"));
+    assert_eq!(request.resource.as_deref(), Some("/pet/{petId}"));
+    assert_eq!(
+      request.request_context.operation_name.as_deref(),
+      Some("updatePet")
+    );
+    assert_eq!(request.path_parameters["petId"], "123");
+    assert_eq!(request.query_string_parameters.first("tag"), Some("b"));
+    assert_eq!(
+      request.multi_value_query_string_parameters.all("tag"),
+      Some(vec!["a", "b"])
+    );
+    assert_eq!(request.headers[AUTHORIZATION], "Bearer foobar");
+    assert_eq!(
+      request.multi_value_headers[CONTENT_TYPE],
+      "application/json"
+    );
+    assert_eq!(request.body.as_deref(), Some(r#"{"name":"foo"}"#));
+    assert!(!request.is_base64_encoded);
+  }
+
+  #[test]
+  fn test_binary_body() {
+    let request = TestRequest::new(Method::PUT, "/upload")
+      .with_binary_body(b"\x89PNG\r\n\x1a\n")
+      .into_event()
+      .payload;
+    assert_eq!(request.body.as_deref(), Some("iVBORw0KGgo="));
+    assert!(request.is_base64_encoded);
+  }
+}